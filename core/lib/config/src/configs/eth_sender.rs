@@ -117,6 +117,14 @@ impl SenderConfig {
             .ok()
             .map(|pk| pk.parse().unwrap())
     }
+
+    /// Private key of the operator sending EIP-4844 transactions. If not set, blob transactions
+    /// are sent by the main operator.
+    pub fn private_key_blobs(&self) -> Option<H256> {
+        std::env::var("ETH_SENDER_SENDER_OPERATOR_BLOBS_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
 }
 
 #[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
@@ -1,7 +1,19 @@
-use std::time::Duration;
+use std::{num::NonZeroU32, time::Duration};
 
+use anyhow::Context as _;
 use serde::Deserialize;
 
+/// Order in which the witness vector generator picks queued jobs.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum JobOrdering {
+    /// Jobs are picked in the same order as by the provers.
+    #[default]
+    Standard,
+    /// Jobs of the oldest batch that doesn't have a complete proof yet are picked first;
+    /// other jobs are picked in the standard order.
+    CriticalPathFirst,
+}
+
 /// Configuration for the witness vector generator
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct FriWitnessVectorGeneratorConfig {
@@ -20,6 +32,29 @@ pub struct FriWitnessVectorGeneratorConfig {
     // specialized group id for this witness vector generator.
     // witness vector generator running the same (circuit id, round) shall have same group id.
     pub specialized_group_id: u8,
+
+    /// Maximum number of jobs picked per minute. If not set, jobs are picked as fast as possible.
+    pub max_jobs_per_minute: Option<NonZeroU32>,
+
+    /// Path to a bincode-serialized base layer circuit synthesized on startup. If set, the generator
+    /// aborts if the synthesis fails or produces an unexpected witness vector.
+    pub self_test_circuit_path: Option<String>,
+    /// Expected hex-encoded SHA-256 checksum of the witness vector produced by the self-test.
+    /// Required if `self_test_circuit_path` is set.
+    pub self_test_expected_checksum: Option<String>,
+
+    /// Order in which queued jobs are picked.
+    #[serde(default)]
+    pub job_ordering: JobOrdering,
+
+    /// Delay before retrying a database operation after the first failure caused by an unavailable database.
+    /// Doubled after each subsequent failure. If not set, defaults to 1 second.
+    pub reconnect_initial_backoff_ms: Option<u64>,
+    /// Upper bound for the delay between retries of a database operation. If not set, defaults to 30 seconds.
+    pub reconnect_max_backoff_ms: Option<u64>,
+    /// Number of consecutive failed attempts of a database operation after which the generator gives up.
+    /// If not set, defaults to 10.
+    pub reconnect_max_consecutive_failures: Option<u32>,
 }
 
 impl FriWitnessVectorGeneratorConfig {
@@ -34,4 +69,16 @@ impl FriWitnessVectorGeneratorConfig {
     pub fn max_prover_reservation_duration(&self) -> Duration {
         Duration::from_secs(self.max_prover_reservation_duration_in_secs as u64)
     }
+
+    /// Returns the circuit path and the expected witness vector checksum for the startup self-test,
+    /// or `None` if the self-test is disabled.
+    pub fn self_test(&self) -> anyhow::Result<Option<(&str, &str)>> {
+        let Some(circuit_path) = &self.self_test_circuit_path else {
+            return Ok(None);
+        };
+        let expected_checksum = self.self_test_expected_checksum.as_deref().context(
+            "`self_test_expected_checksum` must be set if `self_test_circuit_path` is set",
+        )?;
+        Ok(Some((circuit_path, expected_checksum)))
+    }
 }
@@ -0,0 +1 @@
+ALTER TABLE prover_jobs_fri ADD COLUMN IF NOT EXISTS error_class TEXT;
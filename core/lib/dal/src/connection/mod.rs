@@ -205,6 +205,16 @@ impl ConnectionPool {
         self.max_size
     }
 
+    /// Returns the URL of the database this pool connects to.
+    pub fn database_url(&self) -> &str {
+        &self.database_url
+    }
+
+    /// Closes all connections in this pool. All subsequent attempts to access storage will fail.
+    pub async fn close(&self) {
+        self.inner.close().await;
+    }
+
     /// Creates a `StorageProcessor` entity over a recoverable connection.
     /// Upon a database outage connection will block the thread until
     /// it will be able to recover the connection (or, if connection cannot
@@ -277,7 +287,7 @@ impl ConnectionPool {
             Ok(conn) => Ok(conn),
             Err(err) => {
                 Self::report_connection_error(&err);
-                anyhow::bail!("Run out of retries getting a DB connection, last error: {err}");
+                Err(err).context("Run out of retries getting a DB connection")
             }
         }
     }
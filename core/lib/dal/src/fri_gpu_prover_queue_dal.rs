@@ -15,7 +15,7 @@ impl FriGpuProverQueueDal<'_, '_> {
         processing_timeout: Duration,
         specialized_prover_group_id: u8,
         zone: String,
-    ) -> Option<SocketAddress> {
+    ) -> sqlx::Result<Option<SocketAddress>> {
         let processing_timeout = pg_interval_from_duration(processing_timeout);
         let result: Option<SocketAddress> = sqlx::query!(
             r#"
@@ -55,14 +55,13 @@ impl FriGpuProverQueueDal<'_, '_> {
             zone
         )
         .fetch_optional(self.storage.conn())
-        .await
-        .unwrap()
+        .await?
         .map(|row| SocketAddress {
             host: row.instance_host.network(),
             port: row.instance_port as u16,
         });
 
-        result
+        Ok(result)
     }
 
     pub async fn insert_prover_instance(
@@ -108,7 +107,7 @@ impl FriGpuProverQueueDal<'_, '_> {
         address: SocketAddress,
         status: GpuProverInstanceStatus,
         zone: String,
-    ) {
+    ) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
             UPDATE gpu_prover_queue_fri
@@ -126,8 +125,8 @@ impl FriGpuProverQueueDal<'_, '_> {
             zone
         )
         .execute(self.storage.conn())
-        .await
-        .unwrap();
+        .await?;
+        Ok(())
     }
 
     pub async fn update_prover_instance_from_full_to_available(
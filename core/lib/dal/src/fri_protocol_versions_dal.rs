@@ -55,8 +55,8 @@ impl FriProtocolVersionsDal<'_, '_> {
     pub async fn protocol_version_for(
         &mut self,
         vk_commitments: &L1VerifierConfig,
-    ) -> Vec<FriProtocolVersionId> {
-        sqlx::query!(
+    ) -> sqlx::Result<Vec<FriProtocolVersionId>> {
+        let ids = sqlx::query!(
             r#"
             SELECT
                 id
@@ -83,10 +83,10 @@ impl FriProtocolVersionsDal<'_, '_> {
             vk_commitments.recursion_scheduler_level_vk_hash.as_bytes(),
         )
         .fetch_all(self.storage.conn())
-        .await
-        .unwrap()
+        .await?
         .into_iter()
         .map(|row| FriProtocolVersionId::try_from(row.id as u16).unwrap())
-        .collect()
+        .collect();
+        Ok(ids)
     }
 }
@@ -47,13 +47,16 @@ impl FriProverDal<'_, '_> {
         drop(latency);
     }
 
+    /// Picks the next queued job across all circuits. If `critical_path_first` is set, jobs belonging
+    /// to the oldest batch that doesn't have a complete proof yet are picked before all other jobs.
     pub async fn get_next_job(
         &mut self,
         protocol_versions: &[FriProtocolVersionId],
         picked_by: &str,
-    ) -> Option<FriProverJobMetadata> {
+        critical_path_first: bool,
+    ) -> sqlx::Result<Option<FriProverJobMetadata>> {
         let protocol_versions: Vec<i32> = protocol_versions.iter().map(|&id| id as i32).collect();
-        sqlx::query!(
+        let job = sqlx::query!(
             r#"
             UPDATE prover_jobs_fri
             SET
@@ -72,6 +75,17 @@ impl FriProverDal<'_, '_> {
                         status = 'queued'
                         AND protocol_version = ANY ($1)
                     ORDER BY
+                        (
+                            $3
+                            AND l1_batch_number = (
+                                SELECT
+                                    MIN(l1_batch_number)
+                                FROM
+                                    scheduler_witness_jobs_fri
+                                WHERE
+                                    status <> 'successful'
+                            )
+                        ) DESC NULLS LAST,
                         aggregation_round DESC,
                         l1_batch_number ASC,
                         id ASC
@@ -91,10 +105,10 @@ impl FriProverDal<'_, '_> {
             "#,
             &protocol_versions[..],
             picked_by,
+            critical_path_first,
         )
         .fetch_optional(self.storage.conn())
-        .await
-        .unwrap()
+        .await?
         .map(|row| FriProverJobMetadata {
             id: row.id as u32,
             block_number: L1BatchNumber(row.l1_batch_number as u32),
@@ -103,15 +117,40 @@ impl FriProverDal<'_, '_> {
             sequence_number: row.sequence_number as usize,
             depth: row.depth as u16,
             is_node_final_proof: row.is_node_final_proof,
-        })
+        });
+        Ok(job)
     }
 
+    /// Returns the number of the oldest batch that doesn't have a complete proof yet.
+    pub async fn get_oldest_unproven_l1_batch_number(
+        &mut self,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let number = sqlx::query!(
+            r#"
+            SELECT
+                MIN(l1_batch_number) AS "l1_batch_number?"
+            FROM
+                scheduler_witness_jobs_fri
+            WHERE
+                status <> 'successful'
+            "#
+        )
+        .fetch_one(self.storage.conn())
+        .await?
+        .l1_batch_number
+        .map(|number| L1BatchNumber(number as u32));
+        Ok(number)
+    }
+
+    /// Picks the next queued job for one of `circuits_to_pick`. If `critical_path_first` is set, jobs belonging
+    /// to the oldest batch that doesn't have a complete proof yet are picked before all other jobs.
     pub async fn get_next_job_for_circuit_id_round(
         &mut self,
         circuits_to_pick: &[CircuitIdRoundTuple],
         protocol_versions: &[FriProtocolVersionId],
         picked_by: &str,
-    ) -> Option<FriProverJobMetadata> {
+        critical_path_first: bool,
+    ) -> sqlx::Result<Option<FriProverJobMetadata>> {
         let circuit_ids: Vec<_> = circuits_to_pick
             .iter()
             .map(|tuple| tuple.circuit_id as i16)
@@ -121,7 +160,7 @@ impl FriProverDal<'_, '_> {
             .iter()
             .map(|tuple| tuple.aggregation_round as i16)
             .collect();
-        sqlx::query!(
+        let job = sqlx::query!(
             r#"
             UPDATE prover_jobs_fri
             SET
@@ -152,12 +191,34 @@ impl FriProverDal<'_, '_> {
                                 AND pj.circuit_id = tuple.circuit_id
                                 AND pj.aggregation_round = tuple.round
                             ORDER BY
+                                (
+                                    $5
+                                    AND pj.l1_batch_number = (
+                                        SELECT
+                                            MIN(l1_batch_number)
+                                        FROM
+                                            scheduler_witness_jobs_fri
+                                        WHERE
+                                            status <> 'successful'
+                                    )
+                                ) DESC NULLS LAST,
                                 pj.l1_batch_number ASC,
                                 pj.id ASC
                             LIMIT
                                 1
                         ) AS pj ON TRUE
                     ORDER BY
+                        (
+                            $5
+                            AND pj.l1_batch_number = (
+                                SELECT
+                                    MIN(l1_batch_number)
+                                FROM
+                                    scheduler_witness_jobs_fri
+                                WHERE
+                                    status <> 'successful'
+                            )
+                        ) DESC NULLS LAST,
                         pj.l1_batch_number ASC,
                         pj.aggregation_round DESC,
                         pj.id ASC
@@ -179,10 +240,10 @@ impl FriProverDal<'_, '_> {
             &aggregation_rounds[..],
             &protocol_versions[..],
             picked_by,
+            critical_path_first,
         )
         .fetch_optional(self.storage.conn())
-        .await
-        .unwrap()
+        .await?
         .map(|row| FriProverJobMetadata {
             id: row.id as u32,
             block_number: L1BatchNumber(row.l1_batch_number as u32),
@@ -191,7 +252,8 @@ impl FriProverDal<'_, '_> {
             sequence_number: row.sequence_number as usize,
             depth: row.depth as u16,
             is_node_final_proof: row.is_node_final_proof,
-        })
+        });
+        Ok(job)
     }
 
     pub async fn save_proof_error(&mut self, id: u32, error: String) {
@@ -215,6 +277,92 @@ impl FriProverDal<'_, '_> {
         }
     }
 
+    /// Same as [`Self::save_proof_error()`], but additionally records the class of the failure,
+    /// so that failures can be aggregated without parsing error messages.
+    pub async fn save_proof_error_with_class(
+        &mut self,
+        id: u32,
+        error_class: &str,
+        error: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE prover_jobs_fri
+            SET
+                status = 'failed',
+                error = $1,
+                error_class = $2,
+                updated_at = NOW()
+            WHERE
+                id = $3
+            "#,
+            error,
+            error_class,
+            id as i64,
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the error class and message of a failed job, if any.
+    pub async fn get_prover_job_error(
+        &mut self,
+        id: u32,
+    ) -> sqlx::Result<Option<(Option<String>, Option<String>)>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                error_class,
+                error
+            FROM
+                prover_jobs_fri
+            WHERE
+                id = $1
+            "#,
+            id as i64,
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row.map(|row| (row.error_class, row.error)))
+    }
+
+    /// Returns the age of the oldest queued job for each `(circuit_id, aggregation_round)` that has queued jobs.
+    pub async fn get_oldest_queued_job_ages(
+        &mut self,
+    ) -> sqlx::Result<HashMap<(u8, u8), Duration>> {
+        let ages = sqlx::query!(
+            r#"
+            SELECT
+                circuit_id,
+                aggregation_round,
+                EXTRACT(
+                    EPOCH
+                    FROM
+                        (NOW() - MIN(created_at))
+                )::BIGINT AS "age_secs!"
+            FROM
+                prover_jobs_fri
+            WHERE
+                status = 'queued'
+            GROUP BY
+                circuit_id,
+                aggregation_round
+            "#
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .map(|row| {
+            (
+                (row.circuit_id as u8, row.aggregation_round as u8),
+                Duration::from_secs(row.age_secs.max(0) as u64),
+            )
+        })
+        .collect();
+        Ok(ages)
+    }
+
     pub async fn get_prover_job_attempts(&mut self, id: u32) -> sqlx::Result<Option<u32>> {
         let attempts = sqlx::query!(
             r#"
@@ -503,7 +651,7 @@ impl FriProverDal<'_, '_> {
         .map(|row| L1BatchNumber(row.l1_batch_number as u32))
     }
 
-    pub async fn update_status(&mut self, id: u32, status: &str) {
+    pub async fn update_status(&mut self, id: u32, status: &str) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
             UPDATE prover_jobs_fri
@@ -517,8 +665,8 @@ impl FriProverDal<'_, '_> {
             id as i64,
         )
         .execute(self.storage.conn())
-        .await
-        .unwrap();
+        .await?;
+        Ok(())
     }
 
     pub async fn save_successful_sent_proof(&mut self, l1_batch_number: L1BatchNumber) {
@@ -78,6 +78,7 @@ mod tests {
             ETH_SENDER_SENDER_AGGREGATE_TX_POLL_PERIOD="3"
             ETH_SENDER_SENDER_MAX_TXS_IN_FLIGHT="3"
             ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY="0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
+            ETH_SENDER_SENDER_OPERATOR_BLOBS_PRIVATE_KEY="0xd42e6e7ca5fa6cfc5f2e1a9bb4e4a9b4a1ce3a2b0c1a7f6e0d2c5b3a4f6e8d9c"
             ETH_SENDER_SENDER_PROOF_SENDING_MODE="SkipEveryProof"
             ETH_SENDER_GAS_ADJUSTER_DEFAULT_PRIORITY_FEE_PER_GAS="20000000000"
             ETH_SENDER_GAS_ADJUSTER_MAX_BASE_FEE_SAMPLES="10000"
@@ -109,5 +110,9 @@ mod tests {
             actual.sender.private_key().unwrap(),
             hash("27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be")
         );
+        assert_eq!(
+            actual.sender.private_key_blobs().unwrap(),
+            hash("d42e6e7ca5fa6cfc5f2e1a9bb4e4a9b4a1ce3a2b0c1a7f6e0d2c5b3a4f6e8d9c")
+        );
     }
 }
@@ -13,6 +13,10 @@ impl FromEnv for FriWitnessVectorGeneratorConfig {
 
 #[cfg(test)]
 mod tests {
+    use std::num::NonZeroU32;
+
+    use zksync_config::configs::fri_witness_vector_generator::JobOrdering;
+
     use super::*;
     use crate::test_utils::EnvMutex;
 
@@ -27,6 +31,13 @@ mod tests {
             prometheus_pushgateway_url: "http://127.0.0.1:9091".to_string(),
             prometheus_push_interval_ms: Some(100),
             specialized_group_id: 1,
+            max_jobs_per_minute: NonZeroU32::new(30),
+            self_test_circuit_path: Some("/etc/self_test/base_layer_main_vm.bin".to_owned()),
+            self_test_expected_checksum: Some("0x0123".to_owned()),
+            job_ordering: JobOrdering::CriticalPathFirst,
+            reconnect_initial_backoff_ms: Some(500),
+            reconnect_max_backoff_ms: Some(10_000),
+            reconnect_max_consecutive_failures: None,
         }
     }
 
@@ -41,10 +52,28 @@ mod tests {
             FRI_WITNESS_VECTOR_GENERATOR_PROMETHEUS_PUSHGATEWAY_URL="http://127.0.0.1:9091"
             FRI_WITNESS_VECTOR_GENERATOR_PROMETHEUS_PUSH_INTERVAL_MS=100
             FRI_WITNESS_VECTOR_GENERATOR_SPECIALIZED_GROUP_ID=1
+            FRI_WITNESS_VECTOR_GENERATOR_MAX_JOBS_PER_MINUTE=30
+            FRI_WITNESS_VECTOR_GENERATOR_SELF_TEST_CIRCUIT_PATH=/etc/self_test/base_layer_main_vm.bin
+            FRI_WITNESS_VECTOR_GENERATOR_SELF_TEST_EXPECTED_CHECKSUM=0x0123
+            FRI_WITNESS_VECTOR_GENERATOR_JOB_ORDERING=CriticalPathFirst
+            FRI_WITNESS_VECTOR_GENERATOR_RECONNECT_INITIAL_BACKOFF_MS=500
+            FRI_WITNESS_VECTOR_GENERATOR_RECONNECT_MAX_BACKOFF_MS=10000
         "#;
         lock.set_env(config);
 
         let actual = FriWitnessVectorGeneratorConfig::from_env().unwrap();
         assert_eq!(actual, expected_config());
+
+        assert_eq!(
+            actual.self_test().unwrap(),
+            Some(("/etc/self_test/base_layer_main_vm.bin", "0x0123"))
+        );
+
+        lock.remove_env(&["FRI_WITNESS_VECTOR_GENERATOR_SELF_TEST_EXPECTED_CHECKSUM"]);
+        let config = FriWitnessVectorGeneratorConfig::from_env().unwrap();
+        config.self_test().unwrap_err();
+
+        lock.set_env("FRI_WITNESS_VECTOR_GENERATOR_MAX_JOBS_PER_MINUTE=0");
+        FriWitnessVectorGeneratorConfig::from_env().unwrap_err();
     }
 }
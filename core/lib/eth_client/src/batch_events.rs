@@ -0,0 +1,444 @@
+//! Fetching and decoding events emitted by the zkSync L1 contract when L1 batches are committed, proven
+//! and executed.
+
+use zksync_types::{
+    web3::{
+        signing::keccak256,
+        types::{Address, BlockNumber, FilterBuilder, Log, H256, U256, U64},
+        Transport,
+    },
+    L1BatchNumber,
+};
+
+use crate::{clients::QueryClient, types::Error};
+
+/// Signature of the event emitted for each committed L1 batch. All params are indexed:
+/// `(uint256 batchNumber, bytes32 batchHash, bytes32 commitment)`.
+const BLOCK_COMMIT_SIGNATURE: &str = "BlockCommit(uint256,bytes32,bytes32)";
+/// Signature of the event emitted when a range of L1 batches is proven. All params are indexed:
+/// `(uint256 previousLastVerifiedBatch, uint256 currentLastVerifiedBatch)`.
+const BLOCKS_VERIFICATION_SIGNATURE: &str = "BlocksVerification(uint256,uint256)";
+/// Signature of the event emitted for each executed L1 batch. All params are indexed:
+/// `(uint256 batchNumber, bytes32 batchHash, bytes32 commitment)`.
+const BLOCK_EXECUTION_SIGNATURE: &str = "BlockExecution(uint256,bytes32,bytes32)";
+
+/// Kind of an [`L1BatchEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum L1BatchEventKind {
+    /// `BlockCommit` event.
+    Commit,
+    /// `BlocksVerification` event.
+    Prove,
+    /// `BlockExecution` event.
+    Execute,
+}
+
+impl L1BatchEventKind {
+    const ALL: [Self; 3] = [Self::Commit, Self::Prove, Self::Execute];
+
+    fn signature(self) -> &'static str {
+        match self {
+            Self::Commit => BLOCK_COMMIT_SIGNATURE,
+            Self::Prove => BLOCKS_VERIFICATION_SIGNATURE,
+            Self::Execute => BLOCK_EXECUTION_SIGNATURE,
+        }
+    }
+
+    /// Returns the first topic of logs for this event kind, i.e. the hash of the event signature.
+    pub fn topic(self) -> H256 {
+        H256(keccak256(self.signature().as_bytes()))
+    }
+
+    fn from_topic(topic: H256) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.topic() == topic)
+    }
+}
+
+/// Decoded event emitted by the zkSync L1 contract for L1 batches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum L1BatchEvent {
+    /// L1 batch was committed.
+    Committed {
+        batch_number: L1BatchNumber,
+        batch_hash: H256,
+        commitment: H256,
+    },
+    /// L1 batches in the range `(previous_last_verified_batch, last_verified_batch]` were proven.
+    Proven {
+        previous_last_verified_batch: L1BatchNumber,
+        last_verified_batch: L1BatchNumber,
+    },
+    /// L1 batch was executed.
+    Executed {
+        batch_number: L1BatchNumber,
+        batch_hash: H256,
+        commitment: H256,
+    },
+}
+
+impl L1BatchEvent {
+    /// Returns the kind of this event.
+    pub fn kind(&self) -> L1BatchEventKind {
+        match self {
+            Self::Committed { .. } => L1BatchEventKind::Commit,
+            Self::Proven { .. } => L1BatchEventKind::Prove,
+            Self::Executed { .. } => L1BatchEventKind::Execute,
+        }
+    }
+
+    /// Decodes an event from a `log`. Returns `Ok(None)` if the log is not an L1 batch event.
+    pub fn decode(log: &Log) -> Result<Option<Self>, L1BatchEventError> {
+        let Some(kind) = log
+            .topics
+            .first()
+            .copied()
+            .and_then(L1BatchEventKind::from_topic)
+        else {
+            return Ok(None);
+        };
+        let expected_topics = match kind {
+            L1BatchEventKind::Commit | L1BatchEventKind::Execute => 4,
+            L1BatchEventKind::Prove => 3,
+        };
+        if log.topics.len() != expected_topics {
+            return Err(L1BatchEventError::TopicCount {
+                kind,
+                expected: expected_topics,
+                actual: log.topics.len(),
+            });
+        }
+
+        let topics = &log.topics;
+        Ok(Some(match kind {
+            L1BatchEventKind::Commit => Self::Committed {
+                batch_number: decode_batch_number(topics[1])?,
+                batch_hash: topics[2],
+                commitment: topics[3],
+            },
+            L1BatchEventKind::Prove => Self::Proven {
+                previous_last_verified_batch: decode_batch_number(topics[1])?,
+                last_verified_batch: decode_batch_number(topics[2])?,
+            },
+            L1BatchEventKind::Execute => Self::Executed {
+                batch_number: decode_batch_number(topics[1])?,
+                batch_hash: topics[2],
+                commitment: topics[3],
+            },
+        }))
+    }
+}
+
+fn decode_batch_number(topic: H256) -> Result<L1BatchNumber, L1BatchEventError> {
+    let number = U256::from_big_endian(topic.as_bytes());
+    if number > U256::from(u32::MAX) {
+        return Err(L1BatchEventError::BatchNumberOverflow(number));
+    }
+    Ok(L1BatchNumber(number.as_u32()))
+}
+
+/// Errors decoding an [`L1BatchEvent`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum L1BatchEventError {
+    #[error("{kind:?} event log has {actual} topics, expected {expected}")]
+    TopicCount {
+        kind: L1BatchEventKind,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("L1 batch number {0} doesn't fit into u32")]
+    BatchNumberOverflow(U256),
+}
+
+/// [`L1BatchEvent`] together with the location of the log that has emitted it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct L1BatchEventLog {
+    pub event: L1BatchEvent,
+    /// Number of the L1 block containing the log. `None` for pending logs.
+    pub block_number: Option<U64>,
+    /// Hash of the L1 transaction that has emitted the log. `None` for pending logs.
+    pub tx_hash: Option<H256>,
+}
+
+/// Fetches and decodes commit, prove and execute events emitted by the zkSync L1 contract at `contract_address`
+/// in the specified (inclusive) block range. Logs are fetched using [`QueryClient::get_logs_paginated()`],
+/// so the range is split if it exceeds node limits. Returned events are ordered by block number and log index,
+/// and don't contain duplicates.
+pub async fn fetch_l1_batch_events<T: Transport>(
+    client: &QueryClient<T>,
+    contract_address: Address,
+    from_block: BlockNumber,
+    to_block: BlockNumber,
+    component: &'static str,
+) -> Result<Vec<L1BatchEventLog>, Error> {
+    let topics = L1BatchEventKind::ALL.map(L1BatchEventKind::topic).to_vec();
+    let filter = FilterBuilder::default()
+        .address(vec![contract_address])
+        .from_block(from_block)
+        .to_block(to_block)
+        .topics(Some(topics), None, None, None)
+        .build();
+    let logs = client.get_logs_paginated(filter, component).await?;
+
+    let mut events = Vec::with_capacity(logs.len());
+    for log in &logs {
+        // The node should only return logs matching the filter, but it's cheap to check.
+        if log.address != contract_address {
+            continue;
+        }
+        if let Some(event) = L1BatchEvent::decode(log)? {
+            events.push(L1BatchEventLog {
+                event,
+                block_number: log.block_number,
+                tx_hash: log.transaction_hash,
+            });
+        }
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{clients::mock_server::MockRpcServer, EthInterface};
+
+    const CONTRACT_ADDRESS: &str = "0x32400084c286cf3e17e7b677ea9583e60a000324";
+
+    fn topic_for_number(number: u64) -> H256 {
+        H256::from_low_u64_be(number)
+    }
+
+    /// Log for an L1 batch event in the format returned by `eth_getLogs`.
+    fn log_json(
+        kind: L1BatchEventKind,
+        params: &[H256],
+        block_number: u64,
+        log_index: u64,
+    ) -> Value {
+        let topics: Vec<_> = [kind.topic()].iter().chain(params).copied().collect();
+        json!({
+            "address": CONTRACT_ADDRESS,
+            "topics": topics,
+            "data": "0x",
+            "blockNumber": U64::from(block_number),
+            "blockHash": H256::from_low_u64_be(block_number),
+            "transactionHash": H256::from_low_u64_be(1_000 + block_number),
+            "transactionIndex": "0x0",
+            "logIndex": U64::from(log_index),
+            "removed": false,
+        })
+    }
+
+    fn commit_log(batch_number: u64, block_number: u64, log_index: u64) -> Value {
+        let params = [
+            topic_for_number(batch_number),
+            H256::repeat_byte(batch_number as u8),
+            H256::repeat_byte(0x80 | batch_number as u8),
+        ];
+        log_json(L1BatchEventKind::Commit, &params, block_number, log_index)
+    }
+
+    fn prove_log(from: u64, to: u64, block_number: u64, log_index: u64) -> Value {
+        let params = [topic_for_number(from), topic_for_number(to)];
+        log_json(L1BatchEventKind::Prove, &params, block_number, log_index)
+    }
+
+    fn execute_log(batch_number: u64, block_number: u64, log_index: u64) -> Value {
+        let params = [
+            topic_for_number(batch_number),
+            H256::repeat_byte(batch_number as u8),
+            H256::repeat_byte(0x80 | batch_number as u8),
+        ];
+        log_json(L1BatchEventKind::Execute, &params, block_number, log_index)
+    }
+
+    #[test]
+    fn decoding_events() {
+        let log: Log = serde_json::from_value(commit_log(5, 100, 2)).unwrap();
+        let event = L1BatchEvent::decode(&log).unwrap().unwrap();
+        assert_eq!(
+            event,
+            L1BatchEvent::Committed {
+                batch_number: L1BatchNumber(5),
+                batch_hash: H256::repeat_byte(5),
+                commitment: H256::repeat_byte(0x85),
+            }
+        );
+        assert_eq!(event.kind(), L1BatchEventKind::Commit);
+
+        let log: Log = serde_json::from_value(prove_log(3, 5, 101, 0)).unwrap();
+        let event = L1BatchEvent::decode(&log).unwrap().unwrap();
+        assert_eq!(
+            event,
+            L1BatchEvent::Proven {
+                previous_last_verified_batch: L1BatchNumber(3),
+                last_verified_batch: L1BatchNumber(5),
+            }
+        );
+
+        let log: Log = serde_json::from_value(execute_log(5, 102, 7)).unwrap();
+        let event = L1BatchEvent::decode(&log).unwrap().unwrap();
+        assert_matches!(
+            event,
+            L1BatchEvent::Executed { batch_number, .. } if batch_number == L1BatchNumber(5)
+        );
+
+        // Unrelated events are skipped.
+        let mut log: Log = serde_json::from_value(commit_log(5, 100, 2)).unwrap();
+        log.topics[0] = H256(keccak256(b"Transfer(address,address,uint256)"));
+        assert_eq!(L1BatchEvent::decode(&log).unwrap(), None);
+        log.topics.clear();
+        assert_eq!(L1BatchEvent::decode(&log).unwrap(), None);
+    }
+
+    #[test]
+    fn event_topics_match_deployed_contract() {
+        // Topics of events emitted by the diamond proxy deployed on Ethereum mainnet.
+        let expected_topics = [
+            (
+                L1BatchEventKind::Commit,
+                "0x8f2916b2f2d78cc5890ead36c06c0f6d5d112c7e103589947e8e2f0d6eddb763",
+            ),
+            (
+                L1BatchEventKind::Prove,
+                "0x22c9005dd88c18b552a1cd7e8b3b937fcde9ca69213c1f658f54d572e4877a81",
+            ),
+            (
+                L1BatchEventKind::Execute,
+                "0x2402307311a4d6604e4e7b4c8a15a7e1213edb39c16a31efa70afb06030d3165",
+            ),
+        ];
+        for (kind, topic) in expected_topics {
+            assert_eq!(kind.topic(), topic.parse::<H256>().unwrap(), "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn decoding_malformed_events() {
+        let mut log: Log = serde_json::from_value(commit_log(5, 100, 2)).unwrap();
+        log.topics.pop();
+        let err = L1BatchEvent::decode(&log).unwrap_err();
+        assert_eq!(
+            err,
+            L1BatchEventError::TopicCount {
+                kind: L1BatchEventKind::Commit,
+                expected: 4,
+                actual: 3,
+            }
+        );
+
+        let mut log: Log = serde_json::from_value(execute_log(5, 100, 2)).unwrap();
+        log.topics[1] = topic_for_number(u64::from(u32::MAX) + 1);
+        let err = L1BatchEvent::decode(&log).unwrap_err();
+        assert_matches!(err, L1BatchEventError::BatchNumberOverflow(_));
+    }
+
+    #[tokio::test]
+    async fn fetching_events_with_range_splits() {
+        let server = MockRpcServer::spawn(|method, params| {
+            assert_eq!(method, "eth_getLogs");
+            let filter = &params[0];
+            // Single-item address and topic lists may be serialized as scalars.
+            assert!(filter["address"].to_string().contains(CONTRACT_ADDRESS));
+            let topics = filter["topics"][0].to_string();
+            for kind in L1BatchEventKind::ALL {
+                assert!(topics.contains(&format!("{:?}", kind.topic())), "{topics}");
+            }
+
+            let from: U64 = serde_json::from_value(filter["fromBlock"].clone()).unwrap();
+            let to: U64 = serde_json::from_value(filter["toBlock"].clone()).unwrap();
+            if to - from > U64::from(10) {
+                return Err(jsonrpc_core::Error {
+                    code: jsonrpc_core::ErrorCode::ServerError(-32005),
+                    message: "query returned more than 10000 results".to_owned(),
+                    data: None,
+                });
+            }
+            // Logs at the range boundaries are returned for both adjacent ranges to check deduplication.
+            let all_logs = [
+                commit_log(1, 100, 0),
+                commit_log(2, 100, 1),
+                prove_log(0, 2, 105, 3),
+                execute_log(1, 110, 0),
+                execute_log(2, 110, 1),
+                commit_log(3, 112, 0),
+            ];
+            let logs: Vec<_> = all_logs
+                .into_iter()
+                .filter(|log| {
+                    let block: U64 = serde_json::from_value(log["blockNumber"].clone()).unwrap();
+                    block + 1 >= from && block <= to + 1
+                })
+                .collect();
+            Ok(Value::Array(logs))
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let events = fetch_l1_batch_events(
+            &client,
+            CONTRACT_ADDRESS.parse().unwrap(),
+            BlockNumber::Number(100.into()),
+            BlockNumber::Number(120.into()),
+            "test",
+        )
+        .await
+        .unwrap();
+        assert!(server.http_request_count() > 1);
+
+        let events: Vec<_> = events
+            .iter()
+            .map(|log| (log.block_number.unwrap().as_u64(), log.event.kind()))
+            .collect();
+        assert_eq!(
+            events,
+            [
+                (100, L1BatchEventKind::Commit),
+                (100, L1BatchEventKind::Commit),
+                (105, L1BatchEventKind::Prove),
+                (110, L1BatchEventKind::Execute),
+                (110, L1BatchEventKind::Execute),
+                (112, L1BatchEventKind::Commit),
+            ]
+        );
+    }
+
+    /// Decodes events emitted by the mainnet diamond proxy. Synthetic logs above only check the decoding
+    /// logic; run with `L1_RPC_URL=<url> cargo test -- --ignored` to check it against real logs.
+    #[tokio::test]
+    #[ignore]
+    async fn fetching_events_from_real_node() {
+        let url = std::env::var("L1_RPC_URL").expect("`L1_RPC_URL` must be set");
+        let client = QueryClient::new(&url).unwrap();
+        let latest_block = client.block_number("test").await.unwrap();
+        // Batches are committed, proven and executed several times per hour on mainnet.
+        let from_block = latest_block.saturating_sub(3_000.into());
+
+        let events = fetch_l1_batch_events(
+            &client,
+            CONTRACT_ADDRESS.parse().unwrap(),
+            BlockNumber::Number(from_block),
+            BlockNumber::Number(latest_block),
+            "test",
+        )
+        .await
+        .unwrap();
+        for kind in L1BatchEventKind::ALL {
+            assert!(
+                events.iter().any(|log| log.event.kind() == kind),
+                "no {kind:?} events in blocks {from_block}..={latest_block}"
+            );
+        }
+        for log in &events {
+            if let L1BatchEvent::Proven {
+                previous_last_verified_batch,
+                last_verified_batch,
+            } = log.event
+            {
+                assert!(previous_last_verified_batch < last_verified_batch);
+            }
+        }
+    }
+}
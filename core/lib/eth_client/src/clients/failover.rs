@@ -0,0 +1,688 @@
+//! Client routing calls among several Ethereum nodes.
+
+use std::{
+    future::Future,
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use vise::{Gauge, LabeledFamily, Metrics};
+use zksync_types::{
+    web3::{
+        self, ethabi,
+        types::{
+            Address, Block, BlockId, BlockNumber, CallRequest, FeeHistory, Filter, Log,
+            Transaction, TransactionReceipt, H256, U256, U64,
+        },
+    },
+    L1ChainId,
+};
+
+use crate::{
+    clients::{http::QueryClient, LineaEstimateGas},
+    types::{BlockBlobGas, Error, ExecutedTxStatus, FailureInfo},
+    CallOverrides, ContractCall, EthInterface, RawTransactionBytes,
+};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "eth_client_failover")]
+struct FailoverMetrics {
+    /// Whether an Ethereum node endpoint is considered healthy (1) or not (0).
+    #[metrics(labels = ["endpoint"])]
+    endpoint_healthy: LabeledFamily<String, Gauge<u64>>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<FailoverMetrics> = vise::Global::new();
+
+/// Configuration of [`FailoverClient`].
+#[derive(Debug, Clone)]
+pub struct FailoverConfig {
+    /// Number of consecutive failed calls after which an endpoint is marked as unhealthy.
+    pub max_consecutive_failures: u32,
+    /// Interval between probes of unhealthy endpoints.
+    pub probe_interval: Duration,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            max_consecutive_failures: 3,
+            probe_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct EndpointHealth {
+    consecutive_failures: u32,
+    is_unhealthy: bool,
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    name: String,
+    client: QueryClient,
+    health: Mutex<EndpointHealth>,
+}
+
+impl Endpoint {
+    fn report_health(&self, is_healthy: bool) {
+        METRICS.endpoint_healthy[&self.name].set(is_healthy.into());
+    }
+
+    fn record_success(&self) {
+        let mut health = self.health.lock().unwrap();
+        health.consecutive_failures = 0;
+        if health.is_unhealthy {
+            tracing::info!("L1 endpoint `{}` has recovered", self.name);
+            health.is_unhealthy = false;
+            self.report_health(true);
+        }
+    }
+
+    fn record_failure(&self, max_consecutive_failures: u32) {
+        let mut health = self.health.lock().unwrap();
+        health.consecutive_failures += 1;
+        if !health.is_unhealthy && health.consecutive_failures >= max_consecutive_failures {
+            tracing::warn!(
+                "L1 endpoint `{}` is marked as unhealthy after {} consecutive failures",
+                self.name,
+                health.consecutive_failures
+            );
+            health.is_unhealthy = true;
+            self.report_health(false);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FailoverInner {
+    /// Endpoints in the order of preference.
+    endpoints: Vec<Endpoint>,
+    config: FailoverConfig,
+}
+
+impl FailoverInner {
+    /// Returns endpoint indices in the order they should be tried: healthy endpoints in the order of preference,
+    /// then unhealthy ones starting from those with the fewest consecutive failures.
+    fn routing_order(&self) -> Vec<usize> {
+        let mut order: Vec<_> = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(idx, endpoint)| {
+                let health = endpoint.health.lock().unwrap();
+                let failures = if health.is_unhealthy {
+                    Some(health.consecutive_failures)
+                } else {
+                    None
+                };
+                (failures, idx)
+            })
+            .collect();
+        order.sort_unstable();
+        order.into_iter().map(|(_, idx)| idx).collect()
+    }
+
+    async fn probe_unhealthy_endpoints(&self) {
+        const COMPONENT: &str = "failover_probe";
+
+        for endpoint in &self.endpoints {
+            let is_unhealthy = endpoint.health.lock().unwrap().is_unhealthy;
+            if !is_unhealthy {
+                continue;
+            }
+            match endpoint.client.block_number(COMPONENT).await {
+                Ok(_) => endpoint.record_success(),
+                Err(err) => {
+                    tracing::debug!("Probing L1 endpoint `{}` failed: {err}", endpoint.name);
+                }
+            }
+        }
+    }
+
+    async fn run_probes(inner: Weak<Self>, probe_interval: Duration) {
+        loop {
+            tokio::time::sleep(probe_interval).await;
+            let Some(inner) = inner.upgrade() else {
+                return;
+            };
+            inner.probe_unhealthy_endpoints().await;
+        }
+    }
+}
+
+/// Ethereum client wrapping several [`QueryClient`]s connected to different nodes. Each call is routed
+/// to the most preferred healthy endpoint; if the call fails with a transient error, it is retried
+/// with the next endpoint. Endpoints are marked as unhealthy after several consecutive failures
+/// and are periodically probed for recovery.
+#[derive(Debug, Clone)]
+pub struct FailoverClient {
+    inner: Arc<FailoverInner>,
+}
+
+impl FailoverClient {
+    /// Creates a client from named endpoints in the order of preference. Checks that there is at least one endpoint
+    /// and that all endpoints belong to the same chain. Must be called in the context of a Tokio runtime.
+    pub async fn new(
+        endpoints: Vec<(String, QueryClient)>,
+        config: FailoverConfig,
+    ) -> Result<Self, Error> {
+        const COMPONENT: &str = "failover_client";
+
+        if endpoints.is_empty() {
+            return Err(Error::NoEndpoints);
+        }
+        let mut expected_chain_id = None;
+        for (name, client) in &endpoints {
+            let chain_id = client.fetch_chain_id(COMPONENT).await?;
+            let expected = *expected_chain_id.get_or_insert(chain_id);
+            if chain_id != expected {
+                tracing::error!(
+                    "L1 endpoint `{name}` has unexpected chain ID {}",
+                    chain_id.0
+                );
+                return Err(Error::ChainIdMismatch {
+                    expected,
+                    actual: chain_id,
+                });
+            }
+        }
+
+        let endpoints = endpoints
+            .into_iter()
+            .map(|(name, client)| {
+                let endpoint = Endpoint {
+                    name,
+                    client,
+                    health: Mutex::default(),
+                };
+                endpoint.report_health(true);
+                endpoint
+            })
+            .collect();
+        let inner = Arc::new(FailoverInner {
+            endpoints,
+            config: config.clone(),
+        });
+        tokio::spawn(FailoverInner::run_probes(
+            Arc::downgrade(&inner),
+            config.probe_interval,
+        ));
+        Ok(Self { inner })
+    }
+
+    async fn route<R, Fut>(&self, call: impl Fn(QueryClient) -> Fut) -> Result<R, Error>
+    where
+        Fut: Future<Output = Result<R, Error>>,
+    {
+        let mut last_err = None;
+        for idx in self.inner.routing_order() {
+            let endpoint = &self.inner.endpoints[idx];
+            match call(endpoint.client.clone()).await {
+                Ok(value) => {
+                    endpoint.record_success();
+                    return Ok(value);
+                }
+                Err(err) if err.is_transient() => {
+                    tracing::debug!("Call to L1 endpoint `{}` failed: {err}", endpoint.name);
+                    endpoint.record_failure(self.inner.config.max_consecutive_failures);
+                    last_err = Some(err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Err(last_err.expect("endpoints are non-empty as checked in `new()`"))
+    }
+}
+
+#[async_trait]
+impl EthInterface for FailoverClient {
+    async fn nonce_at_for_account(
+        &self,
+        account: Address,
+        block: BlockNumber,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        self.route(
+            |client| async move { client.nonce_at_for_account(account, block, component).await },
+        )
+        .await
+    }
+
+    async fn base_fee_history(
+        &self,
+        from_block: usize,
+        block_count: usize,
+        component: &'static str,
+    ) -> Result<Vec<u64>, Error> {
+        self.route(|client| async move {
+            client
+                .base_fee_history(from_block, block_count, component)
+                .await
+        })
+        .await
+    }
+
+    async fn fee_history(
+        &self,
+        block_count: usize,
+        newest_block: BlockNumber,
+        reward_percentiles: &[f64],
+        component: &'static str,
+    ) -> Result<FeeHistory, Error> {
+        self.route(|client| async move {
+            client
+                .fee_history(block_count, newest_block, reward_percentiles, component)
+                .await
+        })
+        .await
+    }
+
+    async fn get_pending_block_base_fee_per_gas(
+        &self,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        self.route(
+            |client| async move { client.get_pending_block_base_fee_per_gas(component).await },
+        )
+        .await
+    }
+
+    async fn pending_tx_hash(
+        &self,
+        sender: Address,
+        nonce: U256,
+        component: &'static str,
+    ) -> Result<Option<H256>, Error> {
+        self.route(|client| async move { client.pending_tx_hash(sender, nonce, component).await })
+            .await
+    }
+
+    async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error> {
+        self.route(|client| async move { client.get_gas_price(component).await })
+            .await
+    }
+
+    async fn max_priority_fee_per_gas(&self, component: &'static str) -> Result<U256, Error> {
+        self.route(|client| async move { client.max_priority_fee_per_gas(component).await })
+            .await
+    }
+
+    async fn fetch_chain_id(&self, component: &'static str) -> Result<L1ChainId, Error> {
+        self.route(|client| async move { client.fetch_chain_id(component).await })
+            .await
+    }
+
+    async fn block_number(&self, component: &'static str) -> Result<U64, Error> {
+        self.route(|client| async move { client.block_number(component).await })
+            .await
+    }
+
+    /// Unlike other calls, sending a transaction is not idempotent: an endpoint may accept the transaction
+    /// and still fail the call (e.g., time out). Thus, if the transaction was possibly submitted via a previous
+    /// endpoint, errors from the next endpoints indicating that the transaction is already known are treated
+    /// as success.
+    async fn send_raw_tx(&self, tx: RawTransactionBytes) -> Result<H256, Error> {
+        const COMPONENT: &str = "failover_send_raw_tx";
+
+        let tx_hash = H256(web3::signing::keccak256(tx.as_ref()));
+        let mut last_err = None;
+        for idx in self.inner.routing_order() {
+            let endpoint = &self.inner.endpoints[idx];
+            let err = match endpoint.client.send_raw_tx(tx.clone()).await {
+                Ok(hash) => {
+                    endpoint.record_success();
+                    return Ok(hash);
+                }
+                Err(err) => err,
+            };
+
+            let may_be_submitted = last_err.is_some();
+            if may_be_submitted && err.is_already_known_error() {
+                endpoint.record_success();
+                tracing::info!(
+                    "Transaction {tx_hash:?} is already known to L1 endpoint `{}`: {err}",
+                    endpoint.name
+                );
+                return Ok(tx_hash);
+            }
+            if may_be_submitted && err.is_nonce_error() {
+                // The nonce may be used by the transaction itself, or by another transaction.
+                if let Ok(Some(_)) = endpoint.client.get_tx(tx_hash, COMPONENT).await {
+                    endpoint.record_success();
+                    tracing::info!(
+                        "Transaction {tx_hash:?} was already submitted to L1 endpoint `{}`: {err}",
+                        endpoint.name
+                    );
+                    return Ok(tx_hash);
+                }
+            }
+
+            if !err.is_transient() {
+                return Err(err);
+            }
+            tracing::debug!("Call to L1 endpoint `{}` failed: {err}", endpoint.name);
+            endpoint.record_failure(self.inner.config.max_consecutive_failures);
+            last_err = Some(err);
+        }
+        Err(last_err.expect("endpoints are non-empty as checked in `new()`"))
+    }
+
+    async fn linea_estimate_gas(&self, req: CallRequest) -> Result<LineaEstimateGas, Error> {
+        self.route(|client| {
+            let req = req.clone();
+            async move { client.linea_estimate_gas(req).await }
+        })
+        .await
+    }
+
+    async fn get_tx_status(
+        &self,
+        hash: H256,
+        component: &'static str,
+    ) -> Result<Option<ExecutedTxStatus>, Error> {
+        self.route(|client| async move { client.get_tx_status(hash, component).await })
+            .await
+    }
+
+    async fn failure_reason(&self, tx_hash: H256) -> Result<Option<FailureInfo>, Error> {
+        self.route(|client| async move { client.failure_reason(tx_hash).await })
+            .await
+    }
+
+    async fn get_tx(
+        &self,
+        hash: H256,
+        component: &'static str,
+    ) -> Result<Option<Transaction>, Error> {
+        self.route(|client| async move { client.get_tx(hash, component).await })
+            .await
+    }
+
+    async fn tx_receipt(
+        &self,
+        tx_hash: H256,
+        component: &'static str,
+    ) -> Result<Option<TransactionReceipt>, Error> {
+        self.route(|client| async move { client.tx_receipt(tx_hash, component).await })
+            .await
+    }
+
+    async fn eth_balance_at(
+        &self,
+        address: Address,
+        block: BlockNumber,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        self.route(|client| async move { client.eth_balance_at(address, block, component).await })
+            .await
+    }
+
+    async fn call_contract_function(
+        &self,
+        call: ContractCall,
+    ) -> Result<Vec<ethabi::Token>, Error> {
+        self.route(|client| {
+            let call = call.clone();
+            async move { client.call_contract_function(call).await }
+        })
+        .await
+    }
+
+    async fn call_contract_function_with_overrides(
+        &self,
+        call: ContractCall,
+        overrides: CallOverrides,
+    ) -> Result<Vec<ethabi::Token>, Error> {
+        self.route(|client| {
+            let (call, overrides) = (call.clone(), overrides.clone());
+            async move {
+                client
+                    .call_contract_function_with_overrides(call, overrides)
+                    .await
+            }
+        })
+        .await
+    }
+
+    async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error> {
+        self.route(|client| {
+            let filter = filter.clone();
+            async move { client.logs(filter, component).await }
+        })
+        .await
+    }
+
+    async fn block(
+        &self,
+        block_id: BlockId,
+        component: &'static str,
+    ) -> Result<Option<Block<H256>>, Error> {
+        self.route(|client| async move { client.block(block_id, component).await })
+            .await
+    }
+
+    async fn block_blob_gas(
+        &self,
+        block_id: BlockId,
+        component: &'static str,
+    ) -> Result<Option<BlockBlobGas>, Error> {
+        self.route(|client| async move { client.block_blob_gas(block_id, component).await })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use assert_matches::assert_matches;
+    use serde_json::json;
+
+    use super::*;
+    use crate::clients::http::mock_server::MockRpcServer;
+
+    /// Spawns a node with the specified chain ID and block number, which can be switched
+    /// to respond with rate limiting errors.
+    async fn spawn_node(chain_id: u64, block_number: u64) -> (MockRpcServer, Arc<AtomicBool>) {
+        let is_failing = Arc::new(AtomicBool::new(false));
+        let server_is_failing = is_failing.clone();
+        let server = MockRpcServer::spawn(move |method, _params| {
+            if server_is_failing.load(Ordering::SeqCst) {
+                return Err(jsonrpc_core::Error {
+                    code: jsonrpc_core::ErrorCode::ServerError(-32005),
+                    message: "limit exceeded".to_owned(),
+                    data: None,
+                });
+            }
+            match method {
+                "eth_chainId" => Ok(json!(U64::from(chain_id))),
+                "eth_blockNumber" => Ok(json!(U64::from(block_number))),
+                _ => Err(jsonrpc_core::Error::method_not_found()),
+            }
+        })
+        .await;
+        (server, is_failing)
+    }
+
+    fn endpoint(name: &str, server: &MockRpcServer) -> (String, QueryClient) {
+        (name.to_owned(), QueryClient::new(&server.url()).unwrap())
+    }
+
+    const CONFIG: FailoverConfig = FailoverConfig {
+        max_consecutive_failures: 2,
+        probe_interval: Duration::from_millis(50),
+    };
+
+    #[tokio::test]
+    async fn chain_ids_are_validated() {
+        let (primary, _) = spawn_node(1, 0).await;
+        let (fallback, _) = spawn_node(5, 0).await;
+        let endpoints = vec![
+            endpoint("primary", &primary),
+            endpoint("fallback", &fallback),
+        ];
+
+        let err = FailoverClient::new(endpoints, CONFIG).await.unwrap_err();
+        assert_matches!(
+            err,
+            Error::ChainIdMismatch { expected, actual }
+                if expected == L1ChainId(1) && actual == L1ChainId(5)
+        );
+    }
+
+    #[tokio::test]
+    async fn endpoints_are_required() {
+        let err = FailoverClient::new(vec![], CONFIG).await.unwrap_err();
+        assert_matches!(err, Error::NoEndpoints);
+    }
+
+    #[tokio::test]
+    async fn failing_over_and_recovering() {
+        let (primary, primary_is_failing) = spawn_node(1, 100).await;
+        let (fallback, _) = spawn_node(1, 99).await;
+        let endpoints = vec![
+            endpoint("primary", &primary),
+            endpoint("fallback", &fallback),
+        ];
+        let client = FailoverClient::new(endpoints, CONFIG).await.unwrap();
+        assert_eq!(client.block_number("test").await.unwrap(), 100.into());
+
+        primary_is_failing.store(true, Ordering::SeqCst);
+        for _ in 0..CONFIG.max_consecutive_failures {
+            assert_eq!(client.block_number("test").await.unwrap(), 99.into());
+        }
+        assert!(
+            client.inner.endpoints[0]
+                .health
+                .lock()
+                .unwrap()
+                .is_unhealthy
+        );
+        assert_eq!(client.inner.routing_order(), [1, 0]);
+
+        // Unhealthy primary shouldn't be queried.
+        let primary_requests = primary.http_request_count();
+        assert_eq!(client.block_number("test").await.unwrap(), 99.into());
+        assert_eq!(primary.http_request_count(), primary_requests);
+
+        primary_is_failing.store(false, Ordering::SeqCst);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while client.inner.endpoints[0]
+                .health
+                .lock()
+                .unwrap()
+                .is_unhealthy
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("primary endpoint didn't recover");
+        assert_eq!(client.block_number("test").await.unwrap(), 100.into());
+    }
+
+    #[tokio::test]
+    async fn non_transient_errors_are_not_failed_over() {
+        let (primary, _) = spawn_node(1, 100).await;
+        let (fallback, _) = spawn_node(1, 99).await;
+        let endpoints = vec![
+            endpoint("primary", &primary),
+            endpoint("fallback", &fallback),
+        ];
+        let client = FailoverClient::new(endpoints, CONFIG).await.unwrap();
+
+        let fallback_requests = fallback.http_request_count();
+        client.get_gas_price("test").await.unwrap_err();
+        assert_eq!(fallback.http_request_count(), fallback_requests);
+        assert_eq!(
+            client.inner.endpoints[0]
+                .health
+                .lock()
+                .unwrap()
+                .consecutive_failures,
+            0
+        );
+    }
+
+    /// Spawns a node that fails `eth_sendRawTransaction` with the specified error, and knows transactions
+    /// with the specified hash.
+    async fn spawn_sending_node(
+        send_error: (i64, &'static str),
+        known_tx_hash: Option<H256>,
+    ) -> MockRpcServer {
+        MockRpcServer::spawn(move |method, params| match method {
+            "eth_chainId" => Ok(json!(U64::from(1))),
+            "eth_sendRawTransaction" => Err(jsonrpc_core::Error {
+                code: jsonrpc_core::ErrorCode::ServerError(send_error.0),
+                message: send_error.1.to_owned(),
+                data: None,
+            }),
+            "eth_getTransactionByHash" => {
+                let hash: H256 = serde_json::from_value(params[0].clone()).unwrap();
+                Ok(if Some(hash) == known_tx_hash {
+                    json!({
+                        "hash": hash,
+                        "nonce": "0x0",
+                        "from": Address::repeat_byte(1),
+                        "to": Address::repeat_byte(2),
+                        "value": "0x0",
+                        "gas": "0x5208",
+                        "gasPrice": "0x1",
+                        "input": "0x",
+                    })
+                } else {
+                    serde_json::Value::Null
+                })
+            }
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn sending_transaction_after_failover() {
+        let raw_tx = RawTransactionBytes(vec![1, 2, 3]);
+        let tx_hash = H256(web3::signing::keccak256(&raw_tx.0));
+        let primary = spawn_sending_node((-32005, "limit exceeded"), None).await;
+
+        let fallback = spawn_sending_node((-32000, "already known"), None).await;
+        let endpoints = vec![
+            endpoint("primary", &primary),
+            endpoint("fallback", &fallback),
+        ];
+        let client = FailoverClient::new(endpoints, CONFIG).await.unwrap();
+        assert_eq!(client.send_raw_tx(raw_tx.clone()).await.unwrap(), tx_hash);
+
+        let fallback = spawn_sending_node((-32000, "nonce too low"), Some(tx_hash)).await;
+        let endpoints = vec![
+            endpoint("primary", &primary),
+            endpoint("fallback", &fallback),
+        ];
+        let client = FailoverClient::new(endpoints, CONFIG).await.unwrap();
+        assert_eq!(client.send_raw_tx(raw_tx.clone()).await.unwrap(), tx_hash);
+
+        // The nonce is used by another transaction.
+        let fallback = spawn_sending_node((-32000, "nonce too low"), None).await;
+        let endpoints = vec![
+            endpoint("primary", &primary),
+            endpoint("fallback", &fallback),
+        ];
+        let client = FailoverClient::new(endpoints, CONFIG).await.unwrap();
+        let err = client.send_raw_tx(raw_tx).await.unwrap_err();
+        assert!(err.is_nonce_error(), "{err}");
+    }
+
+    #[tokio::test]
+    async fn already_known_errors_are_not_masked_without_failover() {
+        let raw_tx = RawTransactionBytes(vec![1, 2, 3]);
+        let primary = spawn_sending_node((-32000, "already known"), None).await;
+        let endpoints = vec![endpoint("primary", &primary)];
+        let client = FailoverClient::new(endpoints, CONFIG).await.unwrap();
+        let err = client.send_raw_tx(raw_tx).await.unwrap_err();
+        assert!(err.is_already_known_error(), "{err}");
+    }
+}
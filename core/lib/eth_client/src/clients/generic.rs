@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use zksync_types::{
@@ -6,169 +6,437 @@ use zksync_types::{
         contract::Options,
         ethabi,
         types::{
-            Address, Block, BlockId, BlockNumber, CallRequest, Filter, Log, Transaction,
-            TransactionReceipt, H160, H256, U256, U64,
+            Address, Block, BlockId, BlockNumber, CallRequest, FeeHistory, Filter, Log,
+            Transaction, TransactionReceipt, H160, H256, U256, U64,
         },
     },
     L1ChainId,
 };
 
 use crate::{
-    clients::LineaEstimateGas, BoundEthInterface, ContractCall, Error, EthInterface,
-    ExecutedTxStatus, FailureInfo, RawTransactionBytes, SignedCallResult,
+    clients::LineaEstimateGas, BlobGasUsage, BlobTxSidecar, BlockBlobGas, BoundEthInterface,
+    CallFunctionArgs, CallOverrides, ContractCall, Error, EthInterface, ExecutedTxStatus,
+    FailureInfo, PriorityFeeConfig, RawTransactionBytes, SignedCallResult, TxWaitOutcome,
 };
 
-#[async_trait]
-impl<C: EthInterface + ?Sized> EthInterface for Arc<C> {
-    async fn nonce_at_for_account(
-        &self,
-        account: Address,
-        block: BlockNumber,
-        component: &'static str,
-    ) -> Result<U256, Error> {
-        self.as_ref()
-            .nonce_at_for_account(account, block, component)
-            .await
-    }
+/// Implements Ethereum client traits for a smart pointer by delegating to the pointee. Besides convenience,
+/// this allows using `Arc<dyn EthInterface>` / `Box<dyn BoundEthInterface>` etc. wherever the traits are expected.
+macro_rules! delegate_eth_interface {
+    ($ptr:ident) => {
+        #[async_trait]
+        impl<C: EthInterface + ?Sized> EthInterface for $ptr<C> {
+            async fn nonce_at_for_account(
+                &self,
+                account: Address,
+                block: BlockNumber,
+                component: &'static str,
+            ) -> Result<U256, Error> {
+                self.as_ref()
+                    .nonce_at_for_account(account, block, component)
+                    .await
+            }
 
-    async fn base_fee_history(
-        &self,
-        from_block: usize,
-        block_count: usize,
-        component: &'static str,
-    ) -> Result<Vec<u64>, Error> {
-        self.as_ref()
-            .base_fee_history(from_block, block_count, component)
-            .await
-    }
+            async fn base_fee_history(
+                &self,
+                from_block: usize,
+                block_count: usize,
+                component: &'static str,
+            ) -> Result<Vec<u64>, Error> {
+                self.as_ref()
+                    .base_fee_history(from_block, block_count, component)
+                    .await
+            }
 
-    async fn get_pending_block_base_fee_per_gas(
-        &self,
-        component: &'static str,
-    ) -> Result<U256, Error> {
-        self.as_ref()
-            .get_pending_block_base_fee_per_gas(component)
-            .await
-    }
+            async fn fee_history(
+                &self,
+                block_count: usize,
+                newest_block: BlockNumber,
+                reward_percentiles: &[f64],
+                component: &'static str,
+            ) -> Result<FeeHistory, Error> {
+                self.as_ref()
+                    .fee_history(block_count, newest_block, reward_percentiles, component)
+                    .await
+            }
 
-    async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error> {
-        self.as_ref().get_gas_price(component).await
-    }
+            async fn suggest_priority_fee(
+                &self,
+                config: PriorityFeeConfig,
+                component: &'static str,
+            ) -> Result<Option<U256>, Error> {
+                self.as_ref().suggest_priority_fee(config, component).await
+            }
 
-    async fn block_number(&self, component: &'static str) -> Result<U64, Error> {
-        self.as_ref().block_number(component).await
-    }
+            async fn get_pending_block_base_fee_per_gas(
+                &self,
+                component: &'static str,
+            ) -> Result<U256, Error> {
+                self.as_ref()
+                    .get_pending_block_base_fee_per_gas(component)
+                    .await
+            }
 
-    async fn send_raw_tx(&self, tx: RawTransactionBytes) -> Result<H256, Error> {
-        self.as_ref().send_raw_tx(tx).await
-    }
+            async fn fetch_chain_id(&self, component: &'static str) -> Result<L1ChainId, Error> {
+                self.as_ref().fetch_chain_id(component).await
+            }
 
-    async fn linea_estimate_gas(&self, req: CallRequest) -> Result<LineaEstimateGas, Error> {
-        self.as_ref().linea_estimate_gas(req).await
-    }
+            async fn pending_tx_hash(
+                &self,
+                sender: Address,
+                nonce: U256,
+                component: &'static str,
+            ) -> Result<Option<H256>, Error> {
+                self.as_ref()
+                    .pending_tx_hash(sender, nonce, component)
+                    .await
+            }
 
-    async fn get_tx_status(
-        &self,
-        hash: H256,
-        component: &'static str,
-    ) -> Result<Option<ExecutedTxStatus>, Error> {
-        self.as_ref().get_tx_status(hash, component).await
-    }
+            async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error> {
+                self.as_ref().get_gas_price(component).await
+            }
 
-    async fn failure_reason(&self, tx_hash: H256) -> Result<Option<FailureInfo>, Error> {
-        self.as_ref().failure_reason(tx_hash).await
-    }
+            async fn max_priority_fee_per_gas(
+                &self,
+                component: &'static str,
+            ) -> Result<U256, Error> {
+                self.as_ref().max_priority_fee_per_gas(component).await
+            }
 
-    async fn get_tx(
-        &self,
-        hash: H256,
-        component: &'static str,
-    ) -> Result<Option<Transaction>, Error> {
-        self.as_ref().get_tx(hash, component).await
-    }
+            async fn block_number(&self, component: &'static str) -> Result<U64, Error> {
+                self.as_ref().block_number(component).await
+            }
 
-    async fn tx_receipt(
-        &self,
-        tx_hash: H256,
-        component: &'static str,
-    ) -> Result<Option<TransactionReceipt>, Error> {
-        self.as_ref().tx_receipt(tx_hash, component).await
-    }
+            async fn send_raw_tx(&self, tx: RawTransactionBytes) -> Result<H256, Error> {
+                self.as_ref().send_raw_tx(tx).await
+            }
 
-    async fn eth_balance(&self, address: Address, component: &'static str) -> Result<U256, Error> {
-        self.as_ref().eth_balance(address, component).await
-    }
+            async fn linea_estimate_gas(
+                &self,
+                req: CallRequest,
+            ) -> Result<LineaEstimateGas, Error> {
+                self.as_ref().linea_estimate_gas(req).await
+            }
 
-    async fn call_contract_function(
-        &self,
-        call: ContractCall,
-    ) -> Result<Vec<ethabi::Token>, Error> {
-        self.as_ref().call_contract_function(call).await
-    }
+            async fn get_tx_status(
+                &self,
+                hash: H256,
+                component: &'static str,
+            ) -> Result<Option<ExecutedTxStatus>, Error> {
+                self.as_ref().get_tx_status(hash, component).await
+            }
 
-    async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error> {
-        self.as_ref().logs(filter, component).await
-    }
+            async fn failure_reason(&self, tx_hash: H256) -> Result<Option<FailureInfo>, Error> {
+                self.as_ref().failure_reason(tx_hash).await
+            }
 
-    async fn block(
-        &self,
-        block_id: BlockId,
-        component: &'static str,
-    ) -> Result<Option<Block<H256>>, Error> {
-        self.as_ref().block(block_id, component).await
-    }
-}
+            async fn get_tx(
+                &self,
+                hash: H256,
+                component: &'static str,
+            ) -> Result<Option<Transaction>, Error> {
+                self.as_ref().get_tx(hash, component).await
+            }
 
-#[async_trait::async_trait]
-impl<C: BoundEthInterface + ?Sized> BoundEthInterface for Arc<C> {
-    fn contract(&self) -> &ethabi::Contract {
-        self.as_ref().contract()
-    }
+            async fn tx_receipt(
+                &self,
+                tx_hash: H256,
+                component: &'static str,
+            ) -> Result<Option<TransactionReceipt>, Error> {
+                self.as_ref().tx_receipt(tx_hash, component).await
+            }
 
-    fn contract_addr(&self) -> H160 {
-        self.as_ref().contract_addr()
-    }
+            async fn wait_for_tx(
+                &self,
+                tx_hash: H256,
+                confirmations: u64,
+                timeout: Duration,
+                component: &'static str,
+            ) -> Result<TxWaitOutcome, Error> {
+                self.as_ref()
+                    .wait_for_tx(tx_hash, confirmations, timeout, component)
+                    .await
+            }
 
-    fn chain_id(&self) -> L1ChainId {
-        self.as_ref().chain_id()
-    }
+            async fn eth_balance_at(
+                &self,
+                address: Address,
+                block: BlockNumber,
+                component: &'static str,
+            ) -> Result<U256, Error> {
+                self.as_ref()
+                    .eth_balance_at(address, block, component)
+                    .await
+            }
 
-    fn sender_account(&self) -> Address {
-        self.as_ref().sender_account()
-    }
+            async fn call_contract_function(
+                &self,
+                call: ContractCall,
+            ) -> Result<Vec<ethabi::Token>, Error> {
+                self.as_ref().call_contract_function(call).await
+            }
 
-    async fn allowance_on_account(
-        &self,
-        token_address: Address,
-        contract_address: Address,
-        erc20_abi: ethabi::Contract,
-    ) -> Result<U256, Error> {
-        self.as_ref()
-            .allowance_on_account(token_address, contract_address, erc20_abi)
-            .await
-    }
+            async fn call_contract_function_with_overrides(
+                &self,
+                call: ContractCall,
+                overrides: CallOverrides,
+            ) -> Result<Vec<ethabi::Token>, Error> {
+                self.as_ref()
+                    .call_contract_function_with_overrides(call, overrides)
+                    .await
+            }
 
-    async fn sign_prepared_tx_for_addr(
-        &self,
-        data: Vec<u8>,
-        contract_addr: H160,
-        options: Options,
-        component: &'static str,
-    ) -> Result<SignedCallResult, Error> {
-        self.as_ref()
-            .sign_prepared_tx_for_addr(data, contract_addr, options, component)
-            .await
-    }
+            async fn logs(
+                &self,
+                filter: Filter,
+                component: &'static str,
+            ) -> Result<Vec<Log>, Error> {
+                self.as_ref().logs(filter, component).await
+            }
 
-    async fn nonce_at(&self, block: BlockNumber, component: &'static str) -> Result<U256, Error> {
-        self.as_ref().nonce_at(block, component).await
-    }
+            async fn block(
+                &self,
+                block_id: BlockId,
+                component: &'static str,
+            ) -> Result<Option<Block<H256>>, Error> {
+                self.as_ref().block(block_id, component).await
+            }
 
-    async fn current_nonce(&self, component: &'static str) -> Result<U256, Error> {
-        self.as_ref().current_nonce(component).await
-    }
+            async fn tagged_block_number(
+                &self,
+                tag: BlockNumber,
+                component: &'static str,
+            ) -> Result<U64, Error> {
+                self.as_ref().tagged_block_number(tag, component).await
+            }
+
+            async fn block_blob_gas(
+                &self,
+                block_id: BlockId,
+                component: &'static str,
+            ) -> Result<Option<BlockBlobGas>, Error> {
+                self.as_ref().block_blob_gas(block_id, component).await
+            }
+
+            async fn blob_base_fee(
+                &self,
+                block_id: BlockId,
+                component: &'static str,
+            ) -> Result<Option<U256>, Error> {
+                self.as_ref().blob_base_fee(block_id, component).await
+            }
+
+            async fn predicted_blob_base_fee(
+                &self,
+                usage: BlobGasUsage,
+                component: &'static str,
+            ) -> Result<Option<U256>, Error> {
+                self.as_ref()
+                    .predicted_blob_base_fee(usage, component)
+                    .await
+            }
+        }
+
+        #[async_trait]
+        impl<C: BoundEthInterface + ?Sized> BoundEthInterface for $ptr<C> {
+            fn contract(&self) -> &ethabi::Contract {
+                self.as_ref().contract()
+            }
+
+            fn contract_addr(&self) -> H160 {
+                self.as_ref().contract_addr()
+            }
+
+            fn chain_id(&self) -> L1ChainId {
+                self.as_ref().chain_id()
+            }
+
+            fn sender_account(&self) -> Address {
+                self.as_ref().sender_account()
+            }
+
+            async fn allowance_on_account(
+                &self,
+                token_address: Address,
+                contract_address: Address,
+                erc20_abi: ethabi::Contract,
+            ) -> Result<U256, Error> {
+                self.as_ref()
+                    .allowance_on_account(token_address, contract_address, erc20_abi)
+                    .await
+            }
+
+            async fn sign_prepared_tx_for_addr(
+                &self,
+                data: Vec<u8>,
+                contract_addr: H160,
+                options: Options,
+                component: &'static str,
+            ) -> Result<SignedCallResult, Error> {
+                self.as_ref()
+                    .sign_prepared_tx_for_addr(data, contract_addr, options, component)
+                    .await
+            }
+
+            async fn sign_prepared_blob_tx_for_addr(
+                &self,
+                data: Vec<u8>,
+                contract_addr: H160,
+                options: Options,
+                max_fee_per_blob_gas: U256,
+                sidecar: BlobTxSidecar,
+                component: &'static str,
+            ) -> Result<SignedCallResult, Error> {
+                self.as_ref()
+                    .sign_prepared_blob_tx_for_addr(
+                        data,
+                        contract_addr,
+                        options,
+                        max_fee_per_blob_gas,
+                        sidecar,
+                        component,
+                    )
+                    .await
+            }
+
+            async fn nonce_at(
+                &self,
+                block: BlockNumber,
+                component: &'static str,
+            ) -> Result<U256, Error> {
+                self.as_ref().nonce_at(block, component).await
+            }
+
+            async fn current_nonce(&self, component: &'static str) -> Result<U256, Error> {
+                self.as_ref().current_nonce(component).await
+            }
+
+            async fn pending_nonce(&self, component: &'static str) -> Result<U256, Error> {
+                self.as_ref().pending_nonce(component).await
+            }
+
+            async fn sign_prepared_tx(
+                &self,
+                data: Vec<u8>,
+                options: Options,
+                component: &'static str,
+            ) -> Result<SignedCallResult, Error> {
+                self.as_ref()
+                    .sign_prepared_tx(data, options, component)
+                    .await
+            }
+
+            async fn verify_chain_id(&self, component: &'static str) -> Result<(), Error> {
+                self.as_ref().verify_chain_id(component).await
+            }
+
+            async fn replace_transaction(
+                &self,
+                original_hash: H256,
+                fee_bump_percent: u64,
+                component: &'static str,
+            ) -> Result<H256, Error> {
+                self.as_ref()
+                    .replace_transaction(original_hash, fee_bump_percent, component)
+                    .await
+            }
+
+            async fn replace_blob_transaction(
+                &self,
+                original_hash: H256,
+                fee_bump_percent: u64,
+                original_max_fee_per_blob_gas: U256,
+                sidecar: BlobTxSidecar,
+                component: &'static str,
+            ) -> Result<H256, Error> {
+                self.as_ref()
+                    .replace_blob_transaction(
+                        original_hash,
+                        fee_bump_percent,
+                        original_max_fee_per_blob_gas,
+                        sidecar,
+                        component,
+                    )
+                    .await
+            }
+
+            async fn cancel_transaction(
+                &self,
+                nonce: U256,
+                fee_bump_percent: u64,
+                component: &'static str,
+            ) -> Result<H256, Error> {
+                self.as_ref()
+                    .cancel_transaction(nonce, fee_bump_percent, component)
+                    .await
+            }
+
+            async fn sender_eth_balance(&self, component: &'static str) -> Result<U256, Error> {
+                self.as_ref().sender_eth_balance(component).await
+            }
+
+            async fn allowance(
+                &self,
+                token_address: Address,
+                erc20_abi: ethabi::Contract,
+            ) -> Result<U256, Error> {
+                self.as_ref().allowance(token_address, erc20_abi).await
+            }
+
+            async fn call_main_contract_function(
+                &self,
+                args: CallFunctionArgs,
+            ) -> Result<Vec<ethabi::Token>, Error> {
+                self.as_ref().call_main_contract_function(args).await
+            }
+
+            fn encode_tx_data(&self, func: &str, params: Vec<ethabi::Token>) -> Vec<u8> {
+                self.as_ref().encode_tx_data(func, params)
+            }
+        }
+    };
+}
+
+delegate_eth_interface!(Arc);
+delegate_eth_interface!(Box);
+
+#[cfg(test)]
+mod tests {
+    use static_assertions::assert_impl_all;
+
+    use super::*;
+    use crate::{
+        clients::{
+            FailoverClient, JsonRpcSigningClient, MockEthereum, PKSigningClient, QueryClient,
+            WsQueryClient,
+        },
+        DynBoundEthInterface, DynEthInterface,
+    };
+
+    assert_impl_all!(QueryClient: EthInterface);
+    assert_impl_all!(WsQueryClient: EthInterface);
+    assert_impl_all!(FailoverClient: EthInterface);
+    assert_impl_all!(PKSigningClient: BoundEthInterface);
+    assert_impl_all!(JsonRpcSigningClient: BoundEthInterface);
+    assert_impl_all!(MockEthereum: BoundEthInterface);
+    assert_impl_all!(DynEthInterface: EthInterface);
+    assert_impl_all!(DynBoundEthInterface: BoundEthInterface);
+    assert_impl_all!(Box<dyn EthInterface>: EthInterface);
+
+    #[tokio::test]
+    async fn using_type_erased_clients() {
+        let mock = Arc::new(MockEthereum::default());
+        mock.advance_block_number(3);
+        let bound_client: DynBoundEthInterface = mock.clone();
+        let query_client: DynEthInterface = Arc::new(bound_client.clone());
+        let boxed_client: Box<dyn EthInterface> = Box::new(query_client.clone());
 
-    async fn pending_nonce(&self, component: &'static str) -> Result<U256, Error> {
-        self.as_ref().pending_nonce(component).await
+        let clients: [&dyn EthInterface; 2] = [&query_client, &boxed_client];
+        for client in clients {
+            let block_number = client.block_number("test").await.unwrap();
+            assert_eq!(block_number, 3.into());
+        }
+        let nonce = bound_client.pending_nonce("test").await.unwrap();
+        assert_eq!(nonce, 0.into());
+        assert_eq!(bound_client.contract_addr(), mock.contract_addr());
     }
 }
@@ -0,0 +1,303 @@
+//! Batching of JSON-RPC calls for [`QueryClient`].
+
+use std::{fmt, marker::PhantomData, time::Instant};
+
+use jsonrpc_core::Value;
+use serde::de::DeserializeOwned;
+use zksync_types::web3::{
+    self, helpers,
+    types::{Address, Block, BlockId, BlockNumber, H256, U256, U64},
+    BatchTransport, Transport,
+};
+
+use super::{
+    query::QueryClient, report_request, timeout::TimeoutPolicy, Method, COUNTERS, LATENCIES,
+};
+use crate::types::Error;
+
+/// Handle to a call added to an [`RpcBatch`]. Used to retrieve the call result from [`BatchResponse`].
+#[must_use = "call result can only be retrieved using the handle"]
+pub struct BatchCall<T> {
+    index: usize,
+    _result: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for BatchCall<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("BatchCall")
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+/// Batch of JSON-RPC calls sent to the Ethereum node using as few HTTP requests as possible.
+/// Batches larger than [`QueryClient::with_max_batch_size()`] are split into several requests.
+/// If the client is rate-limited, each HTTP request counts as a single request towards the limit.
+#[derive(Debug)]
+pub struct RpcBatch<'a> {
+    client: &'a QueryClient,
+    calls: Vec<(&'static str, Vec<Value>)>,
+}
+
+impl<'a> RpcBatch<'a> {
+    pub(super) fn new(client: &'a QueryClient) -> Self {
+        Self {
+            client,
+            calls: vec![],
+        }
+    }
+
+    /// Returns the number of calls in this batch.
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Adds an arbitrary call to the batch.
+    pub fn add<T: DeserializeOwned>(
+        &mut self,
+        method: &'static str,
+        params: Vec<Value>,
+    ) -> BatchCall<T> {
+        self.calls.push((method, params));
+        BatchCall {
+            index: self.calls.len() - 1,
+            _result: PhantomData,
+        }
+    }
+
+    pub fn nonce_at_for_account(
+        &mut self,
+        account: Address,
+        block: BlockNumber,
+    ) -> BatchCall<U256> {
+        let params = vec![helpers::serialize(&account), helpers::serialize(&block)];
+        self.add("eth_getTransactionCount", params)
+    }
+
+    pub fn eth_balance(&mut self, address: Address) -> BatchCall<U256> {
+        let params = vec![
+            helpers::serialize(&address),
+            helpers::serialize(&BlockNumber::Latest),
+        ];
+        self.add("eth_getBalance", params)
+    }
+
+    pub fn block_number(&mut self) -> BatchCall<U64> {
+        self.add("eth_blockNumber", vec![])
+    }
+
+    pub fn gas_price(&mut self) -> BatchCall<U256> {
+        self.add("eth_gasPrice", vec![])
+    }
+
+    pub fn block(&mut self, block_id: BlockId) -> BatchCall<Option<Block<H256>>> {
+        let include_txs = helpers::serialize(&false);
+        match block_id {
+            BlockId::Hash(hash) => self.add(
+                "eth_getBlockByHash",
+                vec![helpers::serialize(&hash), include_txs],
+            ),
+            BlockId::Number(number) => self.add(
+                "eth_getBlockByNumber",
+                vec![helpers::serialize(&number), include_txs],
+            ),
+        }
+    }
+
+    /// Sends all calls in the batch. Returns an error only if the batch as a whole has failed
+    /// (e.g., due to network issues); errors of individual calls are returned by [`BatchResponse::take()`].
+    pub async fn execute(self, component: &'static str) -> Result<BatchResponse, Error> {
+        COUNTERS.call[&(Method::Batch, component)].inc();
+        let latency = LATENCIES.direct[&Method::Batch].start();
+        let transport = self.client.web3.transport();
+        let call_count = self.calls.len();
+
+        let mut results = Vec::with_capacity(call_count);
+        let mut calls = self.calls.into_iter().peekable();
+        while calls.peek().is_some() {
+            let requests: Vec<_> = calls
+                .by_ref()
+                .take(self.client.max_batch_size)
+                .map(|(method, params)| transport.prepare(method, params))
+                .collect();
+            let request_count = requests.len();
+            self.client.throttle(Method::Batch).await;
+            let permit = self.client.acquire_permit(Method::Batch).await;
+            let started_at = Instant::now();
+            let request = async { transport.send_batch(requests).await.map_err(Error::from) };
+            let chunk_results = TimeoutPolicy::enforce(
+                self.client.timeout_policy.as_deref(),
+                Method::Batch,
+                request,
+            )
+            .await;
+            drop(permit);
+            report_request(Method::Batch, started_at.elapsed(), &chunk_results);
+            let chunk_results = chunk_results?;
+            if chunk_results.len() != request_count {
+                let err = web3::Error::InvalidResponse(format!(
+                    "expected {request_count} responses in batch, got {}",
+                    chunk_results.len()
+                ));
+                return Err(err.into());
+            }
+            results.extend(chunk_results.into_iter().map(Some));
+        }
+        latency.observe();
+        Ok(BatchResponse { results })
+    }
+}
+
+/// Results of calls in an executed [`RpcBatch`].
+#[derive(Debug)]
+pub struct BatchResponse {
+    results: Vec<Option<Result<Value, web3::Error>>>,
+}
+
+impl BatchResponse {
+    /// Takes the result of the specified call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handle doesn't belong to the executed batch.
+    pub fn take<T: DeserializeOwned>(&mut self, call: BatchCall<T>) -> Result<T, Error> {
+        let result = self.results[call.index]
+            .take()
+            .expect("result was already taken");
+        Ok(helpers::decode(result?)?)
+    }
+}
+
+/// State of the operator account and the network fetched in a single batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorSnapshot {
+    /// Nonce of the account at the latest block.
+    pub nonce: U256,
+    pub balance: U256,
+    pub block_number: U64,
+    pub gas_price: U256,
+}
+
+impl QueryClient {
+    /// Creates an empty batch of calls.
+    pub fn batch(&self) -> RpcBatch<'_> {
+        RpcBatch::new(self)
+    }
+
+    /// Fetches the nonce and balance of the `account` together with the latest block number and gas price
+    /// in a single batch.
+    pub async fn operator_snapshot(
+        &self,
+        account: Address,
+        component: &'static str,
+    ) -> Result<OperatorSnapshot, Error> {
+        let mut batch = self.batch();
+        let nonce = batch.nonce_at_for_account(account, BlockNumber::Latest);
+        let balance = batch.eth_balance(account);
+        let block_number = batch.block_number();
+        let gas_price = batch.gas_price();
+
+        let mut response = batch.execute(component).await?;
+        Ok(OperatorSnapshot {
+            nonce: response.take(nonce)?,
+            balance: response.take(balance)?,
+            block_number: response.take(block_number)?,
+            gas_price: response.take(gas_price)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::json;
+
+    use super::*;
+    use crate::clients::http::mock_server::MockRpcServer;
+
+    async fn spawn_server() -> MockRpcServer {
+        MockRpcServer::spawn(|method, params| match method {
+            "eth_getTransactionCount" => Ok(json!("0x5")),
+            "eth_getBalance" => Ok(json!("0xde0b6b3a7640000")),
+            "eth_blockNumber" => Ok(json!("0x64")),
+            "eth_gasPrice" => Ok(json!("0x3b9aca00")),
+            "eth_getBlockByNumber" if params[0] == "0x1000" => Ok(json!(null)),
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn batch_is_sent_in_single_request() {
+        let server = spawn_server().await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let mut batch = client.batch();
+        let calls: Vec<_> = (0..10).map(|_| batch.block_number()).collect();
+        let mut response = batch.execute("test").await.unwrap();
+        for call in calls {
+            assert_eq!(response.take(call).unwrap(), 100.into());
+        }
+        assert_eq!(server.http_request_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn large_batch_is_split() {
+        let server = spawn_server().await;
+        let client = QueryClient::new(&server.url())
+            .unwrap()
+            .with_max_batch_size(4);
+
+        let mut batch = client.batch();
+        let calls: Vec<_> = (0..10).map(|_| batch.gas_price()).collect();
+        let mut response = batch.execute("test").await.unwrap();
+        for call in calls {
+            assert_eq!(response.take(call).unwrap(), 1_000_000_000.into());
+        }
+        assert_eq!(server.http_request_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn errors_are_returned_per_call() {
+        let server = spawn_server().await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let mut batch = client.batch();
+        let block_number = batch.block_number();
+        let unknown = batch.add::<U256>("eth_unknownMethod", vec![]);
+        let missing_block = batch.block(BlockId::Number(0x1000.into()));
+        let mut response = batch.execute("test").await.unwrap();
+
+        assert_eq!(response.take(block_number).unwrap(), 100.into());
+        let err = response.take(unknown).unwrap_err();
+        assert_matches!(err, Error::EthereumGateway(web3::Error::Rpc(_)));
+        assert_eq!(response.take(missing_block).unwrap(), None);
+        assert_eq!(server.http_request_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn fetching_operator_snapshot() {
+        let server = spawn_server().await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let snapshot = client
+            .operator_snapshot(Address::repeat_byte(1), "test")
+            .await
+            .unwrap();
+        assert_eq!(
+            snapshot,
+            OperatorSnapshot {
+                nonce: 5.into(),
+                balance: U256::exp10(18),
+                block_number: 100.into(),
+                gas_price: 1_000_000_000.into(),
+            }
+        );
+        assert_eq!(server.http_request_count(), 1);
+    }
+}
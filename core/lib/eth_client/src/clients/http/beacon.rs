@@ -0,0 +1,232 @@
+//! Client for the REST API of beacon (consensus layer) nodes.
+
+use std::fmt;
+
+use reqwest::StatusCode;
+use serde::{de, Deserialize, Deserializer};
+use zksync_types::web3::types::{Bytes, H256};
+
+use super::{retry::RetryPolicy, Method, COUNTERS, LATENCIES};
+use crate::types::{kzg_to_versioned_hash, BeaconApiError, Error};
+
+/// Identifier of a beacon block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BeaconBlockId {
+    Head,
+    Genesis,
+    Finalized,
+    Slot(u64),
+    Root(H256),
+}
+
+impl fmt::Display for BeaconBlockId {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Head => formatter.write_str("head"),
+            Self::Genesis => formatter.write_str("genesis"),
+            Self::Finalized => formatter.write_str("finalized"),
+            Self::Slot(slot) => write!(formatter, "{slot}"),
+            Self::Root(root) => write!(formatter, "{root:?}"),
+        }
+    }
+}
+
+/// Blob sidecar returned by a beacon node.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BeaconBlobSidecar {
+    /// Index of the blob in the block.
+    #[serde(deserialize_with = "deserialize_quoted_u64")]
+    pub index: u64,
+    pub blob: Bytes,
+    pub kzg_commitment: Bytes,
+    pub kzg_proof: Bytes,
+}
+
+impl BeaconBlobSidecar {
+    /// Computes the versioned hash of the blob, which is referenced by the EIP-4844 transaction carrying the blob.
+    pub fn versioned_hash(&self) -> H256 {
+        kzg_to_versioned_hash(&self.kzg_commitment.0)
+    }
+}
+
+/// Beacon API encodes integers as strings.
+fn deserialize_quoted_u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(de::Error::custom)
+}
+
+#[derive(Debug, Deserialize)]
+struct BlobSidecarsResponse {
+    data: Vec<BeaconBlobSidecar>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    message: String,
+}
+
+/// Client for the beacon node REST API. Currently, only supports retrieving blob sidecars.
+#[derive(Debug, Clone)]
+pub struct BeaconClient {
+    http: reqwest::Client,
+    base_url: String,
+    retry_policy: Option<RetryPolicy>,
+}
+
+impl BeaconClient {
+    /// Creates a client for the beacon node with the specified base URL, e.g. `http://localhost:5052`.
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            retry_policy: None,
+        }
+    }
+
+    /// Enables retries of transient errors (timeouts, connection errors, rate limiting) according
+    /// to the specified policy. By default, errors are not retried.
+    pub fn with_retries(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Returns all blob sidecars for the specified block. Returns `Ok(None)` if the block is not found.
+    pub async fn blob_sidecars(
+        &self,
+        block_id: BeaconBlockId,
+        component: &'static str,
+    ) -> Result<Option<Vec<BeaconBlobSidecar>>, Error> {
+        COUNTERS.call[&(Method::BeaconBlobSidecars, component)].inc();
+        let latency = LATENCIES.direct[&Method::BeaconBlobSidecars].start();
+        let url = format!("{}/eth/v1/beacon/blob_sidecars/{block_id}", self.base_url);
+        let response = RetryPolicy::retry(
+            self.retry_policy.as_ref(),
+            Method::BeaconBlobSidecars,
+            || self.get_blob_sidecars(&url),
+        )
+        .await?;
+        latency.observe();
+        Ok(response.map(|response| response.data))
+    }
+
+    /// Returns blob sidecars for the specified block with the specified versioned hashes. Sidecars are returned
+    /// in the order of `versioned_hashes`; hashes not present in the block are skipped.
+    /// Returns `Ok(None)` if the block is not found.
+    pub async fn blob_sidecars_by_versioned_hashes(
+        &self,
+        block_id: BeaconBlockId,
+        versioned_hashes: &[H256],
+        component: &'static str,
+    ) -> Result<Option<Vec<BeaconBlobSidecar>>, Error> {
+        let sidecars = self.blob_sidecars(block_id, component).await?;
+        Ok(sidecars.map(|sidecars| filter_by_versioned_hashes(sidecars, versioned_hashes)))
+    }
+
+    async fn get_blob_sidecars(
+        &self,
+        url: &str,
+    ) -> Result<Option<BlobSidecarsResponse>, BeaconApiError> {
+        let response = self.http.get(url).send().await?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let body = response.text().await?;
+            let message = serde_json::from_str::<ErrorResponse>(&body)
+                .map_or(body, |response| response.message);
+            return Err(BeaconApiError::Status {
+                status: status.as_u16(),
+                message,
+            });
+        }
+        Ok(Some(response.json().await?))
+    }
+}
+
+fn filter_by_versioned_hashes(
+    sidecars: Vec<BeaconBlobSidecar>,
+    versioned_hashes: &[H256],
+) -> Vec<BeaconBlobSidecar> {
+    let mut sidecars: Vec<_> = sidecars
+        .into_iter()
+        .map(|sidecar| (sidecar.versioned_hash(), Some(sidecar)))
+        .collect();
+    versioned_hashes
+        .iter()
+        .filter_map(|hash| {
+            let (_, sidecar) = sidecars
+                .iter_mut()
+                .find(|(sidecar_hash, _)| sidecar_hash == hash)?;
+            sidecar.take()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_sidecars() -> Vec<BeaconBlobSidecar> {
+        let response: BlobSidecarsResponse =
+            serde_json::from_str(include_str!("fixtures/blob_sidecars.json")).unwrap();
+        response.data
+    }
+
+    #[test]
+    fn deserializing_blob_sidecars() {
+        // Blobs in the fixture are truncated to keep it small.
+        let sidecars = test_sidecars();
+        assert_eq!(sidecars.len(), 2);
+        assert_eq!(sidecars[0].index, 0);
+        assert_eq!(sidecars[1].index, 1);
+        assert_eq!(sidecars[0].blob.0.len(), 64);
+        assert_eq!(sidecars[0].blob.0[31], 1);
+        assert_eq!(sidecars[1].kzg_commitment.0, [0xb2; 48]);
+        assert_eq!(sidecars[1].kzg_proof.0, [0xd4; 48]);
+
+        let versioned_hash = sidecars[0].versioned_hash();
+        assert_eq!(versioned_hash.0[0], 1);
+        assert_eq!(versioned_hash, kzg_to_versioned_hash(&[0xa1; 48]));
+    }
+
+    #[test]
+    fn filtering_sidecars_by_versioned_hashes() {
+        let sidecars = test_sidecars();
+        let hashes: Vec<_> = sidecars
+            .iter()
+            .map(BeaconBlobSidecar::versioned_hash)
+            .collect();
+
+        let filtered = filter_by_versioned_hashes(sidecars.clone(), &[hashes[1], hashes[0]]);
+        assert_eq!(filtered, [sidecars[1].clone(), sidecars[0].clone()]);
+        let filtered = filter_by_versioned_hashes(sidecars.clone(), &[H256::zero(), hashes[1]]);
+        assert_eq!(filtered, [sidecars[1].clone()]);
+        let filtered = filter_by_versioned_hashes(sidecars, &[hashes[0], hashes[0]]);
+        assert_eq!(filtered.len(), 1);
+    }
+
+    #[test]
+    fn formatting_block_ids() {
+        assert_eq!(BeaconBlockId::Head.to_string(), "head");
+        assert_eq!(BeaconBlockId::Slot(8_626_178).to_string(), "8626178");
+        assert_eq!(
+            BeaconBlockId::Root(H256::repeat_byte(0x3c)).to_string(),
+            format!("0x{}", "3c".repeat(32))
+        );
+    }
+
+    #[test]
+    fn classifying_beacon_errors() {
+        let err = Error::from(BeaconApiError::Status {
+            status: 503,
+            message: "Service unavailable".to_owned(),
+        });
+        assert!(err.is_transient());
+        let err = Error::from(BeaconApiError::Status {
+            status: 400,
+            message: "Invalid block ID".to_owned(),
+        });
+        assert!(!err.is_transient());
+    }
+}
@@ -0,0 +1,45 @@
+//! Limiting the number of concurrent requests sent by HTTP clients.
+
+use std::sync::Arc;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::{Method, GAUGES, LATENCIES};
+
+/// Semaphore limiting the number of in-flight requests, shared among all clones of a client.
+#[derive(Debug, Clone)]
+pub(super) struct RequestLimiter {
+    semaphore: Arc<Semaphore>,
+}
+
+impl RequestLimiter {
+    pub(super) fn new(max_in_flight_requests: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_in_flight_requests)),
+        }
+    }
+
+    /// Waits until a request can be sent. The returned permit must be held while the request is in flight.
+    pub(super) async fn acquire(&self, method: Method) -> InFlightPermit {
+        let latency = LATENCIES.concurrency_limit_wait[&method].start();
+        let permit = Arc::clone(&self.semaphore)
+            .acquire_owned()
+            .await
+            .expect("semaphore is never closed");
+        latency.observe();
+        GAUGES.in_flight_requests.inc_by(1);
+        InFlightPermit { _permit: permit }
+    }
+}
+
+/// Permit to send a request obtained from [`RequestLimiter`].
+#[derive(Debug)]
+pub(super) struct InFlightPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Drop for InFlightPermit {
+    fn drop(&mut self) {
+        GAUGES.in_flight_requests.dec_by(1);
+    }
+}
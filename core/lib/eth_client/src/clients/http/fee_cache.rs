@@ -0,0 +1,254 @@
+//! Time-based caching of fee-related queries for HTTP clients.
+
+use std::{
+    any::Any,
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+use super::{FeeCacheEntry, FeeCacheResult, COUNTERS, LATENCIES};
+use crate::types::Error;
+
+#[derive(Debug)]
+struct CachedValue {
+    value: Arc<dyn Any + Send + Sync>,
+    /// Time when the request for the value was sent. Using the request time rather than the response time
+    /// ensures that a value is never served after TTL since it was actually observed on the node.
+    requested_at: Instant,
+}
+
+/// Cache of fee-related query results (gas price, fee history etc.) shared among all clones of a client.
+/// Cached values are served for at most the configured TTL; errors are never cached.
+#[derive(Debug)]
+pub(super) struct FeeCache {
+    ttl: Duration,
+    /// Entries keyed by the query kind and its serialized params.
+    entries: Mutex<HashMap<(FeeCacheEntry, String), CachedValue>>,
+    /// Number of invalidations so far. Only modified while holding the `entries` lock.
+    generation: AtomicU64,
+}
+
+impl FeeCache {
+    pub(super) fn new(ttl: Duration) -> Self {
+        assert!(ttl > Duration::ZERO, "Fee cache TTL must be positive");
+        Self {
+            ttl,
+            entries: Mutex::default(),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    fn get<T: Clone + 'static>(&self, key: &(FeeCacheEntry, String)) -> Option<T> {
+        let entries = self.entries.lock().unwrap();
+        let Some(cached) = entries.get(key) else {
+            COUNTERS.fee_cache[&(key.0, FeeCacheResult::Miss)].inc();
+            return None;
+        };
+        let staleness = cached.requested_at.elapsed();
+        if staleness >= self.ttl {
+            COUNTERS.fee_cache[&(key.0, FeeCacheResult::Expired)].inc();
+            return None;
+        }
+        let value = cached.value.downcast_ref::<T>()?.clone();
+        COUNTERS.fee_cache[&(key.0, FeeCacheResult::Hit)].inc();
+        LATENCIES.fee_cache_staleness[&key.0].observe(staleness);
+        Some(value)
+    }
+
+    /// Returns the cached value for the specified query if it's not older than TTL, or fetches it using `fetch`
+    /// and caches the result. The fetched value is not cached if the cache was invalidated while fetching it.
+    pub(super) async fn get_or_fetch<T, Fut>(
+        &self,
+        entry: FeeCacheEntry,
+        params: String,
+        fetch: impl FnOnce() -> Fut,
+    ) -> Result<T, Error>
+    where
+        T: Clone + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let key = (entry, params);
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+
+        let requested_at = Instant::now();
+        let generation = self.generation.load(Ordering::SeqCst);
+        let value = fetch().await?;
+        let mut entries = self.entries.lock().unwrap();
+        if self.generation.load(Ordering::SeqCst) != generation {
+            // The value may have been observed before the event that caused invalidation.
+            return Ok(value);
+        }
+        entries.retain(|_, cached| cached.requested_at.elapsed() < self.ttl);
+        // Do not replace a value requested later by a concurrent call.
+        let is_fresher = entries
+            .get(&key)
+            .map_or(true, |existing| existing.requested_at <= requested_at);
+        if is_fresher {
+            let cached = CachedValue {
+                value: Arc::new(value.clone()),
+                requested_at,
+            };
+            entries.insert(key, cached);
+        }
+        Ok(value)
+    }
+
+    /// Removes all cached values.
+    pub(super) fn invalidate(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        for (entry, _) in entries.keys() {
+            COUNTERS.fee_cache[&(*entry, FeeCacheResult::Invalidated)].inc();
+        }
+        entries.clear();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use assert_matches::assert_matches;
+    use zksync_types::U256;
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn values_are_never_served_beyond_ttl() {
+        let cache = FeeCache::new(Duration::from_secs(5));
+        let fetch_count = AtomicUsize::new(0);
+        let fetch = || {
+            let count = fetch_count.fetch_add(1, Ordering::SeqCst) + 1;
+            async move { Ok::<_, Error>(U256::from(count)) }
+        };
+
+        let value = cache
+            .get_or_fetch(FeeCacheEntry::GasPrice, String::new(), fetch)
+            .await
+            .unwrap();
+        assert_eq!(value, 1.into());
+
+        tokio::time::advance(Duration::from_millis(4_999)).await;
+        let value = cache
+            .get_or_fetch(FeeCacheEntry::GasPrice, String::new(), fetch)
+            .await
+            .unwrap();
+        assert_eq!(value, 1.into());
+
+        tokio::time::advance(Duration::from_millis(1)).await;
+        let value = cache
+            .get_or_fetch(FeeCacheEntry::GasPrice, String::new(), fetch)
+            .await
+            .unwrap();
+        assert_eq!(value, 2.into());
+        assert_eq!(fetch_count.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ttl_is_counted_from_request_time() {
+        let cache = FeeCache::new(Duration::from_secs(5));
+        let slow_fetch = || async {
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            Ok::<_, Error>(U256::from(1))
+        };
+        cache
+            .get_or_fetch(FeeCacheEntry::GasPrice, String::new(), slow_fetch)
+            .await
+            .unwrap();
+
+        // 3s have passed since the request was sent, so the value must expire in 2s.
+        tokio::time::advance(Duration::from_secs(2)).await;
+        let value = cache
+            .get_or_fetch(FeeCacheEntry::GasPrice, String::new(), || async {
+                Ok::<_, Error>(U256::from(2))
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, 2.into());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn caching_by_params_and_invalidation() {
+        let cache = FeeCache::new(Duration::from_secs(5));
+        for (params, value) in [("[1]", 1_u64), ("[2]", 2)] {
+            let cached = cache
+                .get_or_fetch(FeeCacheEntry::FeeHistory, params.to_owned(), || async {
+                    Ok::<_, Error>(U256::from(value))
+                })
+                .await
+                .unwrap();
+            assert_eq!(cached, value.into());
+        }
+        let cached = cache
+            .get_or_fetch(FeeCacheEntry::FeeHistory, "[1]".to_owned(), || async {
+                Ok::<_, Error>(U256::from(100))
+            })
+            .await
+            .unwrap();
+        assert_eq!(cached, 1.into());
+
+        // Errors are not cached.
+        let err = cache
+            .get_or_fetch::<U256, _>(FeeCacheEntry::GasPrice, String::new(), || async {
+                Err(Error::UnsupportedMethod("eth_gasPrice"))
+            })
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::UnsupportedMethod(_));
+        assert!(cache
+            .get::<U256>(&(FeeCacheEntry::GasPrice, String::new()))
+            .is_none());
+
+        cache.invalidate();
+        let cached = cache
+            .get_or_fetch(FeeCacheEntry::FeeHistory, "[1]".to_owned(), || async {
+                Ok::<_, Error>(U256::from(3))
+            })
+            .await
+            .unwrap();
+        assert_eq!(cached, 3.into());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn values_fetched_before_invalidation_are_not_cached() {
+        let cache = FeeCache::new(Duration::from_secs(5));
+        let slow_fetch = cache.get_or_fetch(FeeCacheEntry::GasPrice, String::new(), || async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok::<_, Error>(U256::from(1))
+        });
+        let invalidate = async {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            cache.invalidate();
+        };
+        let (value, ()) = tokio::join!(slow_fetch, invalidate);
+        // The stale value is still returned to the caller, but is not cached.
+        assert_eq!(value.unwrap(), 1.into());
+        assert!(cache
+            .get::<U256>(&(FeeCacheEntry::GasPrice, String::new()))
+            .is_none());
+
+        let value = cache
+            .get_or_fetch(FeeCacheEntry::GasPrice, String::new(), || async {
+                Ok::<_, Error>(U256::from(2))
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, 2.into());
+        let value = cache
+            .get_or_fetch(FeeCacheEntry::GasPrice, String::new(), || async {
+                Ok::<_, Error>(U256::from(3))
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, 2.into());
+    }
+}
@@ -0,0 +1,232 @@
+//! Types for blocks with full transactions returned by `eth_getBlockBy{Number,Hash}` with `full = true`.
+
+use serde::{Deserialize, Deserializer};
+use zksync_types::web3::types::{AccessList, Address, Bytes, H256, U256, U64};
+
+use crate::types::BlockBlobGas;
+
+/// Type of legacy transactions.
+const LEGACY_TX_TYPE: u64 = 0;
+/// Type of EIP-4844 (blob) transactions.
+const EIP_4844_TX_TYPE: u64 = 3;
+
+/// Block with full transaction objects. Fields not needed to inspect transactions (e.g., state and receipt roots)
+/// are ignored.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FullBlock {
+    /// Block hash. `None` for pending blocks.
+    #[serde(default)]
+    pub hash: Option<H256>,
+    pub parent_hash: H256,
+    /// Block number. `None` for pending blocks.
+    #[serde(default)]
+    pub number: Option<U64>,
+    pub timestamp: U256,
+    /// Fee recipient of the block. May be `None` for pending blocks.
+    #[serde(default)]
+    pub miner: Option<Address>,
+    pub gas_used: U256,
+    pub gas_limit: U256,
+    /// Base fee per gas. Missing for blocks preceding EIP-1559.
+    #[serde(default)]
+    pub base_fee_per_gas: Option<U256>,
+    /// Blob gas used by the block. Missing for blocks preceding EIP-4844.
+    #[serde(default)]
+    pub blob_gas_used: Option<U64>,
+    /// Excess blob gas of the block. Missing for blocks preceding EIP-4844.
+    #[serde(default)]
+    pub excess_blob_gas: Option<U64>,
+    #[serde(default)]
+    pub parent_beacon_block_root: Option<H256>,
+    #[serde(default, deserialize_with = "deserialize_nullable_vec")]
+    pub transactions: Vec<BlockTransaction>,
+}
+
+impl FullBlock {
+    /// Returns EIP-4844 blob gas accounting for the block, or `None` if the block predates EIP-4844.
+    pub fn blob_gas(&self) -> Option<BlockBlobGas> {
+        Some(BlockBlobGas::new(
+            self.blob_gas_used?.as_u64(),
+            self.excess_blob_gas?.as_u64(),
+        ))
+    }
+
+    /// Returns transactions sent by the specified account.
+    pub fn transactions_from(
+        &self,
+        sender: Address,
+    ) -> impl Iterator<Item = &BlockTransaction> + '_ {
+        self.transactions.iter().filter(move |tx| tx.from == sender)
+    }
+}
+
+/// Transaction included into a [`FullBlock`]. Fields specific to certain transaction types are `None`
+/// (or empty) for other types.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTransaction {
+    pub hash: H256,
+    pub nonce: U256,
+    /// Hash of the containing block. `None` for pending blocks.
+    #[serde(default)]
+    pub block_hash: Option<H256>,
+    /// Number of the containing block. `None` for pending blocks.
+    #[serde(default)]
+    pub block_number: Option<U64>,
+    #[serde(default)]
+    pub transaction_index: Option<U64>,
+    pub from: Address,
+    /// Recipient of the transaction. `None` for contract deployments.
+    #[serde(default)]
+    pub to: Option<Address>,
+    pub value: U256,
+    pub gas: U256,
+    /// Gas price for legacy transactions, or the effective gas price for EIP-1559 transactions.
+    #[serde(default)]
+    pub gas_price: Option<U256>,
+    #[serde(default)]
+    pub max_fee_per_gas: Option<U256>,
+    #[serde(default)]
+    pub max_priority_fee_per_gas: Option<U256>,
+    #[serde(default)]
+    pub max_fee_per_blob_gas: Option<U256>,
+    /// Versioned hashes of blobs carried by an EIP-4844 transaction.
+    #[serde(default, deserialize_with = "deserialize_nullable_vec")]
+    pub blob_versioned_hashes: Vec<H256>,
+    #[serde(default, deserialize_with = "deserialize_nullable_vec")]
+    pub access_list: AccessList,
+    pub input: Bytes,
+    /// Transaction type. Missing for legacy transactions returned by some nodes.
+    #[serde(default, rename = "type")]
+    pub transaction_type: Option<U64>,
+    #[serde(default)]
+    pub chain_id: Option<U64>,
+    #[serde(default)]
+    pub v: Option<U64>,
+    #[serde(default)]
+    pub r: Option<U256>,
+    #[serde(default)]
+    pub s: Option<U256>,
+}
+
+impl BlockTransaction {
+    /// Returns the transaction type, treating transactions without the type as legacy ones.
+    pub fn tx_type(&self) -> u64 {
+        self.transaction_type
+            .map_or(LEGACY_TX_TYPE, |tx_type| tx_type.as_u64())
+    }
+
+    /// Checks whether this is an EIP-4844 transaction.
+    pub fn is_blob_tx(&self) -> bool {
+        self.tx_type() == EIP_4844_TX_TYPE
+    }
+}
+
+/// Some nodes return `null` instead of empty arrays, e.g. for transactions in pending blocks.
+fn deserialize_nullable_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(Option::<Vec<T>>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn deserializing_post_dencun_block() {
+        let block: FullBlock =
+            serde_json::from_str(include_str!("fixtures/block_with_txs_dencun.json")).unwrap();
+        assert_eq!(block.number, Some(19_531_250.into()));
+        assert!(block.hash.is_some());
+        assert_eq!(block.base_fee_per_gas, Some(20_000_000_000_u64.into()));
+        let blob_gas = block.blob_gas().unwrap();
+        assert_eq!(blob_gas.blob_gas_used, 0x40000);
+        assert_eq!(blob_gas.excess_blob_gas, 0x3a0000);
+        assert_eq!(block.transactions.len(), 3);
+        for tx in &block.transactions {
+            assert_eq!(tx.block_hash, block.hash);
+            assert_eq!(tx.block_number, block.number);
+        }
+
+        let legacy_tx = &block.transactions[0];
+        assert_eq!(legacy_tx.tx_type(), 0);
+        assert_eq!(legacy_tx.gas_price, Some(20_000_000_000_u64.into()));
+        assert_eq!(legacy_tx.max_fee_per_gas, None);
+        assert!(legacy_tx.access_list.is_empty());
+        assert_eq!(legacy_tx.v, Some(0x25.into()));
+
+        let eip1559_tx = &block.transactions[1];
+        assert_eq!(eip1559_tx.tx_type(), 2);
+        assert_eq!(eip1559_tx.max_fee_per_gas, Some(30_000_000_000_u64.into()));
+        assert_eq!(eip1559_tx.max_priority_fee_per_gas, Some(0.into()));
+        assert_eq!(eip1559_tx.max_fee_per_blob_gas, None);
+        assert!(eip1559_tx.blob_versioned_hashes.is_empty());
+        assert_eq!(eip1559_tx.access_list.len(), 1);
+        assert_eq!(eip1559_tx.access_list[0].storage_keys.len(), 2);
+        assert_eq!(&eip1559_tx.input.0[..4], [0x2e, 0x1a, 0x7d, 0x4d]);
+
+        let blob_tx = &block.transactions[2];
+        assert!(blob_tx.is_blob_tx());
+        assert_eq!(
+            blob_tx.max_fee_per_blob_gas,
+            Some(10_000_000_000_u64.into())
+        );
+        assert_eq!(blob_tx.blob_versioned_hashes.len(), 2);
+        assert!(blob_tx
+            .blob_versioned_hashes
+            .iter()
+            .all(|hash| hash[0] == 1));
+
+        let sender: Address = "0x6887246668a3b87f54deb3b94ba47a6f63f32985"
+            .parse()
+            .unwrap();
+        let sender_txs: Vec<_> = block.transactions_from(sender).collect();
+        assert_eq!(sender_txs, [blob_tx]);
+    }
+
+    #[test]
+    fn deserializing_pending_block() {
+        let block = json!({
+            "hash": null,
+            "parentHash": H256::repeat_byte(1),
+            "number": null,
+            "timestamp": "0x65fd4b27",
+            "miner": null,
+            "gasUsed": "0x5208",
+            "gasLimit": "0x1c9c380",
+            "baseFeePerGas": "0x4a817c800",
+            "transactions": [{
+                "hash": H256::repeat_byte(2),
+                "nonce": "0x1",
+                "blockHash": null,
+                "blockNumber": null,
+                "transactionIndex": null,
+                "from": Address::repeat_byte(3),
+                "to": null,
+                "value": "0x0",
+                "gas": "0x5208",
+                "input": "0x",
+                "accessList": null,
+                "blobVersionedHashes": null,
+            }],
+        });
+        let block: FullBlock = serde_json::from_value(block).unwrap();
+        assert_eq!(block.hash, None);
+        assert_eq!(block.number, None);
+        assert_eq!(block.miner, None);
+        assert_eq!(block.blob_gas(), None);
+
+        let tx = &block.transactions[0];
+        assert_eq!(tx.block_hash, None);
+        assert_eq!(tx.to, None);
+        assert_eq!(tx.tx_type(), 0);
+        assert!(tx.access_list.is_empty());
+        assert!(tx.blob_versioned_hashes.is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+//! Helpers for splitting the block range of `eth_getLogs` queries.
+
+use std::collections::HashSet;
+
+use serde_json::Value;
+use zksync_types::web3::types::{Filter, Log};
+
+/// Maximum depth of splitting the block range of a single `eth_getLogs` query in half.
+pub(super) const MAX_LOGS_SPLIT_DEPTH: usize = 16;
+
+/// Bound of the block range of an `eth_getLogs` filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RangeBound {
+    Number(u64),
+    Latest,
+}
+
+impl RangeBound {
+    /// Parses the bound from its JSON-RPC representation. Missing bounds default to `latest`
+    /// according to the `eth_getLogs` spec. Returns `None` for tags that cannot be resolved
+    /// to a block number without additional queries (e.g., `finalized`).
+    fn parse(value: Option<&Value>) -> Option<Self> {
+        let Some(value) = value else {
+            return Some(Self::Latest);
+        };
+        match value.as_str()? {
+            "earliest" => Some(Self::Number(0)),
+            "latest" | "pending" => Some(Self::Latest),
+            hex => {
+                let hex = hex.strip_prefix("0x")?;
+                u64::from_str_radix(hex, 16).ok().map(Self::Number)
+            }
+        }
+    }
+}
+
+/// `eth_getLogs` filter in the JSON-RPC representation, which allows changing the queried block range.
+#[derive(Debug)]
+pub(super) struct SplittableFilter(Value);
+
+impl SplittableFilter {
+    pub fn new(filter: &Filter) -> Self {
+        Self(serde_json::to_value(filter).expect("failed serializing logs filter"))
+    }
+
+    pub fn as_json(&self) -> &Value {
+        &self.0
+    }
+
+    /// Returns the block range of the filter, or `None` if the range cannot be split (e.g.,
+    /// if the filter queries a single block by its hash).
+    pub fn range(&self) -> Option<(RangeBound, RangeBound)> {
+        if self.0.get("blockHash").is_some() {
+            return None;
+        }
+        let from = RangeBound::parse(self.0.get("fromBlock"))?;
+        let to = RangeBound::parse(self.0.get("toBlock"))?;
+        Some((from, to))
+    }
+
+    pub fn with_range(&self, from: u64, to: u64) -> Value {
+        let mut filter = self.0.clone();
+        filter["fromBlock"] = format!("{from:#x}").into();
+        filter["toBlock"] = format!("{to:#x}").into();
+        filter
+    }
+}
+
+/// Merges logs returned for sub-ranges. Logs are ordered by block number and log index;
+/// logs returned for multiple sub-ranges are deduplicated.
+pub(super) fn merge_logs(chunks: Vec<Vec<Log>>) -> Vec<Log> {
+    let mut seen_logs = HashSet::new();
+    let mut logs: Vec<_> = chunks
+        .into_iter()
+        .flatten()
+        .filter(|log| match (log.block_hash, log.log_index) {
+            (Some(block_hash), Some(log_index)) => seen_logs.insert((block_hash, log_index)),
+            // Logs from pending blocks cannot be identified reliably.
+            _ => true,
+        })
+        .collect();
+    // The sort is stable, so logs with the same block number and index (e.g., pending logs)
+    // retain their relative order.
+    logs.sort_by_key(|log| (log.block_number, log.log_index));
+    logs
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use zksync_types::{
+        web3::types::{BlockNumber, FilterBuilder},
+        H256,
+    };
+
+    use super::*;
+
+    fn mock_log(block_number: u64, log_index: u64) -> Log {
+        Log {
+            address: Default::default(),
+            topics: vec![],
+            data: Default::default(),
+            block_hash: Some(H256::from_low_u64_be(block_number)),
+            block_number: Some(block_number.into()),
+            transaction_hash: Some(H256::repeat_byte(1)),
+            transaction_index: Some(0.into()),
+            log_index: Some(log_index.into()),
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        }
+    }
+
+    #[test]
+    fn parsing_filter_range() {
+        let filter = FilterBuilder::default()
+            .from_block(BlockNumber::Number(100.into()))
+            .to_block(BlockNumber::Latest)
+            .build();
+        let filter = SplittableFilter::new(&filter);
+        assert_eq!(
+            filter.range(),
+            Some((RangeBound::Number(100), RangeBound::Latest))
+        );
+        let json = filter.with_range(100, 200);
+        assert_eq!(json["fromBlock"], "0x64");
+        assert_eq!(json["toBlock"], "0xc8");
+
+        let filter = FilterBuilder::default()
+            .from_block(BlockNumber::Earliest)
+            .build();
+        let filter = SplittableFilter::new(&filter);
+        assert_eq!(
+            filter.range(),
+            Some((RangeBound::Number(0), RangeBound::Latest))
+        );
+
+        let filter = FilterBuilder::default()
+            .from_block(BlockNumber::Finalized)
+            .build();
+        assert_eq!(SplittableFilter::new(&filter).range(), None);
+
+        let filter = FilterBuilder::default()
+            .block_hash(H256::repeat_byte(1))
+            .build();
+        assert_eq!(SplittableFilter::new(&filter).range(), None);
+        assert_eq!(RangeBound::parse(Some(&json!("0xzz"))), None);
+    }
+
+    #[test]
+    fn merging_logs() {
+        let chunks = vec![
+            vec![mock_log(3, 1), mock_log(1, 0), mock_log(3, 0)],
+            vec![mock_log(3, 1), mock_log(4, 2)],
+            vec![],
+            vec![mock_log(2, 0), mock_log(4, 2), mock_log(5, 0)],
+        ];
+        let logs = merge_logs(chunks);
+        let log_ids: Vec<_> = logs
+            .iter()
+            .map(|log| {
+                let block_number = log.block_number.unwrap().as_u64();
+                (block_number, log.log_index.unwrap().as_u64())
+            })
+            .collect();
+        assert_eq!(log_ids, [(1, 0), (2, 0), (3, 0), (3, 1), (4, 2), (5, 0)]);
+    }
+}
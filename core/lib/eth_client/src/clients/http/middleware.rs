@@ -0,0 +1,435 @@
+//! Middleware observing and modifying JSON-RPC requests sent by HTTP clients.
+
+use std::{fmt, time::Duration};
+
+use jsonrpc_core::{Call, Params, Request, Value};
+use rand::Rng;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    StatusCode,
+};
+
+/// Header with a unique ID of a request set by [`TracingMiddleware`].
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+/// Header with the name of the current `tracing` span set by [`TracingMiddleware`].
+pub const SPAN_HEADER: &str = "x-tracing-span";
+
+/// Max length of request / response payloads logged by [`PayloadLogger`]; longer payloads are truncated.
+const MAX_LOGGED_PAYLOAD_LEN: usize = 4_096;
+/// Placeholder for redacted secrets.
+const REDACTED: &str = "[REDACTED]";
+/// Substrings of (lowercase) header names and JSON object keys considered secret.
+const SECRET_NAMES: &[&str] = &[
+    "authorization",
+    "cookie",
+    "api-key",
+    "apikey",
+    "api_key",
+    "password",
+    "passphrase",
+    "privatekey",
+    "private_key",
+    "secret",
+    "token",
+];
+
+/// JSON-RPC request about to be sent by an HTTP client.
+#[derive(Debug, Clone)]
+pub struct RpcRequest {
+    /// Request payload. Calls in [`RpcBatch`](super::RpcBatch)es are sent as a batch request.
+    pub payload: Request,
+    /// HTTP headers sent with this request in addition to the headers configured
+    /// in [`HttpOptions`](super::HttpOptions).
+    pub headers: HeaderMap,
+}
+
+impl RpcRequest {
+    /// Returns names of all methods called in this request.
+    pub fn methods(&self) -> Vec<&str> {
+        let calls = match &self.payload {
+            Request::Single(call) => std::slice::from_ref(call),
+            Request::Batch(calls) => calls.as_slice(),
+        };
+        calls
+            .iter()
+            .filter_map(|call| match call {
+                Call::MethodCall(call) => Some(call.method.as_str()),
+                Call::Notification(notification) => Some(notification.method.as_str()),
+                Call::Invalid { .. } => None,
+            })
+            .collect()
+    }
+}
+
+/// Response to an [`RpcRequest`], or an error sending the request.
+#[derive(Debug)]
+pub struct RpcResponse {
+    /// Request as sent to the node, i.e. after all [`RpcMiddleware::on_request()`] hooks were applied.
+    pub request: RpcRequest,
+    /// HTTP status of the response. `None` if the request has failed before receiving a response.
+    pub status: Option<StatusCode>,
+    /// Raw response body. Empty if the response wasn't received.
+    pub body: Vec<u8>,
+    /// Error sending the request or receiving the response, if any.
+    pub error: Option<String>,
+}
+
+/// Hooks invoked for each HTTP request sent by [`QueryClient`](super::QueryClient), including batch requests
+/// and retries. Hooks are invoked synchronously and thus should be fast.
+///
+/// If several middleware are registered, `on_request()` hooks are invoked in the order of registration,
+/// and `on_response()` hooks in the reverse order.
+pub trait RpcMiddleware: fmt::Debug + Send + Sync + 'static {
+    /// Invoked before sending a request. May modify the request payload or add HTTP headers.
+    fn on_request(&self, _request: &mut RpcRequest) {}
+
+    /// Invoked after receiving a response, or after the request has failed. `latency` is the time
+    /// between sending the request and receiving the response body.
+    fn on_response(&self, _response: &RpcResponse, _latency: Duration) {}
+}
+
+/// Middleware correlating requests with the `tracing` context. Sets a unique request ID header
+/// ([`REQUEST_ID_HEADER`]) and the name of the current span ([`SPAN_HEADER`]) for each request,
+/// and logs completed requests with their IDs on the `DEBUG` level.
+#[derive(Debug, Default)]
+pub struct TracingMiddleware(());
+
+impl TracingMiddleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RpcMiddleware for TracingMiddleware {
+    fn on_request(&self, request: &mut RpcRequest) {
+        let request_id = format!("{:032x}", rand::thread_rng().gen::<u128>());
+        let request_id = HeaderValue::try_from(request_id).expect("hex string is a valid header");
+        request
+            .headers
+            .insert(HeaderName::from_static(REQUEST_ID_HEADER), request_id);
+
+        let span = tracing::Span::current();
+        let span_name = span.metadata().map(|metadata| metadata.name());
+        if let Some(Ok(span_name)) = span_name.map(HeaderValue::try_from) {
+            request
+                .headers
+                .insert(HeaderName::from_static(SPAN_HEADER), span_name);
+        }
+    }
+
+    fn on_response(&self, response: &RpcResponse, latency: Duration) {
+        let request_id = response.request.headers.get(REQUEST_ID_HEADER);
+        let request_id = request_id.and_then(|id| id.to_str().ok()).unwrap_or("?");
+        tracing::debug!(
+            request_id,
+            methods = ?response.request.methods(),
+            status = ?response.status,
+            error = response.error.as_deref(),
+            "L1 RPC request completed in {latency:?}"
+        );
+    }
+}
+
+/// Middleware logging raw payloads of a sample of requests and their responses on the `INFO` level.
+/// Values of headers and JSON object fields looking like secrets (e.g., `Authorization` headers
+/// or `password` fields) and params of `personal_*` methods are redacted. Long payloads are truncated.
+#[derive(Debug)]
+pub struct PayloadLogger {
+    sample_rate: f64,
+}
+
+impl PayloadLogger {
+    /// Creates a logger for the specified fraction of requests (from 0 to 1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sample_rate` is not in the `[0, 1]` range.
+    pub fn new(sample_rate: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&sample_rate),
+            "sample rate must be in [0, 1], got {sample_rate}"
+        );
+        Self { sample_rate }
+    }
+
+    fn should_log(&self) -> bool {
+        self.sample_rate > 0.0 && rand::thread_rng().gen_bool(self.sample_rate)
+    }
+
+    fn redacted_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if value.is_sensitive() || is_secret_name(name.as_str()) {
+                    REDACTED.to_owned()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                (name.as_str().to_owned(), value)
+            })
+            .collect()
+    }
+
+    fn redacted_payload(request: &Request) -> String {
+        let mut payload = serde_json::to_value(request).expect("requests are serializable");
+        let calls = match &mut payload {
+            Value::Array(calls) => calls.iter_mut().collect(),
+            call => vec![call],
+        };
+        for call in calls {
+            let is_personal = call["method"]
+                .as_str()
+                .map_or(false, |method| method.starts_with("personal_"));
+            if is_personal {
+                if let Some(params) = call.get_mut("params") {
+                    *params = Value::String(REDACTED.to_owned());
+                }
+            }
+        }
+        redact_secrets(&mut payload);
+        truncate(payload.to_string())
+    }
+
+    fn redacted_body(body: &[u8]) -> String {
+        match serde_json::from_slice::<Value>(body) {
+            Ok(mut body) => {
+                redact_secrets(&mut body);
+                truncate(body.to_string())
+            }
+            Err(_) => truncate(String::from_utf8_lossy(body).into_owned()),
+        }
+    }
+}
+
+impl RpcMiddleware for PayloadLogger {
+    fn on_response(&self, response: &RpcResponse, latency: Duration) {
+        if !self.should_log() {
+            return;
+        }
+        tracing::info!(
+            headers = ?Self::redacted_headers(&response.request.headers),
+            request = Self::redacted_payload(&response.request.payload),
+            status = ?response.status,
+            response = Self::redacted_body(&response.body),
+            error = response.error.as_deref(),
+            "Sampled L1 RPC request completed in {latency:?}"
+        );
+    }
+}
+
+fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_NAMES.iter().any(|secret| name.contains(secret))
+}
+
+/// Recursively redacts values of JSON object fields with secret-looking names.
+fn redact_secrets(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                if is_secret_name(key) {
+                    *value = Value::String(REDACTED.to_owned());
+                } else {
+                    redact_secrets(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_secrets),
+        _ => { /* primitive values are left as is */ }
+    }
+}
+
+fn truncate(mut payload: String) -> String {
+    if payload.len() > MAX_LOGGED_PAYLOAD_LEN {
+        let mut end = MAX_LOGGED_PAYLOAD_LEN;
+        while !payload.is_char_boundary(end) {
+            end -= 1;
+        }
+        payload.truncate(end);
+        payload.push_str("...");
+    }
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use serde_json::json;
+    use zksync_types::{web3::helpers, U64};
+
+    use super::*;
+    use crate::{
+        clients::http::{mock_server::MockRpcServer, QueryClient},
+        EthInterface,
+    };
+
+    /// Middleware recording invoked hooks and marking requests with a header.
+    #[derive(Debug)]
+    struct RecordingMiddleware {
+        name: &'static str,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl RpcMiddleware for RecordingMiddleware {
+        fn on_request(&self, request: &mut RpcRequest) {
+            let methods = request.methods().join(",");
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("{}: request {methods}", self.name));
+            let header = HeaderName::from_static(self.name);
+            request
+                .headers
+                .insert(header, HeaderValue::from_static("1"));
+        }
+
+        fn on_response(&self, response: &RpcResponse, _latency: Duration) {
+            assert!(response.request.headers.contains_key(self.name));
+            assert_eq!(response.status, Some(StatusCode::OK));
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("{}: response", self.name));
+        }
+    }
+
+    /// Middleware redirecting `eth_blockNumber` calls to `eth_chainId`.
+    #[derive(Debug)]
+    struct RewritingMiddleware;
+
+    impl RpcMiddleware for RewritingMiddleware {
+        fn on_request(&self, request: &mut RpcRequest) {
+            if let Request::Single(Call::MethodCall(call)) = &mut request.payload {
+                if call.method == "eth_blockNumber" {
+                    call.method = "eth_chainId".to_owned();
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn middleware_ordering_and_mutation() {
+        let server = MockRpcServer::spawn(|method, _| match method {
+            "eth_blockNumber" => Ok(json!("0x10")),
+            "eth_chainId" => Ok(json!("0x9")),
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await;
+        let events = Arc::<Mutex<Vec<_>>>::default();
+        let client = QueryClient::new(&server.url())
+            .unwrap()
+            .with_middleware(RecordingMiddleware {
+                name: "x-first",
+                events: events.clone(),
+            })
+            .with_middleware(RecordingMiddleware {
+                name: "x-second",
+                events: events.clone(),
+            })
+            .with_middleware(TracingMiddleware::new());
+
+        let block_number = client.block_number("test").await.unwrap();
+        assert_eq!(block_number, 16.into());
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "x-first: request eth_blockNumber",
+                "x-second: request eth_blockNumber",
+                "x-second: response",
+                "x-first: response",
+            ]
+        );
+
+        events.lock().unwrap().clear();
+        let mut batch = client.batch();
+        let block_number = batch.block_number();
+        let chain_id = batch.add::<U64>("eth_chainId", vec![]);
+        let mut response = batch.execute("test").await.unwrap();
+        assert_eq!(response.take(block_number).unwrap(), 16.into());
+        assert_eq!(response.take(chain_id).unwrap(), 9.into());
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "x-first: request eth_blockNumber,eth_chainId",
+                "x-second: request eth_blockNumber,eth_chainId",
+                "x-second: response",
+                "x-first: response",
+            ]
+        );
+
+        let all_headers = server.request_headers();
+        assert_eq!(all_headers.len(), 2);
+        for headers in &all_headers {
+            assert_eq!(headers["x-first"], "1");
+            assert_eq!(headers["x-second"], "1");
+            assert_eq!(headers[REQUEST_ID_HEADER].len(), 32);
+        }
+        assert_ne!(
+            all_headers[0][REQUEST_ID_HEADER],
+            all_headers[1][REQUEST_ID_HEADER]
+        );
+
+        // Mutations of the payload are applied.
+        let client = QueryClient::new(&server.url())
+            .unwrap()
+            .with_middleware(RewritingMiddleware);
+        let block_number = client.block_number("test").await.unwrap();
+        assert_eq!(block_number, 9.into());
+    }
+
+    #[test]
+    fn redacting_secrets() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static("key-value"));
+        let mut auth = HeaderValue::from_static("Basic dXNlcjpwYXNz");
+        auth.set_sensitive(true);
+        headers.insert("x-custom-auth", auth);
+        headers.insert("x-request-id", HeaderValue::from_static("123"));
+        let headers = PayloadLogger::redacted_headers(&headers);
+        let headers: HashMap<_, _> = headers.into_iter().collect();
+        assert_eq!(headers["x-api-key"], REDACTED);
+        assert_eq!(headers["x-custom-auth"], REDACTED);
+        assert_eq!(headers["x-request-id"], "123");
+
+        let call = helpers::build_request(
+            1,
+            "personal_unlockAccount",
+            vec![json!("0x01"), json!("password")],
+        );
+        let payload = PayloadLogger::redacted_payload(&Request::Single(call));
+        assert!(!payload.contains("password"), "{payload}");
+        assert!(payload.contains("personal_unlockAccount"), "{payload}");
+
+        let calls = vec![
+            helpers::build_request(1, "eth_blockNumber", vec![]),
+            helpers::build_request(
+                2,
+                "custom_call",
+                vec![json!({ "apiKey": "key", "to": "0x01" })],
+            ),
+        ];
+        let payload = PayloadLogger::redacted_payload(&Request::Batch(calls));
+        assert!(!payload.contains("\"key\""), "{payload}");
+        assert!(payload.contains("0x01"), "{payload}");
+        assert!(payload.contains("eth_blockNumber"), "{payload}");
+
+        let body = br#"{"jsonrpc":"2.0","id":1,"result":{"token":"secret-token","value":"0x1"}}"#;
+        let body = PayloadLogger::redacted_body(body);
+        assert!(!body.contains("secret-token"), "{body}");
+        assert!(body.contains("0x1"), "{body}");
+
+        let long_body = vec![b'a'; 2 * MAX_LOGGED_PAYLOAD_LEN];
+        let body = PayloadLogger::redacted_body(&long_body);
+        assert_eq!(body.len(), MAX_LOGGED_PAYLOAD_LEN + 3);
+    }
+
+    #[test]
+    fn sampling_payloads() {
+        assert!(!PayloadLogger::new(0.0).should_log());
+        assert!(PayloadLogger::new(1.0).should_log());
+    }
+}
@@ -0,0 +1,174 @@
+//! Minimal JSON-RPC server over HTTP used to test HTTP clients.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+type Handler = dyn Fn(&str, &Value) -> Result<Value, jsonrpc_core::Error> + Send + Sync;
+/// HTTP headers of a request keyed by the lowercase header name.
+pub(crate) type RequestHeaders = HashMap<String, String>;
+
+/// JSON-RPC server responding to each call using the provided handler. Supports batch requests.
+#[derive(Debug)]
+pub(crate) struct MockRpcServer {
+    local_addr: SocketAddr,
+    http_request_count: Arc<AtomicUsize>,
+    request_headers: Arc<Mutex<Vec<RequestHeaders>>>,
+    /// Raw HTTP status lines and headers of responses to return instead of invoking the handler.
+    queued_responses: Arc<Mutex<VecDeque<String>>>,
+    server_task: JoinHandle<()>,
+}
+
+impl Drop for MockRpcServer {
+    fn drop(&mut self) {
+        self.server_task.abort();
+    }
+}
+
+impl MockRpcServer {
+    /// Spawns a server on a random local port. The handler receives the method name and params of each call.
+    pub async fn spawn(
+        handler: impl Fn(&str, &Value) -> Result<Value, jsonrpc_core::Error> + Send + Sync + 'static,
+    ) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let http_request_count = Arc::new(AtomicUsize::new(0));
+        let request_headers = Arc::<Mutex<_>>::default();
+        let queued_responses = Arc::<Mutex<_>>::default();
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let request_count = http_request_count.clone();
+        let headers = Arc::clone(&request_headers);
+        let responses = Arc::clone(&queued_responses);
+        let server_task = tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let handler = handler.clone();
+                let request_count = request_count.clone();
+                let headers = headers.clone();
+                let responses = responses.clone();
+                tokio::spawn(async move {
+                    // Connection errors are irrelevant for tests; the client will observe them.
+                    Self::serve_connection(stream, &*handler, &request_count, &headers, &responses)
+                        .await
+                        .ok();
+                });
+            }
+        });
+
+        Self {
+            local_addr,
+            http_request_count,
+            request_headers,
+            queued_responses,
+            server_task,
+        }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.local_addr)
+    }
+
+    /// Returns the number of HTTP requests served so far.
+    pub fn http_request_count(&self) -> usize {
+        self.http_request_count.load(Ordering::SeqCst)
+    }
+
+    /// Returns headers of all HTTP requests served so far.
+    pub fn request_headers(&self) -> Vec<RequestHeaders> {
+        self.request_headers.lock().unwrap().clone()
+    }
+
+    /// Makes the server respond to the next HTTP request with the specified status (e.g., `429 Too Many Requests`)
+    /// and headers, and an empty body, instead of invoking the handler. If called multiple times, responses
+    /// are returned in the order of calls.
+    pub fn respond_with_status(&self, status: &str, headers: &[(&str, &str)]) {
+        let mut response = format!("HTTP/1.1 {status}\r\n");
+        for (name, value) in headers {
+            response += &format!("{name}: {value}\r\n");
+        }
+        response += "Content-Length: 0\r\n\r\n";
+        self.queued_responses.lock().unwrap().push_back(response);
+    }
+
+    async fn serve_connection(
+        stream: TcpStream,
+        handler: &Handler,
+        request_count: &AtomicUsize,
+        all_headers: &Mutex<Vec<RequestHeaders>>,
+        queued_responses: &Mutex<VecDeque<String>>,
+    ) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        loop {
+            // Request line; an empty read means that the client has closed the connection.
+            line.clear();
+            if reader.read_line(&mut line).await? == 0 {
+                return Ok(());
+            }
+
+            let mut headers = RequestHeaders::new();
+            loop {
+                line.clear();
+                reader.read_line(&mut line).await?;
+                let header = line.trim_end();
+                if header.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    headers.insert(name.to_ascii_lowercase(), value.trim().to_owned());
+                }
+            }
+            let content_length = headers
+                .get("content-length")
+                .map_or(0, |len| len.parse().unwrap());
+            all_headers.lock().unwrap().push(headers);
+            let mut body = vec![0_u8; content_length];
+            reader.read_exact(&mut body).await?;
+            request_count.fetch_add(1, Ordering::SeqCst);
+
+            let queued_response = queued_responses.lock().unwrap().pop_front();
+            if let Some(response) = queued_response {
+                reader.get_mut().write_all(response.as_bytes()).await?;
+                continue;
+            }
+
+            let request: Value = serde_json::from_slice(&body).unwrap();
+            let response = match request {
+                Value::Array(calls) => Value::Array(
+                    calls
+                        .iter()
+                        .map(|call| Self::respond(call, handler))
+                        .collect(),
+                ),
+                call => Self::respond(&call, handler),
+            };
+            let response = serde_json::to_string(&response).unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{response}",
+                response.len()
+            );
+            reader.get_mut().write_all(response.as_bytes()).await?;
+        }
+    }
+
+    fn respond(call: &Value, handler: &Handler) -> Value {
+        let method = call["method"].as_str().unwrap();
+        match handler(method, &call["params"]) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": call["id"], "result": result }),
+            Err(err) => json!({ "jsonrpc": "2.0", "id": call["id"], "error": err }),
+        }
+    }
+}
@@ -1,16 +1,56 @@
 use std::time::Duration;
 
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, LabeledFamily, Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    Metrics,
 };
 
 pub use self::{
+    batch::{BatchCall, BatchResponse, OperatorSnapshot, RpcBatch},
+    beacon::{BeaconBlobSidecar, BeaconBlockId, BeaconClient},
+    full_block::{BlockTransaction, FullBlock},
+    middleware::{
+        PayloadLogger, RpcMiddleware, RpcRequest, RpcResponse, TracingMiddleware,
+        REQUEST_ID_HEADER, SPAN_HEADER,
+    },
+    multicall::{Multicall, MulticallResult, MULTICALL3_ADDRESS},
+    options::{BearerToken, HttpOptions},
     query::QueryClient,
-    signing::{PKSigningClient, SigningClient},
+    rate_limit::RateLimit,
+    receipt_cache::ReceiptCacheConfig,
+    retry::RetryPolicy,
+    signing::{
+        JsonRpcSigningClient, PKSigningClient, SigningClient, TxFeeModel, BLOB_OPERATOR_SIGNER,
+        DEFAULT_SIGNER,
+    },
+    timeout::TimeoutPolicy,
+    trace::{CallFrame, StructLog, StructLogTrace, TracerConfig, TransactionTrace},
+    transport::HttpTransport,
+    txpool::{TxPoolContent, TxPoolContentFrom, TxPoolTransaction},
 };
+use crate::types::{Error, ErrorKind};
 
+mod batch;
+mod beacon;
+mod concurrency;
+mod fee_cache;
+mod full_block;
+mod logs;
+mod middleware;
+#[cfg(test)]
+pub(crate) mod mock_server;
+mod multicall;
+mod nonce;
+mod options;
 mod query;
+mod rate_limit;
+mod receipt_cache;
+mod retry;
 mod signing;
+mod timeout;
+mod trace;
+mod transport;
+mod txpool;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "method", rename_all = "snake_case")]
@@ -21,6 +61,7 @@ enum Method {
     SendRawTx,
     EstimateGas,
     BaseFeeHistory,
+    FeeHistory,
     #[metrics(name = "get_pending_block_base_fee_per_gas")]
     PendingBlockBaseFee,
     GetTxStatus,
@@ -31,9 +72,150 @@ enum Method {
     EthBalance,
     Logs,
     Block,
+    BlockWithTxs,
+    GetProof,
+    BlockBlobGas,
+    Batch,
+    ChainId,
     #[metrics(name = "sign_prepared_tx_for_addr")]
     SignPreparedTx,
+    #[metrics(name = "sign_prepared_blob_tx_for_addr")]
+    SignPreparedBlobTx,
     Allowance,
+    TraceTransaction,
+    BeaconBlobSidecars,
+    TxPoolContent,
+    TxPoolContentFrom,
+    MaxPriorityFeePerGas,
+    EstimateGasWithBuffer,
+    SimulateCall,
+    Multicall,
+}
+
+impl Method {
+    /// Returns the name of the main JSON-RPC method used to implement this method. Used to look up timeouts.
+    fn rpc_name(self) -> &'static str {
+        match self {
+            Self::NonceAtForAccount => "eth_getTransactionCount",
+            Self::BlockNumber => "eth_blockNumber",
+            Self::GetGasPrice => "eth_gasPrice",
+            Self::SendRawTx => "eth_sendRawTransaction",
+            Self::EstimateGas => "linea_estimateGas",
+            Self::BaseFeeHistory | Self::FeeHistory => "eth_feeHistory",
+            Self::PendingBlockBaseFee | Self::Block | Self::BlockWithTxs | Self::BlockBlobGas => {
+                "eth_getBlockByNumber"
+            }
+            Self::GetTxStatus | Self::TxReceipt => "eth_getTransactionReceipt",
+            Self::GetTx | Self::FailureReason => "eth_getTransactionByHash",
+            Self::CallContractFunction | Self::Allowance => "eth_call",
+            Self::EthBalance => "eth_getBalance",
+            Self::GetProof => "eth_getProof",
+            Self::Logs => "eth_getLogs",
+            Self::ChainId => "eth_chainId",
+            Self::TraceTransaction => "debug_traceTransaction",
+            Self::BeaconBlobSidecars => "blob_sidecars",
+            Self::TxPoolContent => "txpool_content",
+            Self::TxPoolContentFrom => "txpool_contentFrom",
+            Self::MaxPriorityFeePerGas => "eth_maxPriorityFeePerGas",
+            Self::EstimateGasWithBuffer => "eth_estimateGas",
+            Self::SimulateCall | Self::Multicall => "eth_call",
+            Self::SignPreparedTx | Self::SignPreparedBlobTx => "eth_signTransaction",
+            // Batches can contain arbitrary methods, so they only use the default timeout.
+            Self::Batch => "batch",
+        }
+    }
+}
+
+/// Outcome of a single RPC request. Errors are classified coarsely to keep label cardinality bounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+enum RequestStatus {
+    Success,
+    Timeout,
+    /// Network errors and unsuccessful HTTP responses other than timeouts.
+    Transport,
+    /// Error returned by the node, e.g., a revert or rate limiting.
+    RpcError,
+    /// Node response cannot be decoded.
+    Decode,
+    Other,
+}
+
+impl RequestStatus {
+    fn new(outcome: Result<(), ErrorKind>) -> Self {
+        match outcome {
+            Ok(()) => Self::Success,
+            Err(ErrorKind::Timeout) => Self::Timeout,
+            Err(ErrorKind::Connection | ErrorKind::Unavailable | ErrorKind::HttpStatus) => {
+                Self::Transport
+            }
+            Err(
+                ErrorKind::RateLimited
+                | ErrorKind::RpcInvalidRequest
+                | ErrorKind::RpcExecution
+                | ErrorKind::RpcServer,
+            ) => Self::RpcError,
+            Err(ErrorKind::Decode) => Self::Decode,
+            Err(ErrorKind::Other) => Self::Other,
+        }
+    }
+}
+
+/// Records the outcome and latency of a single RPC request (i.e., a single attempt if retries are enabled).
+fn report_request<T>(method: Method, latency: Duration, result: &Result<T, Error>) {
+    let outcome = result.as_ref().map(|_| ()).map_err(Error::kind);
+    report_request_outcome(method, latency, outcome);
+}
+
+fn report_request_outcome(method: Method, latency: Duration, outcome: Result<(), ErrorKind>) {
+    let status = RequestStatus::new(outcome);
+    COUNTERS.requests[&(method, status)].inc();
+    LATENCIES.request[&(method, status)].observe(latency);
+    if let Err(kind) = outcome {
+        COUNTERS.errors[&(method, kind)].inc();
+        if kind == ErrorKind::RateLimited {
+            COUNTERS.rate_limited[&method].inc();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+enum ReceiptCacheResult {
+    Hit,
+    Miss,
+    Invalidated,
+}
+
+/// Kind of a query cached by the fee cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "entry", rename_all = "snake_case")]
+enum FeeCacheEntry {
+    GasPrice,
+    FeeHistory,
+    MaxPriorityFeePerGas,
+    BlobGas,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+enum FeeCacheResult {
+    Hit,
+    Miss,
+    /// Cached value was found, but is older than TTL.
+    Expired,
+    Invalidated,
+}
+
+/// Result of updating the node URL of a client at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+enum EndpointUpdateResult {
+    Switched,
+    /// New node reports an unexpected chain ID.
+    ChainIdMismatch,
+    /// New URL is invalid or the chain ID cannot be fetched from the new node.
+    Failed,
 }
 
 #[derive(Debug, Metrics)]
@@ -42,6 +224,31 @@ struct ClientCounters {
     /// Number of calls for a specific Ethereum client method.
     #[metrics(labels = ["method", "component"])]
     call: LabeledFamily<(Method, &'static str), Counter, 2>,
+    /// Number of retries of transient errors for a specific Ethereum client method.
+    retries: Family<Method, Counter>,
+    /// Number of RPC requests for a specific method by their outcome. Unlike `call`, each retry
+    /// is counted separately.
+    #[metrics(labels = ["method", "status"])]
+    requests: LabeledFamily<(Method, RequestStatus), Counter, 2>,
+    /// Number of receipt cache lookups and invalidations of cached receipts caused by reorgs.
+    receipt_cache: Family<ReceiptCacheResult, Counter>,
+    /// Number of failed RPC requests for a specific method by the error kind. Like `requests`, each retry
+    /// is counted separately.
+    #[metrics(labels = ["method", "kind"])]
+    errors: LabeledFamily<(Method, ErrorKind), Counter, 2>,
+    /// Number of RPC requests rate-limited by the node, either with an HTTP 429 response or a JSON-RPC error.
+    rate_limited: Family<Method, Counter>,
+    /// Number of fee cache lookups by their result, and the number of values removed by invalidation.
+    #[metrics(labels = ["entry", "result"])]
+    fee_cache: LabeledFamily<(FeeCacheEntry, FeeCacheResult), Counter, 2>,
+    /// Number of times the block range of an `eth_getLogs` query was split in half because the query
+    /// exceeded node limits.
+    logs_range_splits: Counter,
+    /// Number of times gas was re-estimated with a larger buffer because the call simulated
+    /// with the buffered gas limit ran out of gas.
+    gas_estimation_retries: Counter,
+    /// Number of attempts to switch the node URL at runtime by their result.
+    endpoint_updates: Family<EndpointUpdateResult, Counter>,
 }
 
 #[vise::register]
@@ -53,7 +260,32 @@ struct ClientLatencies {
     /// Latency of interacting with the Ethereum client.
     #[metrics(buckets = Buckets::LATENCIES)]
     direct: Family<Method, Histogram<Duration>>,
+    /// Latency of a single RPC request by its outcome.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["method", "status"])]
+    request: LabeledFamily<(Method, RequestStatus), Histogram<Duration>, 2>,
+    /// Time spent waiting for the client-side rate limiter before sending a request.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    rate_limit_wait: Family<Method, Histogram<Duration>>,
+    /// Time spent waiting for a permit from the client-side limit on in-flight requests.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    concurrency_limit_wait: Family<Method, Histogram<Duration>>,
+    /// Delay before retrying a request rate-limited by the node, including delays requested via `Retry-After`.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    rate_limited_wait: Family<Method, Histogram<Duration>>,
+    /// Age of values served from the fee cache, measured from the time the value was requested from the node.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    fee_cache_staleness: Family<FeeCacheEntry, Histogram<Duration>>,
 }
 
 #[vise::register]
 static LATENCIES: vise::Global<ClientLatencies> = vise::Global::new();
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "eth_client")]
+struct ClientGauges {
+    /// Number of in-flight requests holding a permit from the client-side concurrency limit.
+    in_flight_requests: Gauge<u64>,
+}
+
+#[vise::register]
+static GAUGES: vise::Global<ClientGauges> = vise::Global::new();
@@ -0,0 +1,373 @@
+//! Aggregating contract view calls into a single `eth_call` using the [Multicall3] contract.
+//!
+//! [Multicall3]: https://github.com/mds1/multicall
+
+use futures::future;
+use zksync_types::web3::{
+    self,
+    ethabi::{self, ParamType, Token},
+    types::{Address, BlockId, Bytes, CallRequest, H160},
+    Transport,
+};
+
+use super::{query::QueryClient, Method, COUNTERS, LATENCIES};
+use crate::types::Error;
+
+/// Address of the Multicall3 contract. The contract is deployed at this address on most EVM chains.
+pub const MULTICALL3_ADDRESS: Address = H160([
+    0xca, 0x11, 0xbd, 0xe0, 0x59, 0x77, 0xb3, 0x63, 0x11, 0x67, 0x02, 0x88, 0x62, 0xbe, 0x2a, 0x17,
+    0x39, 0x76, 0xca, 0x11,
+]);
+
+/// Call aggregated by [`Multicall`].
+#[derive(Debug, Clone, PartialEq)]
+struct Call3 {
+    target: Address,
+    allow_failure: bool,
+    call_data: Vec<u8>,
+}
+
+fn aggregate3_params() -> Vec<ParamType> {
+    let call3 = ParamType::Tuple(vec![ParamType::Address, ParamType::Bool, ParamType::Bytes]);
+    vec![ParamType::Array(Box::new(call3))]
+}
+
+fn aggregate3_output() -> Vec<ParamType> {
+    let result = ParamType::Tuple(vec![ParamType::Bool, ParamType::Bytes]);
+    vec![ParamType::Array(Box::new(result))]
+}
+
+/// Encodes calldata for `aggregate3((address,bool,bytes)[])`.
+fn encode_aggregate3(calls: &[Call3]) -> Vec<u8> {
+    let calls = calls
+        .iter()
+        .map(|call| {
+            Token::Tuple(vec![
+                Token::Address(call.target),
+                Token::Bool(call.allow_failure),
+                Token::Bytes(call.call_data.clone()),
+            ])
+        })
+        .collect();
+    let selector = ethabi::short_signature("aggregate3", &aggregate3_params());
+    let mut data = selector.to_vec();
+    data.extend_from_slice(&ethabi::encode(&[Token::Array(calls)]));
+    data
+}
+
+/// Decodes the output of `aggregate3()`, checking that it contains a result for each of `call_count` calls.
+fn decode_aggregate3(output: &[u8], call_count: usize) -> Result<Vec<MulticallResult>, Error> {
+    let invalid_output = || Error::Decode(ethabi::Error::InvalidData);
+    let mut tokens = ethabi::decode(&aggregate3_output(), output)?;
+    let Some(Token::Array(results)) = tokens.pop() else {
+        return Err(invalid_output());
+    };
+    if results.len() != call_count {
+        let message = format!(
+            "Multicall3 returned {} results for {call_count} calls",
+            results.len()
+        );
+        return Err(web3::Error::InvalidResponse(message).into());
+    }
+
+    results
+        .into_iter()
+        .map(|result| match result {
+            Token::Tuple(fields) => match fields.as_slice() {
+                [Token::Bool(success), Token::Bytes(return_data)] => Ok(MulticallResult {
+                    success: *success,
+                    return_data: return_data.clone(),
+                }),
+                _ => Err(invalid_output()),
+            },
+            _ => Err(invalid_output()),
+        })
+        .collect()
+}
+
+/// Result of a single call aggregated by [`Multicall`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MulticallResult {
+    /// Whether the call has succeeded. Can be `false` only for calls allowing failure.
+    pub success: bool,
+    /// Data returned by the call, or revert data if the call has failed.
+    pub return_data: Vec<u8>,
+}
+
+/// Batch of contract calls executed in a single `eth_call` using the Multicall3 `aggregate3()` method.
+/// If the Multicall3 contract is not deployed on the chain, calls are executed individually.
+#[derive(Debug)]
+pub struct Multicall<'a, T: Transport> {
+    client: &'a QueryClient<T>,
+    address: Address,
+    block: Option<BlockId>,
+    calls: Vec<Call3>,
+}
+
+impl<'a, T: Transport> Multicall<'a, T> {
+    fn new(client: &'a QueryClient<T>) -> Self {
+        Self {
+            client,
+            address: MULTICALL3_ADDRESS,
+            block: None,
+            calls: vec![],
+        }
+    }
+
+    /// Sets the address of the Multicall3 contract. By default, [`MULTICALL3_ADDRESS`] is used.
+    pub fn with_address(mut self, address: Address) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Sets the block to execute calls at. By default, calls are executed at the latest block.
+    pub fn with_block(mut self, block: BlockId) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Returns the number of calls in this batch.
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Adds a call to the batch and returns its index in the results returned by [`Self::execute()`].
+    /// If the call fails and `allow_failure` is not set, the whole batch fails.
+    pub fn add(&mut self, target: Address, call_data: Vec<u8>, allow_failure: bool) -> usize {
+        self.calls.push(Call3 {
+            target,
+            allow_failure,
+            call_data,
+        });
+        self.calls.len() - 1
+    }
+
+    /// Executes all calls in the batch and returns their results in the order the calls were added.
+    pub async fn execute(self, component: &'static str) -> Result<Vec<MulticallResult>, Error> {
+        if self.calls.is_empty() {
+            return Ok(vec![]);
+        }
+
+        COUNTERS.call[&(Method::Multicall, component)].inc();
+        let latency = LATENCIES.direct[&Method::Multicall].start();
+        let request = CallRequest {
+            to: Some(self.address),
+            data: Some(encode_aggregate3(&self.calls).into()),
+            ..CallRequest::default()
+        };
+        let output = self
+            .client
+            .retry(Method::Multicall, || {
+                self.client.web3.eth().call(request.clone(), self.block)
+            })
+            .await?;
+        latency.observe();
+
+        // Calls to addresses without code succeed with empty output.
+        if output.0.is_empty() {
+            tracing::debug!(
+                "Multicall3 contract is not deployed at {:?}; executing {} calls individually",
+                self.address,
+                self.calls.len()
+            );
+            return self.execute_individually(component).await;
+        }
+        decode_aggregate3(&output.0, self.calls.len())
+    }
+
+    async fn execute_individually(
+        &self,
+        component: &'static str,
+    ) -> Result<Vec<MulticallResult>, Error> {
+        let calls = self.calls.iter().map(|call| async move {
+            COUNTERS.call[&(Method::CallContractFunction, component)].inc();
+            let request = CallRequest {
+                to: Some(call.target),
+                data: Some(call.call_data.clone().into()),
+                ..CallRequest::default()
+            };
+            let result = self
+                .client
+                .retry(Method::CallContractFunction, || {
+                    self.client.web3.eth().call(request.clone(), self.block)
+                })
+                .await;
+            match result {
+                Ok(output) => Ok(MulticallResult {
+                    success: true,
+                    return_data: output.0,
+                }),
+                Err(Error::EthereumGateway(web3::Error::Rpc(err))) if call.allow_failure => {
+                    let revert_data = err
+                        .data
+                        .and_then(|data| serde_json::from_value::<Bytes>(data).ok());
+                    Ok(MulticallResult {
+                        success: false,
+                        return_data: revert_data.map(|data| data.0).unwrap_or_default(),
+                    })
+                }
+                Err(err) => Err(err),
+            }
+        });
+        future::try_join_all(calls).await
+    }
+}
+
+impl<T: Transport> QueryClient<T> {
+    /// Creates an empty batch of contract calls executed using Multicall3.
+    pub fn multicall(&self) -> Multicall<'_, T> {
+        Multicall::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde::Deserialize;
+    use serde_json::json;
+    use zksync_types::web3::types::BlockNumber;
+
+    use super::*;
+    use crate::clients::http::mock_server::MockRpcServer;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct FixtureCall {
+        target: Address,
+        allow_failure: bool,
+        call_data: Bytes,
+    }
+
+    /// `aggregate3()` call to Ethereum mainnet querying `decimals()` and `symbol()` of USDC,
+    /// and an unknown method allowed to fail.
+    #[derive(Debug, Deserialize)]
+    struct Fixture {
+        calls: Vec<FixtureCall>,
+        request: Bytes,
+        response: Bytes,
+    }
+
+    impl Fixture {
+        fn load() -> Self {
+            serde_json::from_str(include_str!("fixtures/multicall3_aggregate3.json")).unwrap()
+        }
+
+        fn add_calls<T: Transport>(&self, multicall: &mut Multicall<'_, T>) {
+            for call in &self.calls {
+                multicall.add(call.target, call.call_data.0.clone(), call.allow_failure);
+            }
+        }
+
+        fn calls(&self) -> Vec<Call3> {
+            self.calls
+                .iter()
+                .map(|call| Call3 {
+                    target: call.target,
+                    allow_failure: call.allow_failure,
+                    call_data: call.call_data.0.clone(),
+                })
+                .collect()
+        }
+    }
+
+    fn assert_fixture_results(results: &[MulticallResult]) {
+        assert_eq!(results.len(), 3);
+        assert!(results[0].success);
+        let decimals = ethabi::decode(&[ParamType::Uint(8)], &results[0].return_data).unwrap();
+        assert_eq!(decimals, [Token::Uint(6.into())]);
+        assert!(results[1].success);
+        let symbol = ethabi::decode(&[ParamType::String], &results[1].return_data).unwrap();
+        assert_eq!(symbol, [Token::String("USDC".to_owned())]);
+        assert!(!results[2].success);
+        assert!(results[2].return_data.is_empty());
+    }
+
+    #[test]
+    fn encoding_aggregate3_call() {
+        let fixture = Fixture::load();
+        let data = encode_aggregate3(&fixture.calls());
+        assert_eq!(data[..4], [0x82, 0xad, 0x56, 0xcb]);
+        assert_eq!(data, fixture.request.0);
+    }
+
+    #[test]
+    fn decoding_aggregate3_output() {
+        let fixture = Fixture::load();
+        let results = decode_aggregate3(&fixture.response.0, 3).unwrap();
+        assert_fixture_results(&results);
+
+        let err = decode_aggregate3(&fixture.response.0, 2).unwrap_err();
+        assert!(err.to_string().contains("3 results for 2 calls"), "{err}");
+        decode_aggregate3(&fixture.response.0[..100], 3).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn executing_multicall() {
+        let fixture = Fixture::load();
+        let request = json!(fixture.request);
+        let response = json!(fixture.response);
+        let server = MockRpcServer::spawn(move |method, params| {
+            assert_eq!(method, "eth_call");
+            assert_eq!(params[0]["to"], json!(MULTICALL3_ADDRESS));
+            assert_eq!(params[0]["data"], request);
+            assert_eq!(params[1], "0x12a05f2");
+            Ok(response.clone())
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        assert_eq!(client.multicall().execute("test").await.unwrap(), []);
+        let mut multicall = client
+            .multicall()
+            .with_block(BlockId::Number(BlockNumber::Number(19_531_250.into())));
+        fixture.add_calls(&mut multicall);
+        assert_eq!(multicall.len(), 3);
+        let results = multicall.execute("test").await.unwrap();
+        assert_fixture_results(&results);
+        assert_eq!(server.http_request_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn falling_back_to_individual_calls() {
+        let fixture = Fixture::load();
+        let multicall_address = Address::repeat_byte(0x11);
+        let server = MockRpcServer::spawn(move |method, params| {
+            assert_eq!(method, "eth_call");
+            let to: Address = serde_json::from_value(params[0]["to"].clone()).unwrap();
+            if to == multicall_address {
+                // No contract is deployed at the address.
+                return Ok(json!("0x"));
+            }
+            match params[0]["data"].as_str().unwrap() {
+                "0x313ce567" => Ok(json!(format!("0x{:064x}", 6))),
+                "0x95d89b41" => {
+                    let symbol = ethabi::encode(&[Token::String("USDC".to_owned())]);
+                    Ok(json!(Bytes(symbol)))
+                }
+                _ => Err(jsonrpc_core::Error {
+                    code: jsonrpc_core::ErrorCode::ServerError(3),
+                    message: "execution reverted".to_owned(),
+                    data: Some(json!("0x")),
+                }),
+            }
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let mut multicall = client.multicall().with_address(multicall_address);
+        fixture.add_calls(&mut multicall);
+        let results = multicall.execute("test").await.unwrap();
+        assert_fixture_results(&results);
+        assert_eq!(server.http_request_count(), 4);
+
+        // A failing call that doesn't allow failure fails the entire batch.
+        let mut multicall = client.multicall().with_address(multicall_address);
+        multicall.add(fixture.calls[0].target, vec![0xde, 0xad, 0xbe, 0xef], false);
+        let err = multicall.execute("test").await.unwrap_err();
+        assert_matches!(err, Error::EthereumGateway(web3::Error::Rpc(_)));
+    }
+}
@@ -0,0 +1,119 @@
+//! Local nonce tracking for [`SigningClient`].
+//!
+//! [`SigningClient`]: super::SigningClient
+
+use std::{
+    collections::{hash_map, HashMap},
+    future::Future,
+    sync::Mutex,
+};
+
+use tokio::sync::Mutex as AsyncMutex;
+use zksync_types::{Address, H256, U256};
+
+use crate::types::Error;
+
+/// Nonce of a signed transaction tracked until the transaction is submitted.
+#[derive(Debug, Clone, Copy)]
+struct SignedTxNonce {
+    sender: Address,
+    nonce: U256,
+    /// Whether the nonce was reserved by the manager (as opposed to being provided by the caller).
+    is_reserved: bool,
+}
+
+/// Caches the next nonce for each sender account, so that concurrent senders don't race
+/// on `eth_getTransactionCount`. Nonces are reserved when transactions are signed and released
+/// if a transaction fails to be signed or submitted. On errors indicating nonce issues, the nonce
+/// is resynchronized with the pending nonce of the account.
+#[derive(Debug, Default)]
+pub(super) struct NonceManager {
+    /// Next nonce for each sender. An async mutex is used so that the pending nonce is fetched
+    /// at most once if the cache is empty.
+    next_nonces: AsyncMutex<HashMap<Address, U256>>,
+    /// Signed transactions that weren't submitted yet, keyed by the hash of raw transaction bytes.
+    signed_txs: Mutex<HashMap<H256, SignedTxNonce>>,
+}
+
+impl NonceManager {
+    /// Reserves the next nonce for `sender`. If the nonce isn't cached, it's fetched using `fetch_pending_nonce`.
+    pub async fn reserve(
+        &self,
+        sender: Address,
+        fetch_pending_nonce: impl Future<Output = Result<U256, Error>>,
+    ) -> Result<U256, Error> {
+        let mut next_nonces = self.next_nonces.lock().await;
+        let next_nonce = match next_nonces.entry(sender) {
+            hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            hash_map::Entry::Vacant(entry) => {
+                let pending_nonce = fetch_pending_nonce.await?;
+                tracing::debug!("Synced nonce for account {sender:?}: {pending_nonce}");
+                entry.insert(pending_nonce)
+            }
+        };
+        let nonce = *next_nonce;
+        *next_nonce += U256::one();
+        Ok(nonce)
+    }
+
+    /// Releases a nonce reserved by [`Self::reserve()`]. If this is impossible without creating a nonce gap
+    /// (i.e., other nonces were reserved after it), the cached nonce is reset.
+    pub async fn release(&self, sender: Address, nonce: U256) {
+        let mut next_nonces = self.next_nonces.lock().await;
+        if let Some(next_nonce) = next_nonces.get_mut(&sender) {
+            if *next_nonce == nonce + U256::one() {
+                *next_nonce = nonce;
+            } else {
+                next_nonces.remove(&sender);
+            }
+        }
+    }
+
+    /// Resets the cached nonce for `sender`; it will be synced on the next reservation.
+    pub async fn reset(&self, sender: Address) {
+        self.next_nonces.lock().await.remove(&sender);
+    }
+
+    /// Records a signed transaction so that its submission result can be handled in [`Self::on_submission()`].
+    pub fn track(&self, raw_tx_hash: H256, sender: Address, nonce: U256, is_reserved: bool) {
+        let tx_nonce = SignedTxNonce {
+            sender,
+            nonce,
+            is_reserved,
+        };
+        self.signed_txs
+            .lock()
+            .unwrap()
+            .insert(raw_tx_hash, tx_nonce);
+    }
+
+    /// Updates the manager state after a transaction tracked via [`Self::track()`] was submitted.
+    pub async fn on_submission(&self, raw_tx_hash: H256, result: Result<(), &Error>) {
+        let tx_nonce = self.signed_txs.lock().unwrap().remove(&raw_tx_hash);
+        let Some(SignedTxNonce {
+            sender,
+            nonce,
+            is_reserved,
+        }) = tx_nonce
+        else {
+            return;
+        };
+
+        match result {
+            Ok(()) => {
+                let mut next_nonces = self.next_nonces.lock().await;
+                if let Some(next_nonce) = next_nonces.get_mut(&sender) {
+                    *next_nonce = (*next_nonce).max(nonce + U256::one());
+                }
+            }
+            Err(err) if err.is_nonce_error() => {
+                tracing::info!(
+                    "Transaction with nonce {nonce} from account {sender:?} was rejected: {err}; resyncing nonce"
+                );
+                self.reset(sender).await;
+            }
+            Err(_) if is_reserved => self.release(sender, nonce).await,
+            Err(_) => { /* The nonce provided by the caller doesn't influence the manager state */ }
+        }
+    }
+}
@@ -1,53 +1,767 @@
-use std::sync::Arc;
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
-use zksync_types::web3::{
-    self,
-    contract::Contract,
-    ethabi, helpers,
-    helpers::CallFuture,
-    transports::Http,
-    types::{
-        Address, Block, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
-        TransactionId, TransactionReceipt, H256, U256, U64,
+use jsonrpc_core::ErrorCode;
+use serde::Deserialize;
+use zksync_types::{
+    web3::{
+        self,
+        contract::Contract,
+        ethabi, helpers,
+        helpers::CallFuture,
+        types::{
+            Address, Block, BlockId, BlockNumber, Bytes, CallRequest, FeeHistory, Filter, Log,
+            Transaction, TransactionId, TransactionReceipt, H256, U256, U64,
+        },
+        Transport, Web3,
     },
-    Transport, Web3,
+    L1ChainId,
 };
 
 use crate::{
     clients::{
-        http::{Method, COUNTERS, LATENCIES},
+        http::{
+            concurrency::{InFlightPermit, RequestLimiter},
+            fee_cache::FeeCache,
+            full_block::FullBlock,
+            logs::{merge_logs, RangeBound, SplittableFilter, MAX_LOGS_SPLIT_DEPTH},
+            middleware::RpcMiddleware,
+            options::HttpOptions,
+            rate_limit::{RateLimit, RpcRateLimiter},
+            receipt_cache::{ReceiptCache, ReceiptCacheConfig},
+            report_request, report_request_outcome,
+            retry::RetryPolicy,
+            timeout::TimeoutPolicy,
+            trace::{RawTracerConfig, TracerConfig, TransactionTrace},
+            transport::HttpTransport,
+            txpool::{TxPoolContent, TxPoolContentFrom},
+            EndpointUpdateResult, FeeCacheEntry, Method, COUNTERS, LATENCIES,
+        },
         LineaEstimateGas,
     },
-    types::{Error, ExecutedTxStatus, FailureInfo, RawTokens},
-    ContractCall, EthInterface, RawTransactionBytes,
+    proof::AccountProof,
+    revert::parse_revert_data,
+    types::{BlockBlobGas, Error, ErrorKind, ExecutedTxStatus, FailureInfo, RawTokens},
+    CallOverrides, ContractCall, EthInterface, RawTransactionBytes, RevertReason,
 };
 
+/// Name of the Linea-specific gas estimation RPC method.
+const LINEA_ESTIMATE_GAS_METHOD: &str = "linea_estimateGas";
+/// Name of the RPC method suggesting the priority fee. It's not a part of the Ethereum JSON-RPC spec,
+/// so it may be unsupported by some nodes.
+const MAX_PRIORITY_FEE_PER_GAS_METHOD: &str = "eth_maxPriorityFeePerGas";
+
+/// Cap on the gas buffer applied by [`QueryClient::estimate_gas_with_buffer()`], in percent of the estimate.
+const MAX_GAS_BUFFER_PERCENT: u64 = 200;
+/// Minimum increase of the gas buffer after a call has run out of gas, in percent of the estimate.
+const MIN_GAS_BUFFER_INCREMENT_PERCENT: u64 = 10;
+
+/// Default maximum number of calls in a single JSON-RPC batch request.
+const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+
+/// Blob gas fields of a block header. Missing for blocks preceding EIP-4844.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlobGasHeader {
+    blob_gas_used: Option<U64>,
+    excess_blob_gas: Option<U64>,
+}
+
+/// Fee fields of a block header. `base_fee_per_gas` is missing for blocks preceding EIP-1559
+/// and for chains not supporting it.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BaseFeeHeader {
+    base_fee_per_gas: Option<U256>,
+}
+
+/// Identity of a block header.
+#[derive(Debug, Deserialize)]
+struct BlockHeaderId {
+    number: U64,
+    hash: H256,
+}
+
 /// An "anonymous" Ethereum client that can invoke read-only methods that aren't
-/// tied to a particular account.
+/// tied to a particular account. Uses HTTP transport by default.
 #[derive(Debug, Clone)]
-pub struct QueryClient {
-    web3: Arc<Web3<Http>>,
+pub struct QueryClient<T: Transport = HttpTransport> {
+    pub(crate) web3: Arc<Web3<T>>,
+    pub(super) max_batch_size: usize,
+    retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<RpcRateLimiter>,
+    request_limiter: Option<RequestLimiter>,
+    pub(super) timeout_policy: Option<Arc<TimeoutPolicy>>,
+    receipt_cache: Option<Arc<ReceiptCache>>,
+    fee_cache: Option<Arc<FeeCache>>,
 }
 
-impl From<Http> for QueryClient {
-    fn from(transport: Http) -> Self {
-        Self {
-            web3: Arc::new(Web3::new(transport)),
-        }
+impl From<HttpTransport> for QueryClient {
+    fn from(transport: HttpTransport) -> Self {
+        Self::from_transport(transport)
     }
 }
 
 impl QueryClient {
     /// Creates a new HTTP client.
     pub fn new(node_url: &str) -> Result<Self, Error> {
-        let transport = Http::new(node_url)?;
+        let transport = HttpTransport::new(node_url)?;
         Ok(transport.into())
     }
+
+    /// Creates a new HTTP client with the specified transport options, e.g. custom headers.
+    pub fn new_with_options(node_url: &str, options: &HttpOptions) -> Result<Self, Error> {
+        Ok(options.transport(node_url)?.into())
+    }
+
+    /// Sets the maximum number of calls sent in a single HTTP request. Larger batches are split
+    /// into several requests.
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        assert!(max_batch_size > 0, "Max batch size must be positive");
+        self.max_batch_size = max_batch_size;
+        self
+    }
+
+    /// Adds middleware invoked for each HTTP request sent by this client, including batch requests.
+    /// Middleware is invoked in the order of registration; see [`RpcMiddleware`] for details.
+    pub fn with_middleware(mut self, middleware: impl RpcMiddleware) -> Self {
+        let mut transport = self.web3.transport().clone();
+        transport.push_middleware(Arc::new(middleware));
+        self.web3 = Arc::new(Web3::new(transport));
+        self
+    }
+
+    /// Switches this client and all its clones to the node at `new_url`, e.g. to rotate the provider API key
+    /// without recreating the client. Before switching, checks that the new node reports `expected_chain_id`;
+    /// if the check fails, the client keeps using the current node and the error is returned.
+    ///
+    /// Requests sent before the switch are completed against the previous node; requests sent after it
+    /// use the new node.
+    pub async fn update_url(
+        &self,
+        new_url: &str,
+        expected_chain_id: L1ChainId,
+        component: &'static str,
+    ) -> Result<(), Error> {
+        let transport = self.web3.transport();
+        let result = self
+            .check_endpoint(transport, new_url, expected_chain_id, component)
+            .await;
+        let new_transport = match result {
+            Ok(new_transport) => new_transport,
+            Err(err) => {
+                let update_result = if matches!(err, Error::ChainIdMismatch { .. }) {
+                    EndpointUpdateResult::ChainIdMismatch
+                } else {
+                    EndpointUpdateResult::Failed
+                };
+                COUNTERS.endpoint_updates[&update_result].inc();
+                tracing::warn!(
+                    "Failed switching Ethereum node endpoint, keeping the current one: {err}"
+                );
+                return Err(err);
+            }
+        };
+
+        let new_url = new_transport.url();
+        // Only the host is logged since the URL may contain an API key.
+        tracing::info!(
+            "Switched Ethereum node endpoint to host {:?}",
+            new_url.host_str().unwrap_or_default()
+        );
+        transport.set_url(new_url);
+        COUNTERS.endpoint_updates[&EndpointUpdateResult::Switched].inc();
+        Ok(())
+    }
+
+    /// Checks that the node at `new_url` reports the expected chain ID. Returns a transport for the node.
+    async fn check_endpoint(
+        &self,
+        transport: &HttpTransport,
+        new_url: &str,
+        expected_chain_id: L1ChainId,
+        component: &'static str,
+    ) -> Result<HttpTransport, Error> {
+        let new_transport = transport.with_url(new_url)?;
+        let new_client = Self {
+            web3: Arc::new(Web3::new(new_transport.clone())),
+            ..self.clone()
+        };
+        let actual = new_client.fetch_chain_id(component).await?;
+        if actual != expected_chain_id {
+            return Err(Error::ChainIdMismatch {
+                expected: expected_chain_id,
+                actual,
+            });
+        }
+        Ok(new_transport)
+    }
+}
+
+impl<T: Transport> QueryClient<T> {
+    pub(crate) fn from_transport(transport: T) -> Self {
+        Self {
+            web3: Arc::new(Web3::new(transport)),
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            retry_policy: None,
+            rate_limiter: None,
+            request_limiter: None,
+            timeout_policy: None,
+            receipt_cache: None,
+            fee_cache: None,
+        }
+    }
+
+    /// Enables retries of transient errors (timeouts, connection errors, rate limiting) according
+    /// to the specified policy. By default, errors are not retried.
+    pub fn with_retries(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Limits the rate of requests sent to the node. The limit applies to all methods and is shared
+    /// among all clones of the client. Calls exceeding the limit wait until they can be sent.
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limiter = Some(RpcRateLimiter::new(limit));
+        self
+    }
+
+    /// Limits the number of concurrent requests sent to the node. The limit applies to all methods
+    /// (a batch request counts as a single request) and is shared among all clones of the client.
+    /// Requests exceeding the limit wait until one of in-flight requests completes. `0` means no limit,
+    /// which is the default.
+    pub fn with_max_in_flight_requests(mut self, max_in_flight_requests: usize) -> Self {
+        self.request_limiter =
+            (max_in_flight_requests > 0).then(|| RequestLimiter::new(max_in_flight_requests));
+        self
+    }
+
+    /// Sets timeouts for requests sent by the client. Timed out requests fail with [`Error::RequestTimeout`],
+    /// which is considered transient and thus retried according to the retry policy (if any).
+    pub fn with_timeouts(mut self, policy: TimeoutPolicy) -> Self {
+        self.timeout_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Enables caching of receipts for transactions included in finalized blocks. The cache is shared
+    /// among all clones of the client. Cached receipts are invalidated if the client observes
+    /// a different hash for their block (e.g., when getting the block or the finalized block).
+    pub fn with_receipt_cache(mut self, config: ReceiptCacheConfig) -> Self {
+        self.receipt_cache = (!config.bypass).then(|| Arc::new(ReceiptCache::new(&config)));
+        self
+    }
+
+    /// Enables caching of fee-related queries (gas price, fee history, max priority fee per gas and blob gas
+    /// of blocks) for the specified TTL. The cache is shared among all clones of the client, so that components
+    /// using the same client don't query fees independently. A cached value is never served after `ttl` has passed
+    /// since the request for it was sent; errors are not cached.
+    pub fn with_fee_cache(mut self, ttl: Duration) -> Self {
+        self.fee_cache = Some(Arc::new(FeeCache::new(ttl)));
+        self
+    }
+
+    /// Removes all values from the fee cache, e.g. after a transaction was rejected because of outdated fees.
+    /// No-op if the fee cache is not enabled.
+    pub fn invalidate_fee_cache(&self) {
+        if let Some(cache) = &self.fee_cache {
+            cache.invalidate();
+        }
+    }
+
+    /// Fetches logs matching the `filter` similar to [`EthInterface::logs()`], but splits the block range
+    /// of the filter in half if the node rejects the query because the range or the number of returned logs
+    /// exceeds node limits (e.g., Infura caps results at 10,000 logs). Logs fetched for sub-ranges are ordered
+    /// by block number and log index, and are deduplicated.
+    ///
+    /// Splitting is only supported for filters with block range bounds specified as block numbers
+    /// or `earliest` / `latest` / `pending` tags. The range is split at most 16 times; if the node still
+    /// rejects the query, or if the range cannot be split, the node error is returned.
+    pub async fn get_logs_paginated(
+        &self,
+        filter: Filter,
+        component: &'static str,
+    ) -> Result<Vec<Log>, Error> {
+        COUNTERS.call[&(Method::Logs, component)].inc();
+        let latency = LATENCIES.direct[&Method::Logs].start();
+        let filter = SplittableFilter::new(&filter);
+        let err = match self.raw_logs(filter.as_json()).await {
+            Ok(logs) => {
+                latency.observe();
+                return Ok(logs);
+            }
+            Err(err) if err.is_logs_limit_error() => err,
+            Err(err) => return Err(err),
+        };
+
+        let Some((from, to)) = filter.range() else {
+            return Err(err);
+        };
+        let from = self.resolve_range_bound(from, component).await?;
+        let to = self.resolve_range_bound(to, component).await?;
+        if from > to {
+            return Err(err);
+        }
+
+        // Ranges are processed in the LIFO order; the upper half of a split range is pushed first,
+        // so that chunks are fetched in the block order.
+        let mut ranges = vec![(from, to, 0)];
+        let mut full_range_err = Some(err);
+        let mut chunks = vec![];
+        while let Some((from, to, depth)) = ranges.pop() {
+            let result = match full_range_err.take() {
+                Some(err) => Err(err),
+                None => self.raw_logs(&filter.with_range(from, to)).await,
+            };
+            match result {
+                Ok(logs) => chunks.push(logs),
+                Err(err) if err.is_logs_limit_error() && from < to => {
+                    if depth >= MAX_LOGS_SPLIT_DEPTH {
+                        tracing::warn!(
+                            "Cannot fetch logs for blocks {from}..={to} after splitting the block range {depth} times"
+                        );
+                        return Err(err);
+                    }
+                    let mid = from + (to - from) / 2;
+                    tracing::debug!(
+                        "Splitting block range for `eth_getLogs`: {from}..={mid}, {}..={to}",
+                        mid + 1
+                    );
+                    COUNTERS.logs_range_splits.inc();
+                    ranges.push((mid + 1, to, depth + 1));
+                    ranges.push((from, mid, depth + 1));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        latency.observe();
+        Ok(merge_logs(chunks))
+    }
+
+    async fn raw_logs(&self, filter: &serde_json::Value) -> Result<Vec<Log>, Error> {
+        self.retry(Method::Logs, || {
+            CallFuture::new(
+                self.web3
+                    .transport()
+                    .execute("eth_getLogs", vec![filter.clone()]),
+            )
+        })
+        .await
+    }
+
+    async fn resolve_range_bound(
+        &self,
+        bound: RangeBound,
+        component: &'static str,
+    ) -> Result<u64, Error> {
+        Ok(match bound {
+            RangeBound::Number(number) => number,
+            RangeBound::Latest => {
+                COUNTERS.call[&(Method::BlockNumber, component)].inc();
+                let block_number = self
+                    .retry(Method::BlockNumber, || self.web3.eth().block_number())
+                    .await?;
+                block_number.as_u64()
+            }
+        })
+    }
+
+    /// Traces the specified transaction using `debug_traceTransaction` with the specified tracer.
+    /// The node must have the `debug` namespace enabled.
+    pub async fn trace_transaction(
+        &self,
+        tx_hash: H256,
+        tracer: TracerConfig,
+        component: &'static str,
+    ) -> Result<TransactionTrace, Error> {
+        COUNTERS.call[&(Method::TraceTransaction, component)].inc();
+        let latency = LATENCIES.direct[&Method::TraceTransaction].start();
+        let params = vec![
+            helpers::serialize(&tx_hash),
+            helpers::serialize(&RawTracerConfig::from(tracer)),
+        ];
+        let request = || {
+            // Traces are deserialized from the owned JSON response without copying it.
+            self.web3
+                .transport()
+                .execute("debug_traceTransaction", params.clone())
+        };
+        let trace = match tracer {
+            TracerConfig::CallTracer { .. } => {
+                let trace = self
+                    .retry(Method::TraceTransaction, || CallFuture::new(request()))
+                    .await?;
+                TransactionTrace::Call(trace)
+            }
+            TracerConfig::StructLogs { .. } => {
+                let trace = self
+                    .retry(Method::TraceTransaction, || CallFuture::new(request()))
+                    .await?;
+                TransactionTrace::StructLogs(trace)
+            }
+        };
+        latency.observe();
+        Ok(trace)
+    }
+
+    /// Returns the specified block with full transaction objects, which allows inspecting transactions
+    /// without additional `eth_getTransactionByHash` calls. Returns `Ok(None)` if the block doesn't exist.
+    pub async fn block_with_txs(
+        &self,
+        block_id: BlockId,
+        component: &'static str,
+    ) -> Result<Option<FullBlock>, Error> {
+        COUNTERS.call[&(Method::BlockWithTxs, component)].inc();
+        let latency = LATENCIES.direct[&Method::BlockWithTxs].start();
+        let block: Option<FullBlock> = self
+            .retry(Method::BlockWithTxs, || {
+                let include_txs = helpers::serialize(&true);
+                let request = match block_id {
+                    BlockId::Hash(hash) => self.web3.transport().execute(
+                        "eth_getBlockByHash",
+                        vec![helpers::serialize(&hash), include_txs],
+                    ),
+                    BlockId::Number(number) => self.web3.transport().execute(
+                        "eth_getBlockByNumber",
+                        vec![helpers::serialize(&number), include_txs],
+                    ),
+                };
+                CallFuture::new(request)
+            })
+            .await
+            .map_err(|err| err.for_block(block_id))?;
+        if let (Some(cache), Some(block)) = (&self.receipt_cache, &block) {
+            if let (Some(number), Some(hash)) = (block.number, block.hash) {
+                cache.observe_block(number.as_u64(), hash);
+            }
+        }
+        latency.observe();
+        Ok(block)
+    }
+
+    /// Returns the state of the specified account together with Merkle proofs for the account
+    /// and the specified storage slots (`eth_getProof`). The proofs can be checked against the state root
+    /// of the block using [`AccountProof::verify()`].
+    pub async fn get_proof(
+        &self,
+        address: Address,
+        storage_keys: &[H256],
+        block_id: BlockId,
+        component: &'static str,
+    ) -> Result<AccountProof, Error> {
+        COUNTERS.call[&(Method::GetProof, component)].inc();
+        let latency = LATENCIES.direct[&Method::GetProof].start();
+        let proof = self
+            .retry(Method::GetProof, || {
+                let params = vec![
+                    helpers::serialize(&address),
+                    helpers::serialize(&storage_keys),
+                    helpers::serialize(&block_id),
+                ];
+                CallFuture::new(self.web3.transport().execute("eth_getProof", params))
+            })
+            .await
+            .map_err(|err| err.for_block(block_id))?;
+        latency.observe();
+        Ok(proof)
+    }
+
+    /// Returns transactions in the node mempool using `txpool_content`. The response may be large
+    /// for public nodes; consider using [`Self::txpool_content_from()`] to inspect a specific sender.
+    pub async fn txpool_content(&self, component: &'static str) -> Result<TxPoolContent, Error> {
+        COUNTERS.call[&(Method::TxPoolContent, component)].inc();
+        let latency = LATENCIES.direct[&Method::TxPoolContent].start();
+        let content = self
+            .retry(Method::TxPoolContent, || {
+                CallFuture::new(self.web3.transport().execute("txpool_content", vec![]))
+            })
+            .await?;
+        latency.observe();
+        Ok(content)
+    }
+
+    /// Returns mempool transactions of the specified sender using `txpool_contentFrom`. If the node
+    /// doesn't support this method (e.g., Erigon), falls back to filtering the output of `txpool_content`.
+    pub async fn txpool_content_from(
+        &self,
+        sender: Address,
+        component: &'static str,
+    ) -> Result<TxPoolContentFrom, Error> {
+        COUNTERS.call[&(Method::TxPoolContentFrom, component)].inc();
+        let latency = LATENCIES.direct[&Method::TxPoolContentFrom].start();
+        let params = vec![helpers::serialize(&sender)];
+        let result = self
+            .retry(Method::TxPoolContentFrom, || {
+                CallFuture::new(
+                    self.web3
+                        .transport()
+                        .execute("txpool_contentFrom", params.clone()),
+                )
+            })
+            .await;
+        let content = match result {
+            Err(Error::EthereumGateway(web3::Error::Rpc(err)))
+                if err.code == ErrorCode::MethodNotFound =>
+            {
+                tracing::debug!(
+                    "L1 node doesn't support `txpool_contentFrom`; falling back to `txpool_content`"
+                );
+                self.txpool_content(component).await?.for_sender(sender)
+            }
+            result => result?,
+        };
+        latency.observe();
+        Ok(content)
+    }
+
+    /// Finds nonces of the `sender` missing from the node mempool, which prevent queued transactions
+    /// of the sender from being included. Only nonces starting from the sender nonce in the latest block
+    /// are considered.
+    pub async fn stuck_nonces(
+        &self,
+        sender: Address,
+        component: &'static str,
+    ) -> Result<Vec<U256>, Error> {
+        let content = self.txpool_content_from(sender, component).await?;
+        COUNTERS.call[&(Method::NonceAtForAccount, component)].inc();
+        let next_nonce = self
+            .retry(Method::NonceAtForAccount, || {
+                self.web3
+                    .eth()
+                    .transaction_count(sender, Some(BlockNumber::Latest))
+            })
+            .await?;
+        let gaps = content.nonce_gaps(next_nonce.as_u64());
+        Ok(gaps.into_iter().map(U256::from).collect())
+    }
+
+    /// Estimates gas for the `call` using `eth_estimateGas` and applies a buffer of `buffer_percent` on top
+    /// of the estimate (e.g., 20 means that the returned gas limit is 120% of the estimate). The call is then
+    /// simulated with the buffered gas limit using `eth_call`. If the simulation runs out of gas (e.g., because
+    /// of state changes since the estimation), gas is re-estimated with a larger buffer, up to `max_retries` times.
+    /// The buffer is doubled on each retry, but is capped at 200%.
+    ///
+    /// The `gas` field of the `call` is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns the out-of-gas error of the last simulation if retries are exhausted. Other simulation errors
+    /// (e.g., reverts) are returned immediately.
+    pub async fn estimate_gas_with_buffer(
+        &self,
+        mut call: CallRequest,
+        buffer_percent: u64,
+        max_retries: usize,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        call.gas = None;
+        let mut buffer_percent = buffer_percent.min(MAX_GAS_BUFFER_PERCENT);
+        let mut retry = 0;
+        loop {
+            COUNTERS.call[&(Method::EstimateGasWithBuffer, component)].inc();
+            let latency = LATENCIES.direct[&Method::EstimateGasWithBuffer].start();
+            let estimate = self
+                .retry(Method::EstimateGasWithBuffer, || {
+                    self.web3.eth().estimate_gas(call.clone(), None)
+                })
+                .await?;
+            latency.observe();
+
+            let gas_limit = estimate.saturating_mul((100 + buffer_percent).into()) / 100;
+            let simulated_call = CallRequest {
+                gas: Some(gas_limit),
+                ..call.clone()
+            };
+            COUNTERS.call[&(Method::SimulateCall, component)].inc();
+            let latency = LATENCIES.direct[&Method::SimulateCall].start();
+            let simulation_result = self
+                .retry(Method::SimulateCall, || {
+                    self.web3.eth().call(simulated_call.clone(), None)
+                })
+                .await;
+            latency.observe();
+
+            match simulation_result {
+                Ok(_) => return Ok(gas_limit),
+                Err(err) if err.is_out_of_gas_error() && retry < max_retries => {
+                    let new_buffer_percent = (buffer_percent * 2)
+                        .max(buffer_percent + MIN_GAS_BUFFER_INCREMENT_PERCENT)
+                        .min(MAX_GAS_BUFFER_PERCENT);
+                    tracing::info!(
+                        "Call with gas limit {gas_limit} (estimate {estimate} + {buffer_percent}%) has run out of gas: {err}; \
+                         re-estimating with {new_buffer_percent}% buffer"
+                    );
+                    COUNTERS.gas_estimation_retries.inc();
+                    buffer_percent = new_buffer_percent;
+                    retry += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Executes `call` at the specified block without creating a transaction. Used to simulate transactions
+    /// before sending them.
+    pub(super) async fn simulate_call(
+        &self,
+        call: CallRequest,
+        block: BlockNumber,
+        component: &'static str,
+    ) -> Result<Bytes, Error> {
+        COUNTERS.call[&(Method::SimulateCall, component)].inc();
+        let latency = LATENCIES.direct[&Method::SimulateCall].start();
+        let output = self
+            .retry(Method::SimulateCall, || {
+                self.web3
+                    .eth()
+                    .call(call.clone(), Some(BlockId::Number(block)))
+            })
+            .await?;
+        latency.observe();
+        Ok(output)
+    }
+
+    /// Waits until the rate limiter (if any) allows sending a request.
+    pub(super) async fn throttle(&self, method: Method) {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(method).await;
+        }
+    }
+
+    /// Waits until the limit on in-flight requests (if any) allows sending a request. The returned permit
+    /// must be held while the request is in flight.
+    pub(super) async fn acquire_permit(&self, method: Method) -> Option<InFlightPermit> {
+        match &self.request_limiter {
+            Some(limiter) => Some(limiter.acquire(method).await),
+            None => None,
+        }
+    }
+
+    /// Caches the receipt if it belongs to a finalized block, refreshing the finalized block if necessary.
+    /// Errors getting the finalized block are logged and don't influence the receipt call.
+    async fn cache_receipt(
+        &self,
+        cache: &ReceiptCache,
+        receipt: &TransactionReceipt,
+        component: &'static str,
+    ) {
+        let Some(block_number) = receipt.block_number else {
+            return;
+        };
+        if cache.needs_finalized_block(block_number.as_u64()) {
+            match self.finalized_block_id(component).await {
+                Ok(Some(header)) => {
+                    cache.update_finalized_block(header.number.as_u64(), header.hash)
+                }
+                Ok(None) => tracing::debug!("Node has returned no finalized block"),
+                Err(err) => {
+                    tracing::warn!("Failed getting finalized block to cache receipt: {err}")
+                }
+            }
+        }
+        cache.insert(receipt);
+    }
+
+    async fn finalized_block_id(
+        &self,
+        component: &'static str,
+    ) -> Result<Option<BlockHeaderId>, Error> {
+        COUNTERS.call[&(Method::Block, component)].inc();
+        let latency = LATENCIES.direct[&Method::Block].start();
+        let header = self
+            .retry(Method::Block, || {
+                let params = vec![
+                    helpers::serialize(&BlockNumber::Finalized),
+                    helpers::serialize(&false),
+                ];
+                CallFuture::new(
+                    self.web3
+                        .transport()
+                        .execute("eth_getBlockByNumber", params),
+                )
+            })
+            .await?;
+        latency.observe();
+        Ok(header)
+    }
+
+    /// Checks whether the chain supports EIP-1559 transactions based on whether the latest block
+    /// has a base fee.
+    pub async fn supports_eip1559(&self, component: &'static str) -> Result<bool, Error> {
+        COUNTERS.call[&(Method::Block, component)].inc();
+        let latency = LATENCIES.direct[&Method::Block].start();
+        let header: Option<BaseFeeHeader> = self
+            .retry(Method::Block, || {
+                let params = vec![
+                    helpers::serialize(&BlockNumber::Latest),
+                    helpers::serialize(&false),
+                ];
+                CallFuture::new(
+                    self.web3
+                        .transport()
+                        .execute("eth_getBlockByNumber", params),
+                )
+            })
+            .await?;
+        latency.observe();
+
+        let header = header
+            .ok_or_else(|| web3::Error::InvalidResponse("latest block is missing".to_owned()))?;
+        Ok(header.base_fee_per_gas.is_some())
+    }
+
+    /// Serves a fee-related query from the fee cache (if enabled), or fetches it using `fetch`.
+    async fn cached<R, Fut>(
+        &self,
+        entry: FeeCacheEntry,
+        params: serde_json::Value,
+        fetch: impl FnOnce() -> Fut,
+    ) -> Result<R, Error>
+    where
+        R: Clone + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, Error>>,
+    {
+        match &self.fee_cache {
+            Some(cache) => cache.get_or_fetch(entry, params.to_string(), fetch).await,
+            None => fetch().await,
+        }
+    }
+
+    pub(super) async fn retry<R, E, Fut>(
+        &self,
+        method: Method,
+        mut call: impl FnMut() -> Fut,
+    ) -> Result<R, Error>
+    where
+        Fut: Future<Output = Result<R, E>>,
+        E: Into<Error>,
+    {
+        RetryPolicy::retry(self.retry_policy.as_ref(), method, || {
+            let call = call();
+            async move {
+                self.throttle(method).await;
+                let _permit = self.acquire_permit(method).await;
+                let started_at = Instant::now();
+                let request = async { call.await.map_err(Into::into) };
+                let result =
+                    TimeoutPolicy::enforce(self.timeout_policy.as_deref(), method, request).await;
+                report_request(method, started_at.elapsed(), &result);
+                result
+            }
+        })
+        .await
+    }
 }
 
 #[async_trait]
-impl EthInterface for QueryClient {
+impl<T> EthInterface for QueryClient<T>
+where
+    T: Transport + Send + Sync,
+    T::Out: Send,
+{
     async fn nonce_at_for_account(
         &self,
         account: Address,
@@ -57,10 +771,11 @@ impl EthInterface for QueryClient {
         COUNTERS.call[&(Method::NonceAtForAccount, component)].inc();
         let latency = LATENCIES.direct[&Method::NonceAtForAccount].start();
         let nonce = self
-            .web3
-            .eth()
-            .transaction_count(account, Some(block))
-            .await?;
+            .retry(Method::NonceAtForAccount, || {
+                self.web3.eth().transaction_count(account, Some(block))
+            })
+            .await
+            .map_err(|err| err.for_block(block))?;
         latency.observe();
         Ok(nonce)
     }
@@ -68,22 +783,97 @@ impl EthInterface for QueryClient {
     async fn block_number(&self, component: &'static str) -> Result<U64, Error> {
         COUNTERS.call[&(Method::BlockNumber, component)].inc();
         let latency = LATENCIES.direct[&Method::BlockNumber].start();
-        let block_number = self.web3.eth().block_number().await?;
+        let block_number = self
+            .retry(Method::BlockNumber, || self.web3.eth().block_number())
+            .await?;
         latency.observe();
         Ok(block_number)
     }
 
+    async fn max_priority_fee_per_gas(&self, component: &'static str) -> Result<U256, Error> {
+        COUNTERS.call[&(Method::MaxPriorityFeePerGas, component)].inc();
+        let latency = LATENCIES.direct[&Method::MaxPriorityFeePerGas].start();
+        let fetch = || async {
+            self.retry(Method::MaxPriorityFeePerGas, || {
+                CallFuture::new(
+                    self.web3
+                        .transport()
+                        .execute(MAX_PRIORITY_FEE_PER_GAS_METHOD, vec![]),
+                )
+            })
+            .await
+            .map_err(|err| match err {
+                Error::EthereumGateway(web3::Error::Rpc(err))
+                    if err.code == ErrorCode::MethodNotFound =>
+                {
+                    Error::UnsupportedMethod(MAX_PRIORITY_FEE_PER_GAS_METHOD)
+                }
+                err => err,
+            })
+        };
+        let fee = self
+            .cached(
+                FeeCacheEntry::MaxPriorityFeePerGas,
+                serde_json::Value::Null,
+                fetch,
+            )
+            .await?;
+        latency.observe();
+        Ok(fee)
+    }
+
+    async fn fetch_chain_id(&self, component: &'static str) -> Result<L1ChainId, Error> {
+        COUNTERS.call[&(Method::ChainId, component)].inc();
+        let latency = LATENCIES.direct[&Method::ChainId].start();
+        let chain_id = self
+            .retry(Method::ChainId, || self.web3.eth().chain_id())
+            .await?;
+        latency.observe();
+        Ok(L1ChainId(chain_id.as_u64()))
+    }
+
     async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error> {
         COUNTERS.call[&(Method::GetGasPrice, component)].inc();
         let latency = LATENCIES.direct[&Method::GetGasPrice].start();
-        let network_gas_price = self.web3.eth().gas_price().await?;
+        let fetch = || self.retry(Method::GetGasPrice, || self.web3.eth().gas_price());
+        let network_gas_price = self
+            .cached(FeeCacheEntry::GasPrice, serde_json::Value::Null, fetch)
+            .await?;
         latency.observe();
         Ok(network_gas_price)
     }
 
+    /// Sending a transaction is not idempotent: a node may accept the transaction and still fail the call
+    /// (e.g., time out). Thus, if the call is retried, errors indicating that the transaction is already known
+    /// are treated as success.
     async fn send_raw_tx(&self, tx: RawTransactionBytes) -> Result<H256, Error> {
+        const COMPONENT: &str = "send_raw_tx_retry";
+
         let latency = LATENCIES.direct[&Method::SendRawTx].start();
-        let tx = self.web3.eth().send_raw_transaction(Bytes(tx.0)).await?;
+        let tx_hash = H256(web3::signing::keccak256(tx.as_ref()));
+        let mut attempts = 0;
+        let result = self
+            .retry(Method::SendRawTx, || {
+                attempts += 1;
+                self.web3.eth().send_raw_transaction(Bytes(tx.0.clone()))
+            })
+            .await;
+        let tx = match result {
+            Err(err) if attempts > 1 && err.is_already_known_error() => {
+                tracing::info!("Transaction {tx_hash:?} is already known after a retry: {err}");
+                tx_hash
+            }
+            Err(err) if attempts > 1 && err.is_nonce_error() => {
+                // The nonce may be used by the transaction itself, or by another transaction.
+                if let Ok(Some(_)) = self.get_tx(tx_hash, COMPONENT).await {
+                    tracing::info!("Transaction {tx_hash:?} was submitted before a retry: {err}");
+                    tx_hash
+                } else {
+                    return Err(err);
+                }
+            }
+            result => result?,
+        };
         latency.observe();
         Ok(tx)
     }
@@ -108,9 +898,11 @@ impl EthInterface for QueryClient {
             let chunk_end = (chunk_start + MAX_REQUEST_CHUNK).min(upto_block);
             let chunk_size = chunk_end - chunk_start;
             let chunk = self
-                .web3
-                .eth()
-                .fee_history(chunk_size.into(), chunk_end.into(), None)
+                .retry(Method::BaseFeeHistory, || {
+                    self.web3
+                        .eth()
+                        .fee_history(chunk_size.into(), chunk_end.into(), None)
+                })
                 .await?
                 .base_fee_per_gas;
 
@@ -121,6 +913,62 @@ impl EthInterface for QueryClient {
         Ok(history.into_iter().map(|fee| fee.as_u64()).collect())
     }
 
+    async fn fee_history(
+        &self,
+        block_count: usize,
+        newest_block: BlockNumber,
+        reward_percentiles: &[f64],
+        component: &'static str,
+    ) -> Result<FeeHistory, Error> {
+        COUNTERS.call[&(Method::FeeHistory, component)].inc();
+        let latency = LATENCIES.direct[&Method::FeeHistory].start();
+        let reward_percentiles =
+            (!reward_percentiles.is_empty()).then(|| reward_percentiles.to_vec());
+        let params = helpers::serialize(&(block_count, newest_block, &reward_percentiles));
+        let fetch = || async {
+            self.retry(Method::FeeHistory, || {
+                self.web3.eth().fee_history(
+                    block_count.into(),
+                    newest_block,
+                    reward_percentiles.clone(),
+                )
+            })
+            .await
+            .map_err(|err| err.for_block(newest_block))
+        };
+        let history = self
+            .cached(FeeCacheEntry::FeeHistory, params, fetch)
+            .await?;
+        latency.observe();
+        Ok(history)
+    }
+
+    async fn pending_tx_hash(
+        &self,
+        sender: Address,
+        nonce: U256,
+        component: &'static str,
+    ) -> Result<Option<H256>, Error> {
+        let content = match self.txpool_content_from(sender, component).await {
+            Ok(content) => content,
+            Err(Error::EthereumGateway(web3::Error::Rpc(err)))
+                if err.code == ErrorCode::MethodNotFound =>
+            {
+                tracing::debug!("L1 node doesn't support inspecting its mempool: {err}");
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
+        };
+        let Ok(nonce) = u64::try_from(nonce) else {
+            return Ok(None);
+        };
+        let tx = content
+            .pending
+            .get(&nonce)
+            .or_else(|| content.queued.get(&nonce));
+        Ok(tx.map(|tx| tx.hash))
+    }
+
     async fn get_pending_block_base_fee_per_gas(
         &self,
         component: &'static str,
@@ -129,9 +977,9 @@ impl EthInterface for QueryClient {
         let latency = LATENCIES.direct[&Method::PendingBlockBaseFee].start();
 
         let block = self
-            .web3
-            .eth()
-            .block(BlockId::Number(BlockNumber::Pending))
+            .retry(Method::PendingBlockBaseFee, || {
+                self.web3.eth().block(BlockId::Number(BlockNumber::Pending))
+            })
             .await?
             .expect("Pending block should always exist");
 
@@ -168,8 +1016,16 @@ impl EthInterface for QueryClient {
 
     async fn failure_reason(&self, tx_hash: H256) -> Result<Option<FailureInfo>, Error> {
         let latency = LATENCIES.direct[&Method::FailureReason].start();
-        let transaction = self.web3.eth().transaction(tx_hash.into()).await?;
-        let receipt = self.web3.eth().transaction_receipt(tx_hash).await?;
+        let transaction = self
+            .retry(Method::FailureReason, || {
+                self.web3.eth().transaction(tx_hash.into())
+            })
+            .await?;
+        let receipt = self
+            .retry(Method::FailureReason, || {
+                self.web3.eth().transaction_receipt(tx_hash)
+            })
+            .await?;
 
         match (transaction, receipt) {
             (Some(transaction), Some(receipt)) => {
@@ -189,18 +1045,34 @@ impl EthInterface for QueryClient {
                     access_list: None,
                 };
 
-                let call_error = self
+                self.throttle(Method::FailureReason).await;
+                let started_at = Instant::now();
+                let call_result = self
                     .web3
                     .eth()
                     .call(call_request, receipt.block_number.map(Into::into))
-                    .await
-                    .err();
+                    .await;
+                let outcome = call_result
+                    .as_ref()
+                    .map(|_| ())
+                    .map_err(ErrorKind::from_web3_error);
+                report_request_outcome(Method::FailureReason, started_at.elapsed(), outcome);
+                let call_error = call_result.err();
 
                 let failure_info = match call_error {
                     Some(web3::Error::Rpc(rpc_error)) => {
                         let revert_code = rpc_error.code.code();
-                        let message_len = "execution reverted: ".len().min(rpc_error.message.len());
-                        let revert_reason = rpc_error.message[message_len..].to_string();
+                        let revert_data = rpc_error.data.as_ref().and_then(parse_revert_data);
+                        let revert_reason = match revert_data {
+                            Some(data) if !data.is_empty() => {
+                                RevertReason::decode(&data).to_string()
+                            }
+                            _ => {
+                                let message_len =
+                                    "execution reverted: ".len().min(rpc_error.message.len());
+                                rpc_error.message[message_len..].to_string()
+                            }
+                        };
 
                         Ok(Some(FailureInfo {
                             revert_code,
@@ -227,9 +1099,9 @@ impl EthInterface for QueryClient {
     ) -> Result<Option<Transaction>, Error> {
         COUNTERS.call[&(Method::GetTx, component)].inc();
         let tx = self
-            .web3
-            .eth()
-            .transaction(TransactionId::Hash(hash))
+            .retry(Method::GetTx, || {
+                self.web3.eth().transaction(TransactionId::Hash(hash))
+            })
             .await?;
         Ok(tx)
     }
@@ -240,19 +1112,75 @@ impl EthInterface for QueryClient {
     ) -> Result<Vec<ethabi::Token>, Error> {
         let latency = LATENCIES.direct[&Method::CallContractFunction].start();
         let contract = Contract::new(self.web3.eth(), call.contract_address, call.contract_abi);
-        let RawTokens(res) = contract
-            .query(
-                &call.inner.name,
-                call.inner.params,
-                call.inner.from,
-                call.inner.options,
-                call.inner.block,
-            )
-            .await?;
+        let RawTokens(res) = self
+            .retry(Method::CallContractFunction, || {
+                contract.query(
+                    &call.inner.name,
+                    RawTokens(call.inner.params.0.clone()),
+                    call.inner.from,
+                    call.inner.options.clone(),
+                    call.inner.block,
+                )
+            })
+            .await
+            .map_err(|err| match call.inner.block {
+                Some(block) => err.for_block(block),
+                None => err,
+            })?;
         latency.observe();
         Ok(res)
     }
 
+    async fn call_contract_function_with_overrides(
+        &self,
+        call: ContractCall,
+        overrides: CallOverrides,
+    ) -> Result<Vec<ethabi::Token>, Error> {
+        let latency = LATENCIES.direct[&Method::CallContractFunction].start();
+        let function = call.contract_abi.function(&call.inner.name)?;
+        let data = function.encode_input(&call.inner.params.0)?;
+        let options = &call.inner.options;
+        let request = CallRequest {
+            from: call.inner.from,
+            to: Some(call.contract_address),
+            gas: options.gas,
+            gas_price: options.gas_price,
+            value: options.value,
+            data: Some(data.into()),
+            transaction_type: options.transaction_type,
+            access_list: options.access_list.clone(),
+            max_fee_per_gas: options.max_fee_per_gas,
+            max_priority_fee_per_gas: options.max_priority_fee_per_gas,
+        };
+        let block = call
+            .inner
+            .block
+            .unwrap_or(BlockId::Number(BlockNumber::Latest));
+        let params = vec![
+            helpers::serialize(&request),
+            helpers::serialize(&block),
+            helpers::serialize(&overrides),
+        ];
+
+        let output: Bytes = self
+            .retry(Method::CallContractFunction, || {
+                CallFuture::new(self.web3.transport().execute("eth_call", params.clone()))
+            })
+            .await
+            .map_err(|err| match err {
+                // Nodes not supporting overrides (or failing to parse them) reject the third param.
+                Error::EthereumGateway(web3::Error::Rpc(err))
+                    if err.code == ErrorCode::InvalidParams =>
+                {
+                    Error::CallOverridesRejected(err.message)
+                }
+                err => err,
+            })?;
+        let tokens = function.decode_output(&output.0)?;
+        latency.observe();
+        Ok(tokens)
+    }
+
     async fn tx_receipt(
         &self,
         tx_hash: H256,
@@ -260,15 +1188,41 @@ impl EthInterface for QueryClient {
     ) -> Result<Option<TransactionReceipt>, Error> {
         COUNTERS.call[&(Method::TxReceipt, component)].inc();
         let latency = LATENCIES.direct[&Method::TxReceipt].start();
-        let receipt = self.web3.eth().transaction_receipt(tx_hash).await?;
+        if let Some(receipt) = self
+            .receipt_cache
+            .as_ref()
+            .and_then(|cache| cache.get(tx_hash))
+        {
+            latency.observe();
+            return Ok(Some(receipt));
+        }
+
+        let receipt = self
+            .retry(Method::TxReceipt, || {
+                self.web3.eth().transaction_receipt(tx_hash)
+            })
+            .await?;
+        if let (Some(cache), Some(receipt)) = (&self.receipt_cache, &receipt) {
+            self.cache_receipt(cache, receipt, component).await;
+        }
         latency.observe();
         Ok(receipt)
     }
 
-    async fn eth_balance(&self, address: Address, component: &'static str) -> Result<U256, Error> {
+    async fn eth_balance_at(
+        &self,
+        address: Address,
+        block: BlockNumber,
+        component: &'static str,
+    ) -> Result<U256, Error> {
         COUNTERS.call[&(Method::EthBalance, component)].inc();
         let latency = LATENCIES.direct[&Method::EthBalance].start();
-        let balance = self.web3.eth().balance(address, None).await?;
+        let balance = self
+            .retry(Method::EthBalance, || {
+                self.web3.eth().balance(address, Some(block))
+            })
+            .await
+            .map_err(|err| err.for_block(block))?;
         latency.observe();
         Ok(balance)
     }
@@ -276,7 +1230,9 @@ impl EthInterface for QueryClient {
     async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error> {
         COUNTERS.call[&(Method::Logs, component)].inc();
         let latency = LATENCIES.direct[&Method::Logs].start();
-        let logs = self.web3.eth().logs(filter).await?;
+        let logs = self
+            .retry(Method::Logs, || self.web3.eth().logs(filter.clone()))
+            .await?;
         latency.observe();
         Ok(logs)
     }
@@ -288,22 +1244,1304 @@ impl EthInterface for QueryClient {
     ) -> Result<Option<Block<H256>>, Error> {
         COUNTERS.call[&(Method::Block, component)].inc();
         let latency = LATENCIES.direct[&Method::Block].start();
-        let block = self.web3.eth().block(block_id).await?;
+        let block = self
+            .retry(Method::Block, || self.web3.eth().block(block_id))
+            .await
+            .map_err(|err| err.for_block(block_id))?;
+        if let (Some(cache), Some(block)) = (&self.receipt_cache, &block) {
+            if let (Some(number), Some(hash)) = (block.number, block.hash) {
+                cache.observe_block(number.as_u64(), hash);
+            }
+        }
         latency.observe();
         Ok(block)
     }
 
+    async fn block_blob_gas(
+        &self,
+        block_id: BlockId,
+        component: &'static str,
+    ) -> Result<Option<BlockBlobGas>, Error> {
+        COUNTERS.call[&(Method::BlockBlobGas, component)].inc();
+        let latency = LATENCIES.direct[&Method::BlockBlobGas].start();
+        let fetch = || async {
+            self.retry(Method::BlockBlobGas, || {
+                let include_txs = helpers::serialize(&false);
+                let request = match block_id {
+                    BlockId::Hash(hash) => self.web3.transport().execute(
+                        "eth_getBlockByHash",
+                        vec![helpers::serialize(&hash), include_txs],
+                    ),
+                    BlockId::Number(number) => self.web3.transport().execute(
+                        "eth_getBlockByNumber",
+                        vec![helpers::serialize(&number), include_txs],
+                    ),
+                };
+                CallFuture::new(request)
+            })
+            .await
+            .map_err(|err| err.for_block(block_id))
+            .map(|header: Option<BlobGasHeader>| {
+                header.and_then(|header| {
+                    Some(BlockBlobGas::new(
+                        header.blob_gas_used?.as_u64(),
+                        header.excess_blob_gas?.as_u64(),
+                    ))
+                })
+            })
+        };
+        let blob_gas = self
+            .cached(FeeCacheEntry::BlobGas, helpers::serialize(&block_id), fetch)
+            .await?;
+        latency.observe();
+        Ok(blob_gas)
+    }
+
     async fn linea_estimate_gas(&self, req: CallRequest) -> Result<LineaEstimateGas, Error> {
         let latency = LATENCIES.direct[&Method::EstimateGas].start();
         let req = helpers::serialize(&req);
 
-        let res = CallFuture::new(
-            self.web3
-                .transport()
-                .execute("linea_estimateGas", vec![req]),
-        )
-        .await?;
+        let res = self
+            .retry(Method::EstimateGas, || {
+                CallFuture::new(
+                    self.web3
+                        .transport()
+                        .execute(LINEA_ESTIMATE_GAS_METHOD, vec![req.clone()]),
+                )
+            })
+            .await
+            .map_err(|err| match err {
+                Error::EthereumGateway(web3::Error::Rpc(err))
+                    if err.code == ErrorCode::MethodNotFound =>
+                {
+                    Error::UnsupportedMethod(LINEA_ESTIMATE_GAS_METHOD)
+                }
+                err => err,
+            })?;
         latency.observe();
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::{NonZeroU32, NonZeroUsize},
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{Duration, Instant},
+    };
+
+    use assert_matches::assert_matches;
+    use serde_json::{json, Value};
+    use zksync_types::web3::types::FilterBuilder;
+
+    use super::*;
+    use crate::{
+        clients::http::{mock_server::MockRpcServer, BearerToken, RequestStatus},
+        AccountOverride, CallFunctionArgs,
+    };
+
+    #[tokio::test]
+    async fn finding_stuck_nonces() {
+        let sender: Address = "0x976a3fc5d6f7d259ebfb4cc2ae75115475e9867c"
+            .parse()
+            .unwrap();
+        let server = MockRpcServer::spawn(move |method, params| match method {
+            "txpool_contentFrom" => {
+                assert_eq!(params[0], json!(sender));
+                let content: Value =
+                    serde_json::from_str(include_str!("fixtures/txpool_content_geth.json"))
+                        .unwrap();
+                Ok(json!({
+                    "pending": {},
+                    "queued": content["queued"]["0x976A3Fc5d6f7d259EBfb4cc2Ae75115475E9867C"],
+                }))
+            }
+            "eth_getTransactionCount" => {
+                assert_eq!(params[1], "latest");
+                Ok(json!("0x2"))
+            }
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let content = client.txpool_content_from(sender, "test").await.unwrap();
+        assert!(content.pending.is_empty());
+        assert_eq!(content.queued.len(), 2);
+        let stuck_nonces = client.stuck_nonces(sender, "test").await.unwrap();
+        assert_eq!(stuck_nonces, [2.into(), 4.into()]);
+    }
+
+    #[tokio::test]
+    async fn getting_block_with_txs() {
+        let server = MockRpcServer::spawn(|method, params| {
+            assert_eq!(params[1], true);
+            match method {
+                "eth_getBlockByNumber" if params[0] == "0x12a05f2" => Ok(serde_json::from_str(
+                    include_str!("fixtures/block_with_txs_dencun.json"),
+                )
+                .unwrap()),
+                "eth_getBlockByNumber" | "eth_getBlockByHash" => Ok(Value::Null),
+                _ => Err(jsonrpc_core::Error::method_not_found()),
+            }
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let block_id = BlockId::Number(19_531_250.into());
+        let block = client.block_with_txs(block_id, "test").await.unwrap();
+        let block = block.expect("no block");
+        assert_eq!(block.number, Some(19_531_250.into()));
+        assert_eq!(block.transactions.len(), 3);
+        let blob_txs: Vec<_> = block
+            .transactions
+            .iter()
+            .filter(|tx| tx.is_blob_tx())
+            .collect();
+        assert_eq!(blob_txs.len(), 1);
+        assert_eq!(blob_txs[0].blob_versioned_hashes.len(), 2);
+
+        let block_id = BlockId::Hash(H256::repeat_byte(1));
+        let block = client.block_with_txs(block_id, "test").await.unwrap();
+        assert_eq!(block, None);
+    }
+
+    #[tokio::test]
+    async fn unsupported_block_tags() {
+        let server = MockRpcServer::spawn(|method, params| match (method, params[0].as_str()) {
+            ("eth_getBlockByNumber", Some("safe")) => Ok(Value::Null),
+            ("eth_getBlockByNumber", Some("finalized")) => {
+                Err(jsonrpc_core::Error::invalid_params(
+                    "invalid argument 0: hex string without 0x prefix",
+                ))
+            }
+            ("eth_getBalance", _) => match params[1].as_str() {
+                Some("latest") => Ok(json!("0x64")),
+                Some("finalized") => Err(jsonrpc_core::Error {
+                    code: ErrorCode::ServerError(-32000),
+                    message: "finalized block not found".to_owned(),
+                    data: None,
+                }),
+                _ => Err(jsonrpc_core::Error::invalid_params("unexpected block")),
+            },
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let err = client
+            .tagged_block_number(BlockNumber::Safe, "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::UnsupportedBlockTag("safe"));
+        let err = client
+            .tagged_block_number(BlockNumber::Finalized, "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::UnsupportedBlockTag("finalized"));
+
+        let address = Address::repeat_byte(1);
+        let balance = client.eth_balance(address, "test").await.unwrap();
+        assert_eq!(balance, 100.into());
+        let err = client
+            .eth_balance_at(address, BlockNumber::Finalized, "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::UnsupportedBlockTag("finalized"));
+        // Errors for requests without tags are not converted.
+        let err = client
+            .eth_balance_at(address, BlockNumber::Number(1.into()), "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::EthereumGateway(web3::Error::Rpc(_)));
+    }
+
+    #[tokio::test]
+    async fn getting_account_proof() {
+        let fixture: Value =
+            serde_json::from_str(include_str!("../../fixtures/account_proof.json")).unwrap();
+        let state_root: H256 = serde_json::from_value(fixture["stateRoot"].clone()).unwrap();
+        let proof = fixture["proof"].clone();
+        let address: Address = serde_json::from_value(proof["address"].clone()).unwrap();
+        let storage_keys: Vec<H256> = proof["storageProof"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|slot| serde_json::from_value(slot["key"].clone()).unwrap())
+            .collect();
+
+        let expected_params = json!([address, storage_keys, { "blockHash": H256::repeat_byte(1) }]);
+        let server = MockRpcServer::spawn(move |method, params| {
+            assert_eq!(method, "eth_getProof");
+            assert_eq!(*params, expected_params);
+            Ok(proof.clone())
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let block_id = BlockId::Hash(H256::repeat_byte(1));
+        let proof = client
+            .get_proof(address, &storage_keys, block_id, "test")
+            .await
+            .unwrap();
+        assert_eq!(proof.address, address);
+        assert_eq!(proof.storage_proof.len(), storage_keys.len());
+        proof.verify(state_root).unwrap();
+    }
+
+    /// Verifies a proof returned by a real L1 node. The stored fixture is hand-built, so this is
+    /// the only check against node-produced proofs; run with `L1_RPC_URL=<url> cargo test -- --ignored`.
+    #[tokio::test]
+    #[ignore]
+    async fn verifying_account_proof_from_real_node() {
+        let url = std::env::var("L1_RPC_URL").expect("`L1_RPC_URL` must be set");
+        let client = QueryClient::new(&url).unwrap();
+        let block = client
+            .block(BlockId::Number(BlockNumber::Finalized), "test")
+            .await
+            .unwrap()
+            .expect("no finalized block");
+        let block_id = BlockId::Hash(block.hash.unwrap());
+
+        // zkSync Era diamond proxy on mainnet; the first slots are set in the diamond storage.
+        let address: Address = "0x32400084c286cf3e17e7b677ea9583e60a000324"
+            .parse()
+            .unwrap();
+        let storage_keys = [
+            H256::zero(),
+            H256::from_low_u64_be(1),
+            H256::repeat_byte(0xff),
+        ];
+        let proof = client
+            .get_proof(address, &storage_keys, block_id, "test")
+            .await
+            .unwrap();
+        assert_eq!(proof.storage_proof.len(), storage_keys.len());
+        proof.verify(block.state_root).unwrap();
+
+        let missing_address = Address::repeat_byte(0xee);
+        let missing_proof = client
+            .get_proof(missing_address, &[], block_id, "test")
+            .await
+            .unwrap();
+        missing_proof.verify(block.state_root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn fee_cache_is_shared_among_clones() {
+        let server = MockRpcServer::spawn(|method, params| match method {
+            "eth_gasPrice" => Ok(json!("0x3b9aca00")),
+            "eth_feeHistory" => Ok(json!({
+                "oldestBlock": "0x10",
+                "baseFeePerGas": vec!["0x1"; params[0].as_u64().unwrap() as usize + 1],
+                "gasUsedRatio": vec![0.5; params[0].as_u64().unwrap() as usize],
+            })),
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await;
+        let client = QueryClient::new(&server.url())
+            .unwrap()
+            .with_fee_cache(Duration::from_secs(60));
+        let other_client = client.clone();
+
+        let gas_price = client.get_gas_price("test").await.unwrap();
+        assert_eq!(gas_price, 1_000_000_000_u64.into());
+        let gas_price = other_client.get_gas_price("other").await.unwrap();
+        assert_eq!(gas_price, 1_000_000_000_u64.into());
+        assert_eq!(server.http_request_count(), 1);
+
+        // Queries with different params are cached separately.
+        for block_count in [2, 3, 2] {
+            let history = other_client
+                .fee_history(block_count, BlockNumber::Latest, &[], "test")
+                .await
+                .unwrap();
+            assert_eq!(history.gas_used_ratio.len(), block_count);
+        }
+        assert_eq!(server.http_request_count(), 3);
+
+        client.invalidate_fee_cache();
+        other_client.get_gas_price("other").await.unwrap();
+        assert_eq!(server.http_request_count(), 4);
+    }
+
+    #[tokio::test]
+    async fn txpool_content_from_fallback() {
+        let server = MockRpcServer::spawn(|method, _| match method {
+            "txpool_content" => Ok(serde_json::from_str(include_str!(
+                "fixtures/txpool_content_erigon.json"
+            ))
+            .unwrap()),
+            "eth_getTransactionCount" => Ok(json!("0x1")),
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+        let sender: Address = "0x976a3fc5d6f7d259ebfb4cc2ae75115475e9867c"
+            .parse()
+            .unwrap();
+
+        let content = client.txpool_content_from(sender, "test").await.unwrap();
+        assert_eq!(content.pending.keys().copied().collect::<Vec<_>>(), [2]);
+        assert_eq!(content.queued.keys().copied().collect::<Vec<_>>(), [4]);
+        let stuck_nonces = client.stuck_nonces(sender, "test").await.unwrap();
+        assert_eq!(stuck_nonces, [1.into(), 3.into()]);
+
+        let content = client
+            .txpool_content_from(Address::zero(), "test")
+            .await
+            .unwrap();
+        assert_eq!(content, TxPoolContentFrom::default());
+
+        let pending_hash = client.pending_tx_hash(sender, 2.into(), "test").await;
+        assert_eq!(
+            pending_hash.unwrap(),
+            Some(content_hash(&client, sender, 2).await)
+        );
+        let queued_hash = client.pending_tx_hash(sender, 4.into(), "test").await;
+        assert_eq!(
+            queued_hash.unwrap(),
+            Some(content_hash(&client, sender, 4).await)
+        );
+        let missing_hash = client.pending_tx_hash(sender, 3.into(), "test").await;
+        assert_eq!(missing_hash.unwrap(), None);
+    }
+
+    async fn content_hash(client: &QueryClient, sender: Address, nonce: u64) -> H256 {
+        let content = client.txpool_content_from(sender, "test").await.unwrap();
+        let tx = content
+            .pending
+            .get(&nonce)
+            .or_else(|| content.queued.get(&nonce));
+        tx.unwrap().hash
+    }
+
+    #[tokio::test]
+    async fn pending_tx_hash_without_mempool_methods() {
+        let server =
+            MockRpcServer::spawn(|_, _| Err(jsonrpc_core::Error::method_not_found())).await;
+        let client = QueryClient::new(&server.url()).unwrap();
+        let hash = client
+            .pending_tx_hash(Address::repeat_byte(1), 0.into(), "test")
+            .await
+            .unwrap();
+        assert_eq!(hash, None);
+    }
+
+    fn parse_block_number(value: &Value) -> u64 {
+        let hex = value.as_str().unwrap().strip_prefix("0x").unwrap();
+        u64::from_str_radix(hex, 16).unwrap()
+    }
+
+    fn mock_log(block_number: u64) -> Value {
+        json!({
+            "address": Address::repeat_byte(1),
+            "topics": [],
+            "data": "0x",
+            "blockHash": H256::from_low_u64_be(block_number),
+            "blockNumber": U64::from(block_number),
+            "transactionHash": H256::repeat_byte(2),
+            "transactionIndex": "0x0",
+            "logIndex": "0x0",
+        })
+    }
+
+    #[tokio::test]
+    async fn splitting_logs_range() {
+        let server = MockRpcServer::spawn(|method, params| match method {
+            "eth_blockNumber" => Ok(json!("0x63")),
+            "eth_getLogs" => {
+                let from = parse_block_number(&params[0]["fromBlock"]);
+                let to = parse_block_number(&params[0]["toBlock"]);
+                if to - from >= 10 {
+                    return Err(jsonrpc_core::Error {
+                        code: ErrorCode::ServerError(-32005),
+                        message: "query returned more than 10000 results".to_owned(),
+                        data: None,
+                    });
+                }
+                // Emulate a node returning logs in the reverse order, and including the log
+                // from the block preceding the range.
+                let logs = (from.saturating_sub(1)..=to).rev().map(mock_log);
+                Ok(logs.collect())
+            }
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+        let splits_before = COUNTERS.logs_range_splits.get();
+
+        let filter = FilterBuilder::default()
+            .from_block(BlockNumber::Earliest)
+            .to_block(BlockNumber::Latest)
+            .build();
+        let logs = client.get_logs_paginated(filter, "test").await.unwrap();
+        let block_numbers: Vec<_> = logs
+            .iter()
+            .map(|log| log.block_number.unwrap().as_u64())
+            .collect();
+        assert_eq!(block_numbers, (0..100).collect::<Vec<_>>());
+        // Metrics are global, so other tests may increase the counter concurrently.
+        assert!(COUNTERS.logs_range_splits.get() > splits_before);
+
+        let filter = FilterBuilder::default()
+            .from_block(BlockNumber::Number(10.into()))
+            .to_block(BlockNumber::Number(15.into()))
+            .build();
+        let requests_before = server.http_request_count();
+        let logs = client.get_logs_paginated(filter, "test").await.unwrap();
+        // The range isn't split, so the response is returned as is.
+        assert_eq!(logs.len(), 7);
+        assert_eq!(server.http_request_count(), requests_before + 1);
+    }
+
+    #[tokio::test]
+    async fn logs_range_splitting_is_bounded() {
+        let server = MockRpcServer::spawn(|method, _| {
+            assert_eq!(method, "eth_getLogs");
+            Err(jsonrpc_core::Error {
+                code: ErrorCode::InternalError,
+                message: "Log response size exceeded".to_owned(),
+                data: None,
+            })
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+        let filter = FilterBuilder::default()
+            .from_block(BlockNumber::Number(0.into()))
+            .to_block(BlockNumber::Number((1_u64 << 20).into()))
+            .build();
+
+        let err = client.get_logs_paginated(filter, "test").await.unwrap_err();
+        assert!(err.is_logs_limit_error(), "{err:?}");
+        assert!(!err.is_transient(), "{err:?}");
+        assert_eq!(server.http_request_count(), MAX_LOGS_SPLIT_DEPTH + 1);
+
+        // Filters without a block range cannot be split.
+        let filter = FilterBuilder::default()
+            .block_hash(H256::repeat_byte(1))
+            .build();
+        let err = client.get_logs_paginated(filter, "test").await.unwrap_err();
+        assert!(err.is_logs_limit_error(), "{err:?}");
+        assert_eq!(server.http_request_count(), MAX_LOGS_SPLIT_DEPTH + 2);
+    }
+
+    #[tokio::test]
+    async fn sending_custom_headers() {
+        let server = MockRpcServer::spawn(|method, _| match method {
+            "eth_blockNumber" => Ok(json!("0x64")),
+            "eth_gasPrice" => Ok(json!("0x3b9aca00")),
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await;
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-project-id", "test-project".parse().unwrap());
+        let options = HttpOptions::default()
+            .with_headers(headers)
+            .with_bearer_token(BearerToken::new("secret-token"));
+        let client = QueryClient::new_with_options(&server.url(), &options).unwrap();
+
+        assert_eq!(client.block_number("test").await.unwrap(), 100.into());
+        let mut batch = client.batch();
+        let calls = [batch.block_number(), batch.block_number()];
+        let gas_price = batch.gas_price();
+        let mut response = batch.execute("test").await.unwrap();
+        for call in calls {
+            assert_eq!(response.take(call).unwrap(), 100.into());
+        }
+        assert_eq!(response.take(gas_price).unwrap(), 1_000_000_000.into());
+
+        let request_headers = server.request_headers();
+        assert_eq!(request_headers.len(), 2);
+        for headers in &request_headers {
+            assert_eq!(headers["authorization"], "Bearer secret-token");
+            assert_eq!(headers["x-project-id"], "test-project");
+        }
+
+        let client_debug = format!("{client:?}");
+        assert!(!client_debug.contains("secret-token"), "{client_debug}");
+        assert!(!client_debug.contains("test-project"), "{client_debug}");
+
+        let unreachable_client =
+            QueryClient::new_with_options("http://127.0.0.1:1", &options).unwrap();
+        let err = unreachable_client.block_number("test").await.unwrap_err();
+        let err = format!("{err} / {err:?}");
+        assert!(!err.contains("secret-token"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn getting_block_blob_gas() {
+        let server = MockRpcServer::spawn(|method, params| {
+            assert_eq!(method, "eth_getBlockByNumber");
+            assert_eq!(params[1], false);
+            Ok(match params[0].as_str().unwrap() {
+                "0x1" => json!({ "number": "0x1", "baseFeePerGas": "0x7" }),
+                "0x2" => json!({
+                    "number": "0x2",
+                    "baseFeePerGas": "0x7",
+                    "blobGasUsed": "0x20000",
+                    "excessBlobGas": "0x60000",
+                }),
+                _ => Value::Null,
+            })
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let blob_gas = client
+            .block_blob_gas(BlockId::Number(2.into()), "test")
+            .await
+            .unwrap();
+        assert_eq!(blob_gas, Some(BlockBlobGas::new(0x20000, 0x60000)));
+        let pre_cancun_blob_gas = client
+            .block_blob_gas(BlockId::Number(1.into()), "test")
+            .await
+            .unwrap();
+        assert_eq!(pre_cancun_blob_gas, None);
+        let missing_block_blob_gas = client
+            .block_blob_gas(BlockId::Number(3.into()), "test")
+            .await
+            .unwrap();
+        assert_eq!(missing_block_blob_gas, None);
+    }
+
+    #[tokio::test]
+    async fn getting_fee_history() {
+        let server = MockRpcServer::spawn(|method, params| {
+            assert_eq!(method, "eth_feeHistory");
+            assert_eq!(*params, json!(["0x2", "latest", [50.0]]));
+            Ok(json!({
+                "oldestBlock": "0x63",
+                "baseFeePerGas": ["0x7", "0x8", "0x9"],
+                "gasUsedRatio": [0.5, 0.0],
+                "reward": [["0x3b9aca00"], ["0x0"]],
+            }))
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let history = client
+            .fee_history(2, BlockNumber::Latest, &[50.0], "test")
+            .await
+            .unwrap();
+        assert_eq!(history.oldest_block, BlockNumber::Number(0x63.into()));
+        assert_eq!(
+            history.base_fee_per_gas,
+            [U256::from(7), U256::from(8), U256::from(9)]
+        );
+        assert_eq!(history.gas_used_ratio, [0.5, 0.0]);
+        let expected_reward: Vec<Vec<U256>> = vec![vec![1_000_000_000.into()], vec![0.into()]];
+        assert_eq!(history.reward, Some(expected_reward));
+    }
+
+    #[tokio::test]
+    async fn retrying_rate_limited_calls() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let server_calls = calls.clone();
+        let server = MockRpcServer::spawn(move |_method, _params| {
+            if server_calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(jsonrpc_core::Error {
+                    code: ErrorCode::ServerError(-32005),
+                    message: "limit exceeded".to_owned(),
+                    data: None,
+                })
+            } else {
+                Ok(json!("0x64"))
+            }
+        })
+        .await;
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            jitter: 0.0,
+        };
+
+        let client = QueryClient::new(&server.url()).unwrap();
+        client.block_number("test").await.unwrap_err();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        calls.store(0, Ordering::SeqCst);
+        let client = client.with_retries(policy);
+        let block_number = client.block_number("test").await.unwrap();
+        assert_eq!(block_number, 100.into());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn retried_transaction_sending_treats_known_transactions_as_sent() {
+        let raw_tx = RawTransactionBytes(vec![1, 2, 3]);
+        let tx_hash = H256(web3::signing::keccak256(&raw_tx.0));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let server_calls = calls.clone();
+        let server = MockRpcServer::spawn(move |method, _| match method {
+            "eth_sendRawTransaction" => {
+                if server_calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                    // The transaction is accepted, but the response is too slow.
+                    std::thread::sleep(Duration::from_millis(500));
+                    Ok(json!(tx_hash))
+                } else {
+                    Err(jsonrpc_core::Error {
+                        code: ErrorCode::ServerError(-32000),
+                        message: "already known".to_owned(),
+                        data: None,
+                    })
+                }
+            }
+            _ => panic!("unexpected call: {method}"),
+        })
+        .await;
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            jitter: 0.0,
+        };
+        let client = QueryClient::new(&server.url())
+            .unwrap()
+            .with_timeouts(TimeoutPolicy::new(Duration::from_millis(100)));
+
+        let err = client.send_raw_tx(raw_tx.clone()).await.unwrap_err();
+        assert_matches!(err, Error::RequestTimeout { .. });
+        // Without retries, the error is returned as is.
+        let err = client.send_raw_tx(raw_tx.clone()).await.unwrap_err();
+        assert!(err.is_already_known_error(), "{err}");
+
+        calls.store(0, Ordering::SeqCst);
+        let client = client.with_retries(policy);
+        assert_eq!(client.send_raw_tx(raw_tx).await.unwrap(), tx_hash);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    async fn spawn_node_with_chain_id(chain_id: u64) -> MockRpcServer {
+        MockRpcServer::spawn(move |method, _| match method {
+            "eth_chainId" => Ok(json!(U64::from(chain_id))),
+            "eth_blockNumber" => Ok(json!("0x1")),
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn updating_node_url() {
+        let old_server = spawn_node_with_chain_id(9).await;
+        let client = QueryClient::new(&old_server.url()).unwrap();
+        let client_clone = client.clone();
+        let updates = |result| COUNTERS.endpoint_updates[&result].get();
+        let (switched_count, mismatch_count, failed_count) = (
+            updates(EndpointUpdateResult::Switched),
+            updates(EndpointUpdateResult::ChainIdMismatch),
+            updates(EndpointUpdateResult::Failed),
+        );
+
+        // Node with another chain ID must be rejected.
+        let wrong_server = spawn_node_with_chain_id(1).await;
+        let err = client
+            .update_url(&wrong_server.url(), L1ChainId(9), "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::ChainIdMismatch { expected, actual } if expected.0 == 9 && actual.0 == 1);
+        assert!(updates(EndpointUpdateResult::ChainIdMismatch) > mismatch_count);
+        client.block_number("test").await.unwrap();
+        assert_eq!(old_server.http_request_count(), 1);
+        assert_eq!(wrong_server.http_request_count(), 1);
+
+        // Unreachable nodes and invalid URLs must be rejected as well.
+        client
+            .update_url("http://127.0.0.1:1", L1ChainId(9), "test")
+            .await
+            .unwrap_err();
+        client
+            .update_url("not a URL", L1ChainId(9), "test")
+            .await
+            .unwrap_err();
+        assert!(updates(EndpointUpdateResult::Failed) > failed_count);
+        client_clone.block_number("test").await.unwrap();
+        assert_eq!(old_server.http_request_count(), 2);
+
+        let new_server = spawn_node_with_chain_id(9).await;
+        client
+            .update_url(&new_server.url(), L1ChainId(9), "test")
+            .await
+            .unwrap();
+        assert!(updates(EndpointUpdateResult::Switched) > switched_count);
+        // The URL must be updated for all clones of the client.
+        client.block_number("test").await.unwrap();
+        client_clone.block_number("test").await.unwrap();
+        assert_eq!(old_server.http_request_count(), 2);
+        assert_eq!(new_server.http_request_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn respecting_retry_after_from_node() {
+        let server = MockRpcServer::spawn(|_method, _params| Ok(json!("0x64"))).await;
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_secs(2),
+            jitter: 0.0,
+        };
+        let client = QueryClient::new(&server.url())
+            .unwrap()
+            .with_retries(policy);
+        // Metrics are global, so other tests may increase counters concurrently.
+        let rate_limited_count = || COUNTERS.rate_limited[&Method::BlockNumber].get();
+        let initial_rate_limited_count = rate_limited_count();
+
+        // Without `Retry-After`, the request is retried according to the policy.
+        server.respond_with_status("429 Too Many Requests", &[]);
+        let started_at = Instant::now();
+        let block_number = client.block_number("test").await.unwrap();
+        assert_eq!(block_number, 100.into());
+        assert!(started_at.elapsed() < Duration::from_secs(1));
+        assert_eq!(server.http_request_count(), 2);
+
+        server.respond_with_status("429 Too Many Requests", &[("Retry-After", "1")]);
+        let started_at = Instant::now();
+        let block_number = client.block_number("test").await.unwrap();
+        assert_eq!(block_number, 100.into());
+        assert!(started_at.elapsed() >= Duration::from_secs(1));
+        assert_eq!(server.http_request_count(), 4);
+
+        // The requested delay exceeds the max delay in the policy, so the request is not retried.
+        server.respond_with_status("429 Too Many Requests", &[("Retry-After", "60")]);
+        let err = client.block_number("test").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::RateLimited);
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(60)));
+        assert_eq!(server.http_request_count(), 5);
+
+        assert!(rate_limited_count() >= initial_rate_limited_count + 3);
+    }
+
+    #[tokio::test]
+    async fn rate_limiting_concurrent_calls() {
+        const CALL_COUNT: u32 = 6;
+
+        let server = MockRpcServer::spawn(|_method, _params| Ok(json!("0x64"))).await;
+        let limit = RateLimit::per_second(NonZeroU32::new(20).unwrap())
+            .with_burst(NonZeroU32::new(2).unwrap());
+        let client = QueryClient::new(&server.url())
+            .unwrap()
+            .with_rate_limit(limit);
+
+        let started_at = Instant::now();
+        let calls = (0..CALL_COUNT).map(|i| {
+            // Clones must share the limiter.
+            let client = client.clone();
+            async move {
+                if i % 2 == 0 {
+                    client.block_number("test").await
+                } else {
+                    client
+                        .get_gas_price("test")
+                        .await
+                        .map(|price| price.as_u64().into())
+                }
+            }
+        });
+        let results = futures::future::try_join_all(calls).await.unwrap();
+        let elapsed = started_at.elapsed();
+
+        assert!(results.iter().all(|&number| number == 100.into()));
+        assert_eq!(server.http_request_count(), CALL_COUNT as usize);
+        // The first `burst` calls are sent immediately, and the remaining ones at the sustained rate.
+        let min_elapsed = Duration::from_millis(50) * (CALL_COUNT - 2);
+        assert!(elapsed >= min_elapsed, "{elapsed:?}");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn limiting_in_flight_requests() {
+        const CALL_COUNT: u32 = 6;
+        const MAX_IN_FLIGHT_REQUESTS: usize = 2;
+        const RESPONSE_DELAY: Duration = Duration::from_millis(50);
+
+        let in_flight_requests = Arc::new(AtomicUsize::new(0));
+        let max_observed_requests = Arc::new(AtomicUsize::new(0));
+        let server = MockRpcServer::spawn({
+            let in_flight_requests = in_flight_requests.clone();
+            let max_observed_requests = max_observed_requests.clone();
+            move |_method, _params| {
+                let count = in_flight_requests.fetch_add(1, Ordering::SeqCst) + 1;
+                max_observed_requests.fetch_max(count, Ordering::SeqCst);
+                // The server handler is synchronous; blocking is fine since the runtime is multi-threaded.
+                std::thread::sleep(RESPONSE_DELAY);
+                in_flight_requests.fetch_sub(1, Ordering::SeqCst);
+                Ok(json!("0x64"))
+            }
+        })
+        .await;
+        let client = QueryClient::new(&server.url())
+            .unwrap()
+            .with_max_in_flight_requests(MAX_IN_FLIGHT_REQUESTS);
+
+        let started_at = Instant::now();
+        let calls = (0..CALL_COUNT).map(|_| {
+            // Clones must share the limit.
+            let client = client.clone();
+            async move { client.block_number("test").await }
+        });
+        let results = futures::future::try_join_all(calls).await.unwrap();
+        let elapsed = started_at.elapsed();
+
+        assert!(results.iter().all(|&number| number == 100.into()));
+        assert_eq!(server.http_request_count(), CALL_COUNT as usize);
+        let max_observed_requests = max_observed_requests.load(Ordering::SeqCst);
+        assert!(
+            max_observed_requests <= MAX_IN_FLIGHT_REQUESTS,
+            "{max_observed_requests}"
+        );
+        let min_elapsed = RESPONSE_DELAY * CALL_COUNT / MAX_IN_FLIGHT_REQUESTS as u32;
+        assert!(elapsed >= min_elapsed, "{elapsed:?}");
+
+        // Batches must acquire a permit as well.
+        let mut batch = client.batch();
+        let call = batch.block_number();
+        let mut response = batch.execute("test").await.unwrap();
+        assert_eq!(response.take(call).unwrap(), 100.into());
+    }
+
+    #[tokio::test]
+    async fn zero_in_flight_requests_means_no_limit() {
+        let server = MockRpcServer::spawn(|_method, _params| Ok(json!("0x64"))).await;
+        let client = QueryClient::new(&server.url())
+            .unwrap()
+            .with_max_in_flight_requests(0);
+        assert!(client.request_limiter.is_none());
+        let block_number = client.block_number("test").await.unwrap();
+        assert_eq!(block_number, 100.into());
+    }
+
+    #[tokio::test]
+    async fn linea_estimate_gas_success() {
+        let server = MockRpcServer::spawn(|method, _params| {
+            assert_eq!(method, LINEA_ESTIMATE_GAS_METHOD);
+            Ok(json!({
+                "baseFeePerGas": "0x7",
+                "gasLimit": "0x5208",
+                "priorityFeePerGas": "0x3b9aca00",
+            }))
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let estimate = client
+            .linea_estimate_gas(CallRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            estimate,
+            LineaEstimateGas {
+                base_fee_per_gas: 7.into(),
+                gas_limit: 21_000.into(),
+                priority_fee_per_gas: 1_000_000_000.into(),
+            }
+        );
+        assert_eq!(server.http_request_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn linea_estimate_gas_unsupported() {
+        let server = MockRpcServer::spawn(|method, _params| {
+            Err(jsonrpc_core::Error {
+                code: ErrorCode::MethodNotFound,
+                message: format!("the method {method} does not exist/is not available"),
+                data: None,
+            })
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let err = client
+            .linea_estimate_gas(CallRequest::default())
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::UnsupportedMethod(LINEA_ESTIMATE_GAS_METHOD));
+    }
+
+    #[tokio::test]
+    async fn estimating_gas_with_buffer() {
+        let estimate_count = Arc::new(AtomicUsize::new(0));
+        let server = MockRpcServer::spawn({
+            let estimate_count = estimate_count.clone();
+            move |method, params| match method {
+                "eth_estimateGas" => {
+                    assert!(params[0].get("gas").is_none(), "{params}");
+                    // Emulate state drift: the required gas grows with each estimate.
+                    let count = estimate_count.fetch_add(1, Ordering::SeqCst) as u64;
+                    Ok(json!(format!("{:#x}", 100_000 + count * 10_000)))
+                }
+                "eth_call" => {
+                    let gas: U256 = serde_json::from_value(params[0]["gas"].clone()).unwrap();
+                    if gas < 150_000.into() {
+                        Err(jsonrpc_core::Error {
+                            code: ErrorCode::ServerError(-32000),
+                            message: "out of gas".to_owned(),
+                            data: None,
+                        })
+                    } else {
+                        Ok(json!("0x"))
+                    }
+                }
+                _ => Err(jsonrpc_core::Error::method_not_found()),
+            }
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+        let call = CallRequest {
+            gas: Some(21_000.into()),
+            ..CallRequest::default()
+        };
+
+        // Estimates: 100_000 (+10% = 110_000), 110_000 (+20% = 132_000), 120_000 (+40% = 168_000)
+        let gas_limit = client
+            .estimate_gas_with_buffer(call.clone(), 10, 5, "test")
+            .await
+            .unwrap();
+        assert_eq!(gas_limit, 168_000.into());
+        assert_eq!(estimate_count.load(Ordering::SeqCst), 3);
+
+        estimate_count.store(0, Ordering::SeqCst);
+        let err = client
+            .estimate_gas_with_buffer(call.clone(), 10, 1, "test")
+            .await
+            .unwrap_err();
+        assert!(err.is_out_of_gas_error(), "{err}");
+        assert_eq!(estimate_count.load(Ordering::SeqCst), 2);
+
+        // The buffer is capped at 200%.
+        estimate_count.store(0, Ordering::SeqCst);
+        let gas_limit = client
+            .estimate_gas_with_buffer(call, 1_000, 0, "test")
+            .await
+            .unwrap();
+        assert_eq!(gas_limit, 300_000.into());
+    }
+
+    #[tokio::test]
+    async fn estimating_gas_with_buffer_does_not_retry_reverts() {
+        let server = MockRpcServer::spawn(|method, _params| match method {
+            "eth_estimateGas" => Ok(json!("0x5208")),
+            "eth_call" => Err(jsonrpc_core::Error {
+                code: ErrorCode::ServerError(3),
+                message: "execution reverted: out of gas".to_owned(),
+                data: Some(json!(
+                    "0x4e487b710000000000000000000000000000000000000000000000000000000000000011"
+                )),
+            }),
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+        let err = client
+            .estimate_gas_with_buffer(CallRequest::default(), 10, 5, "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::EthereumGateway(web3::Error::Rpc(_)));
+        assert!(!err.is_out_of_gas_error());
+        assert_eq!(server.http_request_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn getting_max_priority_fee() {
+        let server = MockRpcServer::spawn(|method, params| {
+            assert_eq!(method, MAX_PRIORITY_FEE_PER_GAS_METHOD);
+            assert_eq!(*params, json!([]));
+            Ok(json!("0x3b9aca00"))
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+        let fee = client.max_priority_fee_per_gas("test").await.unwrap();
+        assert_eq!(fee, 1_000_000_000.into());
+
+        let server = MockRpcServer::spawn(|method, _params| {
+            Err(jsonrpc_core::Error {
+                code: ErrorCode::MethodNotFound,
+                message: format!("the method {method} does not exist/is not available"),
+                data: None,
+            })
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+        let err = client.max_priority_fee_per_gas("test").await.unwrap_err();
+        assert_matches!(
+            err,
+            Error::UnsupportedMethod(MAX_PRIORITY_FEE_PER_GAS_METHOD)
+        );
+    }
+
+    #[tokio::test]
+    async fn linea_estimate_gas_other_errors_are_not_mapped() {
+        let server = MockRpcServer::spawn(|_method, _params| {
+            Err(jsonrpc_core::Error::invalid_params("bogus call"))
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let err = client
+            .linea_estimate_gas(CallRequest::default())
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::EthereumGateway(web3::Error::Rpc(_)));
+    }
+
+    fn test_receipt(tx_hash: H256, block_number: u64, block_hash: H256) -> Value {
+        serde_json::to_value(TransactionReceipt {
+            transaction_hash: tx_hash,
+            block_number: Some(block_number.into()),
+            block_hash: Some(block_hash),
+            status: Some(1.into()),
+            ..TransactionReceipt::default()
+        })
+        .unwrap()
+    }
+
+    fn test_block(number: u64, hash: H256) -> Value {
+        serde_json::to_value(Block::<H256> {
+            number: Some(number.into()),
+            hash: Some(hash),
+            ..Block::default()
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn caching_finalized_receipts() {
+        const FINALIZED_TX: H256 = H256::repeat_byte(1);
+        const PENDING_TX: H256 = H256::repeat_byte(2);
+
+        let canonical_hash = Arc::new(std::sync::Mutex::new(H256::repeat_byte(0xa)));
+        let server_hash = canonical_hash.clone();
+        let server = MockRpcServer::spawn(move |method, params| {
+            let block_hash = *server_hash.lock().unwrap();
+            Ok(match (method, &params[0]) {
+                ("eth_getTransactionReceipt", tx_hash) if *tx_hash == json!(FINALIZED_TX) => {
+                    test_receipt(FINALIZED_TX, 5, block_hash)
+                }
+                ("eth_getTransactionReceipt", _) => {
+                    test_receipt(PENDING_TX, 20, H256::repeat_byte(0xb))
+                }
+                ("eth_getBlockByNumber", tag) if *tag == "finalized" => {
+                    test_block(16, H256::repeat_byte(0xf))
+                }
+                ("eth_getBlockByNumber", _) => test_block(5, block_hash),
+                _ => panic!("unexpected call: {method}"),
+            })
+        })
+        .await;
+        let config = ReceiptCacheConfig {
+            finalized_block_refresh_interval: Duration::ZERO,
+            ..ReceiptCacheConfig::new(NonZeroUsize::new(10).unwrap())
+        };
+        let client = QueryClient::new(&server.url())
+            .unwrap()
+            .with_receipt_cache(config);
+
+        // The first call fetches the receipt and the finalized block.
+        let receipt = client.tx_receipt(FINALIZED_TX, "test").await.unwrap();
+        assert_eq!(receipt.unwrap().block_hash, Some(H256::repeat_byte(0xa)));
+        assert_eq!(server.http_request_count(), 2);
+        let receipt = client.tx_receipt(FINALIZED_TX, "test").await.unwrap();
+        assert_eq!(receipt.unwrap().block_hash, Some(H256::repeat_byte(0xa)));
+        assert_eq!(server.http_request_count(), 2);
+        let status = client.get_tx_status(FINALIZED_TX, "test").await.unwrap();
+        assert!(status.unwrap().success);
+        assert_eq!(server.http_request_count(), 2);
+
+        // Receipts from non-finalized blocks are not cached.
+        for _ in 0..2 {
+            client.tx_receipt(PENDING_TX, "test").await.unwrap();
+        }
+        assert_eq!(server.http_request_count(), 6);
+
+        // Observing a block with the same hash doesn't invalidate the cache.
+        client
+            .block(BlockId::Number(5.into()), "test")
+            .await
+            .unwrap();
+        client.tx_receipt(FINALIZED_TX, "test").await.unwrap();
+        assert_eq!(server.http_request_count(), 7);
+
+        // Simulate a reorg and observe the reorganized block.
+        *canonical_hash.lock().unwrap() = H256::repeat_byte(0xc);
+        client
+            .block(BlockId::Number(5.into()), "test")
+            .await
+            .unwrap();
+        assert_eq!(server.http_request_count(), 8);
+        let receipt = client.tx_receipt(FINALIZED_TX, "test").await.unwrap();
+        assert_eq!(receipt.unwrap().block_hash, Some(H256::repeat_byte(0xc)));
+        assert_eq!(server.http_request_count(), 9);
+        // The new receipt is cached again.
+        client.tx_receipt(FINALIZED_TX, "test").await.unwrap();
+        assert_eq!(server.http_request_count(), 9);
+    }
+
+    #[tokio::test]
+    async fn bypassing_receipt_cache() {
+        let server = MockRpcServer::spawn(|method, params| {
+            Ok(match method {
+                "eth_getTransactionReceipt" => {
+                    let tx_hash = serde_json::from_value(params[0].clone()).unwrap();
+                    test_receipt(tx_hash, 5, H256::repeat_byte(0xa))
+                }
+                _ => test_block(16, H256::repeat_byte(0xf)),
+            })
+        })
+        .await;
+        let config = ReceiptCacheConfig {
+            bypass: true,
+            ..ReceiptCacheConfig::new(NonZeroUsize::new(10).unwrap())
+        };
+        let client = QueryClient::new(&server.url())
+            .unwrap()
+            .with_receipt_cache(config);
+
+        for _ in 0..3 {
+            client.tx_receipt(H256::zero(), "test").await.unwrap();
+        }
+        assert_eq!(server.http_request_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn reporting_request_metrics() {
+        let server = MockRpcServer::spawn(|method, _| match method {
+            "eth_blockNumber" => Ok(json!("0x10")),
+            "eth_gasPrice" => Err(jsonrpc_core::Error::internal_error()),
+            "eth_chainId" => Ok(json!({ "not": "a number" })),
+            _ => panic!("unexpected call: {method}"),
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+        let count =
+            |method: Method, status: RequestStatus| COUNTERS.requests[&(method, status)].get();
+        // Metrics are global, so other tests may increase counters concurrently.
+        let success_count = count(Method::BlockNumber, RequestStatus::Success);
+        let rpc_error_count = count(Method::GetGasPrice, RequestStatus::RpcError);
+        let decode_error_count = count(Method::ChainId, RequestStatus::Decode);
+        let error_count = |method: Method, kind: ErrorKind| COUNTERS.errors[&(method, kind)].get();
+        let rpc_server_error_count = error_count(Method::GetGasPrice, ErrorKind::RpcServer);
+        let decode_kind_count = error_count(Method::ChainId, ErrorKind::Decode);
+
+        client.block_number("test").await.unwrap();
+        let err = client.get_gas_price("test").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::RpcServer);
+        let err = client.fetch_chain_id("test").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Decode);
+
+        assert!(count(Method::BlockNumber, RequestStatus::Success) > success_count);
+        assert!(count(Method::GetGasPrice, RequestStatus::RpcError) > rpc_error_count);
+        assert!(count(Method::ChainId, RequestStatus::Decode) > decode_error_count);
+        assert!(error_count(Method::GetGasPrice, ErrorKind::RpcServer) > rpc_server_error_count);
+        assert!(error_count(Method::ChainId, ErrorKind::Decode) > decode_kind_count);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn per_method_timeouts() {
+        let server = MockRpcServer::spawn(|method, _| match method {
+            "eth_getLogs" => {
+                std::thread::sleep(Duration::from_millis(500));
+                Ok(json!([]))
+            }
+            "eth_blockNumber" => Ok(json!("0x10")),
+            _ => panic!("unexpected call: {method}"),
+        })
+        .await;
+        let policy = TimeoutPolicy::new(Duration::from_secs(5))
+            .with_method_timeout("eth_getLogs", Duration::from_millis(100));
+        let client = QueryClient::new(&server.url())
+            .unwrap()
+            .with_timeouts(policy);
+
+        let err = client.logs(Filter::default(), "test").await.unwrap_err();
+        assert_matches!(
+            err,
+            Error::RequestTimeout { method: "eth_getLogs", timeout }
+                if timeout == Duration::from_millis(100)
+        );
+        assert!(err.is_transient());
+        let block_number = client.block_number("test").await.unwrap();
+        assert_eq!(block_number, 0x10.into());
+
+        let policy = TimeoutPolicy::new(Duration::from_millis(100))
+            .with_method_timeout("eth_getLogs", Duration::from_secs(5));
+        let client = QueryClient::new(&server.url())
+            .unwrap()
+            .with_timeouts(policy);
+        let logs = client.logs(Filter::default(), "test").await.unwrap();
+        assert!(logs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn calling_contract_with_overrides() {
+        const TOKEN: Address = Address::repeat_byte(0x11);
+        const HOLDER: Address = Address::repeat_byte(0x22);
+
+        let server = MockRpcServer::spawn(|method, params| {
+            assert_eq!(method, "eth_call");
+            assert_eq!(params[0]["to"], json!(TOKEN));
+            assert_eq!(params[1], "latest");
+            let expected_overrides = json!({
+                format!("{TOKEN:?}"): {
+                    "balance": "0x2a",
+                    "stateDiff": { format!("{:?}", H256::zero()): format!("{:?}", H256::repeat_byte(1)) },
+                },
+            });
+            if params[2] == expected_overrides {
+                Ok(json!(format!("0x{:0>64x}", 42)))
+            } else {
+                Err(jsonrpc_core::Error {
+                    code: ErrorCode::InvalidParams,
+                    message: "invalid argument 2".to_owned(),
+                    data: None,
+                })
+            }
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let call = CallFunctionArgs::new("balanceOf", HOLDER)
+            .for_contract(TOKEN, zksync_contracts::erc20_contract());
+        let account = AccountOverride {
+            balance: Some(42.into()),
+            state_diff: Some([(H256::zero(), H256::repeat_byte(1))].into()),
+            ..AccountOverride::default()
+        };
+        let overrides = CallOverrides::default().with_account(TOKEN, account);
+        let output = client
+            .call_contract_function_with_overrides(call.clone(), overrides)
+            .await
+            .unwrap();
+        assert_eq!(output, [ethabi::Token::Uint(42.into())]);
+
+        let overrides = CallOverrides::default().with_account(TOKEN, AccountOverride::default());
+        let err = client
+            .call_contract_function_with_overrides(call, overrides)
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::CallOverridesRejected(message) if message == "invalid argument 2");
+    }
+
+    #[tokio::test]
+    async fn tracing_transaction() {
+        let tx_hash = H256::repeat_byte(0x42);
+        let server = MockRpcServer::spawn(move |method, params| {
+            assert_eq!(method, "debug_traceTransaction");
+            assert_eq!(params[0], json!(tx_hash));
+            assert_eq!(params[1]["tracer"], "callTracer");
+            Ok(serde_json::from_str(include_str!("fixtures/call_tracer.json")).unwrap())
+        })
+        .await;
+        let client = QueryClient::new(&server.url()).unwrap();
+
+        let trace = client
+            .trace_transaction(tx_hash, TracerConfig::CALL_TRACER, "test")
+            .await
+            .unwrap();
+        let TransactionTrace::Call(trace) = trace else {
+            panic!("unexpected trace: {trace:?}");
+        };
+        let failed_call = trace.innermost_failed_call().unwrap();
+        assert_eq!(failed_call.revert_reason.as_deref(), Some("i"));
+    }
+}
@@ -0,0 +1,72 @@
+//! Client-side rate limiting for HTTP clients.
+
+use std::{fmt, num::NonZeroU32, sync::Arc};
+
+use governor::{
+    clock::DefaultClock,
+    middleware::NoOpMiddleware,
+    state::{InMemoryState, NotKeyed},
+    Quota, RateLimiter,
+};
+
+use super::{Method, LATENCIES};
+
+/// Limit on the rate of requests sent to the Ethereum node by a [`QueryClient`].
+///
+/// [`QueryClient`]: super::QueryClient
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Sustained number of requests per second.
+    pub requests_per_second: NonZeroU32,
+    /// Maximum number of requests that can be sent at once after the client was idle.
+    pub burst: NonZeroU32,
+}
+
+impl RateLimit {
+    /// Creates a limit with burst equal to the number of requests per second.
+    pub fn per_second(requests_per_second: NonZeroU32) -> Self {
+        Self {
+            requests_per_second,
+            burst: requests_per_second,
+        }
+    }
+
+    /// Sets the maximum burst size.
+    pub fn with_burst(mut self, burst: NonZeroU32) -> Self {
+        self.burst = burst;
+        self
+    }
+}
+
+/// Token bucket shared among all clones of a client.
+#[derive(Clone)]
+pub(super) struct RpcRateLimiter {
+    inner: Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>>,
+    limit: RateLimit,
+}
+
+impl fmt::Debug for RpcRateLimiter {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RpcRateLimiter")
+            .field("limit", &self.limit)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RpcRateLimiter {
+    pub(super) fn new(limit: RateLimit) -> Self {
+        let quota = Quota::per_second(limit.requests_per_second).allow_burst(limit.burst);
+        Self {
+            inner: Arc::new(RateLimiter::direct(quota)),
+            limit,
+        }
+    }
+
+    /// Waits until a single request can be sent.
+    pub(super) async fn acquire(&self, method: Method) {
+        let latency = LATENCIES.rate_limit_wait[&method].start();
+        self.inner.until_ready().await;
+        latency.observe();
+    }
+}
@@ -0,0 +1,138 @@
+//! Caching of finalized transaction receipts for HTTP clients.
+
+use std::{
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+use zksync_types::web3::types::{TransactionReceipt, H256};
+
+use super::{ReceiptCacheResult, COUNTERS};
+
+/// Configuration of the receipt cache in [`QueryClient`]. Only receipts of transactions included
+/// in finalized blocks are cached, so that cached receipts normally never become stale.
+///
+/// [`QueryClient`]: super::QueryClient
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReceiptCacheConfig {
+    /// Maximum number of cached receipts. Least recently used receipts are evicted first.
+    pub capacity: NonZeroUsize,
+    /// Minimum interval between requests for the finalized block. Receipts for blocks above the last
+    /// known finalized block are not cached until the finalized block is refreshed.
+    pub finalized_block_refresh_interval: Duration,
+    /// If set, the cache is disabled: receipts are always fetched from the node.
+    pub bypass: bool,
+}
+
+impl ReceiptCacheConfig {
+    /// Creates a config with the specified capacity and default values for other params.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            capacity,
+            finalized_block_refresh_interval: Duration::from_secs(12),
+            bypass: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ReceiptCacheInner {
+    receipts: LruCache<H256, TransactionReceipt>,
+    finalized_block: Option<u64>,
+    finalized_block_updated_at: Option<Instant>,
+}
+
+/// LRU cache of finalized receipts shared among all clones of a client.
+#[derive(Debug)]
+pub(super) struct ReceiptCache {
+    finalized_block_refresh_interval: Duration,
+    inner: Mutex<ReceiptCacheInner>,
+}
+
+impl ReceiptCache {
+    pub(super) fn new(config: &ReceiptCacheConfig) -> Self {
+        Self {
+            finalized_block_refresh_interval: config.finalized_block_refresh_interval,
+            inner: Mutex::new(ReceiptCacheInner {
+                receipts: LruCache::new(config.capacity),
+                finalized_block: None,
+                finalized_block_updated_at: None,
+            }),
+        }
+    }
+
+    pub(super) fn get(&self, tx_hash: H256) -> Option<TransactionReceipt> {
+        let receipt = self.inner.lock().unwrap().receipts.get(&tx_hash).cloned();
+        let result = if receipt.is_some() {
+            ReceiptCacheResult::Hit
+        } else {
+            ReceiptCacheResult::Miss
+        };
+        COUNTERS.receipt_cache[&result].inc();
+        receipt
+    }
+
+    /// Checks whether the finalized block should be refreshed in order to cache a receipt
+    /// from the specified block.
+    pub(super) fn needs_finalized_block(&self, block_number: u64) -> bool {
+        let inner = self.inner.lock().unwrap();
+        if inner.finalized_block >= Some(block_number) {
+            return false;
+        }
+        inner.finalized_block_updated_at.map_or(true, |at| {
+            at.elapsed() >= self.finalized_block_refresh_interval
+        })
+    }
+
+    /// Records the finalized block and checks it against cached receipts.
+    pub(super) fn update_finalized_block(&self, number: u64, hash: H256) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.finalized_block = inner.finalized_block.max(Some(number));
+        inner.finalized_block_updated_at = Some(Instant::now());
+        drop(inner);
+        self.observe_block(number, hash);
+    }
+
+    /// Invalidates cached receipts from the block with the specified number if the block hash differs,
+    /// i.e., the block was reorganized since the receipts were cached.
+    pub(super) fn observe_block(&self, number: u64, hash: H256) {
+        let mut inner = self.inner.lock().unwrap();
+        let stale_tx_hashes: Vec<_> = inner
+            .receipts
+            .iter()
+            .filter(|(_, receipt)| {
+                receipt.block_number.map(|number| number.as_u64()) == Some(number)
+                    && receipt.block_hash != Some(hash)
+            })
+            .map(|(tx_hash, _)| *tx_hash)
+            .collect();
+        if stale_tx_hashes.is_empty() {
+            return;
+        }
+
+        tracing::warn!(
+            "Block #{number} has changed hash to {hash:?}; invalidating {} cached receipt(s)",
+            stale_tx_hashes.len()
+        );
+        for tx_hash in &stale_tx_hashes {
+            inner.receipts.pop(tx_hash);
+        }
+        COUNTERS.receipt_cache[&ReceiptCacheResult::Invalidated]
+            .inc_by(stale_tx_hashes.len() as u64);
+    }
+
+    /// Caches the receipt if it belongs to a finalized block.
+    pub(super) fn insert(&self, receipt: &TransactionReceipt) {
+        let (Some(block_number), Some(_)) = (receipt.block_number, receipt.block_hash) else {
+            return;
+        };
+        let mut inner = self.inner.lock().unwrap();
+        if inner.finalized_block >= Some(block_number.as_u64()) {
+            inner
+                .receipts
+                .put(receipt.transaction_hash, receipt.clone());
+        }
+    }
+}
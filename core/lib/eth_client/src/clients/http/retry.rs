@@ -0,0 +1,255 @@
+//! Retries of transient errors for HTTP clients.
+
+use std::{future::Future, time::Duration};
+
+use rand::Rng;
+
+use super::{Method, COUNTERS, LATENCIES};
+use crate::types::{Error, ErrorKind};
+
+/// Policy for retrying transient errors (timeouts, connection errors, rate limiting) in [`QueryClient`].
+/// Other errors, e.g. reverts or nonce errors, are never retried.
+///
+/// If the node rate-limits a request and specifies the `Retry-After` header, the request is retried
+/// no earlier than requested. If the requested delay exceeds `max_delay`, the request is not retried.
+///
+/// [`QueryClient`]: super::QueryClient
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts for a single call, including the first one.
+    pub max_attempts: usize,
+    /// Delay after the first failed attempt. Doubled after each subsequent failure.
+    pub base_delay: Duration,
+    /// Upper bound for the delay between attempts (not including jitter).
+    pub max_delay: Duration,
+    /// Maximum random delay added to each backoff as a fraction of the backoff, e.g. 0.5 for up to 50%.
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the delay after the specified (1-based) failed attempt.
+    fn delay(&self, attempt: usize) -> Duration {
+        let exponent = u32::try_from(attempt - 1).unwrap_or(u32::MAX).min(16);
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+        if self.jitter > 0.0 {
+            let jitter = rand::thread_rng().gen_range(0.0..=self.jitter);
+            backoff + backoff.mul_f64(jitter)
+        } else {
+            backoff
+        }
+    }
+
+    pub(super) async fn retry<R, E, Fut>(
+        policy: Option<&Self>,
+        method: Method,
+        mut call: impl FnMut() -> Fut,
+    ) -> Result<R, Error>
+    where
+        Fut: Future<Output = Result<R, E>>,
+        E: Into<Error>,
+    {
+        let Some(policy) = policy else {
+            return call().await.map_err(Into::into);
+        };
+
+        let mut attempt = 1;
+        loop {
+            match call().await.map_err(Into::into) {
+                Err(err) if attempt < policy.max_attempts && err.is_transient() => {
+                    let mut delay = policy.delay(attempt);
+                    if let Some(retry_after) = err.retry_after() {
+                        if retry_after > policy.max_delay {
+                            tracing::debug!(
+                                "Node has asked to retry {method:?} after {retry_after:?}, which exceeds max delay {:?}",
+                                policy.max_delay
+                            );
+                            return Err(err);
+                        }
+                        delay = delay.max(retry_after);
+                    }
+                    if err.kind() == ErrorKind::RateLimited {
+                        LATENCIES.rate_limited_wait[&method].observe(delay);
+                    }
+                    tracing::debug!(
+                        "Transient error calling {method:?} (attempt {attempt}/{}), retrying in {delay:?}: {err}",
+                        policy.max_attempts
+                    );
+                    COUNTERS.retries[&method].inc();
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use jsonrpc_core::ErrorCode;
+    use zksync_types::web3::{self, error::TransportError};
+
+    use super::*;
+    use crate::types::rate_limited_error;
+
+    const POLICY: RetryPolicy = RetryPolicy {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_millis(150),
+        jitter: 0.0,
+    };
+
+    fn rpc_error(code: i64, message: &str) -> Error {
+        Error::EthereumGateway(web3::Error::Rpc(jsonrpc_core::Error {
+            code: ErrorCode::from(code),
+            message: message.to_owned(),
+            data: None,
+        }))
+    }
+
+    #[test]
+    fn computing_delays() {
+        assert_eq!(POLICY.delay(1), Duration::from_millis(100));
+        assert_eq!(POLICY.delay(2), Duration::from_millis(150));
+        assert_eq!(POLICY.delay(100), Duration::from_millis(150));
+
+        let policy = RetryPolicy {
+            jitter: 0.5,
+            ..POLICY
+        };
+        for _ in 0..100 {
+            let delay = policy.delay(1);
+            assert!(
+                delay >= Duration::from_millis(100) && delay <= Duration::from_millis(150),
+                "{delay:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn classifying_errors() {
+        let transient_errors = [
+            Error::EthereumGateway(web3::Error::Transport(TransportError::Code(429))),
+            Error::EthereumGateway(web3::Error::Transport(TransportError::Message(
+                "connection reset by peer".to_owned(),
+            ))),
+            Error::EthereumGateway(web3::Error::Unreachable),
+            rpc_error(-32005, "limit exceeded"),
+        ];
+        for err in transient_errors {
+            assert!(err.is_transient(), "{err}");
+        }
+
+        let permanent_errors = [
+            Error::EthereumGateway(web3::Error::Transport(TransportError::Code(400))),
+            rpc_error(-32000, "nonce too low"),
+            rpc_error(3, "execution reverted"),
+            Error::WrongFeeProvided(1.into(), 2.into()),
+        ];
+        for err in permanent_errors {
+            assert!(!err.is_transient(), "{err}");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retrying_transient_errors() {
+        let mut attempts = 0;
+        let value = RetryPolicy::retry(Some(&POLICY), Method::BlockNumber, || {
+            attempts += 1;
+            let result = if attempts < 3 {
+                Err(rpc_error(-32005, "limit exceeded"))
+            } else {
+                Ok(attempts)
+            };
+            async move { result }
+        })
+        .await
+        .unwrap();
+        assert_eq!(value, 3);
+
+        attempts = 0;
+        let err = RetryPolicy::retry(Some(&POLICY), Method::BlockNumber, || {
+            attempts += 1;
+            async { Result::<(), _>::Err(rpc_error(-32005, "limit exceeded")) }
+        })
+        .await
+        .unwrap_err();
+        assert_matches!(err, Error::EthereumGateway(web3::Error::Rpc(_)));
+        assert_eq!(attempts, POLICY.max_attempts);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn respecting_retry_after() {
+        let policy = RetryPolicy {
+            max_delay: Duration::from_secs(5),
+            ..POLICY
+        };
+        let rate_limited = |retry_after| Error::EthereumGateway(rate_limited_error(retry_after));
+
+        let started_at = tokio::time::Instant::now();
+        let mut attempts = 0;
+        RetryPolicy::retry(Some(&policy), Method::BlockNumber, || {
+            attempts += 1;
+            let result = if attempts < 2 {
+                Err(rate_limited(Duration::from_secs(3)))
+            } else {
+                Ok(())
+            };
+            async move { result }
+        })
+        .await
+        .unwrap();
+        assert_eq!(attempts, 2);
+        assert_eq!(started_at.elapsed(), Duration::from_secs(3));
+
+        // Delays exceeding the policy are not waited for.
+        let started_at = tokio::time::Instant::now();
+        attempts = 0;
+        let err = RetryPolicy::retry(Some(&policy), Method::BlockNumber, || {
+            attempts += 1;
+            async { Result::<(), _>::Err(rate_limited(Duration::from_secs(60))) }
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::RateLimited);
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(60)));
+        assert_eq!(attempts, 1);
+        assert_eq!(started_at.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn not_retrying_permanent_errors_or_without_policy() {
+        let mut attempts = 0;
+        RetryPolicy::retry(Some(&POLICY), Method::SendRawTx, || {
+            attempts += 1;
+            async { Result::<(), _>::Err(rpc_error(-32000, "nonce too low")) }
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(attempts, 1);
+
+        attempts = 0;
+        RetryPolicy::retry(None, Method::SendRawTx, || {
+            attempts += 1;
+            async { Result::<(), _>::Err(rpc_error(-32005, "limit exceeded")) }
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(attempts, 1);
+    }
+}
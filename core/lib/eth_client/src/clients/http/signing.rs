@@ -1,33 +1,45 @@
-use std::{fmt, sync::Arc};
+use std::{collections::HashMap, fmt, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
+use tokio::sync::OnceCell;
 use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
 use zksync_contracts::zksync_contract;
-use zksync_eth_signer::{raw_ethereum_tx::TransactionParameters, EthereumSigner, PrivateKeySigner};
+use zksync_eth_signer::{
+    error::SignerError, raw_ethereum_tx::TransactionParameters, EthereumSigner, JsonRpcSigner,
+    PrivateKeySigner, TypedData, TypedDataDomain, TypedDataTypes,
+};
 use zksync_types::{
     web3::{
         self,
         contract::{tokens::Detokenize, Options},
         ethabi,
-        transports::Http,
         types::{
-            Address, Block, BlockId, BlockNumber, CallRequest, Filter, Log, Transaction,
-            TransactionReceipt, H160, H256, U256, U64,
+            Address, Block, BlockId, BlockNumber, CallRequest, FeeHistory, Filter, Log,
+            Transaction, TransactionReceipt, H160, H256, U256, U64,
         },
     },
-    L1ChainId, PackedEthSignature, EIP_1559_TX_TYPE,
+    L1ChainId, PackedEthSignature, EIP_1559_TX_TYPE, LEGACY_TX_TYPE,
 };
 
-use super::{query::QueryClient, Method, LATENCIES};
+use super::{
+    middleware::RpcMiddleware, nonce::NonceManager, query::QueryClient, rate_limit::RateLimit,
+    retry::RetryPolicy, transport::HttpTransport, Method, LATENCIES,
+};
 use crate::{
     clients::LineaEstimateGas,
-    types::{Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
-    BoundEthInterface, CallFunctionArgs, ContractCall, EthInterface, RawTransactionBytes,
+    hd_wallet::derive_private_key,
+    types::{Error, ExecutedTxStatus, FailureInfo, SignedCallResult, EIP_4844_TX_TYPE},
+    BlobTxSidecar, BlockBlobGas, BoundEthInterface, CallFunctionArgs, CallOverrides, ContractCall,
+    EthInterface, RawTransactionBytes,
 };
 
 /// HTTP-based Ethereum client, backed by a private key to sign transactions.
 pub type PKSigningClient = SigningClient<PrivateKeySigner>;
 
+/// HTTP-based Ethereum client, which delegates signing to an external signer via JSON-RPC
+/// (`eth_signTransaction` etc.), so that the client doesn't need access to the private key.
+pub type JsonRpcSigningClient = SigningClient<JsonRpcSigner>;
+
 impl PKSigningClient {
     pub fn from_config(
         eth_sender: &ETHSenderConfig,
@@ -45,13 +57,13 @@ impl PKSigningClient {
         let default_priority_fee_per_gas = eth_sender.gas_adjuster.default_priority_fee_per_gas;
         let l1_chain_id = eth_client.chain_id;
 
-        let transport = Http::new(main_node_url).expect("Failed to create transport");
+        let transport = HttpTransport::new(main_node_url).expect("Failed to create transport");
         let operator_address = PackedEthSignature::address_from_private_key(&operator_private_key)
             .expect("Failed to get address from private key");
 
         tracing::info!("Operator address: {:?}", operator_address);
 
-        SigningClient::new(
+        let mut client = SigningClient::new(
             transport,
             zksync_contract(),
             operator_address,
@@ -60,6 +72,55 @@ impl PKSigningClient {
             default_priority_fee_per_gas.into(),
             L1ChainId(l1_chain_id),
         )
+        .with_chain_id_check();
+        if let Some(blobs_private_key) = eth_sender.sender.private_key_blobs() {
+            client = client.with_signer_key(BLOB_OPERATOR_SIGNER, blobs_private_key);
+        }
+        client
+    }
+
+    /// Adds a signer backed by the specified private key. See [`SigningClient::with_signer()`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the private key is invalid or if a signer with the same name is already added.
+    pub fn with_signer_key(self, name: &str, private_key: H256) -> Self {
+        let address = PackedEthSignature::address_from_private_key(&private_key)
+            .expect("Failed to get address from private key");
+        tracing::info!("Address of signer `{name}`: {address:?}");
+        self.with_signer(name, address, PrivateKeySigner::new(private_key))
+    }
+
+    /// Creates a client with the operator key derived from a BIP-39 English `mnemonic` using the specified
+    /// BIP-32 derivation path (e.g., [`DEFAULT_DERIVATION_PATH`](crate::DEFAULT_DERIVATION_PATH)).
+    /// The mnemonic and the path are validated before the client is created; the node is not queried.
+    ///
+    /// The derived operator address is logged; the private key is not.
+    pub fn from_mnemonic(
+        mnemonic: &str,
+        derivation_path: &str,
+        transport: HttpTransport,
+        contract: ethabi::Contract,
+        contract_eth_addr: H160,
+        default_priority_fee_per_gas: U256,
+        chain_id: L1ChainId,
+    ) -> Result<Self, Error> {
+        let private_key = derive_private_key(mnemonic, derivation_path)?;
+        let operator_address = PackedEthSignature::address_from_private_key(&private_key)
+            .map_err(|_| SignerError::DefineAddress)?;
+        tracing::info!(
+            "Operator address derived from mnemonic at `{derivation_path}`: {operator_address:?}"
+        );
+
+        Ok(SigningClient::new(
+            transport,
+            contract,
+            operator_address,
+            PrivateKeySigner::new(private_key),
+            contract_eth_addr,
+            default_priority_fee_per_gas,
+            chain_id,
+        ))
     }
 }
 
@@ -69,17 +130,53 @@ impl PKSigningClient {
 /// This is an emergency value, which will not be used normally.
 const FALLBACK_GAS_LIMIT: u64 = 3_000_000;
 
+/// Fee model of transactions signed by [`SigningClient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxFeeModel {
+    /// Legacy transactions with a single gas price. Used for chains not supporting EIP-1559.
+    Legacy,
+    /// EIP-1559 transactions with max fee and max priority fee per gas.
+    Eip1559,
+}
+
+/// Name of the signer provided when creating a [`SigningClient`]. This signer is used unless another one
+/// is selected with [`SigningClient::for_signer()`].
+pub const DEFAULT_SIGNER: &str = "default";
+/// Conventional name of the signer for EIP-4844 transactions, so that blobs are sent from an address
+/// separate from the main operator.
+pub const BLOB_OPERATOR_SIGNER: &str = "blob_operator";
+
+/// Operator account with the signer managing it.
+struct OperatorAccount<S> {
+    name: String,
+    address: Address,
+    eth_signer: S,
+}
+
 /// HTTP-based client, instantiated for a certain account.
 /// This client is capable of signing transactions.
+///
+/// The client may hold several named signers (e.g., [`DEFAULT_SIGNER`] and [`BLOB_OPERATOR_SIGNER`]), one of which
+/// is used for signing and as [`BoundEthInterface::sender_account()`]. The signer can be selected
+/// with [`Self::for_signer()`]; by default, the [`DEFAULT_SIGNER`] is used.
 #[derive(Clone)]
 pub struct SigningClient<S: EthereumSigner> {
     inner: Arc<ETHDirectClientInner<S>>,
+    /// Currently selected signer.
+    signer: Arc<OperatorAccount<S>>,
+    /// All signers, keyed by name.
+    signers: Arc<HashMap<String, Arc<OperatorAccount<S>>>>,
     query_client: QueryClient,
+    /// Either set explicitly, or detected lazily on the first signed transaction.
+    tx_fee_model: Arc<OnceCell<TxFeeModel>>,
+    nonce_manager: Option<Arc<NonceManager>>,
+    /// Set if the chain ID should be checked before signing the first transaction.
+    chain_id_check: Option<Arc<OnceCell<()>>>,
+    /// Whether non-blob transactions should be simulated with `eth_call` before signing.
+    simulate_before_send: bool,
 }
 
 struct ETHDirectClientInner<S: EthereumSigner> {
-    eth_signer: S,
-    sender_account: Address,
     contract_addr: H160,
     contract: ethabi::Contract,
     chain_id: L1ChainId,
@@ -91,7 +188,8 @@ impl<S: EthereumSigner> fmt::Debug for SigningClient<S> {
         // We do not want to have a private key in the debug representation.
 
         f.debug_struct("ETHDirectClient")
-            .field("sender_account", &self.inner.sender_account)
+            .field("signer", &self.signer.name)
+            .field("sender_account", &self.signer.address)
             .field("contract_addr", &self.inner.contract_addr)
             .field("chain_id", &self.inner.chain_id)
             .finish()
@@ -115,12 +213,28 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
         self.query_client.block_number(component).await
     }
 
+    async fn fetch_chain_id(&self, component: &'static str) -> Result<L1ChainId, Error> {
+        self.query_client.fetch_chain_id(component).await
+    }
+
     async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error> {
         self.query_client.get_gas_price(component).await
     }
 
+    async fn max_priority_fee_per_gas(&self, component: &'static str) -> Result<U256, Error> {
+        self.query_client.max_priority_fee_per_gas(component).await
+    }
+
     async fn send_raw_tx(&self, tx: RawTransactionBytes) -> Result<H256, Error> {
-        self.query_client.send_raw_tx(tx).await
+        let Some(nonce_manager) = &self.nonce_manager else {
+            return self.query_client.send_raw_tx(tx).await;
+        };
+        let raw_tx_hash = H256(web3::signing::keccak256(tx.as_ref()));
+        let result = self.query_client.send_raw_tx(tx).await;
+        nonce_manager
+            .on_submission(raw_tx_hash, result.as_ref().map(drop))
+            .await;
+        result
     }
 
     async fn base_fee_history(
@@ -134,6 +248,18 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
             .await
     }
 
+    async fn fee_history(
+        &self,
+        block_count: usize,
+        newest_block: BlockNumber,
+        reward_percentiles: &[f64],
+        component: &'static str,
+    ) -> Result<FeeHistory, Error> {
+        self.query_client
+            .fee_history(block_count, newest_block, reward_percentiles, component)
+            .await
+    }
+
     async fn get_pending_block_base_fee_per_gas(
         &self,
         component: &'static str,
@@ -143,6 +269,17 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
             .await
     }
 
+    async fn pending_tx_hash(
+        &self,
+        sender: Address,
+        nonce: U256,
+        component: &'static str,
+    ) -> Result<Option<H256>, Error> {
+        self.query_client
+            .pending_tx_hash(sender, nonce, component)
+            .await
+    }
+
     async fn get_tx_status(
         &self,
         hash: H256,
@@ -170,6 +307,16 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
         self.query_client.call_contract_function(call).await
     }
 
+    async fn call_contract_function_with_overrides(
+        &self,
+        call: ContractCall,
+        overrides: CallOverrides,
+    ) -> Result<Vec<ethabi::Token>, Error> {
+        self.query_client
+            .call_contract_function_with_overrides(call, overrides)
+            .await
+    }
+
     async fn tx_receipt(
         &self,
         tx_hash: H256,
@@ -178,8 +325,15 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
         self.query_client.tx_receipt(tx_hash, component).await
     }
 
-    async fn eth_balance(&self, address: Address, component: &'static str) -> Result<U256, Error> {
-        self.query_client.eth_balance(address, component).await
+    async fn eth_balance_at(
+        &self,
+        address: Address,
+        block: BlockNumber,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        self.query_client
+            .eth_balance_at(address, block, component)
+            .await
     }
 
     async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error> {
@@ -194,6 +348,14 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
         self.query_client.block(block_id, component).await
     }
 
+    async fn block_blob_gas(
+        &self,
+        block_id: BlockId,
+        component: &'static str,
+    ) -> Result<Option<BlockBlobGas>, Error> {
+        self.query_client.block_blob_gas(block_id, component).await
+    }
+
     async fn linea_estimate_gas(&self, req: CallRequest) -> Result<LineaEstimateGas, Error> {
         self.query_client.linea_estimate_gas(req).await
     }
@@ -214,7 +376,7 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
     }
 
     fn sender_account(&self) -> Address {
-        self.inner.sender_account
+        self.signer.address
     }
 
     async fn sign_prepared_tx_for_addr(
@@ -224,64 +386,62 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
         options: Options,
         component: &'static str,
     ) -> Result<SignedCallResult, Error> {
+        self.ensure_chain_id(component).await?;
+        self.simulate_tx(&data, contract_addr, &options, component)
+            .await?;
         let latency = LATENCIES.direct[&Method::SignPreparedTx].start();
-        // Fetch current max priority fee per gas
-        let max_priority_fee_per_gas = match options.max_priority_fee_per_gas {
-            Some(max_priority_fee_per_gas) => max_priority_fee_per_gas,
-            None => self.inner.default_priority_fee_per_gas,
-        };
-
-        // Fetch current base fee and add `max_priority_fee_per_gas`
-        let max_fee_per_gas = match options.max_fee_per_gas {
-            Some(max_fee_per_gas) => max_fee_per_gas,
-            None => {
-                self.get_pending_block_base_fee_per_gas(component).await? + max_priority_fee_per_gas
-            }
-        };
-
-        if max_fee_per_gas < max_priority_fee_per_gas {
-            return Err(Error::WrongFeeProvided(
-                max_fee_per_gas,
-                max_priority_fee_per_gas,
-            ));
-        }
-
-        let nonce = match options.nonce {
-            Some(nonce) => nonce,
-            None => self.pending_nonce(component).await?,
-        };
-
-        let gas = options.gas.unwrap_or_else(|| {
-            // Verbosity level is set to `error`, since we expect all the transactions to have
-            // a set limit, but don't want to crаsh the application if for some reason in some
-            // place limit was not set.
-            tracing::error!(
-                "No gas limit was set for transaction, using the default limit: {}",
-                FALLBACK_GAS_LIMIT
-            );
+        let fee_model = self.tx_fee_model(component).await?;
+        let is_reserved_nonce = options.nonce.is_none();
+        let tx = self
+            .prepare_tx(data, contract_addr, options, fee_model, component)
+            .await?;
+        let (max_priority_fee_per_gas, max_fee_per_gas, nonce) =
+            (tx.max_priority_fee_per_gas, tx.max_fee_per_gas, tx.nonce);
 
-            U256::from(FALLBACK_GAS_LIMIT)
-        });
-
-        let tx = TransactionParameters {
-            nonce,
-            to: Some(contract_addr),
-            gas,
-            value: options.value.unwrap_or_default(),
-            data,
-            chain_id: self.inner.chain_id.0,
+        let signed_tx = self.sign_tx(tx, is_reserved_nonce).await?;
+        let hash = web3::signing::keccak256(&signed_tx).into();
+        self.track_signed_tx(&signed_tx, nonce, is_reserved_nonce);
+        latency.observe();
+        Ok(SignedCallResult {
+            raw_tx: RawTransactionBytes(signed_tx),
             max_priority_fee_per_gas,
-            gas_price: None,
-            transaction_type: Some(EIP_1559_TX_TYPE.into()),
-            access_list: None,
             max_fee_per_gas,
-        };
+            nonce,
+            hash,
+        })
+    }
+
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        max_fee_per_blob_gas: U256,
+        sidecar: BlobTxSidecar,
+        component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        sidecar.validate()?;
+        self.ensure_chain_id(component).await?;
 
-        let signed_tx = self.inner.eth_signer.sign_transaction(tx).await?;
+        let latency = LATENCIES.direct[&Method::SignPreparedBlobTx].start();
+        let is_reserved_nonce = options.nonce.is_none();
+        let mut tx = self
+            .prepare_tx(data, contract_addr, options, TxFeeModel::Eip1559, component)
+            .await?;
+        tx.transaction_type = Some(EIP_4844_TX_TYPE.into());
+        tx.max_fee_per_blob_gas = Some(max_fee_per_blob_gas);
+        tx.blob_versioned_hashes = Some(sidecar.versioned_hashes());
+        let (max_priority_fee_per_gas, max_fee_per_gas, nonce) =
+            (tx.max_priority_fee_per_gas, tx.max_fee_per_gas, tx.nonce);
+
+        let signed_tx = self.sign_tx(tx, is_reserved_nonce).await?;
+        // The transaction hash doesn't cover the sidecar.
         let hash = web3::signing::keccak256(&signed_tx).into();
+        let raw_tx = sidecar.encode_with_signed_tx(&signed_tx);
+        self.track_signed_tx(&raw_tx, nonce, is_reserved_nonce);
         latency.observe();
         Ok(SignedCallResult {
-            raw_tx: RawTransactionBytes(signed_tx),
+            raw_tx: RawTransactionBytes(raw_tx),
             max_priority_fee_per_gas,
             max_fee_per_gas,
             nonce,
@@ -296,7 +456,7 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
         erc20_abi: ethabi::Contract,
     ) -> Result<U256, Error> {
         let latency = LATENCIES.direct[&Method::Allowance].start();
-        let args = CallFunctionArgs::new("allowance", (self.inner.sender_account, address))
+        let args = CallFunctionArgs::new("allowance", (self.signer.address, address))
             .for_contract(token_address, erc20_abi);
         let res = self.call_contract_function(args).await?;
         latency.observe();
@@ -306,7 +466,7 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
 
 impl<S: EthereumSigner> SigningClient<S> {
     pub fn new(
-        transport: Http,
+        transport: HttpTransport,
         contract: ethabi::Contract,
         operator_eth_addr: H160,
         eth_signer: S,
@@ -314,16 +474,1180 @@ impl<S: EthereumSigner> SigningClient<S> {
         default_priority_fee_per_gas: U256,
         chain_id: L1ChainId,
     ) -> Self {
+        let signer = Arc::new(OperatorAccount {
+            name: DEFAULT_SIGNER.to_owned(),
+            address: operator_eth_addr,
+            eth_signer,
+        });
         Self {
             inner: Arc::new(ETHDirectClientInner {
-                sender_account: operator_eth_addr,
-                eth_signer,
                 contract_addr: contract_eth_addr,
                 chain_id,
                 contract,
                 default_priority_fee_per_gas,
             }),
+            signers: Arc::new(HashMap::from([(DEFAULT_SIGNER.to_owned(), signer.clone())])),
+            signer,
             query_client: transport.into(),
+            tx_fee_model: Arc::default(),
+            nonce_manager: None,
+            chain_id_check: None,
+            simulate_before_send: false,
+        }
+    }
+
+    /// Adds a signer with the specified `name` for the account at `address`. The signer is not used
+    /// until it's selected with [`Self::for_signer()`].
+    ///
+    /// If the nonce manager is enabled, nonces are tracked independently for each account.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a signer with the same name is already added.
+    pub fn with_signer(mut self, name: &str, address: Address, eth_signer: S) -> Self {
+        let signers = Arc::make_mut(&mut self.signers);
+        assert!(
+            !signers.contains_key(name),
+            "Signer `{name}` is already added"
+        );
+        let signer = OperatorAccount {
+            name: name.to_owned(),
+            address,
+            eth_signer,
+        };
+        signers.insert(name.to_owned(), Arc::new(signer));
+        self
+    }
+
+    /// Returns a client using the signer with the specified `name` to sign transactions. The returned client
+    /// shares all other state (caches, the nonce manager etc.) with this client.
+    pub fn for_signer(&self, name: &str) -> Result<Self, Error> {
+        let signer = self
+            .signers
+            .get(name)
+            .ok_or_else(|| Error::UnknownSigner(name.to_owned()))?;
+        Ok(Self {
+            signer: signer.clone(),
+            ..self.clone()
+        })
+    }
+
+    /// Returns the name of the signer used by this client.
+    pub fn signer_name(&self) -> &str {
+        &self.signer.name
+    }
+
+    /// Returns the names of all signers in this client.
+    pub fn signer_names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.signers.keys().map(String::as_str)
+    }
+
+    /// Enables checking that the node is connected to the configured L1 network before signing
+    /// the first transaction. A successful check is cached and shared among all clones of the client;
+    /// use [`BoundEthInterface::verify_chain_id()`] to re-check the chain ID periodically.
+    pub fn with_chain_id_check(mut self) -> Self {
+        self.chain_id_check = Some(Arc::default());
+        self
+    }
+
+    /// Checks the chain ID if the check is enabled and hasn't succeeded yet.
+    async fn ensure_chain_id(&self, component: &'static str) -> Result<(), Error> {
+        if let Some(chain_id_check) = &self.chain_id_check {
+            chain_id_check
+                .get_or_try_init(|| self.verify_chain_id(component))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Enables simulating transactions with `eth_call` at the `pending` block before signing them.
+    /// If the simulation reverts, [`Error::WouldRevert`] is returned instead of a signed transaction,
+    /// so that the transaction is never sent and doesn't occupy a nonce. Custom errors in the revert reason
+    /// are resolved using the contract ABI of the client. Other simulation errors are returned as is.
+    ///
+    /// EIP-4844 transactions are not simulated since `eth_call` doesn't account for blobs.
+    pub fn with_preflight_simulation(mut self) -> Self {
+        self.simulate_before_send = true;
+        self
+    }
+
+    /// Simulates a transaction if preflight simulation is enabled.
+    async fn simulate_tx(
+        &self,
+        data: &[u8],
+        contract_addr: H160,
+        options: &Options,
+        component: &'static str,
+    ) -> Result<(), Error> {
+        if !self.simulate_before_send {
+            return Ok(());
+        }
+        let call = CallRequest {
+            from: Some(self.signer.address),
+            to: Some(contract_addr),
+            gas: options.gas,
+            value: options.value,
+            data: Some(data.to_vec().into()),
+            ..CallRequest::default()
+        };
+        let result = self
+            .query_client
+            .simulate_call(call, BlockNumber::Pending, component)
+            .await;
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) => match err.revert_reason() {
+                Some(reason) => {
+                    let reason = reason.resolve(&self.inner.contract);
+                    tracing::warn!("Transaction to {contract_addr:?} would revert: {reason}");
+                    Err(Error::WouldRevert { reason })
+                }
+                None => Err(err),
+            },
+        }
+    }
+
+    /// Switches this client and all its clones to the node at `new_url`, checking that the new node
+    /// is connected to the configured L1 network. See [`QueryClient::update_url()`] for details.
+    pub async fn update_url(&self, new_url: &str, component: &'static str) -> Result<(), Error> {
+        self.query_client
+            .update_url(new_url, self.inner.chain_id, component)
+            .await
+    }
+
+    /// Enables tracking nonces locally. Nonces of transactions signed without an explicitly specified nonce
+    /// are allocated from a cache shared among all clones of the client, rather than fetched
+    /// from the node for each transaction. The cache is synced with the pending nonce of the sender account
+    /// when the node rejects a transaction due to its nonce.
+    pub fn with_nonce_manager(mut self) -> Self {
+        self.nonce_manager = Some(Arc::default());
+        self
+    }
+
+    /// Adds middleware invoked for each HTTP request sent by this client.
+    /// See [`QueryClient::with_middleware()`] for details.
+    pub fn with_middleware(mut self, middleware: impl RpcMiddleware) -> Self {
+        self.query_client = self.query_client.with_middleware(middleware);
+        self
+    }
+
+    /// Resets the nonce cached by the nonce manager, so that it's synced with the node
+    /// when the next transaction is signed. No-op if the nonce manager is not enabled.
+    pub async fn reset_nonce(&self) {
+        if let Some(nonce_manager) = &self.nonce_manager {
+            nonce_manager.reset(self.signer.address).await;
         }
     }
+
+    /// Sets the fee model of signed transactions. By default, the fee model is detected based on
+    /// whether the latest L1 block has a base fee. Does not affect EIP-4844 transactions.
+    pub fn with_tx_fee_model(mut self, fee_model: TxFeeModel) -> Self {
+        self.tx_fee_model = Arc::new(OnceCell::from(fee_model));
+        self
+    }
+
+    /// Returns the fee model of signed transactions, detecting it if necessary.
+    pub async fn tx_fee_model(&self, component: &'static str) -> Result<TxFeeModel, Error> {
+        let fee_model = self
+            .tx_fee_model
+            .get_or_try_init(|| async {
+                let fee_model = if self.query_client.supports_eip1559(component).await? {
+                    TxFeeModel::Eip1559
+                } else {
+                    TxFeeModel::Legacy
+                };
+                tracing::info!("Detected L1 transaction fee model: {fee_model:?}");
+                Ok::<_, Error>(fee_model)
+            })
+            .await?;
+        Ok(*fee_model)
+    }
+
+    /// Creates a client for the account managed by `eth_signer`. Useful for signers not backed
+    /// by a local private key, e.g. [`JsonRpcSigner`].
+    pub async fn from_signer(
+        transport: HttpTransport,
+        contract: ethabi::Contract,
+        eth_signer: S,
+        contract_eth_addr: H160,
+        default_priority_fee_per_gas: U256,
+        chain_id: L1ChainId,
+    ) -> Result<Self, Error> {
+        let operator_eth_addr = eth_signer.get_address().await?;
+        tracing::info!("Operator address: {operator_eth_addr:?}");
+        Ok(Self::new(
+            transport,
+            contract,
+            operator_eth_addr,
+            eth_signer,
+            contract_eth_addr,
+            default_priority_fee_per_gas,
+            chain_id,
+        ))
+    }
+
+    /// Checks that the signer manages the account this client was created for.
+    pub async fn check_signer_address(&self) -> Result<(), Error> {
+        let signer_address = self.signer.eth_signer.get_address().await?;
+        if signer_address != self.signer.address {
+            return Err(Error::SignerAddressMismatch {
+                expected: self.signer.address,
+                actual: signer_address,
+            });
+        }
+        Ok(())
+    }
+
+    /// Signs EIP-712 typed data with the key managed by the signer. The primary type is inferred from `types`
+    /// as the only type not referenced by other types. Signers delegating to a remote service
+    /// use `eth_signTypedData_v4`.
+    pub async fn sign_typed_data(
+        &self,
+        domain: TypedDataDomain,
+        types: TypedDataTypes,
+        message: serde_json::Value,
+    ) -> Result<PackedEthSignature, Error> {
+        let typed_data = TypedData::new(domain, types, message)?;
+        Ok(self
+            .inner
+            .eth_signer
+            .sign_typed_data_v4(&typed_data)
+            .await?)
+    }
+
+    /// Checks whether `signature` for the typed data was produced by the account of this client.
+    pub fn verify_typed_data_signature(
+        &self,
+        typed_data: &TypedData,
+        signature: &PackedEthSignature,
+    ) -> Result<bool, Error> {
+        Ok(typed_data.recover_signer(signature)? == self.signer.address)
+    }
+
+    /// Enables retries of transient errors for queries made by this client. Signing is not affected.
+    pub fn with_retries(mut self, policy: RetryPolicy) -> Self {
+        self.query_client = self.query_client.with_retries(policy);
+        self
+    }
+
+    /// Limits the rate of requests sent by this client. Signing is not affected.
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.query_client = self.query_client.with_rate_limit(limit);
+        self
+    }
+
+    /// Limits the number of concurrent requests sent by this client; `0` means no limit.
+    /// Signing is not affected.
+    pub fn with_max_in_flight_requests(mut self, max_in_flight_requests: usize) -> Self {
+        self.query_client = self
+            .query_client
+            .with_max_in_flight_requests(max_in_flight_requests);
+        self
+    }
+
+    /// Enables caching of fee-related queries made by this client for the specified TTL.
+    /// See [`QueryClient::with_fee_cache()`] for details.
+    pub fn with_fee_cache(mut self, ttl: Duration) -> Self {
+        self.query_client = self.query_client.with_fee_cache(ttl);
+        self
+    }
+
+    /// Removes all values from the fee cache of this client (if enabled).
+    pub fn invalidate_fee_cache(&self) {
+        self.query_client.invalidate_fee_cache();
+    }
+
+    /// Fills in the transaction fields not provided in `options` (fees, nonce and gas limit).
+    /// If the nonce is reserved by the nonce manager, it must be released if the transaction isn't signed.
+    async fn prepare_tx(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        fee_model: TxFeeModel,
+        component: &'static str,
+    ) -> Result<TransactionParameters, Error> {
+        let gas = options.gas.unwrap_or_else(|| {
+            // Verbosity level is set to `error`, since we expect all the transactions to have
+            // a set limit, but don't want to crаsh the application if for some reason in some
+            // place limit was not set.
+            tracing::error!(
+                "No gas limit was set for transaction, using the default limit: {}",
+                FALLBACK_GAS_LIMIT
+            );
+
+            U256::from(FALLBACK_GAS_LIMIT)
+        });
+
+        let mut tx = TransactionParameters {
+            to: Some(contract_addr),
+            gas,
+            value: options.value.unwrap_or_default(),
+            data,
+            chain_id: self.inner.chain_id.0,
+            ..TransactionParameters::default()
+        };
+
+        if fee_model == TxFeeModel::Legacy {
+            let gas_price = match options.gas_price {
+                Some(gas_price) => gas_price,
+                None => self.get_gas_price(component).await?,
+            };
+            // The signer uses `max_fee_per_gas` as the gas price of legacy transactions.
+            tx.gas_price = Some(gas_price);
+            tx.max_fee_per_gas = gas_price;
+            tx.max_priority_fee_per_gas = gas_price;
+            tx.transaction_type = Some(LEGACY_TX_TYPE.into());
+        } else {
+            // Fetch current max priority fee per gas
+            let max_priority_fee_per_gas = match options.max_priority_fee_per_gas {
+                Some(max_priority_fee_per_gas) => max_priority_fee_per_gas,
+                None => self.inner.default_priority_fee_per_gas,
+            };
+
+            // Fetch current base fee and add `max_priority_fee_per_gas`
+            let max_fee_per_gas = match options.max_fee_per_gas {
+                Some(max_fee_per_gas) => max_fee_per_gas,
+                None => {
+                    self.get_pending_block_base_fee_per_gas(component).await?
+                        + max_priority_fee_per_gas
+                }
+            };
+
+            if max_fee_per_gas < max_priority_fee_per_gas {
+                return Err(Error::WrongFeeProvided(
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                ));
+            }
+
+            tx.max_priority_fee_per_gas = max_priority_fee_per_gas;
+            tx.max_fee_per_gas = max_fee_per_gas;
+            tx.transaction_type = Some(EIP_1559_TX_TYPE.into());
+        }
+
+        // The nonce is obtained last, so that the reserved nonce isn't lost if fetching fees fails.
+        tx.nonce = match (options.nonce, &self.nonce_manager) {
+            (Some(nonce), _) => nonce,
+            (None, Some(nonce_manager)) => {
+                let pending_nonce = self.pending_nonce(component);
+                nonce_manager
+                    .reserve(self.signer.address, pending_nonce)
+                    .await?
+            }
+            (None, None) => self.pending_nonce(component).await?,
+        };
+        Ok(tx)
+    }
+
+    /// Signs a prepared transaction, releasing its nonce if it was reserved by the nonce manager
+    /// and signing fails.
+    async fn sign_tx(
+        &self,
+        tx: TransactionParameters,
+        is_reserved_nonce: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let nonce = tx.nonce;
+        let result = self.signer.eth_signer.sign_transaction(tx).await;
+        if let (Err(_), Some(nonce_manager)) = (&result, &self.nonce_manager) {
+            if is_reserved_nonce {
+                nonce_manager.release(self.signer.address, nonce).await;
+            }
+        }
+        Ok(result?)
+    }
+
+    /// Records a signed transaction in the nonce manager (if any).
+    fn track_signed_tx(&self, raw_tx: &[u8], nonce: U256, is_reserved_nonce: bool) {
+        if let Some(nonce_manager) = &self.nonce_manager {
+            let raw_tx_hash = H256(web3::signing::keccak256(raw_tx));
+            nonce_manager.track(raw_tx_hash, self.signer.address, nonce, is_reserved_nonce);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        mem,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Mutex,
+        },
+    };
+
+    use assert_matches::assert_matches;
+    use serde_json::json;
+    use zksync_eth_signer::{json_rpc_signer::SignerType, TypedDataField};
+
+    use super::*;
+    use crate::{
+        clients::http::mock_server::MockRpcServer, BlobSidecarError, HdWalletError, RevertReason,
+        BYTES_PER_BLOB, BYTES_PER_COMMITMENT, BYTES_PER_PROOF,
+    };
+
+    fn test_client_with_url(url: &str) -> PKSigningClient {
+        let private_key = H256::repeat_byte(0x5);
+        let operator_address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        SigningClient::new(
+            HttpTransport::new(url).unwrap(),
+            zksync_contract(),
+            operator_address,
+            PrivateKeySigner::new(private_key),
+            Address::repeat_byte(0x22),
+            1.into(),
+            L1ChainId(9),
+        )
+    }
+
+    fn test_client() -> PKSigningClient {
+        test_client_with_url("http://127.0.0.1:1")
+    }
+
+    fn test_options() -> Options {
+        Options {
+            nonce: Some(1.into()),
+            gas: Some(100_000.into()),
+            max_fee_per_gas: Some(100.into()),
+            max_priority_fee_per_gas: Some(10.into()),
+            ..Options::default()
+        }
+    }
+
+    #[test]
+    fn creating_client_from_mnemonic() {
+        let new_client = |mnemonic: &str, path: &str| {
+            PKSigningClient::from_mnemonic(
+                mnemonic,
+                path,
+                // The client must not query the node, so an unreachable URL is fine.
+                HttpTransport::new("http://127.0.0.1:1").unwrap(),
+                zksync_contract(),
+                Address::repeat_byte(0x22),
+                1.into(),
+                L1ChainId(9),
+            )
+        };
+
+        let mnemonic = "test test test test test test test test test test test junk";
+        let client = new_client(mnemonic, crate::DEFAULT_DERIVATION_PATH).unwrap();
+        let expected_address: Address = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+            .parse()
+            .unwrap();
+        assert_eq!(client.sender_account(), expected_address);
+
+        let err = new_client("test junk", crate::DEFAULT_DERIVATION_PATH).unwrap_err();
+        assert_matches!(err, Error::HdWallet(HdWalletError::InvalidMnemonic(_)));
+        let err = new_client(mnemonic, "m/44'/60'/0'/0/zero").unwrap_err();
+        assert_matches!(
+            err,
+            Error::HdWallet(HdWalletError::InvalidDerivationPath { .. })
+        );
+    }
+
+    #[tokio::test]
+    async fn signing_blob_transaction() {
+        let client = test_client();
+        let sidecar = BlobTxSidecar {
+            blobs: vec![vec![1; BYTES_PER_BLOB]; 2],
+            commitments: vec![vec![2; BYTES_PER_COMMITMENT], vec![3; BYTES_PER_COMMITMENT]],
+            proofs: vec![vec![4; BYTES_PER_PROOF]; 2],
+        };
+        let signed_tx = client
+            .sign_prepared_blob_tx_for_addr(
+                b"test".to_vec(),
+                client.contract_addr(),
+                test_options(),
+                5.into(),
+                sidecar.clone(),
+                "test",
+            )
+            .await
+            .unwrap();
+        assert_eq!(signed_tx.nonce, 1.into());
+
+        let raw_tx = signed_tx.raw_tx.as_ref();
+        assert_eq!(raw_tx[0], EIP_4844_TX_TYPE);
+        let network_tx = rlp::Rlp::new(&raw_tx[1..]);
+        assert_eq!(network_tx.item_count().unwrap(), 4);
+        let tx_body = network_tx.at(0).unwrap();
+        // 11 transaction fields + 3 signature fields
+        assert_eq!(tx_body.item_count().unwrap(), 14);
+        let max_fee_per_blob_gas: U256 = tx_body.val_at(9).unwrap();
+        assert_eq!(max_fee_per_blob_gas, 5.into());
+        let versioned_hashes: Vec<H256> = tx_body.list_at(10).unwrap();
+        assert_eq!(versioned_hashes, sidecar.versioned_hashes());
+
+        let expected_hash =
+            web3::signing::keccak256(&[&[EIP_4844_TX_TYPE], tx_body.as_raw()].concat());
+        assert_eq!(signed_tx.hash, H256(expected_hash));
+    }
+
+    #[tokio::test]
+    async fn invalid_blob_sidecar_is_rejected_before_signing() {
+        let client = test_client();
+        let sidecar = BlobTxSidecar {
+            blobs: vec![vec![1; BYTES_PER_BLOB]; 2],
+            commitments: vec![vec![2; BYTES_PER_COMMITMENT]],
+            proofs: vec![vec![4; BYTES_PER_PROOF]; 2],
+        };
+        let err = client
+            .sign_prepared_blob_tx_for_addr(
+                b"test".to_vec(),
+                client.contract_addr(),
+                test_options(),
+                5.into(),
+                sidecar,
+                "test",
+            )
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            Error::BlobSidecar(BlobSidecarError::LengthMismatch { .. })
+        );
+    }
+
+    #[tokio::test]
+    async fn preflight_simulation() {
+        // `Error(string)` revert with the "insufficient balance" message
+        const REVERT_DATA: &str = "0x08c379a0\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            0000000000000000000000000000000000000000000000000000000000000014\
+            696e73756666696369656e742062616c616e6365000000000000000000000000";
+
+        let should_revert = Arc::new(AtomicBool::new(true));
+        let server = MockRpcServer::spawn({
+            let should_revert = should_revert.clone();
+            move |method, params| match method {
+                "eth_call" => {
+                    assert_eq!(params[1], "pending");
+                    assert_eq!(params[0]["to"], json!(Address::repeat_byte(0x22)));
+                    assert_eq!(params[0]["data"], "0x74657374");
+                    assert_eq!(params[0]["gas"], "0x186a0");
+                    if should_revert.load(Ordering::SeqCst) {
+                        Err(jsonrpc_core::Error {
+                            code: jsonrpc_core::ErrorCode::ServerError(3),
+                            message: "execution reverted: insufficient balance".to_owned(),
+                            data: Some(REVERT_DATA.into()),
+                        })
+                    } else {
+                        Ok(json!("0x"))
+                    }
+                }
+                _ => Err(jsonrpc_core::Error::method_not_found()),
+            }
+        })
+        .await;
+        let client = test_client_with_url(&server.url()).with_tx_fee_model(TxFeeModel::Eip1559);
+        let sign = |client: PKSigningClient| async move {
+            client
+                .sign_prepared_tx_for_addr(
+                    b"test".to_vec(),
+                    client.contract_addr(),
+                    test_options(),
+                    "test",
+                )
+                .await
+        };
+
+        // Simulation is disabled by default.
+        sign(client.clone()).await.unwrap();
+        assert_eq!(server.http_request_count(), 0);
+
+        let client = client.with_preflight_simulation();
+        let err = sign(client.clone()).await.unwrap_err();
+        assert_matches!(
+            err,
+            Error::WouldRevert { reason: RevertReason::Error(message) }
+                if message == "insufficient balance"
+        );
+        assert_eq!(server.http_request_count(), 1);
+
+        should_revert.store(false, Ordering::SeqCst);
+        let signed_tx = sign(client.clone()).await.unwrap();
+        assert_eq!(signed_tx.nonce, 1.into());
+        assert_eq!(server.http_request_count(), 2);
+
+        // Blob transactions are not simulated.
+        should_revert.store(true, Ordering::SeqCst);
+        let sidecar = BlobTxSidecar {
+            blobs: vec![vec![1; BYTES_PER_BLOB]],
+            commitments: vec![vec![2; BYTES_PER_COMMITMENT]],
+            proofs: vec![vec![4; BYTES_PER_PROOF]],
+        };
+        client
+            .sign_prepared_blob_tx_for_addr(
+                b"test".to_vec(),
+                client.contract_addr(),
+                test_options(),
+                5.into(),
+                sidecar,
+                "test",
+            )
+            .await
+            .unwrap();
+        assert_eq!(server.http_request_count(), 2);
+    }
+
+    /// Uses the example from EIP-155: <https://eips.ethereum.org/EIPS/eip-155#example>.
+    #[tokio::test]
+    async fn signing_legacy_transaction() {
+        let private_key = H256::repeat_byte(0x46);
+        let operator_address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let recipient = Address::repeat_byte(0x35);
+        let client = SigningClient::new(
+            HttpTransport::new("http://127.0.0.1:1").unwrap(),
+            zksync_contract(),
+            operator_address,
+            PrivateKeySigner::new(private_key),
+            recipient,
+            1.into(),
+            L1ChainId(1),
+        )
+        .with_tx_fee_model(TxFeeModel::Legacy);
+
+        let options = Options {
+            nonce: Some(9.into()),
+            gas: Some(21_000.into()),
+            gas_price: Some(20_000_000_000_u64.into()),
+            value: Some(U256::exp10(18)),
+            ..Options::default()
+        };
+        let signed_tx = client
+            .sign_prepared_tx_for_addr(vec![], recipient, options, "test")
+            .await
+            .unwrap();
+
+        let expected_raw_tx = hex::decode(
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000\
+             8025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f\
+             761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        )
+        .unwrap();
+        assert_eq!(signed_tx.raw_tx.as_ref(), expected_raw_tx);
+        assert_eq!(
+            signed_tx.hash,
+            H256(web3::signing::keccak256(&expected_raw_tx))
+        );
+        assert_eq!(signed_tx.max_fee_per_gas, 20_000_000_000_u64.into());
+    }
+
+    #[tokio::test]
+    async fn signing_eip1559_transaction() {
+        let client = test_client().with_tx_fee_model(TxFeeModel::Eip1559);
+        let signed_tx = client
+            .sign_prepared_tx_for_addr(
+                b"test".to_vec(),
+                client.contract_addr(),
+                test_options(),
+                "test",
+            )
+            .await
+            .unwrap();
+
+        let raw_tx = signed_tx.raw_tx.as_ref();
+        assert_eq!(raw_tx[0], EIP_1559_TX_TYPE);
+        let tx_body = rlp::Rlp::new(&raw_tx[1..]);
+        // 9 transaction fields + 3 signature fields
+        assert_eq!(tx_body.item_count().unwrap(), 12);
+        assert_eq!(tx_body.val_at::<u64>(0).unwrap(), 9);
+        assert_eq!(tx_body.val_at::<U256>(1).unwrap(), 1.into());
+        assert_eq!(tx_body.val_at::<U256>(2).unwrap(), 10.into());
+        assert_eq!(tx_body.val_at::<U256>(3).unwrap(), 100.into());
+        assert_eq!(
+            tx_body.val_at::<Address>(5).unwrap(),
+            client.contract_addr()
+        );
+
+        let mut unsigned_tx = rlp::RlpStream::new_list(9);
+        for i in 0..9 {
+            unsigned_tx.append_raw(tx_body.at(i).unwrap().as_raw(), 1);
+        }
+        let message =
+            web3::signing::keccak256(&[&[EIP_1559_TX_TYPE], unsigned_tx.as_raw()].concat());
+        let [r, s] = [10, 11].map(|i| {
+            let mut bytes = [0_u8; 32];
+            tx_body.val_at::<U256>(i).unwrap().to_big_endian(&mut bytes);
+            H256(bytes)
+        });
+        let v: u8 = tx_body.val_at(9).unwrap();
+        let signer = PackedEthSignature::from_rsv(&r, &s, v)
+            .signature_recover_signer(&H256(message))
+            .unwrap();
+        assert_eq!(signer, client.sender_account());
+
+        // Fixed vector: chain ID 9, nonce 1, max priority fee 10 wei, max fee 100 wei, gas limit 100,000,
+        // recipient 0x22..22, zero value, `b"test"` calldata, empty access list, signed with the private key 0x05..05.
+        let expected_raw_tx =
+            "02f86709010a64830186a0942222222222222222222222222222222222222222808474657374c0\
+            01a09ef0de5380e2b0db1973b81b3847c3a986793f9d04d6389effdb29442331ec0c\
+            a04e61e6c02ddc50d11bfde3786684d25362524761f9d19d0f631cfafef80dc4b5";
+        assert_eq!(hex::encode(raw_tx), expected_raw_tx);
+        let expected_hash: H256 =
+            "0xb6e1e5d316709df6dd872b0c35a6c32fe92c9e1a78219e07ed7bacf5453d88e8"
+                .parse()
+                .unwrap();
+        assert_eq!(signed_tx.hash, expected_hash);
+    }
+
+    #[tokio::test]
+    async fn detecting_tx_fee_model() {
+        for (block, expected_fee_model) in [
+            (
+                json!({ "number": "0x1", "baseFeePerGas": "0x7" }),
+                TxFeeModel::Eip1559,
+            ),
+            (json!({ "number": "0x1" }), TxFeeModel::Legacy),
+        ] {
+            let server = MockRpcServer::spawn(move |method, params| match method {
+                "eth_getBlockByNumber" => {
+                    assert_eq!(params[0], "latest");
+                    Ok(block.clone())
+                }
+                "eth_gasPrice" => Ok(json!("0x3b9aca00")),
+                "eth_getTransactionCount" => Ok(json!("0x1")),
+                _ => Err(jsonrpc_core::Error::method_not_found()),
+            })
+            .await;
+            let client = test_client_with_url(&server.url());
+
+            assert_eq!(
+                client.tx_fee_model("test").await.unwrap(),
+                expected_fee_model
+            );
+            // The fee model should be cached.
+            assert_eq!(
+                client.clone().tx_fee_model("test").await.unwrap(),
+                expected_fee_model
+            );
+            assert_eq!(server.http_request_count(), 1);
+
+            if expected_fee_model == TxFeeModel::Legacy {
+                let options = Options {
+                    gas: Some(100_000.into()),
+                    ..Options::default()
+                };
+                let signed_tx = client
+                    .sign_prepared_tx_for_addr(vec![], client.contract_addr(), options, "test")
+                    .await
+                    .unwrap();
+                // Legacy transactions are RLP lists without a type prefix.
+                assert!(signed_tx.raw_tx.as_ref()[0] >= 0xc0);
+                assert_eq!(signed_tx.nonce, 1.into());
+                assert_eq!(signed_tx.max_fee_per_gas, 1_000_000_000.into());
+            }
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct MockNodeState {
+        pending_nonce: u64,
+        sent_nonces: HashSet<u64>,
+        nonce_queries: usize,
+        fail_next_send: bool,
+    }
+
+    /// Spawns a node accepting EIP-1559 transactions with unused nonces.
+    async fn spawn_node(state: Arc<Mutex<MockNodeState>>) -> MockRpcServer {
+        MockRpcServer::spawn(move |method, params| {
+            let mut state = state.lock().unwrap();
+            match method {
+                "eth_getTransactionCount" => {
+                    assert_eq!(params[1], "pending");
+                    state.nonce_queries += 1;
+                    Ok(json!(U256::from(state.pending_nonce)))
+                }
+                "eth_sendRawTransaction" => {
+                    let raw_tx = params[0].as_str().unwrap();
+                    let raw_tx = hex::decode(raw_tx.strip_prefix("0x").unwrap()).unwrap();
+                    assert_eq!(raw_tx[0], EIP_1559_TX_TYPE);
+                    let nonce: u64 = rlp::Rlp::new(&raw_tx[1..]).val_at(1).unwrap();
+
+                    if mem::take(&mut state.fail_next_send) {
+                        return Err(jsonrpc_core::Error::internal_error());
+                    }
+                    if nonce < state.pending_nonce || state.sent_nonces.contains(&nonce) {
+                        return Err(jsonrpc_core::Error {
+                            code: jsonrpc_core::ErrorCode::ServerError(-32000),
+                            message: "nonce too low".to_owned(),
+                            data: None,
+                        });
+                    }
+                    state.sent_nonces.insert(nonce);
+                    while state.sent_nonces.contains(&state.pending_nonce) {
+                        state.pending_nonce += 1;
+                    }
+                    Ok(json!(H256(web3::signing::keccak256(&raw_tx))))
+                }
+                _ => Err(jsonrpc_core::Error::method_not_found()),
+            }
+        })
+        .await
+    }
+
+    fn options_without_nonce() -> Options {
+        Options {
+            nonce: None,
+            ..test_options()
+        }
+    }
+
+    async fn sign_and_send(client: &PKSigningClient) -> (U256, Result<H256, Error>) {
+        let signed_tx = client
+            .sign_prepared_tx(b"test".to_vec(), options_without_nonce(), "test")
+            .await
+            .unwrap();
+        let result = client.send_raw_tx(signed_tx.raw_tx).await;
+        (signed_tx.nonce, result)
+    }
+
+    #[tokio::test]
+    async fn nonce_manager_with_interleaved_submissions() {
+        let state = Arc::<Mutex<MockNodeState>>::default();
+        let server = spawn_node(state.clone()).await;
+        let client = test_client_with_url(&server.url())
+            .with_tx_fee_model(TxFeeModel::Eip1559)
+            .with_nonce_manager();
+
+        let submissions = (0..5).map(|_| {
+            let client = client.clone();
+            async move { sign_and_send(&client).await }
+        });
+        let results = futures::future::join_all(submissions).await;
+        let mut nonces: Vec<_> = results
+            .into_iter()
+            .map(|(nonce, result)| {
+                result.unwrap();
+                nonce.as_u64()
+            })
+            .collect();
+        nonces.sort_unstable();
+        assert_eq!(nonces, [0, 1, 2, 3, 4]);
+
+        let state = state.lock().unwrap();
+        assert_eq!(state.pending_nonce, 5);
+        assert_eq!(state.nonce_queries, 1);
+    }
+
+    #[tokio::test]
+    async fn selecting_signers() {
+        let blob_private_key = H256::repeat_byte(0x6);
+        let blob_operator =
+            PackedEthSignature::address_from_private_key(&blob_private_key).unwrap();
+        let server = MockRpcServer::spawn(move |method, params| match method {
+            "eth_getTransactionCount" => {
+                let address: Address = serde_json::from_value(params[0].clone()).unwrap();
+                Ok(if address == blob_operator {
+                    json!("0xa")
+                } else {
+                    json!("0x5")
+                })
+            }
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await;
+        let client = test_client_with_url(&server.url())
+            .with_tx_fee_model(TxFeeModel::Eip1559)
+            .with_nonce_manager()
+            .with_signer_key(BLOB_OPERATOR_SIGNER, blob_private_key);
+        let default_operator = client.sender_account();
+        assert_eq!(client.signer_name(), DEFAULT_SIGNER);
+        let mut names: Vec<_> = client.signer_names().collect();
+        names.sort_unstable();
+        assert_eq!(names, [BLOB_OPERATOR_SIGNER, DEFAULT_SIGNER]);
+
+        let blob_client = client.for_signer(BLOB_OPERATOR_SIGNER).unwrap();
+        assert_eq!(blob_client.signer_name(), BLOB_OPERATOR_SIGNER);
+        assert_eq!(blob_client.sender_account(), blob_operator);
+        assert_ne!(blob_operator, default_operator);
+        assert_eq!(
+            client.for_signer(DEFAULT_SIGNER).unwrap().sender_account(),
+            default_operator
+        );
+        let err = client.for_signer("unknown").unwrap_err();
+        assert_matches!(err, Error::UnknownSigner(name) if name == "unknown");
+
+        // Nonces must be managed independently for each signer.
+        for expected_nonce in [5_u64, 6] {
+            let signed_tx = client
+                .sign_prepared_tx(b"test".to_vec(), options_without_nonce(), "test")
+                .await
+                .unwrap();
+            assert_eq!(signed_tx.nonce, expected_nonce.into());
+        }
+        for expected_nonce in [10_u64, 11] {
+            let signed_tx = blob_client
+                .sign_prepared_tx(b"test".to_vec(), options_without_nonce(), "test")
+                .await
+                .unwrap();
+            assert_eq!(signed_tx.nonce, expected_nonce.into());
+        }
+        assert_eq!(server.http_request_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn nonce_manager_resyncs_after_nonce_errors() {
+        let state = Arc::<Mutex<MockNodeState>>::default();
+        let server = spawn_node(state.clone()).await;
+        let client = test_client_with_url(&server.url())
+            .with_tx_fee_model(TxFeeModel::Eip1559)
+            .with_nonce_manager();
+
+        let (nonce, result) = sign_and_send(&client).await;
+        assert_eq!(nonce, 0.into());
+        result.unwrap();
+
+        // Simulate another sender using the same account.
+        state.lock().unwrap().pending_nonce = 3;
+        let (nonce, result) = sign_and_send(&client).await;
+        assert_eq!(nonce, 1.into());
+        let err = result.unwrap_err();
+        assert!(err.is_nonce_error(), "{err}");
+
+        let (nonce, result) = sign_and_send(&client).await;
+        assert_eq!(nonce, 3.into());
+        result.unwrap();
+        assert_eq!(state.lock().unwrap().nonce_queries, 2);
+
+        // A nonce of a transaction failed for other reasons should be reused.
+        state.lock().unwrap().fail_next_send = true;
+        let (nonce, result) = sign_and_send(&client).await;
+        assert_eq!(nonce, 4.into());
+        let err = result.unwrap_err();
+        assert!(!err.is_nonce_error(), "{err}");
+        let (nonce, result) = sign_and_send(&client).await;
+        assert_eq!(nonce, 4.into());
+        result.unwrap();
+
+        client.reset_nonce().await;
+        let (nonce, result) = sign_and_send(&client).await;
+        assert_eq!(nonce, 5.into());
+        result.unwrap();
+        assert_eq!(state.lock().unwrap().nonce_queries, 3);
+    }
+
+    const REMOTE_ACCOUNT: Address = Address::repeat_byte(0x33);
+
+    /// Spawns a remote signer managing [`REMOTE_ACCOUNT`]. Signing is refused for transactions with zero nonce.
+    async fn spawn_remote_signer() -> MockRpcServer {
+        MockRpcServer::spawn(|method, params| match method {
+            "eth_accounts" => Ok(json!([REMOTE_ACCOUNT])),
+            "eth_signTransaction" => {
+                let tx = &params[0];
+                assert_eq!(tx["from"], json!(REMOTE_ACCOUNT));
+                if tx["nonce"] == "0x0" {
+                    return Err(jsonrpc_core::Error {
+                        code: jsonrpc_core::ErrorCode::ServerError(-32000),
+                        message: "account is locked".to_owned(),
+                        data: None,
+                    });
+                }
+                Ok(json!({ "raw": "0x02c0ffee", "tx": tx }))
+            }
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await
+    }
+
+    async fn remote_signer(server: &MockRpcServer) -> JsonRpcSigner {
+        JsonRpcSigner::new(server.url(), None, Some(SignerType::NeedPrefix), None)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn signing_with_remote_signer() {
+        let signer_server = spawn_remote_signer().await;
+        let client = JsonRpcSigningClient::from_signer(
+            HttpTransport::new("http://127.0.0.1:1").unwrap(),
+            zksync_contract(),
+            remote_signer(&signer_server).await,
+            Address::repeat_byte(0x22),
+            1.into(),
+            L1ChainId(9),
+        )
+        .await
+        .unwrap()
+        .with_tx_fee_model(TxFeeModel::Eip1559);
+        assert_eq!(client.sender_account(), REMOTE_ACCOUNT);
+        client.check_signer_address().await.unwrap();
+
+        let signed_tx = client
+            .sign_prepared_tx(b"test".to_vec(), test_options(), "test")
+            .await
+            .unwrap();
+        assert_eq!(signed_tx.raw_tx.as_ref(), [0x02, 0xc0, 0xff, 0xee]);
+        assert_eq!(
+            signed_tx.hash,
+            H256(web3::signing::keccak256(&[0x02, 0xc0, 0xff, 0xee]))
+        );
+
+        let options = Options {
+            nonce: Some(0.into()),
+            ..test_options()
+        };
+        let err = client
+            .sign_prepared_tx(b"test".to_vec(), options, "test")
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            Error::Signer(SignerError::SigningFailed(message)) if message.contains("account is locked")
+        );
+    }
+
+    fn test_typed_data() -> (TypedDataDomain, TypedDataTypes, serde_json::Value) {
+        let domain = TypedDataDomain {
+            name: Some("Governance".to_owned()),
+            version: Some("1".to_owned()),
+            chain_id: Some(9.into()),
+            verifying_contract: Some(Address::repeat_byte(0x22)),
+            salt: None,
+        };
+        let types = TypedDataTypes::from([(
+            "Attestation".to_owned(),
+            vec![
+                TypedDataField::new("batchNumber", "uint256"),
+                TypedDataField::new("commitment", "bytes32"),
+            ],
+        )]);
+        let message = json!({
+            "batchNumber": 42,
+            "commitment": H256::repeat_byte(0xaa),
+        });
+        (domain, types, message)
+    }
+
+    #[tokio::test]
+    async fn checking_chain_id_before_signing() {
+        let server = MockRpcServer::spawn(|method, _| match method {
+            "eth_chainId" => Ok(json!("0x5")),
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await;
+        let client = test_client_with_url(&server.url())
+            .with_tx_fee_model(TxFeeModel::Eip1559)
+            .with_chain_id_check();
+        let err = client
+            .sign_prepared_tx(vec![], test_options(), "test")
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            Error::ChainIdMismatch { expected, actual }
+                if expected == L1ChainId(9) && actual == L1ChainId(5)
+        );
+
+        let server = MockRpcServer::spawn(|method, _| match method {
+            "eth_chainId" => Ok(json!("0x9")),
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await;
+        let client = test_client_with_url(&server.url())
+            .with_tx_fee_model(TxFeeModel::Eip1559)
+            .with_chain_id_check();
+        for _ in 0..3 {
+            client
+                .clone()
+                .sign_prepared_tx(vec![], test_options(), "test")
+                .await
+                .unwrap();
+        }
+        // The successful check should be cached.
+        assert_eq!(server.http_request_count(), 1);
+
+        client.verify_chain_id("test").await.unwrap();
+        assert_eq!(server.http_request_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn signing_typed_data() {
+        let client = test_client();
+        let (domain, types, message) = test_typed_data();
+        let signature = client
+            .sign_typed_data(domain.clone(), types.clone(), message.clone())
+            .await
+            .unwrap();
+
+        let typed_data = TypedData::new(domain, types, message).unwrap();
+        assert_eq!(typed_data.primary_type, "Attestation");
+        assert_eq!(
+            typed_data.recover_signer(&signature).unwrap(),
+            client.sender_account()
+        );
+        assert!(client
+            .verify_typed_data_signature(&typed_data, &signature)
+            .unwrap());
+
+        let other_typed_data = TypedData {
+            message: json!({ "batchNumber": 43, "commitment": H256::repeat_byte(0xaa) }),
+            ..typed_data
+        };
+        assert!(!client
+            .verify_typed_data_signature(&other_typed_data, &signature)
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn signing_typed_data_with_remote_signer() {
+        let private_key = H256::repeat_byte(0x7);
+        let account = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let signer_server = MockRpcServer::spawn(move |method, params| match method {
+            "eth_accounts" => Ok(json!([account])),
+            "eth_signTypedData_v4" => {
+                assert_eq!(params[0], json!(account));
+                assert_eq!(params[1]["primaryType"], "Attestation");
+                let typed_data: TypedData = serde_json::from_value(params[1].clone()).unwrap();
+                let signing_hash = typed_data.signing_hash().unwrap();
+                let signature = PackedEthSignature::sign_raw(&private_key, &signing_hash).unwrap();
+                Ok(json!(signature))
+            }
+            _ => Err(jsonrpc_core::Error::method_not_found()),
+        })
+        .await;
+        let client = JsonRpcSigningClient::from_signer(
+            HttpTransport::new("http://127.0.0.1:1").unwrap(),
+            zksync_contract(),
+            remote_signer(&signer_server).await,
+            Address::repeat_byte(0x22),
+            1.into(),
+            L1ChainId(9),
+        )
+        .await
+        .unwrap();
+
+        let (domain, types, message) = test_typed_data();
+        let typed_data = TypedData::new(domain.clone(), types.clone(), message.clone()).unwrap();
+        let signature = client
+            .sign_typed_data(domain, types, message)
+            .await
+            .unwrap();
+        assert!(client
+            .verify_typed_data_signature(&typed_data, &signature)
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn detecting_signer_address_mismatch() {
+        let signer_server = spawn_remote_signer().await;
+        let operator_address = Address::repeat_byte(0x11);
+        let client = JsonRpcSigningClient::new(
+            HttpTransport::new("http://127.0.0.1:1").unwrap(),
+            zksync_contract(),
+            operator_address,
+            remote_signer(&signer_server).await,
+            Address::repeat_byte(0x22),
+            1.into(),
+            L1ChainId(9),
+        );
+
+        let err = client.check_signer_address().await.unwrap_err();
+        assert_matches!(
+            err,
+            Error::SignerAddressMismatch { expected, actual }
+                if expected == operator_address && actual == REMOTE_ACCOUNT
+        );
+    }
 }
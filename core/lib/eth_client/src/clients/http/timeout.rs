@@ -0,0 +1,53 @@
+//! Per-method request timeouts for HTTP clients.
+
+use std::{collections::HashMap, future::Future, time::Duration};
+
+use super::Method;
+use crate::types::Error;
+
+/// Timeouts for requests sent by a [`QueryClient`], keyed by the JSON-RPC method name (e.g., `eth_getLogs`).
+/// A timeout applies to a single request; if retries are enabled, timed out requests are retried.
+///
+/// [`QueryClient`]: super::QueryClient
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimeoutPolicy {
+    default: Option<Duration>,
+    per_method: HashMap<String, Duration>,
+}
+
+impl TimeoutPolicy {
+    /// Creates a policy with the specified timeout for all methods.
+    pub fn new(default: Duration) -> Self {
+        Self {
+            default: Some(default),
+            per_method: HashMap::new(),
+        }
+    }
+
+    /// Overrides the timeout for the specified JSON-RPC method.
+    pub fn with_method_timeout(mut self, method: impl Into<String>, timeout: Duration) -> Self {
+        self.per_method.insert(method.into(), timeout);
+        self
+    }
+
+    fn timeout(&self, rpc_method: &str) -> Option<Duration> {
+        self.per_method.get(rpc_method).copied().or(self.default)
+    }
+
+    pub(super) async fn enforce<R>(
+        policy: Option<&Self>,
+        method: Method,
+        request: impl Future<Output = Result<R, Error>>,
+    ) -> Result<R, Error> {
+        let rpc_method = method.rpc_name();
+        let Some(timeout) = policy.and_then(|policy| policy.timeout(rpc_method)) else {
+            return request.await;
+        };
+        tokio::time::timeout(timeout, request)
+            .await
+            .unwrap_or(Err(Error::RequestTimeout {
+                method: rpc_method,
+                timeout,
+            }))
+    }
+}
@@ -0,0 +1,256 @@
+//! Types for transaction tracing via `debug_traceTransaction`.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use zksync_types::web3::types::{Address, Bytes, U256};
+
+/// Tracer used by [`QueryClient::trace_transaction()`].
+///
+/// [`QueryClient::trace_transaction()`]: super::QueryClient::trace_transaction()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TracerConfig {
+    /// Built-in `callTracer` returning a tree of call frames.
+    CallTracer {
+        /// If set, only the top-level call is traced.
+        only_top_call: bool,
+    },
+    /// Default opcode-level tracer returning struct logs. Traces produced by this tracer can be very large;
+    /// parts of struct logs can be disabled to reduce the trace size.
+    StructLogs {
+        disable_stack: bool,
+        disable_storage: bool,
+        enable_memory: bool,
+        enable_return_data: bool,
+    },
+}
+
+impl TracerConfig {
+    /// Call tracer tracing all nested calls.
+    pub const CALL_TRACER: Self = Self::CallTracer {
+        only_top_call: false,
+    };
+}
+
+/// Serialized tracer config as expected by `debug_traceTransaction`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct RawTracerConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tracer: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tracer_config: Option<RawCallTracerConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disable_stack: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disable_storage: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_memory: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_return_data: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RawCallTracerConfig {
+    only_top_call: bool,
+}
+
+impl From<TracerConfig> for RawTracerConfig {
+    fn from(config: TracerConfig) -> Self {
+        match config {
+            TracerConfig::CallTracer { only_top_call } => Self {
+                tracer: Some("callTracer"),
+                tracer_config: Some(RawCallTracerConfig { only_top_call }),
+                disable_stack: None,
+                disable_storage: None,
+                enable_memory: None,
+                enable_return_data: None,
+            },
+            TracerConfig::StructLogs {
+                disable_stack,
+                disable_storage,
+                enable_memory,
+                enable_return_data,
+            } => Self {
+                tracer: None,
+                tracer_config: None,
+                disable_stack: Some(disable_stack),
+                disable_storage: Some(disable_storage),
+                enable_memory: Some(enable_memory),
+                enable_return_data: Some(enable_return_data),
+            },
+        }
+    }
+}
+
+/// Transaction trace returned by [`QueryClient::trace_transaction()`]. The variant corresponds to the used tracer.
+///
+/// [`QueryClient::trace_transaction()`]: super::QueryClient::trace_transaction()
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionTrace {
+    Call(CallFrame),
+    StructLogs(StructLogTrace),
+}
+
+/// Call frame produced by `callTracer`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    /// Call type, e.g. `CALL`, `DELEGATECALL` or `CREATE`.
+    #[serde(rename = "type")]
+    pub call_type: String,
+    pub from: Address,
+    /// Called address. May be missing for failed contract creations.
+    #[serde(default)]
+    pub to: Option<Address>,
+    /// Transferred value. Missing for calls not transferring value, such as `STATICCALL`.
+    #[serde(default)]
+    pub value: Option<U256>,
+    pub gas: U256,
+    pub gas_used: U256,
+    pub input: Bytes,
+    #[serde(default)]
+    pub output: Option<Bytes>,
+    /// Error message if the call has failed, e.g. `execution reverted`.
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Decoded revert reason if the call has reverted with an `Error(string)`.
+    #[serde(default)]
+    pub revert_reason: Option<String>,
+    /// Nested calls.
+    #[serde(default)]
+    pub calls: Vec<CallFrame>,
+}
+
+impl CallFrame {
+    /// Returns the innermost failed call on the failure path starting from this frame, which is usually the root cause
+    /// of the failure. Returns `None` if this call hasn't failed.
+    pub fn innermost_failed_call(&self) -> Option<&Self> {
+        self.error.as_ref()?;
+        // Failed nested calls may be handled by the caller, so only the last one may cause the failure.
+        let last_failed_call = self.calls.iter().rev().find(|call| call.error.is_some());
+        Some(last_failed_call.map_or(self, |call| call.innermost_failed_call().unwrap()))
+    }
+}
+
+/// Trace produced by the default struct logs tracer.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogTrace {
+    pub gas: u64,
+    pub failed: bool,
+    /// Hex-encoded return data of the transaction.
+    pub return_value: String,
+    pub struct_logs: Vec<StructLog>,
+}
+
+/// Single executed opcode in [`StructLogTrace`]. Stack, memory and storage are provided as returned by the node
+/// (i.e., as hex strings) since their encoding differs among node implementations.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+    pub pc: u64,
+    pub op: String,
+    pub gas: u64,
+    pub gas_cost: u64,
+    pub depth: u64,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub stack: Option<Vec<String>>,
+    #[serde(default)]
+    pub memory: Option<Vec<String>>,
+    #[serde(default)]
+    pub storage: Option<BTreeMap<String, String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn serializing_tracer_config() {
+        let config = RawTracerConfig::from(TracerConfig::CALL_TRACER);
+        assert_eq!(
+            serde_json::to_value(config).unwrap(),
+            json!({ "tracer": "callTracer", "tracerConfig": { "onlyTopCall": false } })
+        );
+
+        let config = RawTracerConfig::from(TracerConfig::StructLogs {
+            disable_stack: false,
+            disable_storage: true,
+            enable_memory: false,
+            enable_return_data: true,
+        });
+        assert_eq!(
+            serde_json::to_value(config).unwrap(),
+            json!({
+                "disableStack": false,
+                "disableStorage": true,
+                "enableMemory": false,
+                "enableReturnData": true,
+            })
+        );
+    }
+
+    #[test]
+    fn deserializing_call_trace() {
+        let trace: CallFrame =
+            serde_json::from_str(include_str!("fixtures/call_tracer.json")).unwrap();
+        assert_eq!(trace.call_type, "CALL");
+        assert_eq!(trace.value, Some(0.into()));
+        assert_eq!(trace.gas_used, 0x1cbd5.into());
+        assert_eq!(trace.revert_reason.as_deref(), Some("i"));
+        assert_eq!(trace.calls.len(), 1);
+
+        let delegate_call = &trace.calls[0];
+        assert_eq!(delegate_call.call_type, "DELEGATECALL");
+        assert_eq!(delegate_call.value, None);
+        let nested_calls = &delegate_call.calls;
+        assert_eq!(nested_calls.len(), 3);
+        assert!(nested_calls
+            .iter()
+            .all(|call| call.call_type == "STATICCALL"));
+        assert_eq!(nested_calls[0].error, None);
+        assert_eq!(nested_calls[1].output, None);
+
+        let failed_call = trace.innermost_failed_call().unwrap();
+        assert_eq!(failed_call, &nested_calls[2]);
+        assert_eq!(nested_calls[0].innermost_failed_call(), None);
+    }
+
+    #[test]
+    fn deserializing_struct_log_trace() {
+        let trace = json!({
+            "gas": 21_164,
+            "failed": false,
+            "returnValue": "",
+            "structLogs": [
+                {
+                    "pc": 0,
+                    "op": "PUSH1",
+                    "gas": 78_836,
+                    "gasCost": 3,
+                    "depth": 1,
+                    "stack": [],
+                },
+                {
+                    "pc": 2,
+                    "op": "PUSH1",
+                    "gas": 78_833,
+                    "gasCost": 3,
+                    "depth": 1,
+                    "stack": ["0x80"],
+                },
+            ],
+        });
+        let trace: StructLogTrace = serde_json::from_value(trace).unwrap();
+        assert!(!trace.failed);
+        assert_eq!(trace.struct_logs.len(), 2);
+        assert_eq!(trace.struct_logs[1].stack, Some(vec!["0x80".to_owned()]));
+        assert_eq!(trace.struct_logs[1].storage, None);
+    }
+}
@@ -0,0 +1,283 @@
+//! HTTP JSON-RPC transport supporting request / response middleware.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use futures::future::BoxFuture;
+use jsonrpc_core::{Call, Id, Output, Request, Value};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, RETRY_AFTER, USER_AGENT},
+    StatusCode, Url,
+};
+use serde::de::DeserializeOwned;
+use zksync_types::web3::{
+    self, error::TransportError, helpers, BatchTransport, RequestId, Transport,
+};
+
+use super::middleware::{RpcMiddleware, RpcRequest, RpcResponse};
+use crate::types::{rate_limited_error, Error};
+
+/// HTTP transport used by [`QueryClient`](super::QueryClient) and [`SigningClient`](super::SigningClient).
+/// Behaves the same as the `web3` HTTP transport, but allows to observe and modify requests
+/// using [`RpcMiddleware`].
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    client: reqwest::Client,
+    /// Node URL shared among all clones of the transport, so that it can be updated at runtime.
+    url: Arc<RwLock<Url>>,
+    next_request_id: Arc<AtomicUsize>,
+    middleware: Arc<[Arc<dyn RpcMiddleware>]>,
+}
+
+impl HttpTransport {
+    /// Creates a transport for the specified node URL with the default HTTP client settings.
+    pub fn new(node_url: &str) -> Result<Self, Error> {
+        let mut headers = HeaderMap::new();
+        // Mimics the user agent of the default `web3` transport.
+        headers.insert(USER_AGENT, HeaderValue::from_static("web3.rs"));
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(|err| transport_error(format!("failed building HTTP client: {err}")))?;
+        Self::with_client(client, node_url)
+    }
+
+    pub(super) fn with_client(client: reqwest::Client, node_url: &str) -> Result<Self, Error> {
+        Ok(Self {
+            client,
+            url: Arc::new(RwLock::new(parse_url(node_url)?)),
+            next_request_id: Arc::default(),
+            middleware: Arc::new([]),
+        })
+    }
+
+    /// Creates a transport with the same HTTP client and middleware, but sending requests to `node_url`.
+    /// Unlike with clones, the URL of the returned transport is independent of this transport.
+    pub(super) fn with_url(&self, node_url: &str) -> Result<Self, Error> {
+        Ok(Self {
+            url: Arc::new(RwLock::new(parse_url(node_url)?)),
+            ..self.clone()
+        })
+    }
+
+    /// Returns the current node URL.
+    pub(super) fn url(&self) -> Url {
+        self.url.read().unwrap().clone()
+    }
+
+    /// Switches this transport and all its clones to `url`. Requests already being sent are not affected.
+    pub(super) fn set_url(&self, url: Url) {
+        *self.url.write().unwrap() = url;
+    }
+
+    /// Appends `middleware` to the list of middleware invoked for each request.
+    pub(super) fn push_middleware(&mut self, middleware: Arc<dyn RpcMiddleware>) {
+        let mut all_middleware = self.middleware.to_vec();
+        all_middleware.push(middleware);
+        self.middleware = all_middleware.into();
+    }
+
+    /// Sends the request and parses the response, invoking middleware along the way. `on_request` hooks
+    /// are invoked in the order of registration, and `on_response` hooks in the reverse order.
+    async fn execute<T: DeserializeOwned>(self, payload: Request) -> web3::Result<T> {
+        let mut request = RpcRequest {
+            payload,
+            headers: HeaderMap::new(),
+        };
+        for middleware in self.middleware.iter() {
+            middleware.on_request(&mut request);
+        }
+
+        let started_at = Instant::now();
+        let http_request = self
+            .client
+            .post(self.url())
+            .headers(request.headers.clone())
+            .json(&request.payload);
+        let mut retry_after = None;
+        let (status, body, error) = match http_request.send().await {
+            Ok(response) => {
+                let status = response.status();
+                retry_after = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(parse_retry_after);
+                match response.bytes().await {
+                    Ok(body) => (Some(status), body.to_vec(), None),
+                    Err(err) => (
+                        Some(status),
+                        vec![],
+                        Some(format!("failed to read response bytes: {err}")),
+                    ),
+                }
+            }
+            Err(err) => (None, vec![], Some(format!("failed to send request: {err}"))),
+        };
+        let latency = started_at.elapsed();
+
+        let response = RpcResponse {
+            request,
+            status,
+            body,
+            error,
+        };
+        for middleware in self.middleware.iter().rev() {
+            middleware.on_response(&response, latency);
+        }
+
+        let RpcResponse {
+            status,
+            body,
+            error,
+            ..
+        } = response;
+        if let Some(message) = error {
+            return Err(web3::Error::Transport(TransportError::Message(message)));
+        }
+        let status = status.expect("status is set if there's no error");
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            if let Some(retry_after) = retry_after {
+                return Err(rate_limited_error(retry_after));
+            }
+        }
+        if !status.is_success() {
+            return Err(web3::Error::Transport(TransportError::Code(
+                status.as_u16(),
+            )));
+        }
+        serde_json::from_slice(&body).map_err(|err| {
+            let message = format!(
+                "failed to deserialize response: {err}: {}",
+                String::from_utf8_lossy(&body)
+            );
+            web3::Error::Decoder(message)
+        })
+    }
+}
+
+impl Transport for HttpTransport {
+    type Out = BoxFuture<'static, web3::Result<Value>>;
+
+    fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
+        let id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        (id, helpers::build_request(id, method, params))
+    }
+
+    fn send(&self, _id: RequestId, request: Call) -> Self::Out {
+        let this = self.clone();
+        Box::pin(async move {
+            let output: Output = this.execute(Request::Single(request)).await?;
+            helpers::to_result_from_output(output)
+        })
+    }
+}
+
+impl BatchTransport for HttpTransport {
+    type Batch = BoxFuture<'static, web3::Result<Vec<web3::Result<Value>>>>;
+
+    fn send_batch<T>(&self, requests: T) -> Self::Batch
+    where
+        T: IntoIterator<Item = (RequestId, Call)>,
+    {
+        let this = self.clone();
+        let (ids, calls): (Vec<_>, Vec<_>) = requests.into_iter().unzip();
+        Box::pin(async move {
+            let outputs: Vec<Output> = this.execute(Request::Batch(calls)).await?;
+            match_batch_outputs(&ids, outputs)
+        })
+    }
+}
+
+fn parse_url(node_url: &str) -> Result<Url, Error> {
+    node_url.parse().map_err(|err| {
+        Error::EthereumGateway(web3::Error::InvalidResponse(format!(
+            "invalid node URL: {err}"
+        )))
+    })
+}
+
+/// Parses the value of the `Retry-After` header, which is either a number of seconds or an HTTP date.
+fn parse_retry_after(value: &HeaderValue) -> Option<Duration> {
+    let value = value.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let retry_at = httpdate::parse_http_date(value).ok()?;
+    // Dates in the past mean that the request can be retried immediately.
+    Some(
+        retry_at
+            .duration_since(SystemTime::now())
+            .unwrap_or_default(),
+    )
+}
+
+/// Matches outputs of a batch request to the request IDs, since nodes may return outputs in any order.
+fn match_batch_outputs(
+    ids: &[RequestId],
+    outputs: Vec<Output>,
+) -> web3::Result<Vec<web3::Result<Value>>> {
+    if ids.len() != outputs.len() {
+        let message = "unexpected number of responses".to_owned();
+        return Err(web3::Error::InvalidResponse(message));
+    }
+    let mut outputs_by_id = HashMap::with_capacity(outputs.len());
+    for output in outputs {
+        let id = match output.id() {
+            Id::Num(id) => *id as RequestId,
+            _ => {
+                let message = "response ID is not a number".to_owned();
+                return Err(web3::Error::InvalidResponse(message));
+            }
+        };
+        outputs_by_id.insert(id, helpers::to_result_from_output(output));
+    }
+    ids.iter()
+        .map(|id| {
+            outputs_by_id.remove(id).ok_or_else(|| {
+                web3::Error::InvalidResponse(format!("batch response is missing ID {id}"))
+            })
+        })
+        .collect()
+}
+
+pub(super) fn transport_error(message: String) -> Error {
+    Error::EthereumGateway(web3::Error::Transport(TransportError::Message(message)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ErrorKind;
+
+    #[test]
+    fn invalid_node_url_is_not_transient() {
+        let err = HttpTransport::new("not a URL").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Decode);
+        assert!(!err.is_transient(), "{err}");
+    }
+
+    #[test]
+    fn parsing_retry_after() {
+        let parse = |value| parse_retry_after(&HeaderValue::from_static(value));
+        assert_eq!(parse("5"), Some(Duration::from_secs(5)));
+        assert_eq!(parse(" 0 "), Some(Duration::ZERO));
+        assert_eq!(parse("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+        assert_eq!(parse("soon"), None);
+        assert_eq!(parse("-1"), None);
+
+        let retry_at = SystemTime::now() + Duration::from_secs(120);
+        let value = HeaderValue::from_str(&httpdate::fmt_http_date(retry_at)).unwrap();
+        let delay = parse_retry_after(&value).unwrap();
+        // HTTP dates have 1-second precision.
+        assert!(
+            delay > Duration::from_secs(100) && delay <= Duration::from_secs(120),
+            "{delay:?}"
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! Types for mempool inspection via `txpool_content` and `txpool_contentFrom`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Deserialize;
+use zksync_types::web3::types::{Address, Bytes, H256, U256, U64};
+
+/// Transaction in the node mempool. Fields specific to node implementations are ignored.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxPoolTransaction {
+    pub hash: H256,
+    pub nonce: U256,
+    pub from: Address,
+    /// Recipient of the transaction. `None` for contract deployments.
+    #[serde(default)]
+    pub to: Option<Address>,
+    pub value: U256,
+    pub gas: U256,
+    /// Gas price for legacy transactions. Some nodes also return the effective gas price
+    /// for EIP-1559 transactions.
+    #[serde(default)]
+    pub gas_price: Option<U256>,
+    #[serde(default)]
+    pub max_fee_per_gas: Option<U256>,
+    #[serde(default)]
+    pub max_priority_fee_per_gas: Option<U256>,
+    #[serde(default)]
+    pub max_fee_per_blob_gas: Option<U256>,
+    pub input: Bytes,
+    #[serde(default, rename = "type")]
+    pub transaction_type: Option<U64>,
+}
+
+/// Mempool transactions of a single sender keyed by the nonce, as returned by `txpool_contentFrom`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct TxPoolContentFrom {
+    /// Transactions that are ready to be included into a block.
+    #[serde(default)]
+    pub pending: BTreeMap<u64, TxPoolTransaction>,
+    /// Transactions that cannot be included yet, e.g. because of a nonce gap.
+    #[serde(default)]
+    pub queued: BTreeMap<u64, TxPoolTransaction>,
+}
+
+impl TxPoolContentFrom {
+    /// Returns nonces missing from the mempool that prevent queued transactions from being included,
+    /// starting from `next_nonce` (i.e., the nonce of the sender in the latest block).
+    pub fn nonce_gaps(&self, next_nonce: u64) -> Vec<u64> {
+        let nonces: BTreeSet<_> = self
+            .pending
+            .keys()
+            .chain(self.queued.keys())
+            .copied()
+            .collect();
+        let Some(&max_nonce) = nonces.last() else {
+            return vec![];
+        };
+        (next_nonce..max_nonce)
+            .filter(|nonce| !nonces.contains(nonce))
+            .collect()
+    }
+}
+
+/// Mempool transactions of all senders, as returned by `txpool_content`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct TxPoolContent {
+    /// Transactions that are ready to be included into a block, keyed by the sender and the nonce.
+    #[serde(default)]
+    pub pending: BTreeMap<Address, BTreeMap<u64, TxPoolTransaction>>,
+    /// Transactions that cannot be included yet, keyed by the sender and the nonce.
+    #[serde(default)]
+    pub queued: BTreeMap<Address, BTreeMap<u64, TxPoolTransaction>>,
+}
+
+impl TxPoolContent {
+    /// Extracts transactions of the specified sender.
+    pub fn for_sender(&self, sender: Address) -> TxPoolContentFrom {
+        TxPoolContentFrom {
+            pending: self.pending.get(&sender).cloned().unwrap_or_default(),
+            queued: self.queued.get(&sender).cloned().unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializing_geth_txpool_content() {
+        let content: TxPoolContent =
+            serde_json::from_str(include_str!("fixtures/txpool_content_geth.json")).unwrap();
+        assert_eq!(content.pending.len(), 2);
+        assert_eq!(content.queued.len(), 1);
+
+        let sender: Address = "0x24d407e5a0b506e1cb2fae163100b5de01f5193c"
+            .parse()
+            .unwrap();
+        let sender_content = content.for_sender(sender);
+        assert!(sender_content.queued.is_empty());
+        let tx = &sender_content.pending[&34];
+        assert_eq!(tx.from, sender);
+        assert_eq!(tx.nonce, 34.into());
+        assert_eq!(tx.to, None);
+        assert_eq!(tx.transaction_type, Some(2.into()));
+        assert_eq!(tx.max_fee_per_gas, Some(20_000_000_000_u64.into()));
+        assert_eq!(tx.max_priority_fee_per_gas, Some(1_000_000_000.into()));
+
+        let sender: Address = "0x976a3fc5d6f7d259ebfb4cc2ae75115475e9867c"
+            .parse()
+            .unwrap();
+        let sender_content = content.for_sender(sender);
+        assert!(sender_content.pending.is_empty());
+        assert_eq!(
+            sender_content.queued.keys().copied().collect::<Vec<_>>(),
+            [3, 5]
+        );
+        let tx = &sender_content.queued[&3];
+        assert_eq!(tx.gas_price, Some(20_000_000_000_u64.into()));
+        assert_eq!(tx.max_fee_per_gas, None);
+    }
+
+    #[test]
+    fn deserializing_erigon_txpool_content() {
+        let content: TxPoolContent =
+            serde_json::from_str(include_str!("fixtures/txpool_content_erigon.json")).unwrap();
+        let sender: Address = "0x976a3fc5d6f7d259ebfb4cc2ae75115475e9867c"
+            .parse()
+            .unwrap();
+        let sender_content = content.for_sender(sender);
+        assert_eq!(sender_content.pending.len(), 1);
+        assert_eq!(sender_content.pending[&2].nonce, 2.into());
+        let queued_tx = &sender_content.queued[&4];
+        assert_eq!(queued_tx.gas_price, None);
+        assert_eq!(
+            queued_tx.max_priority_fee_per_gas,
+            Some(2_000_000_000.into())
+        );
+        assert_eq!(sender_content.nonce_gaps(1), [1, 3]);
+    }
+
+    #[test]
+    fn finding_nonce_gaps() {
+        let content: TxPoolContent =
+            serde_json::from_str(include_str!("fixtures/txpool_content_geth.json")).unwrap();
+        let sender: Address = "0x976a3fc5d6f7d259ebfb4cc2ae75115475e9867c"
+            .parse()
+            .unwrap();
+        let sender_content = content.for_sender(sender);
+        assert_eq!(sender_content.nonce_gaps(0), [0, 1, 2, 4]);
+        assert_eq!(sender_content.nonce_gaps(3), [4]);
+        assert_eq!(sender_content.nonce_gaps(5), [] as [u64; 0]);
+        // Nonces below the next nonce are not considered gaps.
+        assert_eq!(sender_content.nonce_gaps(10), [] as [u64; 0]);
+
+        let empty_content = content.for_sender(Address::zero());
+        assert_eq!(empty_content.nonce_gaps(0), [] as [u64; 0]);
+    }
+}
@@ -0,0 +1,475 @@
+//! IPC (Unix domain socket) transport for Ethereum clients.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    os::unix::fs::FileTypeExt,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use futures::future::BoxFuture;
+use jsonrpc_core::{Call, Id, Output, Value};
+use tokio::{
+    io::{AsyncReadExt as _, AsyncWriteExt as _},
+    net::{
+        unix::{OwnedReadHalf, OwnedWriteHalf},
+        UnixStream,
+    },
+    sync::oneshot,
+    task::JoinHandle,
+};
+use zksync_types::web3::{self, error::TransportError, helpers, RequestId, Transport};
+
+use crate::{clients::http::QueryClient, types::Error};
+
+/// Initial capacity of the buffer for responses read from the socket.
+const READ_BUFFER_CAPACITY: usize = 8 * 1_024;
+
+type ResponseSender = oneshot::Sender<web3::Result<Value>>;
+
+/// Requests awaiting responses on a single connection. `None` means that the connection is closed.
+#[derive(Debug)]
+struct PendingRequests(Mutex<Option<HashMap<RequestId, ResponseSender>>>);
+
+impl PendingRequests {
+    fn new() -> Self {
+        Self(Mutex::new(Some(HashMap::new())))
+    }
+
+    fn register(&self, id: RequestId) -> io::Result<oneshot::Receiver<web3::Result<Value>>> {
+        let mut requests = self.0.lock().unwrap();
+        let requests = requests
+            .as_mut()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        let (sender, receiver) = oneshot::channel();
+        requests.insert(id, sender);
+        Ok(receiver)
+    }
+
+    fn remove(&self, id: RequestId) {
+        if let Some(requests) = self.0.lock().unwrap().as_mut() {
+            requests.remove(&id);
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0.lock().unwrap().is_none()
+    }
+
+    /// Closes the connection; all pending requests fail.
+    fn close(&self) {
+        self.0.lock().unwrap().take();
+    }
+
+    /// Routes a response to the request with the matching ID. Both single and batch responses are supported.
+    fn resolve(&self, response: Value) {
+        let outputs = match response {
+            Value::Array(outputs) => outputs,
+            output => vec![output],
+        };
+        for output in outputs {
+            let output: Output = match serde_json::from_value(output) {
+                Ok(output) => output,
+                Err(err) => {
+                    // Subscription notifications end up here; they aren't supported by the transport.
+                    tracing::debug!("Ignoring unexpected message received via IPC: {err}");
+                    continue;
+                }
+            };
+            let id = match &output {
+                Output::Success(success) => &success.id,
+                Output::Failure(failure) => &failure.id,
+            };
+            let Id::Num(id) = *id else {
+                tracing::warn!("Received IPC response with unexpected ID: {id:?}");
+                continue;
+            };
+            let sender = self
+                .0
+                .lock()
+                .unwrap()
+                .as_mut()
+                .and_then(|requests| requests.remove(&(id as RequestId)));
+            if let Some(sender) = sender {
+                sender.send(helpers::to_result_from_output(output)).ok();
+            } else {
+                tracing::warn!("Received IPC response for unknown request #{id}");
+            }
+        }
+    }
+}
+
+/// Single connection to the IPC socket. Responses are read by a background task and routed to requests by their IDs.
+#[derive(Debug)]
+struct Connection {
+    writer: tokio::sync::Mutex<OwnedWriteHalf>,
+    pending_requests: Arc<PendingRequests>,
+    reader_task: JoinHandle<()>,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.reader_task.abort();
+    }
+}
+
+impl Connection {
+    async fn open(path: &Path) -> io::Result<Arc<Self>> {
+        let (reader, writer) = UnixStream::connect(path).await?.into_split();
+        let pending_requests = Arc::new(PendingRequests::new());
+        let reader_task = tokio::spawn(Self::read_responses(reader, pending_requests.clone()));
+        Ok(Arc::new(Self {
+            writer: tokio::sync::Mutex::new(writer),
+            pending_requests,
+            reader_task,
+        }))
+    }
+
+    fn is_closed(&self) -> bool {
+        self.pending_requests.is_closed()
+    }
+
+    async fn send(
+        &self,
+        id: RequestId,
+        payload: &[u8],
+    ) -> io::Result<oneshot::Receiver<web3::Result<Value>>> {
+        let receiver = self.pending_requests.register(id)?;
+        let mut writer = self.writer.lock().await;
+        if let Err(err) = writer.write_all(payload).await {
+            self.pending_requests.remove(id);
+            return Err(err);
+        }
+        Ok(receiver)
+    }
+
+    async fn read_responses(mut reader: OwnedReadHalf, pending_requests: Arc<PendingRequests>) {
+        let mut buffer = Vec::with_capacity(READ_BUFFER_CAPACITY);
+        loop {
+            match reader.read_buf(&mut buffer).await {
+                Ok(0) => {
+                    tracing::info!("IPC connection was closed by the node");
+                    break;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    tracing::warn!("Failed reading from IPC socket: {err}");
+                    break;
+                }
+            }
+            match drain_messages(&mut buffer) {
+                Ok(messages) => {
+                    for message in messages {
+                        pending_requests.resolve(message);
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!("Received malformed JSON via IPC, closing connection: {err}");
+                    break;
+                }
+            }
+        }
+        pending_requests.close();
+    }
+}
+
+/// Extracts complete JSON messages from the start of the `buffer`. Incomplete trailing messages are left in the buffer.
+/// Messages don't need to be delimited; nodes usually separate them with newlines.
+fn drain_messages(buffer: &mut Vec<u8>) -> serde_json::Result<Vec<Value>> {
+    let mut stream = serde_json::Deserializer::from_slice(buffer).into_iter::<Value>();
+    let mut messages = vec![];
+    loop {
+        match stream.next() {
+            Some(Ok(message)) => messages.push(message),
+            Some(Err(err)) if err.is_eof() => break,
+            Some(Err(err)) => return Err(err),
+            None => break,
+        }
+    }
+    let consumed_len = stream.byte_offset();
+    buffer.drain(..consumed_len);
+    Ok(messages)
+}
+
+fn is_broken_connection(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::UnexpectedEof
+    )
+}
+
+#[derive(Debug)]
+struct IpcInner {
+    path: PathBuf,
+    next_request_id: AtomicUsize,
+    connection: tokio::sync::Mutex<Option<Arc<Connection>>>,
+}
+
+impl IpcInner {
+    fn transport_error(&self, message: impl std::fmt::Display) -> web3::Error {
+        let message = format!("IPC socket `{}`: {message}", self.path.display());
+        web3::Error::Transport(TransportError::Message(message))
+    }
+
+    /// Returns the current connection, re-establishing it if it's closed.
+    async fn connection(&self) -> io::Result<Arc<Connection>> {
+        let mut connection = self.connection.lock().await;
+        if let Some(conn) = connection.as_ref().filter(|conn| !conn.is_closed()) {
+            return Ok(conn.clone());
+        }
+        if connection.is_some() {
+            tracing::info!("Reconnecting to IPC socket `{}`", self.path.display());
+        }
+        let conn = Connection::open(&self.path).await?;
+        *connection = Some(conn.clone());
+        Ok(conn)
+    }
+
+    /// Drops the connection unless it was already replaced by another request.
+    async fn discard_connection(&self, conn: &Arc<Connection>) {
+        let mut connection = self.connection.lock().await;
+        if connection
+            .as_ref()
+            .map_or(false, |current| Arc::ptr_eq(current, conn))
+        {
+            *connection = None;
+        }
+    }
+
+    async fn request(&self, id: RequestId, payload: String) -> web3::Result<Value> {
+        let mut reconnected = false;
+        let receiver = loop {
+            let conn = self
+                .connection()
+                .await
+                .map_err(|err| self.transport_error(format_args!("failed connecting: {err}")))?;
+            match conn.send(id, payload.as_bytes()).await {
+                Ok(receiver) => break receiver,
+                Err(err) if !reconnected && is_broken_connection(&err) => {
+                    tracing::info!(
+                        "IPC connection to `{}` is broken ({err}), reconnecting",
+                        self.path.display()
+                    );
+                    self.discard_connection(&conn).await;
+                    reconnected = true;
+                }
+                Err(err) => {
+                    self.discard_connection(&conn).await;
+                    return Err(self.transport_error(format_args!("failed sending request: {err}")));
+                }
+            }
+        };
+        receiver
+            .await
+            .map_err(|_| self.transport_error("connection closed before receiving response"))?
+    }
+}
+
+/// Transport sending JSON-RPC requests to an Ethereum node over its IPC (Unix domain socket) endpoint.
+/// Concurrent requests are multiplexed over a single connection and matched with responses by their IDs.
+/// If the connection breaks, it's re-established on the next request.
+#[derive(Debug, Clone)]
+pub struct Ipc {
+    inner: Arc<IpcInner>,
+}
+
+impl Ipc {
+    /// Connects to the IPC socket at the specified `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidIpcPath`] if the path doesn't exist or doesn't point to a Unix socket,
+    /// and a transport error if connecting to the socket fails.
+    pub async fn new(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let invalid_path = |reason: String| Error::InvalidIpcPath {
+            path: path.to_owned(),
+            reason,
+        };
+        let metadata = fs::metadata(path).map_err(|err| {
+            if err.kind() == io::ErrorKind::NotFound {
+                invalid_path("socket doesn't exist".to_owned())
+            } else {
+                invalid_path(err.to_string())
+            }
+        })?;
+        if !metadata.file_type().is_socket() {
+            return Err(invalid_path("not a Unix socket".to_owned()));
+        }
+
+        let inner = IpcInner {
+            path: path.to_owned(),
+            next_request_id: AtomicUsize::new(1),
+            connection: tokio::sync::Mutex::default(),
+        };
+        inner
+            .connection()
+            .await
+            .map_err(|err| inner.transport_error(format_args!("failed connecting: {err}")))?;
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+}
+
+impl Transport for Ipc {
+    type Out = BoxFuture<'static, web3::Result<Value>>;
+
+    fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
+        let id = self.inner.next_request_id.fetch_add(1, Ordering::Relaxed);
+        (id, helpers::build_request(id, method, params))
+    }
+
+    fn send(&self, id: RequestId, request: Call) -> Self::Out {
+        let inner = self.inner.clone();
+        let mut payload = helpers::to_string(&request);
+        payload.push('\n');
+        Box::pin(async move { inner.request(id, payload).await })
+    }
+}
+
+/// Ethereum client connected to the node via IPC.
+pub type IpcQueryClient = QueryClient<Ipc>;
+
+impl QueryClient<Ipc> {
+    /// Creates a new client connected to the IPC socket at the specified `path`. See [`Ipc::new()`]
+    /// for details on errors.
+    pub async fn new_ipc(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(Self::from_transport(Ipc::new(path).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use assert_matches::assert_matches;
+    use tokio::{
+        io::{AsyncBufReadExt as _, AsyncWriteExt as _, BufReader},
+        net::UnixListener,
+    };
+
+    use super::*;
+    use crate::EthInterface;
+
+    /// Serves `eth_blockNumber` requests on the accepted connections. Requests on a single connection
+    /// are answered in the reverse order, in a single write, to check request multiplexing.
+    /// The first connection is closed after `requests_before_close` requests.
+    async fn serve(listener: UnixListener, requests_before_close: usize) {
+        let mut is_first_connection = true;
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            let mut batch = vec![];
+            let mut responses = String::new();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                let request: Value = serde_json::from_str(&line).unwrap();
+                assert_eq!(request["method"], "eth_blockNumber");
+                batch.push(request["id"].clone());
+                if is_first_connection && batch.len() == requests_before_close {
+                    break;
+                }
+                if batch.len() < 2 {
+                    continue;
+                }
+                for id in batch.drain(..).rev() {
+                    let response = serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "result": format!("{:#x}", id.as_u64().unwrap()),
+                    });
+                    responses += &response.to_string();
+                    responses.push('\n');
+                }
+                writer.write_all(responses.as_bytes()).await.unwrap();
+                responses.clear();
+            }
+            is_first_connection = false;
+        }
+    }
+
+    #[test]
+    fn draining_json_messages() {
+        let mut buffer = br#"{"id":1}{"id":2}
+[{"id":3}] {"id":"#
+            .to_vec();
+        let messages = drain_messages(&mut buffer).unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[2][0]["id"], 3);
+        assert_eq!(buffer, br#" {"id":"#);
+
+        buffer.extend_from_slice(b"4}");
+        let messages = drain_messages(&mut buffer).unwrap();
+        assert_eq!(messages, [serde_json::json!({ "id": 4 })]);
+        assert!(buffer.is_empty());
+
+        let mut buffer = b"{\"id\":]".to_vec();
+        drain_messages(&mut buffer).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn connecting_to_missing_socket() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("missing.ipc");
+        let err = Ipc::new(&path).await.unwrap_err();
+        assert_matches!(
+            err,
+            Error::InvalidIpcPath { path: err_path, reason }
+                if err_path == path && reason.contains("doesn't exist")
+        );
+
+        let path = dir.path().join("file.ipc");
+        fs::write(&path, "").unwrap();
+        let err = Ipc::new(&path).await.unwrap_err();
+        assert_matches!(
+            err,
+            Error::InvalidIpcPath { reason, .. } if reason.contains("not a Unix socket")
+        );
+    }
+
+    #[tokio::test]
+    async fn multiplexing_ipc_requests() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("node.ipc");
+        let listener = UnixListener::bind(&path).unwrap();
+        tokio::spawn(serve(listener, usize::MAX));
+
+        let client = QueryClient::new_ipc(&path).await.unwrap();
+        let (first, second) =
+            tokio::join!(client.block_number("test"), client.block_number("test"));
+        let (first, second) = (first.unwrap().as_u64(), second.unwrap().as_u64());
+        // Block numbers returned by the server are equal to request IDs.
+        assert_ne!(first, second);
+        assert_eq!(first.min(second), 1);
+        assert_eq!(first.max(second), 2);
+    }
+
+    #[tokio::test]
+    async fn reconnecting_after_connection_is_closed() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("node.ipc");
+        let listener = UnixListener::bind(&path).unwrap();
+        tokio::spawn(serve(listener, 1));
+
+        let client = QueryClient::new_ipc(&path).await.unwrap();
+        let err = client.block_number("test").await.unwrap_err();
+        assert!(err.is_transient(), "{err}");
+
+        let requests =
+            futures::future::join(client.block_number("test"), client.block_number("test"));
+        let (first, second) = tokio::time::timeout(Duration::from_secs(10), requests)
+            .await
+            .unwrap();
+        first.unwrap();
+        second.unwrap();
+    }
+}
@@ -0,0 +1,198 @@
+//! Types for the Linea-specific `linea_estimateGas` RPC method.
+
+use serde::{Deserialize, Serialize};
+use zksync_types::U256;
+
+use crate::fee_oracle::FeeEstimate;
+
+/// Wei in a single gwei.
+const GWEI: u64 = 1_000_000_000;
+
+/// Response of the `linea_estimateGas` RPC method. All fees are in wei.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineaEstimateGas {
+    pub base_fee_per_gas: U256,
+    pub gas_limit: U256,
+    pub priority_fee_per_gas: U256,
+}
+
+impl LineaEstimateGas {
+    /// Returns the total fee per gas (base fee + priority fee), i.e. the max fee per gas
+    /// that should be specified for an EIP-1559 transaction. Saturates on overflow.
+    pub fn total_fee_per_gas(&self) -> U256 {
+        self.base_fee_per_gas
+            .saturating_add(self.priority_fee_per_gas)
+    }
+
+    /// Converts this estimate into fee params used by fee oracles. The values are not validated;
+    /// use [`Self::validate()`] to sanity-check them beforehand.
+    pub fn into_fee_params(self) -> FeeEstimate {
+        FeeEstimate {
+            base_fee_per_gas: self.base_fee_per_gas,
+            priority_fee_per_gas: self.priority_fee_per_gas,
+            gas_limit: Some(self.gas_limit),
+        }
+    }
+
+    /// Checks that the estimate is sane according to the provided `limits`. This catches misconfigured
+    /// endpoints, e.g. ones returning values in units other than wei.
+    pub fn validate(&self, limits: &LineaEstimateLimits) -> Result<(), LineaEstimateError> {
+        if self.gas_limit.is_zero() {
+            return Err(LineaEstimateError::ZeroGasLimit);
+        }
+        if self.gas_limit > limits.max_gas_limit {
+            return Err(LineaEstimateError::GasLimitTooHigh {
+                value: self.gas_limit,
+                max: limits.max_gas_limit,
+            });
+        }
+        if self.base_fee_per_gas > limits.max_base_fee_per_gas {
+            return Err(LineaEstimateError::BaseFeeTooHigh {
+                value: self.base_fee_per_gas,
+                max: limits.max_base_fee_per_gas,
+            });
+        }
+        if self.priority_fee_per_gas > limits.max_priority_fee_per_gas {
+            return Err(LineaEstimateError::PriorityFeeTooHigh {
+                value: self.priority_fee_per_gas,
+                max: limits.max_priority_fee_per_gas,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Upper bounds for values in a [`LineaEstimateGas`] checked by [`LineaEstimateGas::validate()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineaEstimateLimits {
+    /// Maximum base fee per gas in wei. Default: 10,000 gwei.
+    pub max_base_fee_per_gas: U256,
+    /// Maximum priority fee per gas in wei. Default: 10,000 gwei.
+    pub max_priority_fee_per_gas: U256,
+    /// Maximum gas limit. Default: 30,000,000 (the block gas limit on Ethereum mainnet).
+    pub max_gas_limit: U256,
+}
+
+impl Default for LineaEstimateLimits {
+    fn default() -> Self {
+        Self {
+            max_base_fee_per_gas: U256::from(10_000 * GWEI),
+            max_priority_fee_per_gas: U256::from(10_000 * GWEI),
+            max_gas_limit: U256::from(30_000_000),
+        }
+    }
+}
+
+/// Errors returned by [`LineaEstimateGas::validate()`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum LineaEstimateError {
+    #[error("estimated gas limit is zero")]
+    ZeroGasLimit,
+    #[error("estimated gas limit {value} exceeds the limit {max}")]
+    GasLimitTooHigh { value: U256, max: U256 },
+    #[error("estimated base fee per gas {value} wei exceeds the limit {max} wei")]
+    BaseFeeTooHigh { value: U256, max: U256 },
+    #[error("estimated priority fee per gas {value} wei exceeds the limit {max} wei")]
+    PriorityFeeTooHigh { value: U256, max: U256 },
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn serde_round_trip() {
+        // Responses returned by Linea mainnet and Sepolia nodes.
+        let responses = [
+            json!({
+                "baseFeePerGas": "0x7",
+                "gasLimit": "0xcf08",
+                "priorityFeePerGas": "0x43a82a4",
+            }),
+            json!({
+                "baseFeePerGas": "0x7",
+                "gasLimit": "0x5208",
+                "priorityFeePerGas": "0x1dcd6507",
+            }),
+        ];
+        for response in responses {
+            let estimate: LineaEstimateGas = serde_json::from_value(response.clone()).unwrap();
+            assert_eq!(estimate.base_fee_per_gas, 7.into());
+            estimate.validate(&LineaEstimateLimits::default()).unwrap();
+            assert_eq!(serde_json::to_value(&estimate).unwrap(), response);
+        }
+    }
+
+    #[test]
+    fn converting_estimate() {
+        let estimate = LineaEstimateGas {
+            base_fee_per_gas: 7.into(),
+            gas_limit: 21_000.into(),
+            priority_fee_per_gas: 71_000_740.into(),
+        };
+        assert_eq!(estimate.total_fee_per_gas(), 71_000_747.into());
+        assert_eq!(
+            estimate.into_fee_params(),
+            FeeEstimate {
+                base_fee_per_gas: 7.into(),
+                priority_fee_per_gas: 71_000_740.into(),
+                gas_limit: Some(21_000.into()),
+            }
+        );
+
+        let estimate = LineaEstimateGas {
+            base_fee_per_gas: U256::MAX,
+            gas_limit: 21_000.into(),
+            priority_fee_per_gas: 1.into(),
+        };
+        assert_eq!(estimate.total_fee_per_gas(), U256::MAX);
+    }
+
+    #[test]
+    fn validating_estimate() {
+        let limits = LineaEstimateLimits::default();
+        let estimate = LineaEstimateGas {
+            base_fee_per_gas: 7.into(),
+            gas_limit: 21_000.into(),
+            priority_fee_per_gas: 71_000_740.into(),
+        };
+        estimate.validate(&limits).unwrap();
+
+        let err = LineaEstimateGas {
+            gas_limit: 0.into(),
+            ..estimate.clone()
+        }
+        .validate(&limits)
+        .unwrap_err();
+        assert_eq!(err, LineaEstimateError::ZeroGasLimit);
+
+        let err = LineaEstimateGas {
+            gas_limit: 100_000_000.into(),
+            ..estimate.clone()
+        }
+        .validate(&limits)
+        .unwrap_err();
+        assert_matches!(err, LineaEstimateError::GasLimitTooHigh { .. });
+
+        // Base fee returned by an endpoint scaling values to wei twice.
+        let err = LineaEstimateGas {
+            base_fee_per_gas: U256::from(7 * GWEI) * GWEI,
+            ..estimate.clone()
+        }
+        .validate(&limits)
+        .unwrap_err();
+        assert_matches!(err, LineaEstimateError::BaseFeeTooHigh { .. });
+
+        let err = LineaEstimateGas {
+            priority_fee_per_gas: U256::from(71_000_740) * GWEI,
+            ..estimate
+        }
+        .validate(&limits)
+        .unwrap_err();
+        assert_matches!(err, LineaEstimateError::PriorityFeeTooHigh { .. });
+    }
+}
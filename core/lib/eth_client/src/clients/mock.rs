@@ -1,17 +1,19 @@
 use std::{
     collections::{BTreeMap, HashMap},
-    sync::RwLock,
+    fmt,
+    sync::{Mutex, RwLock},
 };
 
 use async_trait::async_trait;
-use jsonrpc_core::types::error::Error as RpcError;
+use jsonrpc_core::types::error::{Error as RpcError, ErrorCode};
 use zksync_types::{
     web3::{
         contract::{tokens::Tokenize, Options},
+        error::TransportError,
         ethabi,
         types::{
-            Block, BlockId, BlockNumber, CallRequest, Filter, Log, Transaction, TransactionReceipt,
-            U64,
+            Block, BlockId, BlockNumber, Bytes, CallRequest, FeeHistory, Filter, Log, Transaction,
+            TransactionReceipt, U64,
         },
         Error as Web3Error,
     },
@@ -21,7 +23,8 @@ use zksync_types::{
 use crate::{
     clients::LineaEstimateGas,
     types::{Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
-    BoundEthInterface, ContractCall, EthInterface, RawTransactionBytes,
+    BlobTxSidecar, BlockBlobGas, BoundEthInterface, CallOverrides, ContractCall, EthInterface,
+    RawTransactionBytes, GAS_PER_BLOB, MAX_BLOB_GAS_PER_BLOCK, TARGET_BLOB_GAS_PER_BLOCK,
 };
 
 #[derive(Debug, Clone)]
@@ -31,6 +34,7 @@ struct MockTx {
     nonce: u64,
     max_fee_per_gas: U256,
     max_priority_fee_per_gas: U256,
+    blob_sidecar: Option<BlobTxSidecar>,
 }
 
 impl From<Vec<u8>> for MockTx {
@@ -51,20 +55,176 @@ impl From<Vec<u8>> for MockTx {
             hash,
             max_fee_per_gas,
             max_priority_fee_per_gas,
+            blob_sidecar: None,
         }
     }
 }
 
-impl From<MockTx> for Transaction {
-    fn from(tx: MockTx) -> Self {
-        Self {
-            input: tx.input.into(),
-            hash: tx.hash,
-            nonce: tx.nonce.into(),
-            max_fee_per_gas: Some(tx.max_fee_per_gas),
-            max_priority_fee_per_gas: Some(tx.max_priority_fee_per_gas),
-            ..Self::default()
-        }
+/// Blob-related data of a signed EIP-4844 transaction.
+#[derive(Debug, Clone)]
+struct MockBlobTxData {
+    versioned_hashes: Vec<H256>,
+    sidecar: BlobTxSidecar,
+}
+
+/// Parameters of a signed transaction that are not encoded in mock raw transaction bytes.
+#[derive(Debug, Clone)]
+struct MockTxParams {
+    to: Address,
+    value: U256,
+    gas_limit: Option<U256>,
+    tx_type: Option<U64>,
+    max_fee_per_blob_gas: Option<U256>,
+}
+
+/// Type of EIP-1559 transactions.
+const EIP_1559_TX_TYPE: u64 = 2;
+/// Type of EIP-4844 (blob) transactions.
+const EIP_4844_TX_TYPE: u64 = 3;
+
+/// Transaction sent via [`MockEthereum`], as returned by [`MockEthereum::sent_transactions()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockSentTx {
+    pub hash: H256,
+    pub from: Address,
+    pub nonce: U256,
+    pub to: Address,
+    pub value: U256,
+    /// Gas limit specified when signing the transaction, if any.
+    pub gas_limit: Option<U256>,
+    pub data: Vec<u8>,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    /// Max fee per blob gas; only set for blob transactions signed via [`BoundEthInterface`] methods.
+    pub max_fee_per_blob_gas: Option<U256>,
+    /// Transaction type, e.g. 2 for EIP-1559 transactions or 3 for EIP-4844 transactions.
+    pub tx_type: U64,
+    /// Versioned hashes of blobs carried by the transaction. Empty for non-blob transactions.
+    pub blob_versioned_hashes: Vec<H256>,
+    /// Whether the transaction was dropped by a reorg simulated using [`MockEthereum::reorg_to()`]
+    /// with [`ReorgedTxHandling::Drop`].
+    pub dropped_by_reorg: bool,
+}
+
+/// Contract call made via [`MockEthereum`], as returned by [`MockEthereum::contract_calls()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockContractCall {
+    pub contract_address: Address,
+    /// ABI-encoded calldata, including the function selector.
+    pub data: Vec<u8>,
+}
+
+impl MockContractCall {
+    /// Returns the function selector of the call.
+    pub fn selector(&self) -> [u8; 4] {
+        self.data[..4].try_into().unwrap()
+    }
+}
+
+/// Handler of contract calls registered with [`MockEthereum::handle_call()`].
+type MockCallHandler = Box<dyn Fn(&[u8]) -> Result<Vec<u8>, Vec<u8>> + Send + Sync>;
+
+/// Call handlers keyed by the contract address and function selector.
+#[derive(Default)]
+struct MockCallHandlers(HashMap<(Address, [u8; 4]), MockCallHandler>);
+
+impl fmt::Debug for MockCallHandlers {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+impl MockCallHandlers {
+    fn has_contract(&self, address: Address) -> bool {
+        self.0.keys().any(|(contract, _)| *contract == address)
+    }
+}
+
+/// Methods of [`MockEthereum`] into which errors can be injected using [`MockEthereum::inject_error()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MockMethod {
+    BlockNumber,
+    ChainId,
+    GetGasPrice,
+    SendRawTx,
+    BaseFeeHistory,
+    FeeHistory,
+    PendingBlockBaseFee,
+    GetTxStatus,
+    FailureReason,
+    GetTx,
+    CallContractFunction,
+    TxReceipt,
+    Block,
+    BlockBlobGas,
+    LineaEstimateGas,
+    MaxPriorityFeePerGas,
+    SignPreparedTx,
+    NonceAt,
+    PendingNonce,
+    CurrentNonce,
+}
+
+/// Kinds of errors injected into [`MockEthereum`] calls. Errors are modeled after the ones returned
+/// by the HTTP client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockErrorKind {
+    /// Request timeout (a transient transport error).
+    Timeout,
+    /// Node is unreachable (a transient transport error).
+    Unreachable,
+    /// Rate limiting error returned by the node (a transient RPC error).
+    RateLimited,
+    /// Internal node error (a non-transient RPC error).
+    Internal,
+}
+
+impl From<MockErrorKind> for Error {
+    fn from(kind: MockErrorKind) -> Self {
+        let err = match kind {
+            MockErrorKind::Timeout => {
+                Web3Error::Transport(TransportError::Message("request timed out".to_owned()))
+            }
+            MockErrorKind::Unreachable => Web3Error::Unreachable,
+            MockErrorKind::RateLimited => Web3Error::Rpc(RpcError {
+                code: ErrorCode::ServerError(-32005),
+                message: "limit exceeded".to_owned(),
+                data: None,
+            }),
+            MockErrorKind::Internal => Web3Error::Rpc(RpcError::internal_error()),
+        };
+        Self::EthereumGateway(err)
+    }
+}
+
+/// Handling of transactions un-confirmed by a reorg simulated using [`MockEthereum::reorg_to()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReorgedTxHandling {
+    /// Transactions return to the mempool and can be executed again.
+    #[default]
+    ReturnToPending,
+    /// Transactions are dropped, as if they were evicted from the mempool.
+    Drop,
+}
+
+type ErrorTrigger = dyn FnMut(usize) -> Option<MockErrorKind> + Send;
+
+/// Errors injected into a certain [`MockMethod`].
+struct ErrorInjection {
+    trigger: Box<ErrorTrigger>,
+    /// Number of calls to the method since the injection.
+    call_count: usize,
+    /// Number of calls that have failed with an injected error.
+    error_count: usize,
+}
+
+impl fmt::Debug for ErrorInjection {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ErrorInjection")
+            .field("call_count", &self.call_count)
+            .field("error_count", &self.error_count)
+            .finish_non_exhaustive()
     }
 }
 
@@ -74,9 +234,31 @@ struct MockEthereumInner {
     block_number: u64,
     tx_statuses: HashMap<H256, ExecutedTxStatus>,
     sent_txs: HashMap<H256, MockTx>,
+    /// Blob data of signed blob transactions, keyed by the transaction hash.
+    signed_blob_txs: HashMap<H256, MockBlobTxData>,
+    /// Parameters of signed transactions, keyed by the transaction hash.
+    signed_tx_params: HashMap<H256, MockTxParams>,
+    /// All successfully sent transactions in the order of submission.
+    sent_tx_history: Vec<MockSentTx>,
+    /// Blob gas used by executed transactions, keyed by the block number. Blocks without blob transactions are omitted.
+    blob_gas_used: BTreeMap<u64, u64>,
     current_nonce: u64,
     pending_nonce: u64,
     nonces: BTreeMap<u64, u64>,
+    /// Fork points of simulated reorgs. Blocks above a fork point get new hashes.
+    fork_points: Vec<u64>,
+    /// Base fees for blocks starting from block #0.
+    base_fee_history: Vec<u64>,
+    /// Gas used ratios of blocks produced by [`MockEthereum::advance_blocks()`], keyed by the block number.
+    block_fullness: BTreeMap<usize, f64>,
+    /// Chain ID returned by `eth_chainId`. If not set, the configured chain ID is returned.
+    reported_chain_id: Option<L1ChainId>,
+    /// Head returned for the `safe` block tag. If not set, the latest block is returned.
+    safe_block: Option<u64>,
+    /// Head returned for the `finalized` block tag. If not set, the latest block is returned.
+    finalized_block: Option<u64>,
+    /// All contract calls in the order they were made.
+    contract_calls: Vec<MockContractCall>,
 }
 
 impl MockEthereumInner {
@@ -91,7 +273,16 @@ impl MockEthereumInner {
         self.block_number += confirmations;
         let nonce = self.current_nonce;
         self.current_nonce += 1;
-        let tx_nonce = self.sent_txs[&tx_hash].nonce;
+        let tx = &self.sent_txs[&tx_hash];
+        let tx_nonce = tx.nonce;
+        if let Some(sidecar) = &tx.blob_sidecar {
+            let blob_gas_used = self.blob_gas_used.entry(block_number).or_default();
+            *blob_gas_used += sidecar.blobs.len() as u64 * GAS_PER_BLOB;
+            assert!(
+                *blob_gas_used <= MAX_BLOB_GAS_PER_BLOCK,
+                "blob gas limit exceeded in block #{block_number}"
+            );
+        }
 
         if non_ordering_confirmations {
             if tx_nonce >= nonce {
@@ -108,12 +299,138 @@ impl MockEthereumInner {
             receipt: TransactionReceipt {
                 gas_used: Some(21000u32.into()),
                 block_number: Some(block_number.into()),
+                block_hash: Some(self.block_hash(block_number)),
                 transaction_hash: tx_hash,
+                status: Some((success as u64).into()),
                 ..TransactionReceipt::default()
             },
         };
         self.tx_statuses.insert(tx_hash, status);
     }
+
+    /// Returns the hash of the specified block, which changes each time the block is reorganized.
+    fn block_hash(&self, block_number: u64) -> H256 {
+        let generation = self
+            .fork_points
+            .iter()
+            .filter(|&&fork_point| block_number > fork_point)
+            .count();
+        let mut hash = H256::from_low_u64_be(block_number);
+        hash.0[..8].copy_from_slice(&(generation as u64 + 1).to_be_bytes());
+        hash
+    }
+
+    /// Resolves a block number or tag to the block number. The pending block is the one after the latest block.
+    fn resolve_block_number(&self, block: BlockNumber) -> u64 {
+        match block {
+            BlockNumber::Number(number) => number.as_u64(),
+            BlockNumber::Earliest => 0,
+            BlockNumber::Latest => self.block_number,
+            BlockNumber::Safe => self.safe_block.unwrap_or(self.block_number),
+            BlockNumber::Finalized => self.finalized_block.unwrap_or(self.block_number),
+            BlockNumber::Pending => self.block_number + 1,
+        }
+    }
+
+    fn reorg_to(&mut self, fork_point: u64, tx_handling: ReorgedTxHandling) {
+        assert!(
+            fork_point <= self.block_number,
+            "cannot reorg to block #{fork_point} above the latest block #{}",
+            self.block_number
+        );
+        if let Some(finalized_block) = self.finalized_block {
+            assert!(
+                fork_point >= finalized_block,
+                "cannot reorg to block #{fork_point} below the finalized block #{finalized_block}"
+            );
+        }
+        self.block_number = fork_point;
+        self.safe_block = self.safe_block.map(|block| block.min(fork_point));
+        self.fork_points.push(fork_point);
+        self.blob_gas_used
+            .retain(|&block_number, _| block_number <= fork_point);
+        self.nonces
+            .retain(|&block_number, _| block_number <= fork_point);
+        self.current_nonce = self.nonces.values().next_back().copied().unwrap_or(0);
+
+        let reorged_tx_hashes: Vec<_> = self
+            .tx_statuses
+            .iter()
+            .filter(|(_, status)| status.receipt.block_number > Some(fork_point.into()))
+            .map(|(&tx_hash, _)| tx_hash)
+            .collect();
+        for tx_hash in reorged_tx_hashes {
+            self.tx_statuses.remove(&tx_hash);
+            if tx_handling == ReorgedTxHandling::Drop {
+                let tx = self.sent_txs.remove(&tx_hash).unwrap();
+                self.pending_nonce = self.pending_nonce.min(tx.nonce);
+                let sent_tx = self
+                    .sent_tx_history
+                    .iter_mut()
+                    .rev()
+                    .find(|sent_tx| sent_tx.hash == tx_hash);
+                if let Some(sent_tx) = sent_tx {
+                    sent_tx.dropped_by_reorg = true;
+                }
+            }
+        }
+        self.pending_nonce = self.pending_nonce.max(self.current_nonce);
+    }
+
+    /// Returns the base fee of the block following the latest block in the base fee history.
+    fn next_base_fee(&self) -> u64 {
+        let latest_block = self
+            .base_fee_history
+            .len()
+            .checked_sub(1)
+            .expect("base fee history is not set");
+        let base_fee = self.base_fee_history[latest_block];
+        match self.block_fullness.get(&latest_block) {
+            Some(&fullness) => next_eip1559_base_fee(base_fee, fullness),
+            None => base_fee,
+        }
+    }
+
+    /// Computes blob gas accounting for the specified block, assuming that the chain starts with zero excess blob gas.
+    fn block_blob_gas(&self, block_number: u64) -> BlockBlobGas {
+        let mut excess_blob_gas = 0;
+        let mut next_block = 0;
+        for (&number, &blob_gas_used) in self.blob_gas_used.range(..block_number) {
+            // Blocks in `next_block..number` don't contain blobs, so excess blob gas decreases by the target for each of them.
+            excess_blob_gas =
+                excess_blob_gas.saturating_sub(TARGET_BLOB_GAS_PER_BLOCK * (number - next_block));
+            excess_blob_gas =
+                BlockBlobGas::new(blob_gas_used, excess_blob_gas).next_excess_blob_gas();
+            next_block = number + 1;
+        }
+        excess_blob_gas =
+            excess_blob_gas.saturating_sub(TARGET_BLOB_GAS_PER_BLOCK * (block_number - next_block));
+
+        let blob_gas_used = self.blob_gas_used.get(&block_number).copied().unwrap_or(0);
+        BlockBlobGas::new(blob_gas_used, excess_blob_gas)
+    }
+}
+
+/// Gas limit of blocks produced by [`MockEthereum::advance_blocks()`].
+const MOCK_BLOCK_GAS_LIMIT: u64 = 30_000_000;
+
+/// Computes the base fee of the next block according to EIP-1559, given the base fee and the gas used ratio
+/// of the parent block. The base fee changes by up to 12.5% per block depending on how far the gas used
+/// is from the target (half of the gas limit).
+fn next_eip1559_base_fee(base_fee: u64, gas_used_ratio: f64) -> u64 {
+    const BASE_FEE_MAX_CHANGE_DENOMINATOR: u128 = 8;
+
+    let gas_target = u128::from(MOCK_BLOCK_GAS_LIMIT / 2);
+    let gas_used = (MOCK_BLOCK_GAS_LIMIT as f64 * gas_used_ratio.clamp(0.0, 1.0)) as u128;
+    let base_fee = u128::from(base_fee);
+    let next_base_fee = if gas_used > gas_target {
+        let delta =
+            base_fee * (gas_used - gas_target) / gas_target / BASE_FEE_MAX_CHANGE_DENOMINATOR;
+        base_fee + delta.max(1)
+    } else {
+        base_fee - base_fee * (gas_target - gas_used) / gas_target / BASE_FEE_MAX_CHANGE_DENOMINATOR
+    };
+    u64::try_from(next_base_fee).unwrap_or(u64::MAX)
 }
 
 /// Mock Ethereum client is capable of recording all the incoming requests for the further analysis.
@@ -121,11 +438,25 @@ impl MockEthereumInner {
 pub struct MockEthereum {
     max_fee_per_gas: U256,
     max_priority_fee_per_gas: U256,
-    base_fee_history: Vec<u64>,
+    /// Priority fees of transactions included into each block starting from block #0.
+    priority_fee_history: Vec<Vec<u64>>,
+    /// Blob base fees for blocks starting from block #0, overriding values derived from excess blob gas.
+    blob_base_fee_history: Vec<u64>,
     /// If true, the mock will not check the ordering nonces of the transactions.
     /// This is useful for testing the cases when the transactions are executed out of order.
     non_ordering_confirmations: bool,
+    reorged_tx_handling: ReorgedTxHandling,
     multicall_address: Address,
+    /// Chain ID returned from [`BoundEthInterface::chain_id()`].
+    chain_id: L1ChainId,
+    /// Response to `linea_estimateGas` calls. If not set, the method is treated as unsupported.
+    linea_estimate_gas: Option<LineaEstimateGas>,
+    /// Response to `eth_maxPriorityFeePerGas` calls. If not set, the method is treated as unsupported.
+    rpc_max_priority_fee: Option<U256>,
+    /// If false, requests with the `safe` and `finalized` block tags fail with [`Error::UnsupportedBlockTag`].
+    supports_block_tags: bool,
+    injected_errors: Mutex<HashMap<MockMethod, ErrorInjection>>,
+    call_handlers: RwLock<MockCallHandlers>,
     inner: RwLock<MockEthereumInner>,
 }
 
@@ -134,9 +465,17 @@ impl Default for MockEthereum {
         Self {
             max_fee_per_gas: 100.into(),
             max_priority_fee_per_gas: 10.into(),
-            base_fee_history: vec![],
+            priority_fee_history: vec![],
+            blob_base_fee_history: vec![],
             non_ordering_confirmations: false,
+            reorged_tx_handling: ReorgedTxHandling::default(),
             multicall_address: Address::default(),
+            chain_id: L1ChainId(9),
+            linea_estimate_gas: None,
+            rpc_max_priority_fee: None,
+            supports_block_tags: true,
+            injected_errors: Mutex::default(),
+            call_handlers: RwLock::default(),
             inner: RwLock::default(),
         }
     }
@@ -154,11 +493,114 @@ impl MockEthereum {
         H256::from_low_u64_ne(result)
     }
 
+    /// Makes the next `times` calls to `method` fail with an error of the specified kind. Errors are returned
+    /// before the mock state is touched, so e.g. a failed `send_raw_tx()` call doesn't send the transaction.
+    /// Replaces errors previously injected into the method.
+    pub fn inject_error(&self, method: MockMethod, kind: MockErrorKind, times: usize) {
+        self.inject_error_with(method, move |call_idx| (call_idx < times).then_some(kind));
+    }
+
+    /// Makes calls to `method` fail according to the provided closure, which receives the 0-based index
+    /// of the call since the injection and returns the error to fail the call with, if any.
+    /// Replaces errors previously injected into the method.
+    pub fn inject_error_with(
+        &self,
+        method: MockMethod,
+        trigger: impl FnMut(usize) -> Option<MockErrorKind> + Send + 'static,
+    ) {
+        let injection = ErrorInjection {
+            trigger: Box::new(trigger),
+            call_count: 0,
+            error_count: 0,
+        };
+        self.injected_errors
+            .lock()
+            .unwrap()
+            .insert(method, injection);
+    }
+
+    /// Returns the number of calls to `method` that have failed with an injected error.
+    pub fn injected_error_count(&self, method: MockMethod) -> usize {
+        let injected_errors = self.injected_errors.lock().unwrap();
+        injected_errors
+            .get(&method)
+            .map_or(0, |injection| injection.error_count)
+    }
+
+    fn check_injected_error(&self, method: MockMethod) -> Result<(), Error> {
+        let mut injected_errors = self.injected_errors.lock().unwrap();
+        let Some(injection) = injected_errors.get_mut(&method) else {
+            return Ok(());
+        };
+        let call_idx = injection.call_count;
+        injection.call_count += 1;
+        if let Some(kind) = (injection.trigger)(call_idx) {
+            injection.error_count += 1;
+            return Err(kind.into());
+        }
+        Ok(())
+    }
+
+    /// Handles calls to the function with the specified `selector` of the contract at `contract_address`
+    /// using `handler`. The handler receives ABI-encoded call arguments (i.e., calldata without the selector)
+    /// and returns either ABI-encoded output, or revert data (which may be empty) to revert the call.
+    /// Replaces the handler previously registered for the same function.
+    ///
+    /// Once a handler is registered for a contract, calls to the contract with selectors without a handler
+    /// are reverted without data. Calls to contracts without handlers return no output.
+    pub fn handle_call(
+        &self,
+        contract_address: Address,
+        selector: [u8; 4],
+        handler: impl Fn(&[u8]) -> Result<Vec<u8>, Vec<u8>> + Send + Sync + 'static,
+    ) {
+        self.call_handlers
+            .write()
+            .unwrap()
+            .0
+            .insert((contract_address, selector), Box::new(handler));
+    }
+
+    /// Returns all contract calls made via this client in the order they were made.
+    pub fn contract_calls(&self) -> Vec<MockContractCall> {
+        self.inner.read().unwrap().contract_calls.clone()
+    }
+
+    /// Returns an error similar to one returned by Geth for a reverted call.
+    fn revert_error(revert_data: Vec<u8>) -> Error {
+        let err = if revert_data.is_empty() {
+            RpcError {
+                code: ErrorCode::ServerError(-32000),
+                message: "execution reverted".to_owned(),
+                data: None,
+            }
+        } else {
+            RpcError {
+                code: ErrorCode::ServerError(3),
+                message: "execution reverted".to_owned(),
+                data: Some(serde_json::to_value(Bytes(revert_data)).unwrap()),
+            }
+        };
+        Error::EthereumGateway(Web3Error::Rpc(err))
+    }
+
     /// Returns the number of transactions sent via this client.
     pub fn sent_tx_count(&self) -> usize {
         self.inner.read().unwrap().sent_txs.len()
     }
 
+    /// Returns all transactions successfully sent via this client in the order of submission, including transactions
+    /// dropped by simulated reorgs. A transaction sent multiple times is recorded once per submission.
+    pub fn sent_transactions(&self) -> Vec<MockSentTx> {
+        self.inner.read().unwrap().sent_tx_history.clone()
+    }
+
+    /// Clears the history of sent transactions returned by [`Self::sent_transactions()`]. Doesn't affect
+    /// the state of the mock chain.
+    pub fn clear_history(&self) {
+        self.inner.write().unwrap().sent_tx_history.clear();
+    }
+
     /// Increments the blocks by a provided `confirmations` and marks the sent transaction
     /// as a success.
     pub fn execute_tx(&self, tx_hash: H256, success: bool, confirmations: u64) {
@@ -170,10 +612,35 @@ impl MockEthereum {
         );
     }
 
+    /// Simulates a reorg: rewinds the chain to `block_number` (the fork point) and changes hashes of all blocks
+    /// above it. Transactions executed above the fork point lose their receipts and are either returned to the mempool
+    /// or dropped depending on [`Self::with_reorged_tx_handling()`]. Blocks above the fork point are produced anew,
+    /// e.g., by [`Self::execute_tx()`] or [`Self::advance_block_number()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_number` is above the latest block.
+    pub fn reorg_to(&self, block_number: u64) {
+        self.inner
+            .write()
+            .unwrap()
+            .reorg_to(block_number, self.reorged_tx_handling);
+    }
+
     pub fn sign_prepared_tx(
+        &self,
+        raw_tx: Vec<u8>,
+        options: Options,
+    ) -> Result<SignedCallResult, Error> {
+        self.sign_tx(raw_tx, self.contract_addr(), options, None)
+    }
+
+    fn sign_tx(
         &self,
         mut raw_tx: Vec<u8>,
+        to: Address,
         options: Options,
+        max_fee_per_blob_gas: Option<U256>,
     ) -> Result<SignedCallResult, Error> {
         let max_fee_per_gas = options.max_fee_per_gas.unwrap_or(self.max_fee_per_gas);
         let max_priority_fee_per_gas = options
@@ -191,6 +658,19 @@ impl MockEthereum {
         // Concatenate `raw_tx` plus hash for test purposes
         let mut new_raw_tx = hash.as_bytes().to_vec();
         new_raw_tx.extend(raw_tx);
+
+        let params = MockTxParams {
+            to,
+            value: options.value.unwrap_or_default(),
+            gas_limit: options.gas,
+            tx_type: options.transaction_type,
+            max_fee_per_blob_gas,
+        };
+        self.inner
+            .write()
+            .unwrap()
+            .signed_tx_params
+            .insert(hash, params);
         Ok(SignedCallResult {
             raw_tx: RawTransactionBytes(new_raw_tx),
             max_priority_fee_per_gas,
@@ -200,15 +680,153 @@ impl MockEthereum {
         })
     }
 
+    /// Same as [`Self::sign_prepared_tx()`], but for EIP-4844 transactions. The sidecar is recorded
+    /// and attached to the transaction once it's sent.
+    pub fn sign_prepared_blob_tx(
+        &self,
+        raw_tx: Vec<u8>,
+        options: Options,
+        sidecar: BlobTxSidecar,
+    ) -> Result<SignedCallResult, Error> {
+        let versioned_hashes = sidecar.versioned_hashes();
+        self.sign_prepared_blob_tx_with_versioned_hashes(raw_tx, options, versioned_hashes, sidecar)
+    }
+
+    /// Same as [`Self::sign_prepared_blob_tx()`], but allows to specify versioned hashes included
+    /// into the transaction. Hashes not matching the sidecar will lead to the transaction being rejected
+    /// once it's sent.
+    pub fn sign_prepared_blob_tx_with_versioned_hashes(
+        &self,
+        raw_tx: Vec<u8>,
+        options: Options,
+        versioned_hashes: Vec<H256>,
+        sidecar: BlobTxSidecar,
+    ) -> Result<SignedCallResult, Error> {
+        let to = self.contract_addr();
+        self.sign_blob_tx(raw_tx, to, options, None, versioned_hashes, sidecar)
+    }
+
+    fn sign_blob_tx(
+        &self,
+        raw_tx: Vec<u8>,
+        to: Address,
+        options: Options,
+        max_fee_per_blob_gas: Option<U256>,
+        versioned_hashes: Vec<H256>,
+        sidecar: BlobTxSidecar,
+    ) -> Result<SignedCallResult, Error> {
+        sidecar.validate()?;
+        let signed_tx = self.sign_tx(raw_tx, to, options, max_fee_per_blob_gas)?;
+        let blob_data = MockBlobTxData {
+            versioned_hashes,
+            sidecar,
+        };
+        self.inner
+            .write()
+            .unwrap()
+            .signed_blob_txs
+            .insert(signed_tx.hash, blob_data);
+        Ok(signed_tx)
+    }
+
+    fn describe_sent_tx(&self, inner: &MockEthereumInner, tx: &MockTx) -> MockSentTx {
+        let params = inner.signed_tx_params.get(&tx.hash);
+        let blob_data = inner.signed_blob_txs.get(&tx.hash);
+        let tx_type = if blob_data.is_some() {
+            EIP_4844_TX_TYPE.into()
+        } else {
+            let tx_type = params.and_then(|params| params.tx_type);
+            tx_type.unwrap_or(EIP_1559_TX_TYPE.into())
+        };
+        MockSentTx {
+            hash: tx.hash,
+            from: self.sender_account(),
+            nonce: tx.nonce.into(),
+            to: params.map_or_else(|| self.contract_addr(), |params| params.to),
+            value: params.map_or_else(U256::zero, |params| params.value),
+            gas_limit: params.and_then(|params| params.gas_limit),
+            data: tx.input.clone(),
+            max_fee_per_gas: tx.max_fee_per_gas,
+            max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+            max_fee_per_blob_gas: params.and_then(|params| params.max_fee_per_blob_gas),
+            tx_type,
+            blob_versioned_hashes: blob_data
+                .map(|blob_data| blob_data.versioned_hashes.clone())
+                .unwrap_or_default(),
+            dropped_by_reorg: false,
+        }
+    }
+
+    /// Returns the blob sidecar of a sent transaction, or `None` if the transaction isn't sent
+    /// or doesn't carry blobs.
+    pub fn sent_blob_sidecar(&self, tx_hash: H256) -> Option<BlobTxSidecar> {
+        let inner = self.inner.read().unwrap();
+        inner.sent_txs.get(&tx_hash)?.blob_sidecar.clone()
+    }
+
     pub fn advance_block_number(&self, val: u64) -> u64 {
         let mut inner = self.inner.write().unwrap();
         inner.block_number += val;
         inner.block_number
     }
 
-    pub fn with_fee_history(self, history: Vec<u64>) -> Self {
+    pub fn with_fee_history(mut self, history: Vec<u64>) -> Self {
+        let inner = self.inner.get_mut().unwrap();
+        inner.base_fee_history = history;
+        inner.block_fullness.clear();
+        self
+    }
+
+    /// Produces `count` blocks with the specified gas used ratio (from 0 to 1). Base fees of the produced blocks
+    /// evolve according to EIP-1559 starting from the latest block in the base fee history, so that a sequence
+    /// of full blocks leads to a fee spike and a sequence of empty ones to a fee drop. The generated base fees and
+    /// gas used ratios are returned from [`EthInterface::fee_history()`]. Also advances the block number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the base fee history is not set, or if `fullness` is not in `0.0..=1.0`.
+    pub fn advance_blocks(&self, count: usize, fullness: f64) -> u64 {
+        assert!(
+            (0.0..=1.0).contains(&fullness),
+            "block fullness must be in 0..=1"
+        );
+        let mut inner = self.inner.write().unwrap();
+        for _ in 0..count {
+            let base_fee = inner.next_base_fee();
+            inner.base_fee_history.push(base_fee);
+            let block_number = inner.base_fee_history.len() - 1;
+            inner.block_fullness.insert(block_number, fullness);
+        }
+        inner.block_number += count as u64;
+        inner.block_number
+    }
+
+    /// Sets priority fees of transactions included into each block starting from block #0. Used to compute
+    /// rewards and gas used ratios in the fee history; blocks without priority fees are considered empty.
+    pub fn with_priority_fee_history(self, history: Vec<Vec<u64>>) -> Self {
         Self {
-            base_fee_history: history,
+            priority_fee_history: history,
+            ..self
+        }
+    }
+
+    /// Returns the priority fee paid at the specified percentile in the block, or 0 for empty blocks.
+    fn priority_fee_percentile(&self, block_number: usize, percentile: f64) -> U256 {
+        let Some(fees) = self.priority_fee_history.get(block_number) else {
+            return U256::zero();
+        };
+        if fees.is_empty() {
+            return U256::zero();
+        }
+        let mut fees = fees.clone();
+        fees.sort_unstable();
+        let index = ((percentile / 100.0) * fees.len() as f64).ceil() as usize;
+        fees[index.saturating_sub(1).min(fees.len() - 1)].into()
+    }
+
+    pub fn with_blob_base_fee_history(self, history: Vec<u64>) -> Self {
+        Self {
+            blob_base_fee_history: history,
             ..self
         }
     }
@@ -220,12 +838,112 @@ impl MockEthereum {
         }
     }
 
+    pub fn with_reorged_tx_handling(self, reorged_tx_handling: ReorgedTxHandling) -> Self {
+        Self {
+            reorged_tx_handling,
+            ..self
+        }
+    }
+
     pub fn with_multicall_address(self, address: Address) -> Self {
         Self {
             multicall_address: address,
             ..self
         }
     }
+
+    pub fn with_linea_estimate_gas(self, estimate: LineaEstimateGas) -> Self {
+        Self {
+            linea_estimate_gas: Some(estimate),
+            ..self
+        }
+    }
+
+    /// Sets the response to `eth_maxPriorityFeePerGas` calls. If not set, the method is treated as unsupported.
+    pub fn with_rpc_max_priority_fee(self, fee: U256) -> Self {
+        Self {
+            rpc_max_priority_fee: Some(fee),
+            ..self
+        }
+    }
+
+    /// Sets the configured chain ID of the client. Unless overridden with [`Self::set_reported_chain_id()`],
+    /// the same chain ID is reported by `eth_chainId`.
+    pub fn with_chain_id(self, chain_id: L1ChainId) -> Self {
+        Self { chain_id, ..self }
+    }
+
+    /// Sets the chain ID reported by `eth_chainId`, e.g. to emulate the node being switched to another network.
+    pub fn set_reported_chain_id(&self, chain_id: L1ChainId) {
+        self.inner.write().unwrap().reported_chain_id = Some(chain_id);
+    }
+
+    /// Emulates a node not supporting the `safe` and `finalized` block tags, e.g. one predating the Merge.
+    pub fn with_unsupported_block_tags(self) -> Self {
+        Self {
+            supports_block_tags: false,
+            ..self
+        }
+    }
+
+    fn check_block_tag(&self, block: impl Into<BlockId>) -> Result<(), Error> {
+        if self.supports_block_tags {
+            return Ok(());
+        }
+        match block.into() {
+            BlockId::Number(BlockNumber::Safe) => Err(Error::UnsupportedBlockTag("safe")),
+            BlockId::Number(BlockNumber::Finalized) => Err(Error::UnsupportedBlockTag("finalized")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sets the block returned for the `finalized` block tag. Moves the `safe` head to this block as well
+    /// if it's behind. By default, both heads follow the latest block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_number` is above the latest block or below the current finalized block.
+    pub fn set_finalized_block(&self, block_number: u64) {
+        let mut inner = self.inner.write().unwrap();
+        assert!(
+            block_number <= inner.block_number,
+            "finalized block #{block_number} is above the latest block #{}",
+            inner.block_number
+        );
+        if let Some(finalized_block) = inner.finalized_block {
+            assert!(
+                block_number >= finalized_block,
+                "finalized block cannot move back from #{finalized_block} to #{block_number}"
+            );
+        }
+        inner.finalized_block = Some(block_number);
+        inner.safe_block = Some(
+            inner
+                .safe_block
+                .map_or(block_number, |safe| safe.max(block_number)),
+        );
+    }
+
+    /// Sets the block returned for the `safe` block tag. By default, the `safe` head follows the latest block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_number` is above the latest block or below the finalized block.
+    pub fn set_safe_block(&self, block_number: u64) {
+        let mut inner = self.inner.write().unwrap();
+        assert!(
+            block_number <= inner.block_number,
+            "safe block #{block_number} is above the latest block #{}",
+            inner.block_number
+        );
+        if let Some(finalized_block) = inner.finalized_block {
+            assert!(
+                block_number >= finalized_block,
+                "safe block #{block_number} is below the finalized block #{finalized_block}"
+            );
+        }
+        inner.safe_block = Some(block_number);
+    }
 }
 
 #[async_trait]
@@ -235,17 +953,40 @@ impl EthInterface for MockEthereum {
         hash: H256,
         _: &'static str,
     ) -> Result<Option<ExecutedTxStatus>, Error> {
+        self.check_injected_error(MockMethod::GetTxStatus)?;
         Ok(self.inner.read().unwrap().tx_statuses.get(&hash).cloned())
     }
 
     async fn block_number(&self, _: &'static str) -> Result<U64, Error> {
+        self.check_injected_error(MockMethod::BlockNumber)?;
         Ok(self.inner.read().unwrap().block_number.into())
     }
 
+    async fn fetch_chain_id(&self, _: &'static str) -> Result<L1ChainId, Error> {
+        self.check_injected_error(MockMethod::ChainId)?;
+        let reported_chain_id = self.inner.read().unwrap().reported_chain_id;
+        Ok(reported_chain_id.unwrap_or(self.chain_id))
+    }
+
     async fn send_raw_tx(&self, tx: RawTransactionBytes) -> Result<H256, Error> {
-        let mock_tx = MockTx::from(tx.0);
+        self.check_injected_error(MockMethod::SendRawTx)?;
+        let mut mock_tx = MockTx::from(tx.0);
         let mock_tx_hash = mock_tx.hash;
         let mut inner = self.inner.write().unwrap();
+        if let Some(blob_data) = inner.signed_blob_txs.get(&mock_tx_hash) {
+            // Mimics the transaction pool check performed by L1 nodes.
+            if let Err(err) = blob_data
+                .sidecar
+                .verify_versioned_hashes(&blob_data.versioned_hashes)
+            {
+                return Err(Error::EthereumGateway(Web3Error::Rpc(RpcError {
+                    message: err.to_string(),
+                    code: ErrorCode::ServerError(-32000),
+                    data: None,
+                })));
+            }
+            mock_tx.blob_sidecar = Some(blob_data.sidecar.clone());
+        }
 
         if mock_tx.nonce < inner.current_nonce {
             return Err(Error::EthereumGateway(Web3Error::Rpc(RpcError {
@@ -258,6 +999,8 @@ impl EthInterface for MockEthereum {
         if mock_tx.nonce == inner.pending_nonce {
             inner.pending_nonce += 1;
         }
+        let sent_tx = self.describe_sent_tx(&inner, &mock_tx);
+        inner.sent_tx_history.push(sent_tx);
         inner.sent_txs.insert(mock_tx_hash, mock_tx);
         Ok(mock_tx_hash)
     }
@@ -272,6 +1015,7 @@ impl EthInterface for MockEthereum {
     }
 
     async fn get_gas_price(&self, _: &'static str) -> Result<U256, Error> {
+        self.check_injected_error(MockMethod::GetGasPrice)?;
         Ok(self.max_fee_per_gas)
     }
 
@@ -281,19 +1025,118 @@ impl EthInterface for MockEthereum {
         block_count: usize,
         _component: &'static str,
     ) -> Result<Vec<u64>, Error> {
+        self.check_injected_error(MockMethod::BaseFeeHistory)?;
         let start_block = from_block.saturating_sub(block_count - 1);
-        Ok(self.base_fee_history[start_block..=from_block].to_vec())
+        let inner = self.inner.read().unwrap();
+        Ok(inner.base_fee_history[start_block..=from_block].to_vec())
+    }
+
+    async fn fee_history(
+        &self,
+        block_count: usize,
+        newest_block: BlockNumber,
+        reward_percentiles: &[f64],
+        _component: &'static str,
+    ) -> Result<FeeHistory, Error> {
+        self.check_injected_error(MockMethod::FeeHistory)?;
+        self.check_block_tag(newest_block)?;
+        let inner = self.inner.read().unwrap();
+        let latest_block = inner
+            .base_fee_history
+            .len()
+            .checked_sub(1)
+            .expect("base fee history is not set");
+        let newest_block = match newest_block {
+            BlockNumber::Number(number) => number.as_usize().min(latest_block),
+            BlockNumber::Earliest => 0,
+            BlockNumber::Safe | BlockNumber::Finalized => {
+                (inner.resolve_block_number(newest_block) as usize).min(latest_block)
+            }
+            _ => latest_block,
+        };
+        let oldest_block = (newest_block + 1).saturating_sub(block_count);
+        let blocks = oldest_block..=newest_block;
+
+        let next_base_fee = match inner.base_fee_history.get(newest_block + 1) {
+            Some(&fee) => fee,
+            None => inner.next_base_fee(),
+        };
+        let base_fee_per_gas = inner.base_fee_history[blocks.clone()]
+            .iter()
+            .copied()
+            .chain([next_base_fee])
+            .map(U256::from)
+            .collect();
+        let gas_used_ratio = blocks
+            .clone()
+            .map(|number| {
+                if let Some(&fullness) = inner.block_fullness.get(&number) {
+                    return fullness;
+                }
+                let is_empty = self
+                    .priority_fee_history
+                    .get(number)
+                    .map_or(true, Vec::is_empty);
+                if is_empty {
+                    0.0
+                } else {
+                    0.5
+                }
+            })
+            .collect();
+        let reward = (!reward_percentiles.is_empty()).then(|| {
+            blocks
+                .map(|number| {
+                    reward_percentiles
+                        .iter()
+                        .map(|&percentile| self.priority_fee_percentile(number, percentile))
+                        .collect()
+                })
+                .collect()
+        });
+
+        Ok(FeeHistory {
+            oldest_block: BlockNumber::Number(oldest_block.into()),
+            base_fee_per_gas,
+            gas_used_ratio,
+            reward,
+        })
     }
 
     async fn get_pending_block_base_fee_per_gas(
         &self,
         _component: &'static str,
     ) -> Result<U256, Error> {
-        Ok(U256::from(*self.base_fee_history.last().unwrap()))
+        self.check_injected_error(MockMethod::PendingBlockBaseFee)?;
+        Ok(self.inner.read().unwrap().next_base_fee().into())
+    }
+
+    async fn pending_tx_hash(
+        &self,
+        sender: Address,
+        nonce: U256,
+        _component: &'static str,
+    ) -> Result<Option<H256>, Error> {
+        if sender != self.sender_account() {
+            return Ok(None);
+        }
+        let inner = self.inner.read().unwrap();
+        if nonce < U256::from(inner.current_nonce) {
+            return Ok(None);
+        }
+        // If there are several transactions with the nonce, the node keeps the one with the highest fees.
+        let tx = inner
+            .sent_txs
+            .values()
+            .filter(|tx| U256::from(tx.nonce) == nonce && !inner.tx_statuses.contains_key(&tx.hash))
+            .max_by_key(|tx| tx.max_fee_per_gas);
+        Ok(tx.map(|tx| tx.hash))
     }
 
     async fn failure_reason(&self, tx_hash: H256) -> Result<Option<FailureInfo>, Error> {
-        let tx_status = self.get_tx_status(tx_hash, "failure_reason").await.unwrap();
+        self.check_injected_error(MockMethod::FailureReason)?;
+        let inner = self.inner.read().unwrap();
+        let tx_status = inner.tx_statuses.get(&tx_hash).cloned();
 
         Ok(tx_status.map(|status| FailureInfo {
             revert_code: status.success as i64,
@@ -307,8 +1150,32 @@ impl EthInterface for MockEthereum {
         &self,
         call: ContractCall,
     ) -> Result<Vec<ethabi::Token>, Error> {
+        self.check_injected_error(MockMethod::CallContractFunction)?;
         use ethabi::Token;
 
+        let function = call.contract_abi.function(&call.inner.name)?;
+        let data = function.encode_input(&call.inner.params.0)?;
+        let selector: [u8; 4] = data[..4].try_into().unwrap();
+        self.inner
+            .write()
+            .unwrap()
+            .contract_calls
+            .push(MockContractCall {
+                contract_address: call.contract_address,
+                data: data.clone(),
+            });
+
+        let call_handlers = self.call_handlers.read().unwrap();
+        if call_handlers.has_contract(call.contract_address) {
+            let output = match call_handlers.0.get(&(call.contract_address, selector)) {
+                Some(handler) => handler(&data[4..]),
+                None => Err(vec![]),
+            };
+            let output = output.map_err(Self::revert_error)?;
+            return Ok(function.decode_output(&output)?);
+        }
+        drop(call_handlers);
+
         if call.contract_address == self.multicall_address {
             let token = Token::Array(vec![
                 Token::Tuple(vec![Token::Bool(true), Token::Bytes(vec![1u8; 32])]),
@@ -329,29 +1196,74 @@ impl EthInterface for MockEthereum {
         Ok(vec![])
     }
 
+    /// Applies overrides as follows: if the called contract has overridden code, this code is treated as the call output;
+    /// otherwise, if the contract has an overridden balance, the balance is returned as a single `uint256`.
+    /// If neither is overridden, the call is processed as in [`Self::call_contract_function()`].
+    async fn call_contract_function_with_overrides(
+        &self,
+        call: ContractCall,
+        overrides: CallOverrides,
+    ) -> Result<Vec<ethabi::Token>, Error> {
+        let Some(account) = overrides.account(call.contract_address) else {
+            return self.call_contract_function(call).await;
+        };
+        self.check_injected_error(MockMethod::CallContractFunction)?;
+        if let Some(code) = &account.code {
+            let function = call.contract_abi.function(&call.inner.name)?;
+            Ok(function.decode_output(&code.0)?)
+        } else if let Some(balance) = account.balance {
+            Ok(vec![ethabi::Token::Uint(balance)])
+        } else {
+            self.call_contract_function(call).await
+        }
+    }
+
     async fn get_tx(
         &self,
         hash: H256,
         _component: &'static str,
     ) -> Result<Option<Transaction>, Error> {
-        let txs = &self.inner.read().unwrap().sent_txs;
-        let Some(tx) = txs.get(&hash) else {
+        self.check_injected_error(MockMethod::GetTx)?;
+        let inner = self.inner.read().unwrap();
+        let Some(tx) = inner.sent_txs.get(&hash) else {
             return Ok(None);
         };
-        Ok(Some(tx.clone().into()))
+        let sent_tx = self.describe_sent_tx(&inner, tx);
+        let receipt = inner.tx_statuses.get(&hash).map(|status| &status.receipt);
+        Ok(Some(Transaction {
+            hash,
+            nonce: sent_tx.nonce,
+            block_hash: receipt.and_then(|receipt| receipt.block_hash),
+            block_number: receipt.and_then(|receipt| receipt.block_number),
+            from: Some(sent_tx.from),
+            to: Some(sent_tx.to),
+            value: sent_tx.value,
+            gas: sent_tx.gas_limit.unwrap_or_default(),
+            input: sent_tx.data.into(),
+            max_fee_per_gas: Some(sent_tx.max_fee_per_gas),
+            max_priority_fee_per_gas: Some(sent_tx.max_priority_fee_per_gas),
+            transaction_type: Some(sent_tx.tx_type),
+            ..Transaction::default()
+        }))
     }
 
     async fn tx_receipt(
         &self,
-        _tx_hash: H256,
+        tx_hash: H256,
         _component: &'static str,
     ) -> Result<Option<TransactionReceipt>, Error> {
-        unimplemented!("Not needed right now")
+        self.check_injected_error(MockMethod::TxReceipt)?;
+        let inner = self.inner.read().unwrap();
+        Ok(inner
+            .tx_statuses
+            .get(&tx_hash)
+            .map(|status| status.receipt.clone()))
     }
 
-    async fn eth_balance(
+    async fn eth_balance_at(
         &self,
         _address: Address,
+        _block: BlockNumber,
         _component: &'static str,
     ) -> Result<U256, Error> {
         unimplemented!("Not needed right now")
@@ -363,14 +1275,79 @@ impl EthInterface for MockEthereum {
 
     async fn block(
         &self,
-        _block_id: BlockId,
+        block_id: BlockId,
         _component: &'static str,
     ) -> Result<Option<Block<H256>>, Error> {
-        unimplemented!("Not needed right now")
+        self.check_injected_error(MockMethod::Block)?;
+        self.check_block_tag(block_id)?;
+        let inner = self.inner.read().unwrap();
+        let block_number = match block_id {
+            BlockId::Hash(hash) => {
+                let number =
+                    (0..=inner.block_number).find(|&number| inner.block_hash(number) == hash);
+                let Some(number) = number else {
+                    return Ok(None);
+                };
+                number
+            }
+            BlockId::Number(BlockNumber::Number(number))
+                if number > U64::from(inner.block_number) =>
+            {
+                return Ok(None);
+            }
+            BlockId::Number(BlockNumber::Number(number)) => number.as_u64(),
+            BlockId::Number(BlockNumber::Pending) => return Ok(None),
+            BlockId::Number(tag) => inner.resolve_block_number(tag),
+        };
+
+        let parent_hash = match block_number.checked_sub(1) {
+            Some(parent_number) => inner.block_hash(parent_number),
+            None => H256::zero(),
+        };
+        Ok(Some(Block {
+            number: Some(block_number.into()),
+            hash: Some(inner.block_hash(block_number)),
+            parent_hash,
+            ..Block::default()
+        }))
+    }
+
+    async fn block_blob_gas(
+        &self,
+        block_id: BlockId,
+        _component: &'static str,
+    ) -> Result<Option<BlockBlobGas>, Error> {
+        self.check_injected_error(MockMethod::BlockBlobGas)?;
+        self.check_block_tag(block_id)?;
+        let inner = self.inner.read().unwrap();
+        let block_number = match block_id {
+            BlockId::Hash(_) => unimplemented!("Not needed right now"),
+            BlockId::Number(BlockNumber::Number(number))
+                if number > U64::from(inner.block_number) =>
+            {
+                return Ok(None);
+            }
+            BlockId::Number(tag) => inner.resolve_block_number(tag),
+        };
+
+        let mut blob_gas = inner.block_blob_gas(block_number);
+        if let Some(&blob_base_fee) = self.blob_base_fee_history.get(block_number as usize) {
+            blob_gas.blob_base_fee = blob_base_fee.into();
+        }
+        Ok(Some(blob_gas))
+    }
+
+    async fn max_priority_fee_per_gas(&self, _: &'static str) -> Result<U256, Error> {
+        self.check_injected_error(MockMethod::MaxPriorityFeePerGas)?;
+        self.rpc_max_priority_fee
+            .ok_or(Error::UnsupportedMethod("eth_maxPriorityFeePerGas"))
     }
 
     async fn linea_estimate_gas(&self, _req: CallRequest) -> Result<LineaEstimateGas, Error> {
-        unimplemented!("Not needed right now")
+        self.check_injected_error(MockMethod::LineaEstimateGas)?;
+        self.linea_estimate_gas
+            .clone()
+            .ok_or(Error::UnsupportedMethod("linea_estimateGas"))
     }
 }
 
@@ -385,7 +1362,7 @@ impl BoundEthInterface for MockEthereum {
     }
 
     fn chain_id(&self) -> L1ChainId {
-        unimplemented!("Not needed right now")
+        self.chain_id
     }
 
     fn sender_account(&self) -> Address {
@@ -395,45 +1372,100 @@ impl BoundEthInterface for MockEthereum {
     async fn sign_prepared_tx_for_addr(
         &self,
         data: Vec<u8>,
-        _contract_addr: H160,
+        contract_addr: H160,
         options: Options,
         _component: &'static str,
     ) -> Result<SignedCallResult, Error> {
-        self.sign_prepared_tx(data, options)
+        self.check_injected_error(MockMethod::SignPreparedTx)?;
+        self.sign_tx(data, contract_addr, options, None)
     }
 
-    async fn allowance_on_account(
+    async fn sign_prepared_blob_tx_for_addr(
         &self,
-        _token_address: Address,
-        _contract_address: Address,
-        _erc20_abi: ethabi::Contract,
-    ) -> Result<U256, Error> {
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        max_fee_per_blob_gas: U256,
+        sidecar: BlobTxSidecar,
+        _component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        self.check_injected_error(MockMethod::SignPreparedTx)?;
+        let versioned_hashes = sidecar.versioned_hashes();
+        self.sign_blob_tx(
+            data,
+            contract_addr,
+            options,
+            Some(max_fee_per_blob_gas),
+            versioned_hashes,
+            sidecar,
+        )
+    }
+
+    async fn allowance_on_account(
+        &self,
+        _token_address: Address,
+        _contract_address: Address,
+        _erc20_abi: ethabi::Contract,
+    ) -> Result<U256, Error> {
         unimplemented!("Not needed right now")
     }
 
     async fn nonce_at(&self, block: BlockNumber, _component: &'static str) -> Result<U256, Error> {
-        if let BlockNumber::Number(block_number) = block {
-            let inner = self.inner.read().unwrap();
-            let mut nonce_range = inner.nonces.range(..=block_number.as_u64());
-            let (_, &nonce) = nonce_range.next_back().unwrap_or((&0, &0));
-            Ok(nonce.into())
-        } else {
-            panic!("MockEthereum::nonce_at called with non-number block tag");
+        self.check_injected_error(MockMethod::NonceAt)?;
+        self.check_block_tag(block)?;
+        let inner = self.inner.read().unwrap();
+        if block == BlockNumber::Pending {
+            return Ok(inner.pending_nonce.into());
         }
+        let block_number = inner.resolve_block_number(block);
+        let mut nonce_range = inner.nonces.range(..=block_number);
+        let (_, &nonce) = nonce_range.next_back().unwrap_or((&0, &0));
+        Ok(nonce.into())
     }
 
     async fn pending_nonce(&self, _: &'static str) -> Result<U256, Error> {
+        self.check_injected_error(MockMethod::PendingNonce)?;
         Ok(self.inner.read().unwrap().pending_nonce.into())
     }
 
     async fn current_nonce(&self, _: &'static str) -> Result<U256, Error> {
+        self.check_injected_error(MockMethod::CurrentNonce)?;
         Ok(self.inner.read().unwrap().current_nonce.into())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use assert_matches::assert_matches;
+
     use super::*;
+    use crate::{
+        tx_wait::{self, WaitTrigger},
+        AccountOverride, BlobGasUsage, BlobSidecarError, CallFunctionArgs, PriorityFeeConfig,
+        RevertReason, TxWaitOutcome, BYTES_PER_BLOB, BYTES_PER_COMMITMENT, BYTES_PER_PROOF,
+        MAX_BLOBS_PER_TX,
+    };
+
+    #[tokio::test]
+    async fn verifying_chain_id() {
+        let client = MockEthereum::default().with_chain_id(L1ChainId(5));
+        assert_eq!(client.fetch_chain_id("test").await.unwrap(), L1ChainId(5));
+        client.verify_chain_id("test").await.unwrap();
+
+        client.set_reported_chain_id(L1ChainId(1));
+        let err = client.verify_chain_id("test").await.unwrap_err();
+        assert_matches!(
+            err,
+            Error::ChainIdMismatch { expected, actual }
+                if expected == L1ChainId(5) && actual == L1ChainId(1)
+        );
+
+        client.inject_error(MockMethod::ChainId, MockErrorKind::Timeout, 1);
+        let err = client.verify_chain_id("test").await.unwrap_err();
+        assert!(err.is_transient(), "{err:?}");
+    }
 
     #[tokio::test]
     async fn managing_block_number() {
@@ -446,6 +1478,297 @@ mod tests {
         assert_eq!(block_number, 5.into());
     }
 
+    #[tokio::test]
+    async fn tracking_safe_and_finalized_blocks() {
+        let client = MockEthereum::default();
+        let signed_tx = client
+            .sign_prepared_tx(
+                b"test".to_vec(),
+                Options {
+                    nonce: Some(0.into()),
+                    ..Options::default()
+                },
+            )
+            .unwrap();
+        client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+        client.execute_tx(signed_tx.hash, true, 3);
+        client.advance_block_number(7);
+
+        // By default, tagged heads follow the latest block.
+        for tag in [BlockNumber::Safe, BlockNumber::Finalized] {
+            let block_number = client.tagged_block_number(tag, "test").await.unwrap();
+            assert_eq!(block_number, 10.into());
+        }
+
+        client.set_finalized_block(4);
+        let finalized = client
+            .tagged_block_number(BlockNumber::Finalized, "test")
+            .await
+            .unwrap();
+        assert_eq!(finalized, 4.into());
+        let safe = client
+            .tagged_block_number(BlockNumber::Safe, "test")
+            .await
+            .unwrap();
+        assert_eq!(safe, 4.into());
+        let latest = client.block_number("test").await.unwrap();
+        assert_eq!(latest, 10.into());
+
+        client.set_safe_block(8);
+        let safe = client
+            .tagged_block_number(BlockNumber::Safe, "test")
+            .await
+            .unwrap();
+        assert_eq!(safe, 8.into());
+
+        let block = client
+            .block(BlockId::Number(BlockNumber::Finalized), "test")
+            .await
+            .unwrap()
+            .expect("no finalized block");
+        assert_eq!(block.number, Some(4.into()));
+        let nonce = client
+            .nonce_at(BlockNumber::Finalized, "test")
+            .await
+            .unwrap();
+        assert_eq!(nonce, 1.into());
+
+        // The safe head is reorged together with the chain.
+        client.reorg_to(6);
+        let safe = client
+            .tagged_block_number(BlockNumber::Safe, "test")
+            .await
+            .unwrap();
+        assert_eq!(safe, 6.into());
+    }
+
+    #[tokio::test]
+    async fn unsupported_block_tags() {
+        let client = MockEthereum::default().with_unsupported_block_tags();
+        client.advance_block_number(5);
+
+        let err = client
+            .tagged_block_number(BlockNumber::Finalized, "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::UnsupportedBlockTag("finalized"));
+        let err = client
+            .nonce_at(BlockNumber::Safe, "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::UnsupportedBlockTag("safe"));
+
+        let latest = client
+            .tagged_block_number(BlockNumber::Latest, "test")
+            .await
+            .unwrap();
+        assert_eq!(latest, 5.into());
+    }
+
+    #[tokio::test]
+    async fn linea_gas_estimation() {
+        let request = CallRequest::default();
+        let err = MockEthereum::default()
+            .linea_estimate_gas(request.clone())
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::UnsupportedMethod("linea_estimateGas"));
+
+        let estimate = LineaEstimateGas {
+            base_fee_per_gas: 7.into(),
+            gas_limit: 21_000.into(),
+            priority_fee_per_gas: 1_000.into(),
+        };
+        let client = MockEthereum::default().with_linea_estimate_gas(estimate.clone());
+        assert_eq!(client.linea_estimate_gas(request).await.unwrap(), estimate);
+    }
+
+    #[tokio::test]
+    async fn getting_max_priority_fee() {
+        let err = MockEthereum::default()
+            .max_priority_fee_per_gas("test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::UnsupportedMethod("eth_maxPriorityFeePerGas"));
+
+        let client = MockEthereum::default().with_rpc_max_priority_fee(1_500.into());
+        let fee = client.max_priority_fee_per_gas("test").await.unwrap();
+        assert_eq!(fee, 1_500.into());
+
+        client.inject_error(MockMethod::MaxPriorityFeePerGas, MockErrorKind::Timeout, 1);
+        let err = client.max_priority_fee_per_gas("test").await.unwrap_err();
+        assert!(err.is_transient(), "{err:?}");
+        let fee = client.max_priority_fee_per_gas("test").await.unwrap();
+        assert_eq!(fee, 1_500.into());
+    }
+
+    #[tokio::test]
+    async fn suggesting_priority_fee() {
+        let client = MockEthereum::default()
+            .with_fee_history(vec![10; 6])
+            .with_priority_fee_history(vec![
+                vec![1_000],
+                vec![3, 1, 2, 4],
+                vec![],
+                vec![10, 20, 30, 40, 50],
+                vec![5, 6, 7, 8],
+                vec![100, 200],
+            ]);
+
+        let history = client
+            .fee_history(3, BlockNumber::Number(4.into()), &[25.0, 100.0], "test")
+            .await
+            .unwrap();
+        assert_eq!(history.oldest_block, BlockNumber::Number(2.into()));
+        assert_eq!(history.base_fee_per_gas, [U256::from(10); 4]);
+        assert_eq!(history.gas_used_ratio, [0.0, 0.5, 0.5]);
+        let expected_reward: Vec<Vec<U256>> = vec![
+            vec![0.into(), 0.into()],
+            vec![20.into(), 50.into()],
+            vec![5.into(), 8.into()],
+        ];
+        assert_eq!(history.reward, Some(expected_reward));
+
+        let config = PriorityFeeConfig {
+            block_count: 5,
+            percentile: 50.0,
+        };
+        // Medians in non-empty blocks #1..=5 are 2, 30, 6 and 100.
+        let fee = client.suggest_priority_fee(config, "test").await.unwrap();
+        assert_eq!(fee, Some(30.into()));
+
+        let config = PriorityFeeConfig {
+            block_count: 1,
+            percentile: 100.0,
+        };
+        let fee = client.suggest_priority_fee(config, "test").await.unwrap();
+        assert_eq!(fee, Some(200.into()));
+
+        let config = PriorityFeeConfig {
+            block_count: 1,
+            percentile: f64::NAN,
+        };
+        let err = client
+            .suggest_priority_fee(config, "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::InvalidPercentile(_));
+
+        let client = MockEthereum::default().with_fee_history(vec![10; 3]);
+        let fee = client
+            .suggest_priority_fee(PriorityFeeConfig::default(), "test")
+            .await
+            .unwrap();
+        assert_eq!(fee, None);
+    }
+
+    #[test]
+    fn computing_next_base_fee() {
+        assert_eq!(next_eip1559_base_fee(1_000, 0.5), 1_000);
+        assert_eq!(next_eip1559_base_fee(1_000, 1.0), 1_125);
+        assert_eq!(next_eip1559_base_fee(1_000, 0.0), 875);
+        assert_eq!(next_eip1559_base_fee(1_000, 0.75), 1_062);
+        // The base fee must increase by at least 1 wei if the block is above the target.
+        assert_eq!(next_eip1559_base_fee(1, 0.6), 2);
+    }
+
+    #[tokio::test]
+    async fn evolving_base_fee() {
+        let client = MockEthereum::default().with_fee_history(vec![1_000]);
+        assert_eq!(client.advance_blocks(3, 1.0), 3);
+        let pending_base_fee = client
+            .get_pending_block_base_fee_per_gas("test")
+            .await
+            .unwrap();
+        assert_eq!(pending_base_fee, 1_423.into());
+
+        assert_eq!(client.advance_blocks(2, 0.0), 5);
+        let history = client
+            .fee_history(3, BlockNumber::Latest, &[], "test")
+            .await
+            .unwrap();
+        assert_eq!(history.oldest_block, BlockNumber::Number(3.into()));
+        let expected_base_fees: Vec<U256> =
+            vec![1_265.into(), 1_423.into(), 1_246.into(), 1_091.into()];
+        assert_eq!(history.base_fee_per_gas, expected_base_fees);
+        assert_eq!(history.gas_used_ratio, [1.0, 0.0, 0.0]);
+        assert_eq!(history.reward, None);
+
+        let base_fees = client.base_fee_history(5, 6, "test").await.unwrap();
+        assert_eq!(base_fees, [1_000, 1_000, 1_125, 1_265, 1_423, 1_246]);
+        let pending_base_fee = client
+            .get_pending_block_base_fee_per_gas("test")
+            .await
+            .unwrap();
+        assert_eq!(pending_base_fee, 1_091.into());
+    }
+
+    #[tokio::test]
+    async fn suggesting_priority_fee_for_generated_blocks() {
+        let client = MockEthereum::default()
+            .with_fee_history(vec![1_000])
+            .with_priority_fee_history(vec![vec![], vec![1, 2, 3], vec![10], vec![20, 30]]);
+        client.advance_blocks(3, 0.9);
+
+        let config = PriorityFeeConfig {
+            block_count: 3,
+            percentile: 50.0,
+        };
+        let fee = client.suggest_priority_fee(config, "test").await.unwrap();
+        assert_eq!(fee, Some(10.into()));
+
+        // Empty blocks are ignored by the estimator.
+        client.advance_blocks(2, 0.0);
+        let fee = client.suggest_priority_fee(config, "test").await.unwrap();
+        assert_eq!(fee, Some(20.into()));
+    }
+
+    #[tokio::test]
+    async fn injecting_errors() {
+        let client = MockEthereum::default();
+        client.inject_error(MockMethod::SendRawTx, MockErrorKind::Timeout, 2);
+        let signed_tx = client
+            .sign_prepared_tx(
+                b"test".to_vec(),
+                Options {
+                    nonce: Some(0.into()),
+                    ..Options::default()
+                },
+            )
+            .unwrap();
+
+        for _ in 0..2 {
+            let err = client
+                .send_raw_tx(signed_tx.raw_tx.clone())
+                .await
+                .unwrap_err();
+            assert!(err.is_transient(), "{err}");
+        }
+        assert_eq!(client.sent_tx_count(), 0);
+        assert_eq!(client.pending_nonce("test").await.unwrap(), 0.into());
+
+        client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+        assert_eq!(client.sent_tx_count(), 1);
+        assert_eq!(client.pending_nonce("test").await.unwrap(), 1.into());
+        assert_eq!(client.injected_error_count(MockMethod::SendRawTx), 2);
+        assert_eq!(client.injected_error_count(MockMethod::BlockNumber), 0);
+
+        // Fail every other call.
+        client.inject_error_with(MockMethod::BlockNumber, |call_idx| {
+            (call_idx % 2 == 1).then_some(MockErrorKind::Internal)
+        });
+        for call_idx in 0..6 {
+            let result = client.block_number("test").await;
+            if call_idx % 2 == 1 {
+                let err = result.unwrap_err();
+                assert!(!err.is_transient(), "{err}");
+            } else {
+                assert_eq!(result.unwrap(), 0.into());
+            }
+        }
+        assert_eq!(client.injected_error_count(MockMethod::BlockNumber), 3);
+    }
+
     #[tokio::test]
     async fn managing_transactions() {
         let client = MockEthereum::default().with_non_ordering_confirmation(true);
@@ -488,4 +1811,765 @@ mod tests {
         assert_eq!(tx_status.tx_hash, tx_hash);
         assert_eq!(tx_status.receipt.block_number, Some(2.into()));
     }
+
+    #[tokio::test]
+    async fn managing_blob_transactions() {
+        let client = MockEthereum::default();
+        let sidecar = BlobTxSidecar {
+            blobs: vec![vec![1; BYTES_PER_BLOB]],
+            commitments: vec![vec![2; BYTES_PER_COMMITMENT]],
+            proofs: vec![vec![3; BYTES_PER_PROOF]],
+        };
+        let options = Options {
+            nonce: Some(0.into()),
+            ..Options::default()
+        };
+
+        let signed_tx = client
+            .sign_prepared_blob_tx(b"test".to_vec(), options.clone(), sidecar.clone())
+            .unwrap();
+        let tx_hash = client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+        assert_eq!(client.sent_blob_sidecar(tx_hash), Some(sidecar.clone()));
+
+        let too_many_blobs = BlobTxSidecar {
+            blobs: vec![vec![1; BYTES_PER_BLOB]; MAX_BLOBS_PER_TX + 1],
+            commitments: vec![vec![2; BYTES_PER_COMMITMENT]; MAX_BLOBS_PER_TX + 1],
+            proofs: vec![vec![3; BYTES_PER_PROOF]; MAX_BLOBS_PER_TX + 1],
+        };
+        let err = client
+            .sign_prepared_blob_tx(b"test".to_vec(), options, too_many_blobs)
+            .unwrap_err();
+        assert_matches!(
+            err,
+            Error::BlobSidecar(BlobSidecarError::TooManyBlobs(count)) if count == MAX_BLOBS_PER_TX + 1
+        );
+    }
+
+    fn test_sidecar(blob_count: usize) -> BlobTxSidecar {
+        BlobTxSidecar {
+            blobs: vec![vec![1; BYTES_PER_BLOB]; blob_count],
+            commitments: (0..blob_count)
+                .map(|i| vec![i as u8; BYTES_PER_COMMITMENT])
+                .collect(),
+            proofs: vec![vec![3; BYTES_PER_PROOF]; blob_count],
+        }
+    }
+
+    async fn get_block_blob_gas(client: &MockEthereum, block_id: BlockId) -> Option<BlockBlobGas> {
+        client.block_blob_gas(block_id, "test").await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn accounting_blob_gas() {
+        let client = MockEthereum::default();
+        let options = Options {
+            nonce: Some(0.into()),
+            ..Options::default()
+        };
+        let signed_tx = client
+            .sign_prepared_blob_tx(b"test".to_vec(), options, test_sidecar(MAX_BLOBS_PER_TX))
+            .unwrap();
+        let tx_hash = client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+        client.execute_tx(tx_hash, true, 1);
+
+        let block_blob_gas = get_block_blob_gas(&client, BlockId::Number(0.into())).await;
+        assert_eq!(
+            block_blob_gas,
+            Some(BlockBlobGas::new(MAX_BLOB_GAS_PER_BLOCK, 0))
+        );
+        let block_blob_gas = get_block_blob_gas(&client, BlockId::Number(1.into())).await;
+        assert_eq!(
+            block_blob_gas,
+            Some(BlockBlobGas::new(0, TARGET_BLOB_GAS_PER_BLOCK))
+        );
+        let pending_blob_gas = get_block_blob_gas(&client, BlockNumber::Pending.into()).await;
+        assert_eq!(pending_blob_gas, Some(BlockBlobGas::new(0, 0)));
+        let missing_block_blob_gas = get_block_blob_gas(&client, BlockId::Number(2.into())).await;
+        assert_eq!(missing_block_blob_gas, None);
+        let missing_block_fee = client
+            .blob_base_fee(BlockId::Number(2.into()), "test")
+            .await
+            .unwrap();
+        assert_eq!(missing_block_fee, None);
+
+        // The latest block is empty, so the pending block has zero excess blob gas.
+        let predicted_fee = client
+            .predicted_blob_base_fee(BlobGasUsage::Full, "test")
+            .await
+            .unwrap();
+        let expected_fee = BlockBlobGas::blob_base_fee_for_excess(TARGET_BLOB_GAS_PER_BLOCK);
+        assert_eq!(predicted_fee, Some(expected_fee));
+
+        client.advance_block_number(1);
+        let block_blob_gas = get_block_blob_gas(&client, BlockNumber::Latest.into()).await;
+        assert_eq!(block_blob_gas, Some(BlockBlobGas::new(0, 0)));
+    }
+
+    #[tokio::test]
+    async fn configuring_blob_base_fee_history() {
+        let client = MockEthereum::default().with_blob_base_fee_history(vec![10, 20]);
+        client.advance_block_number(2);
+
+        // The last fee falls back to the value derived from excess blob gas.
+        for (number, expected_fee) in [(0, 10), (1, 20), (2, 1)] {
+            let block_blob_gas = get_block_blob_gas(&client, BlockId::Number(number.into()))
+                .await
+                .unwrap();
+            assert_eq!(block_blob_gas.blob_base_fee, expected_fee.into());
+        }
+    }
+
+    #[tokio::test]
+    async fn rejecting_blob_tx_with_mismatched_versioned_hashes() {
+        let client = MockEthereum::default();
+        let options = Options {
+            nonce: Some(0.into()),
+            ..Options::default()
+        };
+        let sidecar = test_sidecar(2);
+        let mut versioned_hashes = sidecar.versioned_hashes();
+        versioned_hashes.swap(0, 1);
+
+        let signed_tx = client
+            .sign_prepared_blob_tx_with_versioned_hashes(
+                b"test".to_vec(),
+                options,
+                versioned_hashes,
+                sidecar,
+            )
+            .unwrap();
+        let err = client.send_raw_tx(signed_tx.raw_tx).await.unwrap_err();
+        assert_matches!(
+            err,
+            Error::EthereumGateway(Web3Error::Rpc(RpcError {
+                code: ErrorCode::ServerError(-32000),
+                ..
+            }))
+        );
+        assert_eq!(client.sent_tx_count(), 0);
+    }
+
+    async fn send_test_tx(client: &MockEthereum, nonce: u64) -> H256 {
+        let options = Options {
+            nonce: Some(nonce.into()),
+            ..Options::default()
+        };
+        let signed_tx = client
+            .sign_prepared_tx(vec![nonce as u8; 10], options)
+            .unwrap();
+        client.send_raw_tx(signed_tx.raw_tx).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn simulating_reorg() {
+        let client = MockEthereum::default();
+        let first_tx_hash = send_test_tx(&client, 0).await;
+        client.execute_tx(first_tx_hash, true, 3);
+        let second_tx_hash = send_test_tx(&client, 1).await;
+        client.execute_tx(second_tx_hash, true, 3);
+        assert_eq!(client.block_number("test").await.unwrap(), 6.into());
+
+        let receipt = client.tx_receipt(second_tx_hash, "test").await.unwrap();
+        let receipt = receipt.expect("no receipt before reorg");
+        assert_eq!(receipt.block_number, Some(3.into()));
+        let old_block = client
+            .block(BlockId::Number(3.into()), "test")
+            .await
+            .unwrap();
+        let old_block_hash = old_block.unwrap().hash.unwrap();
+        assert_eq!(receipt.block_hash, Some(old_block_hash));
+        let untouched_block = client
+            .block(BlockId::Number(2.into()), "test")
+            .await
+            .unwrap();
+        let untouched_block_hash = untouched_block.unwrap().hash.unwrap();
+
+        client.reorg_to(2);
+
+        assert_eq!(client.block_number("test").await.unwrap(), 2.into());
+        assert_eq!(
+            client.tx_receipt(second_tx_hash, "test").await.unwrap(),
+            None
+        );
+        assert!(client
+            .get_tx_status(second_tx_hash, "test")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(client
+            .tx_receipt(first_tx_hash, "test")
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(client.current_nonce("test").await.unwrap(), 1.into());
+        assert_eq!(client.pending_nonce("test").await.unwrap(), 2.into());
+        assert!(client
+            .get_tx(second_tx_hash, "test")
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            client
+                .block(BlockId::Number(3.into()), "test")
+                .await
+                .unwrap(),
+            None
+        );
+        let block = client
+            .block(BlockId::Number(2.into()), "test")
+            .await
+            .unwrap();
+        assert_eq!(block.unwrap().hash, Some(untouched_block_hash));
+
+        // The transaction can be included into the new chain.
+        client.advance_block_number(1);
+        client.execute_tx(second_tx_hash, true, 3);
+        let receipt = client.tx_receipt(second_tx_hash, "test").await.unwrap();
+        let new_block_hash = receipt.unwrap().block_hash.unwrap();
+        assert_ne!(new_block_hash, old_block_hash);
+        let new_block = client
+            .block(BlockId::Number(3.into()), "test")
+            .await
+            .unwrap();
+        assert_eq!(new_block.unwrap().hash, Some(new_block_hash));
+        let old_block = client
+            .block(BlockId::Hash(old_block_hash), "test")
+            .await
+            .unwrap();
+        assert_eq!(old_block, None);
+    }
+
+    #[tokio::test]
+    async fn dropping_txs_on_reorg() {
+        let client = MockEthereum::default().with_reorged_tx_handling(ReorgedTxHandling::Drop);
+        let tx_hash = send_test_tx(&client, 0).await;
+        client.advance_block_number(1);
+        client.execute_tx(tx_hash, true, 3);
+        assert_eq!(client.pending_nonce("test").await.unwrap(), 1.into());
+
+        client.reorg_to(0);
+        assert_eq!(client.tx_receipt(tx_hash, "test").await.unwrap(), None);
+        assert_eq!(client.get_tx(tx_hash, "test").await.unwrap(), None);
+        assert_eq!(client.current_nonce("test").await.unwrap(), 0.into());
+        assert_eq!(client.pending_nonce("test").await.unwrap(), 0.into());
+    }
+
+    #[tokio::test]
+    async fn recording_sent_transactions() {
+        let client = MockEthereum::default().with_reorged_tx_handling(ReorgedTxHandling::Drop);
+        let first_tx_hash = send_test_tx(&client, 0).await;
+
+        let target = Address::repeat_byte(0x33);
+        let options = Options {
+            nonce: Some(1.into()),
+            value: Some(5.into()),
+            gas: Some(100_000.into()),
+            max_fee_per_gas: Some(200.into()),
+            max_priority_fee_per_gas: Some(20.into()),
+            ..Options::default()
+        };
+        let signed_tx = client
+            .sign_prepared_tx_for_addr(b"call".to_vec(), target, options, "test")
+            .await
+            .unwrap();
+        let second_tx_hash = client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+
+        let sidecar = test_sidecar(2);
+        let options = Options {
+            nonce: Some(2.into()),
+            ..Options::default()
+        };
+        let signed_tx = client
+            .sign_prepared_blob_tx_for_addr(
+                b"blob".to_vec(),
+                target,
+                options,
+                7.into(),
+                sidecar.clone(),
+                "test",
+            )
+            .await
+            .unwrap();
+        let blob_tx_hash = client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+
+        client.execute_tx(first_tx_hash, true, 1);
+        client.execute_tx(second_tx_hash, true, 1);
+        client.reorg_to(0);
+
+        let sent_txs = client.sent_transactions();
+        let sent_tx_hashes: Vec<_> = sent_txs.iter().map(|tx| tx.hash).collect();
+        assert_eq!(
+            sent_tx_hashes,
+            [first_tx_hash, second_tx_hash, blob_tx_hash]
+        );
+
+        let first_tx = &sent_txs[0];
+        assert_eq!(first_tx.from, client.sender_account());
+        assert_eq!(first_tx.to, client.contract_addr());
+        assert_eq!(first_tx.nonce, 0.into());
+        assert_eq!(first_tx.data, [0; 10]);
+        assert_eq!(first_tx.tx_type, 2.into());
+        assert!(!first_tx.dropped_by_reorg);
+
+        let second_tx = &sent_txs[1];
+        assert_eq!(second_tx.to, target);
+        assert_eq!(second_tx.nonce, 1.into());
+        assert_eq!(second_tx.value, 5.into());
+        assert_eq!(second_tx.gas_limit, Some(100_000.into()));
+        assert_eq!(second_tx.data, b"call");
+        assert_eq!(second_tx.max_fee_per_gas, 200.into());
+        assert_eq!(second_tx.max_priority_fee_per_gas, 20.into());
+        assert_eq!(second_tx.max_fee_per_blob_gas, None);
+        assert!(second_tx.blob_versioned_hashes.is_empty());
+        assert!(second_tx.dropped_by_reorg);
+
+        let blob_tx = &sent_txs[2];
+        assert_eq!(blob_tx.to, target);
+        assert_eq!(blob_tx.data, b"blob");
+        assert_eq!(blob_tx.tx_type, 3.into());
+        assert_eq!(blob_tx.max_fee_per_blob_gas, Some(7.into()));
+        assert_eq!(blob_tx.blob_versioned_hashes, sidecar.versioned_hashes());
+        assert!(!blob_tx.dropped_by_reorg);
+
+        client.clear_history();
+        assert!(client.sent_transactions().is_empty());
+        assert_eq!(client.sent_tx_count(), 2);
+        let tx_hash = send_test_tx(&client, 1).await;
+        let sent_tx_hashes: Vec<_> = client
+            .sent_transactions()
+            .iter()
+            .map(|tx| tx.hash)
+            .collect();
+        assert_eq!(sent_tx_hashes, [tx_hash]);
+    }
+
+    #[tokio::test]
+    async fn replacing_transactions() {
+        let client = MockEthereum::default();
+        let target = Address::repeat_byte(0x33);
+        let options = Options {
+            nonce: Some(0.into()),
+            value: Some(5.into()),
+            gas: Some(100_000.into()),
+            ..Options::default()
+        };
+        let signed_tx = client
+            .sign_prepared_tx_for_addr(b"commit".to_vec(), target, options, "test")
+            .await
+            .unwrap();
+        let tx_hash = client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+
+        let new_tx_hash = client
+            .replace_transaction(tx_hash, 20, "test")
+            .await
+            .unwrap();
+        assert_ne!(new_tx_hash, tx_hash);
+        // The bump is raised to the minimum accepted by L1 nodes.
+        let newer_tx_hash = client
+            .replace_transaction(new_tx_hash, 5, "test")
+            .await
+            .unwrap();
+
+        let sent_txs = client.sent_transactions();
+        let sent_tx_hashes: Vec<_> = sent_txs.iter().map(|tx| tx.hash).collect();
+        assert_eq!(sent_tx_hashes, [tx_hash, new_tx_hash, newer_tx_hash]);
+        for sent_tx in &sent_txs {
+            assert_eq!(sent_tx.nonce, 0.into());
+            assert_eq!(sent_tx.to, target);
+            assert_eq!(sent_tx.value, 5.into());
+            assert_eq!(sent_tx.gas_limit, Some(100_000.into()));
+            assert_eq!(sent_tx.data, b"commit");
+        }
+        let fees: Vec<_> = sent_txs
+            .iter()
+            .map(|tx| (tx.max_fee_per_gas, tx.max_priority_fee_per_gas))
+            .collect();
+        assert_eq!(
+            fees,
+            [
+                (100.into(), 10.into()),
+                (120.into(), 12.into()),
+                (132.into(), 14.into())
+            ]
+        );
+
+        client.execute_tx(newer_tx_hash, true, 1);
+        let err = client
+            .replace_transaction(newer_tx_hash, 10, "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::TxNotReplaceable { tx_hash, .. } if tx_hash == newer_tx_hash);
+        let err = client
+            .replace_transaction(H256::repeat_byte(1), 10, "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::TxNotReplaceable { .. });
+        assert_eq!(client.sent_transactions().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn replacing_blob_transactions() {
+        let client = MockEthereum::default();
+        let sidecar = test_sidecar(1);
+        let options = Options {
+            nonce: Some(0.into()),
+            ..Options::default()
+        };
+        let signed_tx = client
+            .sign_prepared_blob_tx_for_addr(
+                b"blob".to_vec(),
+                client.contract_addr(),
+                options,
+                7.into(),
+                sidecar.clone(),
+                "test",
+            )
+            .await
+            .unwrap();
+        let tx_hash = client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+
+        // Blob transactions can be neither replaced without the sidecar, nor cancelled.
+        let err = client
+            .replace_transaction(tx_hash, 10, "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::TxNotReplaceable { .. });
+        let err = client
+            .cancel_transaction(0.into(), 10, "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::TxNotReplaceable { tx_hash: hash, .. } if hash == tx_hash);
+
+        let new_tx_hash = client
+            .replace_blob_transaction(tx_hash, 10, 7.into(), sidecar.clone(), "test")
+            .await
+            .unwrap();
+        assert_eq!(client.sent_blob_sidecar(new_tx_hash), Some(sidecar.clone()));
+        let sent_txs = client.sent_transactions();
+        let new_tx = &sent_txs[1];
+        assert_eq!(new_tx.hash, new_tx_hash);
+        assert_eq!(new_tx.nonce, 0.into());
+        assert_eq!(new_tx.tx_type, 3.into());
+        assert_eq!(new_tx.data, b"blob");
+        // All fees are doubled.
+        assert_eq!(new_tx.max_fee_per_gas, 200.into());
+        assert_eq!(new_tx.max_priority_fee_per_gas, 20.into());
+        assert_eq!(new_tx.max_fee_per_blob_gas, Some(14.into()));
+        assert_eq!(new_tx.blob_versioned_hashes, sidecar.versioned_hashes());
+    }
+
+    #[tokio::test]
+    async fn cancelling_transactions() {
+        let client = MockEthereum::default().with_fee_history(vec![50]);
+        let options = Options {
+            nonce: Some(0.into()),
+            value: Some(5.into()),
+            max_fee_per_gas: Some(1_000.into()),
+            max_priority_fee_per_gas: Some(100.into()),
+            ..Options::default()
+        };
+        let signed_tx = client
+            .sign_prepared_tx_for_addr(
+                b"commit".to_vec(),
+                Address::repeat_byte(0x33),
+                options,
+                "test",
+            )
+            .await
+            .unwrap();
+        let tx_hash = client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+
+        assert_eq!(
+            client
+                .pending_tx_hash(client.sender_account(), 0.into(), "test")
+                .await
+                .unwrap(),
+            Some(tx_hash)
+        );
+
+        let cancel_tx_hash = client
+            .cancel_transaction(0.into(), 10, "test")
+            .await
+            .unwrap();
+        assert_ne!(cancel_tx_hash, tx_hash);
+        let sent_txs = client.sent_transactions();
+        let cancel_tx = &sent_txs[1];
+        assert_eq!(cancel_tx.hash, cancel_tx_hash);
+        assert_eq!(cancel_tx.nonce, 0.into());
+        assert_eq!(cancel_tx.to, client.sender_account());
+        assert_eq!(cancel_tx.value, 0.into());
+        assert_eq!(cancel_tx.gas_limit, Some(21_000.into()));
+        assert!(cancel_tx.data.is_empty());
+        // Fees of the original transaction are bumped, even though they exceed the network fees.
+        assert_eq!(cancel_tx.max_fee_per_gas, 1_100.into());
+        assert_eq!(cancel_tx.max_priority_fee_per_gas, 110.into());
+
+        client.execute_tx(cancel_tx_hash, true, 1);
+        assert_eq!(client.current_nonce("test").await.unwrap(), 1.into());
+        let err = client
+            .cancel_transaction(0.into(), 10, "test")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::NonceAlreadyUsed(nonce) if nonce == 0.into());
+    }
+
+    #[tokio::test]
+    async fn cancelling_transactions_with_fees_below_network_fees() {
+        let client = MockEthereum::default().with_fee_history(vec![50]);
+        send_test_tx(&client, 0).await;
+
+        let cancel_tx_hash = client
+            .cancel_transaction(0.into(), 10, "test")
+            .await
+            .unwrap();
+        let sent_txs = client.sent_transactions();
+        let cancel_tx = &sent_txs[1];
+        assert_eq!(cancel_tx.hash, cancel_tx_hash);
+        assert_eq!(cancel_tx.nonce, 0.into());
+        // The original transaction pays 100 / 10, which, after the bump, is below the network fees:
+        // the gas price is 100 and the base fee is 50, so the priority fee is 50.
+        assert_eq!(cancel_tx.max_fee_per_gas, 150.into());
+        assert_eq!(cancel_tx.max_priority_fee_per_gas, 50.into());
+    }
+
+    #[tokio::test]
+    async fn cancelling_nonce_without_pending_transaction() {
+        let client = MockEthereum::default().with_fee_history(vec![50]);
+        assert_eq!(
+            client
+                .pending_tx_hash(client.sender_account(), 0.into(), "test")
+                .await
+                .unwrap(),
+            None
+        );
+
+        let cancel_tx_hash = client
+            .cancel_transaction(0.into(), 10, "test")
+            .await
+            .unwrap();
+        let sent_txs = client.sent_transactions();
+        assert_eq!(sent_txs.len(), 1);
+        let cancel_tx = &sent_txs[0];
+        assert_eq!(cancel_tx.hash, cancel_tx_hash);
+        assert_eq!(cancel_tx.nonce, 0.into());
+        assert_eq!(cancel_tx.to, client.sender_account());
+        assert_eq!(cancel_tx.value, 0.into());
+        // Network fees (the base fee is 50 and the priority fee is 50) are bumped by 10%.
+        assert_eq!(cancel_tx.max_fee_per_gas, 165.into());
+        assert_eq!(cancel_tx.max_priority_fee_per_gas, 55.into());
+    }
+
+    const WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    async fn wait_for_tx(client: &MockEthereum, tx_hash: H256, timeout: Duration) -> TxWaitOutcome {
+        let trigger = WaitTrigger::Poll(POLL_INTERVAL);
+        tx_wait::wait_for_tx(client, tx_hash, 3, timeout, trigger, "test")
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn waiting_for_tx_confirmations() {
+        let client = MockEthereum::default();
+        let tx_hash = send_test_tx(&client, 0).await;
+        let outcome = client
+            .wait_for_tx(tx_hash, 3, Duration::from_millis(50), "test")
+            .await
+            .unwrap();
+        assert_eq!(outcome, TxWaitOutcome::TimedOut);
+
+        client.execute_tx(tx_hash, true, 1);
+        let produce_blocks = async {
+            tokio::time::sleep(POLL_INTERVAL * 3).await;
+            client.advance_block_number(1);
+            tokio::time::sleep(POLL_INTERVAL * 3).await;
+            client.advance_block_number(1);
+        };
+        let (outcome, ()) =
+            tokio::join!(wait_for_tx(&client, tx_hash, WAIT_TIMEOUT), produce_blocks);
+        let TxWaitOutcome::Confirmed(receipt) = outcome else {
+            panic!("unexpected outcome: {outcome:?}");
+        };
+        assert_eq!(receipt.transaction_hash, tx_hash);
+        assert_eq!(receipt.block_number, Some(0.into()));
+
+        // The transaction is already confirmed, so no polling is necessary.
+        let outcome = client
+            .wait_for_tx(tx_hash, 3, WAIT_TIMEOUT, "test")
+            .await
+            .unwrap();
+        assert_eq!(outcome, TxWaitOutcome::Confirmed(receipt));
+    }
+
+    #[tokio::test]
+    async fn waiting_for_tx_reincluded_after_reorg() {
+        let client = MockEthereum::default();
+        let tx_hash = send_test_tx(&client, 0).await;
+        client.advance_block_number(1);
+        client.execute_tx(tx_hash, true, 1);
+        let old_receipt = client.tx_receipt(tx_hash, "test").await.unwrap().unwrap();
+
+        let reorg = async {
+            tokio::time::sleep(POLL_INTERVAL * 3).await;
+            client.reorg_to(0);
+            tokio::time::sleep(POLL_INTERVAL * 3).await;
+            client.advance_block_number(2);
+            client.execute_tx(tx_hash, true, 3);
+        };
+        let (outcome, ()) = tokio::join!(wait_for_tx(&client, tx_hash, WAIT_TIMEOUT), reorg);
+        let TxWaitOutcome::Confirmed(receipt) = outcome else {
+            panic!("unexpected outcome: {outcome:?}");
+        };
+        assert_eq!(receipt.block_number, Some(2.into()));
+        assert_ne!(receipt.block_hash, old_receipt.block_hash);
+    }
+
+    #[tokio::test]
+    async fn waiting_for_tx_dropped_after_reorg() {
+        let client = MockEthereum::default().with_reorged_tx_handling(ReorgedTxHandling::Drop);
+        let tx_hash = send_test_tx(&client, 0).await;
+        client.advance_block_number(1);
+        client.execute_tx(tx_hash, true, 1);
+
+        let reorg = async {
+            tokio::time::sleep(POLL_INTERVAL * 3).await;
+            client.reorg_to(0);
+        };
+        let (outcome, ()) = tokio::join!(wait_for_tx(&client, tx_hash, WAIT_TIMEOUT), reorg);
+        assert_eq!(outcome, TxWaitOutcome::Dropped);
+    }
+
+    #[tokio::test]
+    async fn calling_contract_with_overrides() {
+        let client = MockEthereum::default();
+        let token_address = Address::repeat_byte(0x11);
+        let call = CallFunctionArgs::new("balanceOf", Address::repeat_byte(0x22))
+            .for_contract(token_address, zksync_contracts::erc20_contract());
+
+        let output = client
+            .call_contract_function_with_overrides(call.clone(), CallOverrides::default())
+            .await
+            .unwrap();
+        assert!(output.is_empty());
+
+        let account = AccountOverride {
+            balance: Some(42.into()),
+            ..AccountOverride::default()
+        };
+        let overrides = CallOverrides::default().with_account(token_address, account);
+        let output = client
+            .call_contract_function_with_overrides(call.clone(), overrides)
+            .await
+            .unwrap();
+        assert_eq!(output, [ethabi::Token::Uint(42.into())]);
+
+        let account = AccountOverride {
+            code: Some(ethabi::encode(&[ethabi::Token::Uint(23.into())]).into()),
+            ..AccountOverride::default()
+        };
+        let overrides = CallOverrides::default().with_account(token_address, account);
+        let output = client
+            .call_contract_function_with_overrides(call, overrides)
+            .await
+            .unwrap();
+        assert_eq!(output, [ethabi::Token::Uint(23.into())]);
+    }
+
+    const VERIFIER_ABI: &str = r#"[
+        {
+            "type": "function",
+            "name": "getVerificationKeyHash",
+            "inputs": [],
+            "outputs": [{ "name": "", "type": "bytes32" }],
+            "stateMutability": "view"
+        },
+        {
+            "type": "function",
+            "name": "isVerified",
+            "inputs": [{ "name": "batchNumber", "type": "uint256" }],
+            "outputs": [{ "name": "", "type": "bool" }],
+            "stateMutability": "view"
+        },
+        {
+            "type": "function",
+            "name": "owner",
+            "inputs": [],
+            "outputs": [{ "name": "", "type": "address" }],
+            "stateMutability": "view"
+        }
+    ]"#;
+
+    #[tokio::test]
+    async fn mocking_contract_calls_by_selector() {
+        let client = MockEthereum::default();
+        let abi = ethabi::Contract::load(VERIFIER_ABI.as_bytes()).unwrap();
+        let verifier_address = Address::repeat_byte(0x11);
+        let vk_hash = H256::repeat_byte(0xaa);
+
+        let get_vk_hash = abi.function("getVerificationKeyHash").unwrap();
+        client.handle_call(
+            verifier_address,
+            get_vk_hash.short_signature(),
+            move |args| {
+                assert!(args.is_empty());
+                Ok(vk_hash.0.to_vec())
+            },
+        );
+        let is_verified = abi.function("isVerified").unwrap();
+        client.handle_call(verifier_address, is_verified.short_signature(), |args| {
+            let batch_number = U256::from_big_endian(args);
+            if batch_number > 10.into() {
+                // `Error(string)` revert with "batch not committed" message
+                let revert_data =
+                    ethabi::encode(&[ethabi::Token::String("batch not committed".to_owned())]);
+                return Err([[0x08, 0xc3, 0x79, 0xa0].as_slice(), &revert_data].concat());
+            }
+            Ok(ethabi::encode(&[ethabi::Token::Bool(
+                batch_number <= 5.into(),
+            )]))
+        });
+
+        let call = CallFunctionArgs::new("getVerificationKeyHash", ())
+            .for_contract(verifier_address, abi.clone());
+        let output = client.call_contract_function(call).await.unwrap();
+        assert_eq!(output, [ethabi::Token::FixedBytes(vk_hash.0.to_vec())]);
+
+        let call = CallFunctionArgs::new("isVerified", U256::from(3))
+            .for_contract(verifier_address, abi.clone());
+        let output = client.call_contract_function(call).await.unwrap();
+        assert_eq!(output, [ethabi::Token::Bool(true)]);
+
+        let call = CallFunctionArgs::new("isVerified", U256::from(11))
+            .for_contract(verifier_address, abi.clone());
+        let err = client.call_contract_function(call).await.unwrap_err();
+        assert_matches!(
+            err.revert_reason(),
+            Some(RevertReason::Error(message)) if message == "batch not committed"
+        );
+
+        // Selectors without a handler are reverted.
+        let call = CallFunctionArgs::new("owner", ()).for_contract(verifier_address, abi.clone());
+        let err = client.call_contract_function(call).await.unwrap_err();
+        assert_matches!(err.revert_reason(), Some(RevertReason::Empty));
+
+        // Contracts without handlers are not affected.
+        let call = CallFunctionArgs::new("owner", ())
+            .for_contract(Address::repeat_byte(0x22), abi.clone());
+        let output = client.call_contract_function(call).await.unwrap();
+        assert!(output.is_empty());
+
+        let calls = client.contract_calls();
+        assert_eq!(calls.len(), 5);
+        assert!(calls[..4]
+            .iter()
+            .all(|call| call.contract_address == verifier_address));
+        assert_eq!(calls[0].selector(), get_vk_hash.short_signature());
+        assert_eq!(
+            calls[1].data,
+            is_verified
+                .encode_input(&[ethabi::Token::Uint(3.into())])
+                .unwrap()
+        );
+    }
 }
@@ -1,21 +1,33 @@
 //! Various Ethereum client implementations.
 
+mod failover;
 mod generic;
 mod http;
+#[cfg(unix)]
+mod ipc;
+mod linea;
 mod mock;
+mod ws;
 
-use serde::{Deserialize, Serialize};
-use zksync_types::U256;
-
+#[cfg(test)]
+pub(crate) use self::http::mock_server;
+#[cfg(unix)]
+pub use self::ipc::{Ipc, IpcQueryClient};
 pub use self::{
-    http::{PKSigningClient, QueryClient, SigningClient},
-    mock::MockEthereum,
+    failover::{FailoverClient, FailoverConfig},
+    http::{
+        BatchCall, BatchResponse, BeaconBlobSidecar, BeaconBlockId, BeaconClient, BearerToken,
+        BlockTransaction, CallFrame, FullBlock, HttpOptions, HttpTransport, JsonRpcSigningClient,
+        Multicall, MulticallResult, OperatorSnapshot, PKSigningClient, PayloadLogger, QueryClient,
+        RateLimit, ReceiptCacheConfig, RetryPolicy, RpcBatch, RpcMiddleware, RpcRequest,
+        RpcResponse, SigningClient, StructLog, StructLogTrace, TimeoutPolicy, TracerConfig,
+        TracingMiddleware, TransactionTrace, TxFeeModel, TxPoolContent, TxPoolContentFrom,
+        TxPoolTransaction, BLOB_OPERATOR_SIGNER, DEFAULT_SIGNER, MULTICALL3_ADDRESS,
+        REQUEST_ID_HEADER, SPAN_HEADER,
+    },
+    linea::{LineaEstimateError, LineaEstimateGas, LineaEstimateLimits},
+    mock::{
+        MockContractCall, MockErrorKind, MockEthereum, MockMethod, MockSentTx, ReorgedTxHandling,
+    },
+    ws::{NewHead, NewHeadsStream, WsClientConfig, WsQueryClient},
 };
-
-#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LineaEstimateGas {
-    pub base_fee_per_gas: U256,
-    pub gas_limit: U256,
-    pub priority_fee_per_gas: U256,
-}
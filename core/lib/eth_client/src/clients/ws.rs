@@ -0,0 +1,779 @@
+//! WebSocket-based Ethereum client supporting `newHeads` subscriptions.
+
+use std::{
+    collections::HashSet,
+    pin::Pin,
+    sync::{Arc, RwLock, Weak},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use futures::{stream, Stream, StreamExt as _, TryStreamExt as _};
+use tokio::sync::{mpsc, watch, Notify};
+use zksync_types::{
+    web3::{
+        transports::WebSocket,
+        types::{
+            Address, Block, BlockHeader, BlockId, BlockNumber, CallRequest, FeeHistory, Filter,
+            Log, Transaction, TransactionReceipt, H256, U256, U64,
+        },
+    },
+    L1ChainId,
+};
+
+use crate::{
+    clients::{http::QueryClient, LineaEstimateGas},
+    tx_wait::{self, TxWaitOutcome, WaitTrigger},
+    types::{BlockBlobGas, Error, ExecutedTxStatus, FailureInfo},
+    CallOverrides, ContractCall, EthInterface, RawTransactionBytes,
+};
+
+/// Capacity of the channel buffering new heads for a single subscription.
+const NEW_HEADS_CHANNEL_CAPACITY: usize = 128;
+/// Maximum number of concurrent requests when catching up with missed blocks.
+const CATCH_UP_CONCURRENCY: usize = 8;
+
+/// Configuration of [`WsQueryClient`].
+#[derive(Debug, Clone)]
+pub struct WsClientConfig {
+    /// Interval between heartbeat requests used to detect dead connections.
+    pub heartbeat_interval: Duration,
+    /// Timeout for a heartbeat request; the connection is considered dead if it's exceeded.
+    pub heartbeat_timeout: Duration,
+    /// Delay before the first reconnection attempt. Doubled after each failed attempt.
+    pub initial_reconnect_backoff: Duration,
+    /// Upper bound for the delay between reconnection attempts.
+    pub max_reconnect_backoff: Duration,
+    /// Maximum number of blocks missed while disconnected that are delivered to new heads subscribers
+    /// after reconnecting. If more blocks were missed, only the most recent ones are delivered.
+    pub max_catch_up_blocks: u64,
+}
+
+impl Default for WsClientConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_interval: Duration::from_secs(10),
+            heartbeat_timeout: Duration::from_secs(5),
+            initial_reconnect_backoff: Duration::from_millis(500),
+            max_reconnect_backoff: Duration::from_secs(30),
+            max_catch_up_blocks: 128,
+        }
+    }
+}
+
+/// Header of a new L1 block delivered by [`WsQueryClient::subscribe_new_heads()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewHead {
+    pub number: U64,
+    pub hash: H256,
+    pub parent_hash: H256,
+    pub timestamp: U256,
+    pub base_fee_per_gas: Option<U256>,
+}
+
+impl NewHead {
+    /// Returns `None` for pending blocks, which have neither a number nor a hash.
+    fn from_header(header: BlockHeader) -> Option<Self> {
+        Some(Self {
+            number: header.number?,
+            hash: header.hash?,
+            parent_hash: header.parent_hash,
+            timestamp: header.timestamp,
+            base_fee_per_gas: header.base_fee_per_gas,
+        })
+    }
+
+    fn from_block(block: Block<H256>) -> Option<Self> {
+        Some(Self {
+            number: block.number?,
+            hash: block.hash?,
+            parent_hash: block.parent_hash,
+            timestamp: block.timestamp,
+            base_fee_per_gas: block.base_fee_per_gas,
+        })
+    }
+}
+
+/// Stream of new L1 block headers. Survives reconnections; headers of blocks produced while the client
+/// was disconnected are delivered after reconnecting.
+#[derive(Debug)]
+pub struct NewHeadsStream {
+    receiver: mpsc::Receiver<NewHead>,
+}
+
+impl Stream for NewHeadsStream {
+    type Item = NewHead;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+/// State shared among the client, the connection manager and subscription tasks.
+#[derive(Debug)]
+struct SharedState {
+    url: String,
+    config: WsClientConfig,
+    client: RwLock<QueryClient<WebSocket>>,
+    /// Incremented each time the connection is re-established.
+    connection_generation: watch::Sender<u64>,
+    reconnect_requested: Notify,
+}
+
+impl SharedState {
+    fn client(&self) -> QueryClient<WebSocket> {
+        self.client.read().unwrap().clone()
+    }
+
+    async fn connect(url: &str) -> Result<QueryClient<WebSocket>, Error> {
+        let transport = WebSocket::new(url).await?;
+        Ok(QueryClient::from_transport(transport))
+    }
+
+    /// Checks that the current connection is alive.
+    async fn heartbeat(&self) -> bool {
+        let client = self.client();
+        let heartbeat = client.web3.eth().chain_id();
+        matches!(
+            tokio::time::timeout(self.config.heartbeat_timeout, heartbeat).await,
+            Ok(Ok(_))
+        )
+    }
+
+    /// Reconnects to the endpoint, retrying with exponential backoff. The state is only upgraded for the duration
+    /// of each attempt, so that retries don't keep a dropped client alive. Returns `false` if the client was dropped.
+    async fn reconnect(state: &Weak<Self>) -> bool {
+        let mut backoff = None;
+        loop {
+            let Some(this) = state.upgrade() else {
+                return false;
+            };
+            let err = match Self::connect(&this.url).await {
+                Ok(client) => {
+                    *this.client.write().unwrap() = client;
+                    this.connection_generation
+                        .send_modify(|generation| *generation += 1);
+                    tracing::info!("Reconnected to L1 WebSocket endpoint");
+                    return true;
+                }
+                Err(err) => err,
+            };
+            let delay = match backoff {
+                None => this.config.initial_reconnect_backoff,
+                Some(prev_delay) => (prev_delay * 2).min(this.config.max_reconnect_backoff),
+            };
+            backoff = Some(delay);
+            drop(this);
+            tracing::warn!(
+                "Failed reconnecting to L1 WebSocket endpoint, retrying in {delay:?}: {err}"
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Monitors the connection and re-establishes it if it's lost. Exits once the client is dropped.
+    async fn manage_connection(state: Weak<Self>) {
+        loop {
+            let Some(this) = state.upgrade() else {
+                return;
+            };
+            let reconnect_requested = this.reconnect_requested.notified();
+            tokio::select! {
+                () = tokio::time::sleep(this.config.heartbeat_interval) => {
+                    if this.heartbeat().await {
+                        continue;
+                    }
+                    tracing::warn!("L1 WebSocket connection is dead, reconnecting");
+                }
+                () = reconnect_requested => {}
+            }
+            drop(this);
+            if !Self::reconnect(&state).await {
+                return;
+            }
+        }
+    }
+
+    /// Requests reconnection unless the connection was already re-established since `generation` was last seen,
+    /// and waits for the new connection.
+    async fn request_reconnect_and_wait(&self, generation: &mut watch::Receiver<u64>) {
+        if !generation.has_changed().unwrap_or(false) {
+            self.reconnect_requested.notify_one();
+        }
+        generation.changed().await.ok();
+    }
+
+    /// Forwards new heads to the `sender` until the receiver is dropped, resubscribing after reconnections.
+    async fn run_new_heads_subscription(self: Arc<Self>, sender: mpsc::Sender<NewHead>) {
+        let mut generation = self.connection_generation.subscribe();
+        let mut last_head: Option<U64> = None;
+        loop {
+            generation.borrow_and_update();
+            let client = self.client();
+            let mut stream = match client.web3.eth_subscribe().subscribe_new_heads().await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    tracing::warn!("Failed subscribing to new L1 heads: {err}");
+                    self.request_reconnect_and_wait(&mut generation).await;
+                    continue;
+                }
+            };
+
+            // Catch up with blocks missed while the client was disconnected. This is done after subscribing,
+            // so that no blocks are missed; blocks both caught up and received via subscription are deduplicated.
+            let mut caught_up_hashes = HashSet::new();
+            if let Some(head) = last_head {
+                match Self::catch_up(&client, head, self.config.max_catch_up_blocks).await {
+                    Ok(heads) => {
+                        for new_head in heads {
+                            caught_up_hashes.insert(new_head.hash);
+                            last_head = Some(new_head.number);
+                            if sender.send(new_head).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        tracing::warn!("Failed catching up with L1 heads: {err}");
+                        self.request_reconnect_and_wait(&mut generation).await;
+                        continue;
+                    }
+                }
+            }
+
+            loop {
+                let header = tokio::select! {
+                    header = stream.next() => header,
+                    () = sender.closed() => return,
+                };
+                let Some(Ok(header)) = header else {
+                    break; // The connection is lost
+                };
+                let Some(new_head) = NewHead::from_header(header) else {
+                    continue;
+                };
+                if caught_up_hashes.contains(&new_head.hash) {
+                    continue;
+                }
+                last_head = Some(new_head.number);
+                if sender.send(new_head).await.is_err() {
+                    return;
+                }
+            }
+            tracing::warn!("L1 new heads subscription was interrupted, resubscribing");
+            self.request_reconnect_and_wait(&mut generation).await;
+        }
+    }
+
+    /// Fetches headers of the blocks following `last_head` up to the current latest block. At most `max_blocks`
+    /// latest blocks are fetched.
+    async fn catch_up(
+        client: &QueryClient<WebSocket>,
+        last_head: U64,
+        max_blocks: u64,
+    ) -> Result<Vec<NewHead>, Error> {
+        const COMPONENT: &str = "ws_new_heads";
+
+        let latest = client.block_number(COMPONENT).await?.as_u64();
+        let mut first = last_head.as_u64() + 1;
+        if first <= latest && latest - first >= max_blocks {
+            let capped_first = latest + 1 - max_blocks;
+            tracing::warn!(
+                "Missed {} L1 blocks while disconnected; only catching up with blocks {capped_first}..={latest}",
+                latest + 1 - first
+            );
+            first = capped_first;
+        }
+
+        let blocks = stream::iter(first..=latest)
+            .map(|number| client.block(BlockId::Number(number.into()), COMPONENT))
+            .buffered(CATCH_UP_CONCURRENCY)
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(blocks
+            .into_iter()
+            .filter_map(|block| block.and_then(NewHead::from_block))
+            .collect())
+    }
+}
+
+/// Ethereum client using WebSocket transport. Can be used as a drop-in replacement for [`QueryClient`];
+/// additionally supports subscribing to new heads. Dead connections are detected using periodic heartbeat
+/// requests and are automatically re-established.
+#[derive(Debug, Clone)]
+pub struct WsQueryClient {
+    state: Arc<SharedState>,
+}
+
+impl WsQueryClient {
+    /// Connects to the specified WebSocket endpoint. Must be called in the context of a Tokio runtime.
+    pub async fn connect(node_url: &str, config: WsClientConfig) -> Result<Self, Error> {
+        let client = SharedState::connect(node_url).await?;
+        let (connection_generation, _) = watch::channel(0);
+        let state = Arc::new(SharedState {
+            url: node_url.to_owned(),
+            config,
+            client: RwLock::new(client),
+            connection_generation,
+            reconnect_requested: Notify::new(),
+        });
+        tokio::spawn(SharedState::manage_connection(Arc::downgrade(&state)));
+        Ok(Self { state })
+    }
+
+    /// Subscribes to new L1 block headers.
+    pub fn subscribe_new_heads(&self) -> NewHeadsStream {
+        let (sender, receiver) = mpsc::channel(NEW_HEADS_CHANNEL_CAPACITY);
+        tokio::spawn(self.state.clone().run_new_heads_subscription(sender));
+        NewHeadsStream { receiver }
+    }
+
+    fn client(&self) -> QueryClient<WebSocket> {
+        self.state.client()
+    }
+}
+
+#[async_trait]
+impl EthInterface for WsQueryClient {
+    async fn nonce_at_for_account(
+        &self,
+        account: Address,
+        block: BlockNumber,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        self.client()
+            .nonce_at_for_account(account, block, component)
+            .await
+    }
+
+    async fn base_fee_history(
+        &self,
+        from_block: usize,
+        block_count: usize,
+        component: &'static str,
+    ) -> Result<Vec<u64>, Error> {
+        self.client()
+            .base_fee_history(from_block, block_count, component)
+            .await
+    }
+
+    async fn fee_history(
+        &self,
+        block_count: usize,
+        newest_block: BlockNumber,
+        reward_percentiles: &[f64],
+        component: &'static str,
+    ) -> Result<FeeHistory, Error> {
+        self.client()
+            .fee_history(block_count, newest_block, reward_percentiles, component)
+            .await
+    }
+
+    async fn get_pending_block_base_fee_per_gas(
+        &self,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        self.client()
+            .get_pending_block_base_fee_per_gas(component)
+            .await
+    }
+
+    async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error> {
+        self.client().get_gas_price(component).await
+    }
+
+    async fn max_priority_fee_per_gas(&self, component: &'static str) -> Result<U256, Error> {
+        self.client().max_priority_fee_per_gas(component).await
+    }
+
+    async fn fetch_chain_id(&self, component: &'static str) -> Result<L1ChainId, Error> {
+        self.client().fetch_chain_id(component).await
+    }
+
+    async fn block_number(&self, component: &'static str) -> Result<U64, Error> {
+        self.client().block_number(component).await
+    }
+
+    async fn send_raw_tx(&self, tx: RawTransactionBytes) -> Result<H256, Error> {
+        self.client().send_raw_tx(tx).await
+    }
+
+    async fn linea_estimate_gas(&self, req: CallRequest) -> Result<LineaEstimateGas, Error> {
+        self.client().linea_estimate_gas(req).await
+    }
+
+    async fn get_tx_status(
+        &self,
+        hash: H256,
+        component: &'static str,
+    ) -> Result<Option<ExecutedTxStatus>, Error> {
+        self.client().get_tx_status(hash, component).await
+    }
+
+    async fn failure_reason(&self, tx_hash: H256) -> Result<Option<FailureInfo>, Error> {
+        self.client().failure_reason(tx_hash).await
+    }
+
+    async fn get_tx(
+        &self,
+        hash: H256,
+        component: &'static str,
+    ) -> Result<Option<Transaction>, Error> {
+        self.client().get_tx(hash, component).await
+    }
+
+    async fn tx_receipt(
+        &self,
+        tx_hash: H256,
+        component: &'static str,
+    ) -> Result<Option<TransactionReceipt>, Error> {
+        self.client().tx_receipt(tx_hash, component).await
+    }
+
+    /// Checks the transaction status on each new L1 head instead of polling.
+    async fn wait_for_tx(
+        &self,
+        tx_hash: H256,
+        confirmations: u64,
+        timeout: Duration,
+        component: &'static str,
+    ) -> Result<TxWaitOutcome, Error> {
+        let trigger = WaitTrigger::NewHeads(self.subscribe_new_heads());
+        tx_wait::wait_for_tx(self, tx_hash, confirmations, timeout, trigger, component).await
+    }
+
+    async fn eth_balance_at(
+        &self,
+        address: Address,
+        block: BlockNumber,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        self.client()
+            .eth_balance_at(address, block, component)
+            .await
+    }
+
+    async fn call_contract_function(
+        &self,
+        call: ContractCall,
+    ) -> Result<Vec<zksync_types::web3::ethabi::Token>, Error> {
+        self.client().call_contract_function(call).await
+    }
+
+    async fn call_contract_function_with_overrides(
+        &self,
+        call: ContractCall,
+        overrides: CallOverrides,
+    ) -> Result<Vec<zksync_types::web3::ethabi::Token>, Error> {
+        self.client()
+            .call_contract_function_with_overrides(call, overrides)
+            .await
+    }
+
+    async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error> {
+        self.client().logs(filter, component).await
+    }
+
+    async fn block(
+        &self,
+        block_id: BlockId,
+        component: &'static str,
+    ) -> Result<Option<Block<H256>>, Error> {
+        self.client().block(block_id, component).await
+    }
+
+    async fn block_blob_gas(
+        &self,
+        block_id: BlockId,
+        component: &'static str,
+    ) -> Result<Option<BlockBlobGas>, Error> {
+        self.client().block_blob_gas(block_id, component).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::SocketAddr,
+        sync::atomic::{AtomicU64, Ordering},
+    };
+
+    use jsonrpsee::{
+        server::{IdProvider, ServerBuilder, ServerHandle},
+        types::{ErrorObjectOwned, SubscriptionId},
+        RpcModule, SubscriptionMessage,
+    };
+    use serde_json::{json, Value};
+    use tokio::sync::broadcast;
+
+    use super::*;
+
+    const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+    #[derive(Debug, Default)]
+    struct HexIdProvider(AtomicU64);
+
+    impl IdProvider for HexIdProvider {
+        fn next_id(&self) -> SubscriptionId<'static> {
+            format!("0x{:x}", self.0.fetch_add(1, Ordering::SeqCst)).into()
+        }
+    }
+
+    /// L1 node state surviving server restarts.
+    #[derive(Debug)]
+    struct MockNode {
+        latest_block: AtomicU64,
+        new_heads: broadcast::Sender<u64>,
+    }
+
+    impl MockNode {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                latest_block: AtomicU64::new(0),
+                new_heads: broadcast::channel(16).0,
+            })
+        }
+
+        fn block_json(number: u64) -> Value {
+            let hash = |number: u64| H256::from_low_u64_be(number + 1);
+            json!({
+                "hash": hash(number),
+                "parentHash": if number == 0 { H256::zero() } else { hash(number - 1) },
+                "sha3Uncles": H256::zero(),
+                "miner": Address::zero(),
+                "stateRoot": H256::zero(),
+                "transactionsRoot": H256::zero(),
+                "receiptsRoot": H256::zero(),
+                "number": U64::from(number),
+                "gasUsed": "0x0",
+                "gasLimit": "0x1c9c380",
+                "baseFeePerGas": "0x7",
+                "extraData": "0x",
+                "logsBloom": format!("0x{}", "00".repeat(256)),
+                "timestamp": U256::from(number * 12),
+                "difficulty": "0x0",
+                "totalDifficulty": "0x0",
+                "sealFields": [],
+                "uncles": [],
+                "transactions": [],
+                "size": "0x0",
+                "mixHash": H256::zero(),
+                "nonce": "0x0000000000000000",
+            })
+        }
+
+        fn produce_block(&self) -> u64 {
+            let number = self.latest_block.fetch_add(1, Ordering::SeqCst) + 1;
+            self.new_heads.send(number).ok();
+            number
+        }
+
+        async fn serve(self: &Arc<Self>, addr: SocketAddr) -> (SocketAddr, ServerHandle) {
+            let mut module = RpcModule::new(self.clone());
+            module
+                .register_method("eth_chainId", |_, _| {
+                    Ok::<_, ErrorObjectOwned>(json!("0x9"))
+                })
+                .unwrap();
+            module
+                .register_method("eth_blockNumber", |_, node| {
+                    let latest_block = node.latest_block.load(Ordering::SeqCst);
+                    Ok::<_, ErrorObjectOwned>(json!(U64::from(latest_block)))
+                })
+                .unwrap();
+            module
+                .register_method("eth_getBlockByNumber", |params, node| {
+                    let (number, _) = params.parse::<(U64, bool)>()?;
+                    let number = number.as_u64();
+                    Ok::<_, ErrorObjectOwned>(
+                        if number <= node.latest_block.load(Ordering::SeqCst) {
+                            Self::block_json(number)
+                        } else {
+                            Value::Null
+                        },
+                    )
+                })
+                .unwrap();
+            module
+                .register_subscription(
+                    "eth_subscribe",
+                    "eth_subscription",
+                    "eth_unsubscribe",
+                    |_, pending, node| async move {
+                        let mut new_heads = node.new_heads.subscribe();
+                        let Ok(sink) = pending.accept().await else {
+                            return;
+                        };
+                        while let Ok(number) = new_heads.recv().await {
+                            let message = SubscriptionMessage::from_json(&Self::block_json(number));
+                            if sink.send(message.unwrap()).await.is_err() {
+                                return;
+                            }
+                        }
+                    },
+                )
+                .unwrap();
+
+            let server = ServerBuilder::default()
+                .set_id_provider(HexIdProvider::default())
+                .build(addr)
+                .await
+                .unwrap();
+            let local_addr = server.local_addr().unwrap();
+            (local_addr, server.start(module))
+        }
+
+        async fn wait_for_subscriber(&self) {
+            while self.new_heads.receiver_count() == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+    }
+
+    fn test_config() -> WsClientConfig {
+        WsClientConfig {
+            heartbeat_interval: Duration::from_millis(50),
+            heartbeat_timeout: Duration::from_secs(1),
+            initial_reconnect_backoff: Duration::from_millis(10),
+            max_reconnect_backoff: Duration::from_millis(100),
+            max_catch_up_blocks: 16,
+        }
+    }
+
+    async fn next_head_number(heads: &mut NewHeadsStream) -> u64 {
+        let head = tokio::time::timeout(TEST_TIMEOUT, heads.next())
+            .await
+            .expect("timed out waiting for new head")
+            .expect("new heads stream terminated");
+        assert_eq!(head.hash, H256::from_low_u64_be(head.number.as_u64() + 1));
+        head.number.as_u64()
+    }
+
+    async fn stop_server(handle: ServerHandle) {
+        handle.stop().unwrap();
+        handle.stopped().await;
+    }
+
+    #[tokio::test]
+    async fn querying_node() {
+        let node = MockNode::new();
+        node.produce_block();
+        let (addr, _server) = node.serve(([127, 0, 0, 1], 0).into()).await;
+        let client = WsQueryClient::connect(&format!("ws://{addr}"), test_config())
+            .await
+            .unwrap();
+
+        let block_number = client.block_number("test").await.unwrap();
+        assert_eq!(block_number, 1.into());
+        let block = client
+            .block(BlockId::Number(1.into()), "test")
+            .await
+            .unwrap()
+            .expect("no block");
+        assert_eq!(block.hash, Some(H256::from_low_u64_be(2)));
+    }
+
+    #[tokio::test]
+    async fn reconnecting_after_connection_loss() {
+        let node = MockNode::new();
+        let (addr, server) = node.serve(([127, 0, 0, 1], 0).into()).await;
+        let client = WsQueryClient::connect(&format!("ws://{addr}"), test_config())
+            .await
+            .unwrap();
+        assert_eq!(client.block_number("test").await.unwrap(), 0.into());
+
+        stop_server(server).await;
+        node.produce_block();
+        let _server = node.serve(addr).await;
+
+        // The heartbeat should detect the dead connection and reconnect.
+        tokio::time::timeout(TEST_TIMEOUT, async {
+            loop {
+                match client.block_number("test").await {
+                    Ok(number) if number == 1.into() => break,
+                    _ => tokio::time::sleep(Duration::from_millis(20)).await,
+                }
+            }
+        })
+        .await
+        .expect("timed out waiting for reconnection");
+    }
+
+    #[tokio::test]
+    async fn dropped_client_stops_reconnecting() {
+        let node = MockNode::new();
+        let (addr, server) = node.serve(([127, 0, 0, 1], 0).into()).await;
+        let client = WsQueryClient::connect(&format!("ws://{addr}"), test_config())
+            .await
+            .unwrap();
+        stop_server(server).await;
+        // Let the connection manager detect the dead connection and start reconnecting.
+        tokio::time::sleep(test_config().heartbeat_interval * 4).await;
+
+        let state = Arc::downgrade(&client.state);
+        drop(client);
+        tokio::time::timeout(TEST_TIMEOUT, async {
+            while state.upgrade().is_some() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("client state is kept alive by reconnection attempts");
+    }
+
+    #[tokio::test]
+    async fn new_heads_subscription_catches_up_after_reconnect() {
+        let node = MockNode::new();
+        let (addr, server) = node.serve(([127, 0, 0, 1], 0).into()).await;
+        let client = WsQueryClient::connect(&format!("ws://{addr}"), test_config())
+            .await
+            .unwrap();
+        let mut heads = client.subscribe_new_heads();
+        node.wait_for_subscriber().await;
+
+        node.produce_block();
+        assert_eq!(next_head_number(&mut heads).await, 1);
+
+        // Blocks #2 and #3 are produced while the client is disconnected.
+        stop_server(server).await;
+        node.produce_block();
+        node.produce_block();
+        let _server = node.serve(addr).await;
+
+        assert_eq!(next_head_number(&mut heads).await, 2);
+        assert_eq!(next_head_number(&mut heads).await, 3);
+        node.produce_block();
+        assert_eq!(next_head_number(&mut heads).await, 4);
+    }
+
+    #[tokio::test]
+    async fn catching_up_is_capped() {
+        let node = MockNode::new();
+        let (addr, server) = node.serve(([127, 0, 0, 1], 0).into()).await;
+        let config = WsClientConfig {
+            max_catch_up_blocks: 2,
+            ..test_config()
+        };
+        let client = WsQueryClient::connect(&format!("ws://{addr}"), config)
+            .await
+            .unwrap();
+        let mut heads = client.subscribe_new_heads();
+        node.wait_for_subscriber().await;
+
+        node.produce_block();
+        assert_eq!(next_head_number(&mut heads).await, 1);
+
+        // Blocks #2..=#5 are produced while the client is disconnected; only the last 2 of them are delivered.
+        stop_server(server).await;
+        for _ in 0..4 {
+            node.produce_block();
+        }
+        let _server = node.serve(addr).await;
+
+        assert_eq!(next_head_number(&mut heads).await, 4);
+        assert_eq!(next_head_number(&mut heads).await, 5);
+        node.produce_block();
+        assert_eq!(next_head_number(&mut heads).await, 6);
+    }
+}
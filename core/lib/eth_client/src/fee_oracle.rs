@@ -0,0 +1,333 @@
+//! Pluggable fee estimation for L1 transactions.
+
+use std::{fmt, sync::Arc};
+
+use async_trait::async_trait;
+use zksync_config::ETHSenderConfig;
+use zksync_types::web3::{
+    self,
+    types::{BlockNumber, CallRequest, U256},
+};
+
+use crate::{
+    clients::LineaEstimateLimits,
+    types::{Error, PriorityFeeConfig},
+    DynEthInterface,
+};
+
+/// Fees suggested by a [`FeeOracle`] for an L1 transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    /// Base fee per gas expected for the next block.
+    pub base_fee_per_gas: U256,
+    /// Suggested priority fee per gas.
+    pub priority_fee_per_gas: U256,
+    /// Gas limit for the transaction if the oracle is able to estimate it.
+    pub gas_limit: Option<U256>,
+}
+
+/// Source of fee estimates for L1 transactions.
+#[async_trait]
+pub trait FeeOracle: 'static + Send + Sync + fmt::Debug {
+    /// Estimates fees for the specified transaction. Oracles are not required to take the transaction
+    /// into account; e.g., [`Eip1559FeeOracle`] only looks at the fee history.
+    async fn estimate_fees(
+        &self,
+        request: &CallRequest,
+        component: &'static str,
+    ) -> Result<FeeEstimate, Error>;
+}
+
+/// Creates the fee oracle configured in `config`: [`LineaFeeOracle`] if `linea_estimateGas` is enabled,
+/// and [`Eip1559FeeOracle`] otherwise. Linea estimates are validated against the max acceptable
+/// priority fee from the config.
+pub fn fee_oracle_from_config(
+    config: &ETHSenderConfig,
+    client: DynEthInterface,
+) -> Arc<dyn FeeOracle> {
+    let default_priority_fee = config.gas_adjuster.default_priority_fee_per_gas.into();
+    let oracle = Eip1559FeeOracle::new(client.clone(), default_priority_fee);
+    if config.sender.enable_linea_estimate_gas {
+        let limits = LineaEstimateLimits {
+            max_priority_fee_per_gas: config.sender.max_acceptable_priority_fee_in_gwei.into(),
+            ..LineaEstimateLimits::default()
+        };
+        Arc::new(LineaFeeOracle::new(client, oracle).with_limits(limits))
+    } else {
+        Arc::new(oracle)
+    }
+}
+
+/// Standard EIP-1559 fee oracle based on `eth_feeHistory`. The base fee is the one predicted
+/// for the pending block; the priority fee is suggested based on fees paid in the latest blocks.
+#[derive(Debug, Clone)]
+pub struct Eip1559FeeOracle {
+    client: DynEthInterface,
+    priority_fee_config: PriorityFeeConfig,
+    default_priority_fee: U256,
+}
+
+impl Eip1559FeeOracle {
+    /// Creates an oracle with the default [`PriorityFeeConfig`]. `default_priority_fee` is used
+    /// if the latest blocks don't contain any transactions.
+    pub fn new(client: DynEthInterface, default_priority_fee: U256) -> Self {
+        Self {
+            client,
+            priority_fee_config: PriorityFeeConfig::default(),
+            default_priority_fee,
+        }
+    }
+
+    pub fn with_priority_fee_config(mut self, config: PriorityFeeConfig) -> Self {
+        self.priority_fee_config = config;
+        self
+    }
+}
+
+#[async_trait]
+impl FeeOracle for Eip1559FeeOracle {
+    async fn estimate_fees(
+        &self,
+        _request: &CallRequest,
+        component: &'static str,
+    ) -> Result<FeeEstimate, Error> {
+        self.priority_fee_config.validate()?;
+        let history = self
+            .client
+            .fee_history(
+                self.priority_fee_config.block_count,
+                BlockNumber::Latest,
+                &[self.priority_fee_config.percentile],
+                component,
+            )
+            .await?;
+        // The last base fee in the history is the one for the block following the newest block.
+        let base_fee_per_gas = history.base_fee_per_gas.last().copied().ok_or_else(|| {
+            let message = "`eth_feeHistory` returned empty base fee history".to_owned();
+            Error::EthereumGateway(web3::Error::InvalidResponse(message))
+        })?;
+        let priority_fee_per_gas =
+            PriorityFeeConfig::suggest(&history).unwrap_or(self.default_priority_fee);
+        Ok(FeeEstimate {
+            base_fee_per_gas,
+            priority_fee_per_gas,
+            gas_limit: None,
+        })
+    }
+}
+
+/// Fee oracle using the Linea-specific `linea_estimateGas` RPC method, which estimates fees
+/// for a particular transaction and provides a gas limit hint. Falls back to the wrapped oracle
+/// if the node doesn't support the method. Estimates are validated against [`LineaEstimateLimits`];
+/// estimates failing validation result in [`Error::InvalidLineaEstimate`].
+#[derive(Debug, Clone)]
+pub struct LineaFeeOracle {
+    client: DynEthInterface,
+    fallback: Eip1559FeeOracle,
+    limits: LineaEstimateLimits,
+}
+
+impl LineaFeeOracle {
+    /// Creates an oracle with the default [`LineaEstimateLimits`].
+    pub fn new(client: DynEthInterface, fallback: Eip1559FeeOracle) -> Self {
+        Self {
+            client,
+            fallback,
+            limits: LineaEstimateLimits::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: LineaEstimateLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+#[async_trait]
+impl FeeOracle for LineaFeeOracle {
+    async fn estimate_fees(
+        &self,
+        request: &CallRequest,
+        component: &'static str,
+    ) -> Result<FeeEstimate, Error> {
+        match self.client.linea_estimate_gas(request.clone()).await {
+            Ok(estimate) => {
+                estimate.validate(&self.limits)?;
+                Ok(estimate.into_fee_params())
+            }
+            Err(Error::UnsupportedMethod(method)) => {
+                tracing::warn!(
+                    "L1 node doesn't support `{method}`; falling back to EIP-1559 fee estimation"
+                );
+                self.fallback.estimate_fees(request, component).await
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::clients::{
+        LineaEstimateError, LineaEstimateGas, MockErrorKind, MockEthereum, MockMethod,
+    };
+
+    fn mock_client() -> MockEthereum {
+        MockEthereum::default()
+            .with_fee_history(vec![10, 20, 30, 40])
+            .with_priority_fee_history(vec![vec![1], vec![3], vec![], vec![2]])
+    }
+
+    const LINEA_ESTIMATE: LineaEstimateGas = LineaEstimateGas {
+        base_fee_per_gas: U256([7, 0, 0, 0]),
+        gas_limit: U256([21_000, 0, 0, 0]),
+        priority_fee_per_gas: U256([1_000, 0, 0, 0]),
+    };
+
+    #[tokio::test]
+    async fn eip1559_fee_estimation() {
+        let oracle = Eip1559FeeOracle::new(Arc::new(mock_client()), 100.into());
+        let estimate = oracle
+            .estimate_fees(&CallRequest::default(), "test")
+            .await
+            .unwrap();
+        assert_eq!(
+            estimate,
+            FeeEstimate {
+                base_fee_per_gas: 40.into(),
+                priority_fee_per_gas: 2.into(),
+                gas_limit: None,
+            }
+        );
+
+        let oracle = oracle.with_priority_fee_config(PriorityFeeConfig {
+            block_count: 3,
+            percentile: 50.0,
+        });
+        let estimate = oracle
+            .estimate_fees(&CallRequest::default(), "test")
+            .await
+            .unwrap();
+        assert_eq!(estimate.priority_fee_per_gas, 3.into());
+
+        // Latest blocks don't contain transactions; the default priority fee should be used.
+        let client = MockEthereum::default().with_fee_history(vec![10, 20]);
+        let oracle = Eip1559FeeOracle::new(Arc::new(client), 100.into());
+        let estimate = oracle
+            .estimate_fees(&CallRequest::default(), "test")
+            .await
+            .unwrap();
+        assert_eq!(estimate.base_fee_per_gas, 20.into());
+        assert_eq!(estimate.priority_fee_per_gas, 100.into());
+    }
+
+    #[tokio::test]
+    async fn linea_fee_estimation() {
+        let client: DynEthInterface =
+            Arc::new(mock_client().with_linea_estimate_gas(LINEA_ESTIMATE));
+        let fallback = Eip1559FeeOracle::new(client.clone(), 100.into());
+        let oracle = LineaFeeOracle::new(client, fallback);
+        let estimate = oracle
+            .estimate_fees(&CallRequest::default(), "test")
+            .await
+            .unwrap();
+        assert_eq!(
+            estimate,
+            FeeEstimate {
+                base_fee_per_gas: 7.into(),
+                priority_fee_per_gas: 1_000.into(),
+                gas_limit: Some(21_000.into()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_linea_estimates_are_rejected() {
+        let client: DynEthInterface =
+            Arc::new(mock_client().with_linea_estimate_gas(LINEA_ESTIMATE));
+        let fallback = Eip1559FeeOracle::new(client.clone(), 100.into());
+        let oracle = LineaFeeOracle::new(client, fallback).with_limits(LineaEstimateLimits {
+            max_priority_fee_per_gas: 999.into(),
+            ..LineaEstimateLimits::default()
+        });
+        let err = oracle
+            .estimate_fees(&CallRequest::default(), "test")
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            Error::InvalidLineaEstimate(LineaEstimateError::PriorityFeeTooHigh { value, .. })
+                if value == 1_000.into()
+        );
+    }
+
+    #[tokio::test]
+    async fn linea_fee_estimation_fallback() {
+        let client: DynEthInterface = Arc::new(mock_client());
+        let fallback = Eip1559FeeOracle::new(client.clone(), 100.into());
+        let oracle = LineaFeeOracle::new(client, fallback);
+        let estimate = oracle
+            .estimate_fees(&CallRequest::default(), "test")
+            .await
+            .unwrap();
+        assert_eq!(estimate.base_fee_per_gas, 40.into());
+        assert_eq!(estimate.priority_fee_per_gas, 2.into());
+        assert_eq!(estimate.gas_limit, None);
+    }
+
+    #[tokio::test]
+    async fn linea_fee_estimation_errors_are_not_masked() {
+        let client = mock_client().with_linea_estimate_gas(LINEA_ESTIMATE);
+        client.inject_error(MockMethod::LineaEstimateGas, MockErrorKind::Timeout, 1);
+        let client: DynEthInterface = Arc::new(client);
+        let fallback = Eip1559FeeOracle::new(client.clone(), 100.into());
+        let oracle = LineaFeeOracle::new(client, fallback);
+
+        let err = oracle
+            .estimate_fees(&CallRequest::default(), "test")
+            .await
+            .unwrap_err();
+        assert!(err.is_transient(), "{err:?}");
+        let estimate = oracle
+            .estimate_fees(&CallRequest::default(), "test")
+            .await
+            .unwrap();
+        assert_eq!(estimate.gas_limit, Some(21_000.into()));
+    }
+
+    #[tokio::test]
+    async fn selecting_oracle_from_config() {
+        let mut config = ETHSenderConfig::for_tests();
+        let client: DynEthInterface =
+            Arc::new(mock_client().with_linea_estimate_gas(LINEA_ESTIMATE));
+
+        let oracle = fee_oracle_from_config(&config, client.clone());
+        let estimate = oracle
+            .estimate_fees(&CallRequest::default(), "test")
+            .await
+            .unwrap();
+        assert_matches!(estimate.gas_limit, None);
+
+        config.sender.enable_linea_estimate_gas = true;
+        let oracle = fee_oracle_from_config(&config, client.clone());
+        let estimate = oracle
+            .estimate_fees(&CallRequest::default(), "test")
+            .await
+            .unwrap();
+        assert_matches!(estimate.gas_limit, Some(limit) if limit == 21_000.into());
+
+        config.sender.max_acceptable_priority_fee_in_gwei = 999;
+        let oracle = fee_oracle_from_config(&config, client);
+        let err = oracle
+            .estimate_fees(&CallRequest::default(), "test")
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            Error::InvalidLineaEstimate(LineaEstimateError::PriorityFeeTooHigh { .. })
+        );
+    }
+}
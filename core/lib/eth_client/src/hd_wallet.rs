@@ -0,0 +1,247 @@
+//! Derivation of private keys from BIP-39 mnemonics according to BIP-32 / BIP-44.
+
+use std::fmt;
+
+use hmac::{Hmac, Mac};
+use secp256k1::{PublicKey, Scalar, Secp256k1, SecretKey};
+use sha2::Sha512;
+use zksync_types::H256;
+
+/// Default BIP-44 derivation path for the first Ethereum account.
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+
+/// Index of the first hardened child in BIP-32.
+const HARDENED_OFFSET: u32 = 1 << 31;
+
+/// Errors deriving a private key from a mnemonic.
+#[derive(Debug, thiserror::Error)]
+pub enum HdWalletError {
+    /// Mnemonic is not a valid BIP-39 English mnemonic (e.g., contains an unknown word or has an invalid checksum).
+    #[error("invalid mnemonic: {0}")]
+    InvalidMnemonic(#[from] bip39::Error),
+    /// Derivation path is malformed.
+    #[error("invalid derivation path `{path}`: {reason}")]
+    InvalidDerivationPath { path: String, reason: &'static str },
+    /// Derived key is invalid. This happens with negligible probability; using another account index
+    /// in the derivation path resolves the issue.
+    #[error("derived key at `{path}` is invalid; use another derivation path")]
+    InvalidDerivedKey { path: String },
+}
+
+/// Parsed BIP-32 derivation path, e.g. `m/44'/60'/0'/0/0`.
+#[derive(Clone, PartialEq, Eq)]
+struct DerivationPath(Vec<u32>);
+
+impl fmt::Debug for DerivationPath {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("m")?;
+        for &index in &self.0 {
+            if index >= HARDENED_OFFSET {
+                write!(formatter, "/{}'", index - HARDENED_OFFSET)?;
+            } else {
+                write!(formatter, "/{index}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DerivationPath {
+    /// Parses a path. Hardened indices may be marked with `'`, `h` or `H`.
+    fn parse(path: &str) -> Result<Self, HdWalletError> {
+        let err = |reason| HdWalletError::InvalidDerivationPath {
+            path: path.to_owned(),
+            reason,
+        };
+
+        let mut segments = path.trim().split('/');
+        if segments.next() != Some("m") {
+            return Err(err("path must start with `m`"));
+        }
+        let indices = segments.map(|segment| {
+            let (index, is_hardened) = match segment.strip_suffix(['\'', 'h', 'H']) {
+                Some(index) => (index, true),
+                None => (segment, false),
+            };
+            if index.is_empty() || !index.bytes().all(|ch| ch.is_ascii_digit()) {
+                return Err(err("path segments must be non-negative integers"));
+            }
+            let index: u32 = index.parse().map_err(|_| err("index is too large"))?;
+            if index >= HARDENED_OFFSET {
+                return Err(err("index is too large"));
+            }
+            Ok(if is_hardened {
+                index + HARDENED_OFFSET
+            } else {
+                index
+            })
+        });
+        let indices = indices.collect::<Result<Vec<_>, _>>()?;
+        if indices.is_empty() {
+            return Err(err("path must contain at least one index"));
+        }
+        Ok(Self(indices))
+    }
+}
+
+/// Extended private key (a private key with a chain code) used during BIP-32 derivation.
+struct ExtendedKey {
+    secret: SecretKey,
+    chain_code: [u8; 32],
+}
+
+impl ExtendedKey {
+    fn hmac(key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+        let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any size");
+        for chunk in data {
+            mac.update(chunk);
+        }
+        let output = mac.finalize().into_bytes();
+        let (left, right) = output.split_at(32);
+        (left.try_into().unwrap(), right.try_into().unwrap())
+    }
+
+    fn master(seed: &[u8]) -> Option<Self> {
+        let (secret, chain_code) = Self::hmac(b"Bitcoin seed", &[seed]);
+        Some(Self {
+            secret: SecretKey::from_slice(&secret).ok()?,
+            chain_code,
+        })
+    }
+
+    fn child(&self, secp: &Secp256k1<secp256k1::All>, index: u32) -> Option<Self> {
+        let index_bytes = index.to_be_bytes();
+        let (tweak, chain_code) = if index >= HARDENED_OFFSET {
+            let secret = self.secret.secret_bytes();
+            Self::hmac(&self.chain_code, &[&[0], &secret, &index_bytes])
+        } else {
+            let public_key = PublicKey::from_secret_key(secp, &self.secret).serialize();
+            Self::hmac(&self.chain_code, &[&public_key, &index_bytes])
+        };
+        let tweak = Scalar::from_be_bytes(tweak).ok()?;
+        Some(Self {
+            secret: self.secret.add_tweak(&tweak).ok()?,
+            chain_code,
+        })
+    }
+}
+
+/// Derives a private key from a BIP-39 English `mnemonic` (without a passphrase) using the specified
+/// BIP-32 derivation path, e.g. [`DEFAULT_DERIVATION_PATH`]. The mnemonic and the path are validated
+/// before derivation.
+pub fn derive_private_key(mnemonic: &str, derivation_path: &str) -> Result<H256, HdWalletError> {
+    let path = DerivationPath::parse(derivation_path)?;
+    let mnemonic = bip39::Mnemonic::parse(mnemonic)?;
+    let seed = mnemonic.to_seed("");
+
+    let invalid_key = || HdWalletError::InvalidDerivedKey {
+        path: format!("{path:?}"),
+    };
+    let secp = Secp256k1::new();
+    let mut key = ExtendedKey::master(&seed).ok_or_else(invalid_key)?;
+    for &index in &path.0 {
+        key = key.child(&secp, index).ok_or_else(invalid_key)?;
+    }
+    Ok(H256(key.secret.secret_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use zksync_types::{Address, PackedEthSignature};
+
+    use super::*;
+
+    const TEST_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+    fn derive_address(mnemonic: &str, path: &str) -> Address {
+        let private_key = derive_private_key(mnemonic, path).unwrap();
+        PackedEthSignature::address_from_private_key(&private_key).unwrap()
+    }
+
+    #[test]
+    fn parsing_derivation_paths() {
+        let path = DerivationPath::parse(DEFAULT_DERIVATION_PATH).unwrap();
+        assert_eq!(
+            path.0,
+            [
+                44 + HARDENED_OFFSET,
+                60 + HARDENED_OFFSET,
+                HARDENED_OFFSET,
+                0,
+                0
+            ]
+        );
+        assert_eq!(format!("{path:?}"), DEFAULT_DERIVATION_PATH);
+        assert_eq!(DerivationPath::parse("m/44h/60H/0'/0/5").unwrap().0[4], 5);
+
+        let invalid_paths = [
+            "",
+            "m",
+            "44'/60'/0'/0/0",
+            "m/44'/60'/0'/0/",
+            "m/44'/-60'/0'/0/0",
+            "m/44''/60'/0'/0/0",
+            "m/44'/60'/0'/0/x",
+            "m/2147483648",
+            "m/99999999999",
+        ];
+        for path in invalid_paths {
+            let err = DerivationPath::parse(path).unwrap_err();
+            assert_matches!(err, HdWalletError::InvalidDerivationPath { .. }, "{path}");
+        }
+    }
+
+    #[test]
+    fn deriving_keys_from_mnemonic() {
+        // Default accounts used by Hardhat and Anvil.
+        let private_key = derive_private_key(TEST_MNEMONIC, DEFAULT_DERIVATION_PATH).unwrap();
+        assert_eq!(
+            private_key,
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            derive_address(TEST_MNEMONIC, DEFAULT_DERIVATION_PATH),
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            derive_address(TEST_MNEMONIC, "m/44'/60'/0'/0/1"),
+            "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+                .parse()
+                .unwrap()
+        );
+
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+            abandon abandon about";
+        assert_eq!(
+            derive_address(mnemonic, DEFAULT_DERIVATION_PATH),
+            "0x9858EfFD232B4033E47d90003D41EC34EcaEda94"
+                .parse()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_mnemonics() {
+        let invalid_mnemonics = [
+            // Invalid word count
+            "test test test test test test test test test test test",
+            // Unknown word
+            "test test test test test test test test test test test junkk",
+            // Invalid checksum
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+             abandon abandon",
+        ];
+        for mnemonic in invalid_mnemonics {
+            let err = derive_private_key(mnemonic, DEFAULT_DERIVATION_PATH).unwrap_err();
+            assert_matches!(err, HdWalletError::InvalidMnemonic(_), "{mnemonic}");
+            // The mnemonic must not be leaked via errors.
+            assert!(!err.to_string().contains("test"), "{err}");
+            assert!(!err.to_string().contains("abandon"), "{err}");
+        }
+    }
+}
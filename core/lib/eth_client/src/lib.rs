@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use zksync_types::{
@@ -6,20 +6,45 @@ use zksync_types::{
         contract::Options,
         ethabi,
         types::{
-            Address, Block, BlockId, BlockNumber, CallRequest, Filter, Log, Transaction,
-            TransactionReceipt, H160, H256, U256, U64,
+            Address, Block, BlockId, BlockNumber, CallRequest, FeeHistory, Filter, Log,
+            Transaction, TransactionReceipt, H160, H256, U256, U64,
         },
     },
     L1ChainId,
 };
 
-use crate::clients::LineaEstimateGas;
-pub use crate::types::{
-    CallFunctionArgs, ContractCall, Error, ExecutedTxStatus, FailureInfo, RawTransactionBytes,
-    SignedCallResult,
+pub use crate::{
+    batch_events::{
+        fetch_l1_batch_events, L1BatchEvent, L1BatchEventError, L1BatchEventKind, L1BatchEventLog,
+    },
+    fee_oracle::{
+        fee_oracle_from_config, Eip1559FeeOracle, FeeEstimate, FeeOracle, LineaFeeOracle,
+    },
+    hd_wallet::{derive_private_key, HdWalletError, DEFAULT_DERIVATION_PATH},
+    proof::{AccountProof, ProofError, StorageProof, EMPTY_CODE_HASH, EMPTY_TRIE_ROOT},
+    revert::RevertReason,
+    tx_wait::TxWaitOutcome,
+    types::{
+        kzg_to_versioned_hash, AccountOverride, BeaconApiError, BlobGasUsage, BlobSidecarError,
+        BlobTxSidecar, BlockBlobGas, CallFunctionArgs, CallOverrides, ContractCall, Error,
+        ErrorKind, ExecutedTxStatus, FailureInfo, PriorityFeeConfig, RawTransactionBytes,
+        SignedCallResult, BYTES_PER_BLOB, BYTES_PER_COMMITMENT, BYTES_PER_PROOF, GAS_PER_BLOB,
+        MAX_BLOBS_PER_TX, MAX_BLOB_GAS_PER_BLOCK, TARGET_BLOB_GAS_PER_BLOCK,
+    },
+};
+use crate::{
+    clients::LineaEstimateGas,
+    tx_wait::{WaitTrigger, TX_POLL_INTERVAL},
 };
 
+mod batch_events;
 pub mod clients;
+mod fee_oracle;
+mod hd_wallet;
+mod proof;
+mod replacement;
+mod revert;
+mod tx_wait;
 mod types;
 
 /// Common Web3 interface, as seen by the core applications.
@@ -59,22 +84,74 @@ pub trait EthInterface: 'static + Sync + Send + fmt::Debug {
         component: &'static str,
     ) -> Result<Vec<u64>, Error>;
 
+    /// Returns the fee history for `block_count` blocks up to and including `newest_block`. For each block,
+    /// the history contains priority fees paid at each of `reward_percentiles` (if any are specified).
+    async fn fee_history(
+        &self,
+        block_count: usize,
+        newest_block: BlockNumber,
+        reward_percentiles: &[f64],
+        component: &'static str,
+    ) -> Result<FeeHistory, Error>;
+
+    /// Suggests a priority fee based on the fees paid in the latest blocks, as specified by `config`.
+    ///
+    /// Returns `Ok(None)` if none of the blocks contain transactions, and [`Error::InvalidPercentile`]
+    /// if `config` is invalid.
+    async fn suggest_priority_fee(
+        &self,
+        config: PriorityFeeConfig,
+        component: &'static str,
+    ) -> Result<Option<U256>, Error> {
+        config.validate()?;
+        let history = self
+            .fee_history(
+                config.block_count,
+                BlockNumber::Latest,
+                &[config.percentile],
+                component,
+            )
+            .await?;
+        Ok(PriorityFeeConfig::suggest(&history))
+    }
+
     /// Returns the `base_fee_per_gas` value for the currently pending L1 block.
     async fn get_pending_block_base_fee_per_gas(
         &self,
         component: &'static str,
     ) -> Result<U256, Error>;
 
+    /// Returns the hash of a transaction of the `sender` with the specified `nonce` in the node mempool.
+    /// Returns `Ok(None)` if there is no such transaction or if the client cannot inspect the mempool
+    /// (the default implementation).
+    async fn pending_tx_hash(
+        &self,
+        _sender: Address,
+        _nonce: U256,
+        _component: &'static str,
+    ) -> Result<Option<H256>, Error> {
+        Ok(None)
+    }
+
     /// Returns the current gas price.
     async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error>;
 
+    /// Returns the priority fee per gas suggested by the node via `eth_maxPriorityFeePerGas`.
+    /// Returns [`Error::UnsupportedMethod`] if the node doesn't support this method; in this case,
+    /// the priority fee can be estimated based on the fee history (see [`Self::suggest_priority_fee()`]).
+    async fn max_priority_fee_per_gas(&self, component: &'static str) -> Result<U256, Error>;
+
     /// Returns the current block number.
     async fn block_number(&self, component: &'static str) -> Result<U64, Error>;
 
+    /// Fetches the chain ID reported by the node via `eth_chainId`.
+    async fn fetch_chain_id(&self, component: &'static str) -> Result<L1ChainId, Error>;
+
     /// Sends a transaction to the Ethereum network.
     async fn send_raw_tx(&self, tx: RawTransactionBytes) -> Result<H256, Error>;
 
-    /// Call a contract without changing the state of the blockchain to estimate gas usage.
+    /// Estimates gas usage and fees for a call using the Linea-specific `linea_estimateGas` RPC method.
+    /// Returns [`Error::UnsupportedMethod`] if the node doesn't support this method.
     async fn linea_estimate_gas(&self, req: CallRequest) -> Result<LineaEstimateGas, Error>;
 
     /// Fetches the transaction status for a specified transaction hash.
@@ -109,13 +186,50 @@ pub trait EthInterface: 'static + Sync + Send + fmt::Debug {
         component: &'static str,
     ) -> Result<Option<TransactionReceipt>, Error>;
 
-    /// Returns the ETH balance of the specified token for the specified address.
-    async fn eth_balance(&self, address: Address, component: &'static str) -> Result<U256, Error>;
+    /// Waits until the transaction with the specified hash is included into a block and has at least `confirmations`
+    /// blocks on top of it (0 means that the inclusion is sufficient). The receipt block is checked to be canonical
+    /// on each check; if the transaction is un-included by a reorg, it's waited for again until it's re-included
+    /// or dropped.
+    ///
+    /// Returns `Err` only if a request fails (e.g. due to network issues).
+    async fn wait_for_tx(
+        &self,
+        tx_hash: H256,
+        confirmations: u64,
+        timeout: Duration,
+        component: &'static str,
+    ) -> Result<TxWaitOutcome, Error> {
+        let trigger = WaitTrigger::Poll(TX_POLL_INTERVAL);
+        tx_wait::wait_for_tx(self, tx_hash, confirmations, timeout, trigger, component).await
+    }
+
+    /// Returns the ETH balance of the specified address at the latest block.
+    async fn eth_balance(&self, address: Address, component: &'static str) -> Result<U256, Error> {
+        self.eth_balance_at(address, BlockNumber::Latest, component)
+            .await
+    }
+
+    /// Returns the ETH balance of the specified address at the specified block, which may be
+    /// a block tag such as [`BlockNumber::Finalized`].
+    async fn eth_balance_at(
+        &self,
+        address: Address,
+        block: BlockNumber,
+        component: &'static str,
+    ) -> Result<U256, Error>;
 
     /// Invokes a function on a contract specified by `contract_address` / `contract_abi` using `eth_call`.
     async fn call_contract_function(&self, call: ContractCall)
         -> Result<Vec<ethabi::Token>, Error>;
 
+    /// Same as [`Self::call_contract_function()`], but executes the call with the specified state overrides.
+    /// Returns [`Error::CallOverridesRejected`] if the node rejects the overrides.
+    async fn call_contract_function_with_overrides(
+        &self,
+        call: ContractCall,
+        overrides: CallOverrides,
+    ) -> Result<Vec<ethabi::Token>, Error>;
+
     /// Returns the logs for the specified filter.
     async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error>;
 
@@ -125,11 +239,75 @@ pub trait EthInterface: 'static + Sync + Send + fmt::Debug {
         block_id: BlockId,
         component: &'static str,
     ) -> Result<Option<Block<H256>>, Error>;
+
+    /// Returns the number of the block with the specified tag, e.g. [`BlockNumber::Finalized`]. Comparing
+    /// the result with [`Self::block_number()`] allows to measure the finality lag.
+    ///
+    /// Returns [`Error::UnsupportedBlockTag`] if the node doesn't support the tag or doesn't know the tagged block
+    /// (e.g., the `finalized` block before the Merge).
+    async fn tagged_block_number(
+        &self,
+        tag: BlockNumber,
+        component: &'static str,
+    ) -> Result<U64, Error> {
+        let tag_name = match tag {
+            BlockNumber::Number(number) => return Ok(number),
+            BlockNumber::Latest => "latest",
+            BlockNumber::Earliest => "earliest",
+            BlockNumber::Pending => "pending",
+            BlockNumber::Safe => "safe",
+            BlockNumber::Finalized => "finalized",
+        };
+        let block = self.block(BlockId::Number(tag), component).await?;
+        block
+            .and_then(|block| block.number)
+            .ok_or(Error::UnsupportedBlockTag(tag_name))
+    }
+
+    /// Returns EIP-4844 blob gas accounting for the specified block. Returns `Ok(None)` if the block
+    /// doesn't exist or predates EIP-4844.
+    async fn block_blob_gas(
+        &self,
+        block_id: BlockId,
+        component: &'static str,
+    ) -> Result<Option<BlockBlobGas>, Error>;
+
+    /// Returns the blob base fee in the specified block computed from its excess blob gas as defined in EIP-4844.
+    /// Returns `Ok(None)` if the block doesn't exist or predates EIP-4844.
+    async fn blob_base_fee(
+        &self,
+        block_id: BlockId,
+        component: &'static str,
+    ) -> Result<Option<U256>, Error> {
+        let blob_gas = self.block_blob_gas(block_id, component).await?;
+        Ok(blob_gas.map(|blob_gas| blob_gas.blob_base_fee))
+    }
+
+    /// Predicts the blob base fee for the block after the pending one, assuming that the pending block
+    /// has the specified blob gas `usage`. Unlike the blob base fee of the pending block, which is fully determined
+    /// by the latest block, this fee is uncertain; [`BlobGasUsage::Full`] provides an upper bound for it.
+    ///
+    /// Returns `Ok(None)` if the latest block predates EIP-4844.
+    async fn predicted_blob_base_fee(
+        &self,
+        usage: BlobGasUsage,
+        component: &'static str,
+    ) -> Result<Option<U256>, Error> {
+        let latest_block = BlockId::Number(BlockNumber::Latest);
+        let blob_gas = self.block_blob_gas(latest_block, component).await?;
+        Ok(blob_gas.map(|blob_gas| blob_gas.next_block(usage).next_blob_base_fee()))
+    }
 }
 
 #[cfg(test)]
 static_assertions::assert_obj_safe!(EthInterface);
 
+/// Shared type-erased [`EthInterface`] implementation, e.g., a [`QueryClient`] or a [`FailoverClient`].
+///
+/// [`QueryClient`]: crate::clients::QueryClient
+/// [`FailoverClient`]: crate::clients::FailoverClient
+pub type DynEthInterface = Arc<dyn EthInterface>;
+
 /// An extension of `EthInterface` trait, which is used to perform queries that are bound to
 /// a certain contract and account.
 ///
@@ -176,6 +354,21 @@ pub trait BoundEthInterface: EthInterface {
         component: &'static str,
     ) -> Result<SignedCallResult, Error>;
 
+    /// Signs an EIP-4844 transaction carrying the blobs from `sidecar`. The returned raw transaction
+    /// is in the network encoding (i.e., includes the sidecar) and can be submitted via
+    /// [`EthInterface::send_raw_tx()`]. Expected to use credentials associated with `Self::sender_account()`.
+    ///
+    /// Returns an error if the sidecar is malformed or carries more than [`MAX_BLOBS_PER_TX`] blobs.
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        max_fee_per_blob_gas: U256,
+        sidecar: BlobTxSidecar,
+        component: &'static str,
+    ) -> Result<SignedCallResult, Error>;
+
     /// Returns the nonce of the `Self::sender_account()` at the specified block.
     async fn nonce_at(&self, block: BlockNumber, component: &'static str) -> Result<U256, Error> {
         self.nonce_at_for_account(self.sender_account(), block, component)
@@ -203,6 +396,79 @@ pub trait BoundEthInterface: EthInterface {
             .await
     }
 
+    /// Checks that the chain ID reported by the node matches the configured [`Self::chain_id()`].
+    /// Returns [`Error::ChainIdMismatch`] otherwise. Can be called periodically to detect
+    /// the node being switched to another network.
+    async fn verify_chain_id(&self, component: &'static str) -> Result<(), Error> {
+        let expected = self.chain_id();
+        let actual = self.fetch_chain_id(component).await?;
+        if actual != expected {
+            tracing::error!(
+                "L1 node reports chain ID {}, while the client is configured for chain ID {}",
+                actual.0,
+                expected.0
+            );
+            return Err(Error::ChainIdMismatch { expected, actual });
+        }
+        Ok(())
+    }
+
+    /// Replaces a pending transaction sent by `Self::sender_account()` with a transaction with the same nonce,
+    /// recipient, calldata and value, but with fees bumped by `fee_bump_percent` (at least by 10%, which is
+    /// the minimum bump accepted by L1 nodes). The replacement is signed and sent; returns its hash.
+    ///
+    /// Returns [`Error::TxNotReplaceable`] if the transaction is unknown, already included into a block,
+    /// or is a blob transaction. L1 nodes don't return sidecars of pending blob transactions, so the latter
+    /// must be replaced using [`Self::replace_blob_transaction()`], which accepts the original sidecar.
+    async fn replace_transaction(
+        &self,
+        original_hash: H256,
+        fee_bump_percent: u64,
+        component: &'static str,
+    ) -> Result<H256, Error> {
+        replacement::replace_transaction(self, original_hash, fee_bump_percent, component).await
+    }
+
+    /// Same as [`Self::replace_transaction()`], but for EIP-4844 transactions. Since L1 nodes don't return blobs
+    /// and the blob fee of pending transactions, the original `sidecar` and `original_max_fee_per_blob_gas`
+    /// must be provided by the caller. All fees, including the blob fee, are bumped by at least 100%,
+    /// as required by Geth for blob transaction replacements.
+    async fn replace_blob_transaction(
+        &self,
+        original_hash: H256,
+        fee_bump_percent: u64,
+        original_max_fee_per_blob_gas: U256,
+        sidecar: BlobTxSidecar,
+        component: &'static str,
+    ) -> Result<H256, Error> {
+        replacement::replace_blob_transaction(
+            self,
+            original_hash,
+            fee_bump_percent,
+            original_max_fee_per_blob_gas,
+            sidecar,
+            component,
+        )
+        .await
+    }
+
+    /// Cancels the pending transaction with the specified `nonce` by sending a zero-value self-transfer
+    /// with this nonce. If the node mempool contains a transaction with the nonce
+    /// (see [`EthInterface::pending_tx_hash()`]), its fees are bumped by `fee_bump_percent` (at least by 10%);
+    /// otherwise, the current network fees are bumped.
+    /// In both cases, fees are raised to the current network fees if those are higher.
+    ///
+    /// Returns [`Error::NonceAlreadyUsed`] if a transaction with the nonce is already included into a block,
+    /// and [`Error::TxNotReplaceable`] if the pending transaction is a blob transaction.
+    async fn cancel_transaction(
+        &self,
+        nonce: U256,
+        fee_bump_percent: u64,
+        component: &'static str,
+    ) -> Result<H256, Error> {
+        replacement::cancel_transaction(self, nonce, fee_bump_percent, component).await
+    }
+
     /// Returns the ETH balance of `Self::sender_account()`.
     async fn sender_eth_balance(&self, component: &'static str) -> Result<U256, Error> {
         self.eth_balance(self.sender_account(), component).await
@@ -243,3 +509,13 @@ pub trait BoundEthInterface: EthInterface {
             .expect("failed to encode parameters")
     }
 }
+
+#[cfg(test)]
+static_assertions::assert_obj_safe!(BoundEthInterface);
+
+/// Shared type-erased [`BoundEthInterface`] implementation, e.g., a [`SigningClient`] or a [`MockEthereum`].
+/// Can be used as a [`DynEthInterface`] by wrapping it into another `Arc`.
+///
+/// [`SigningClient`]: crate::clients::SigningClient
+/// [`MockEthereum`]: crate::clients::MockEthereum
+pub type DynBoundEthInterface = Arc<dyn BoundEthInterface>;
@@ -0,0 +1,414 @@
+//! Account and storage proofs returned by `eth_getProof` (EIP-1186), and their verification.
+
+use rlp::Rlp;
+use serde::Deserialize;
+use zksync_types::web3::{
+    signing::keccak256,
+    types::{Address, Bytes, H256, U256},
+};
+
+/// Root hash of an empty Merkle Patricia trie, i.e. `keccak256(rlp(""))`.
+pub const EMPTY_TRIE_ROOT: H256 = H256([
+    0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+    0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+]);
+/// Code hash of accounts without code, i.e. `keccak256("")`.
+pub const EMPTY_CODE_HASH: H256 = H256([
+    0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7, 0x03, 0xc0,
+    0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04, 0x5d, 0x85, 0xa4, 0x70,
+]);
+
+/// Number of items in an RLP-encoded branch node: 16 children and a value.
+const BRANCH_NODE_LEN: usize = 17;
+
+/// Errors verifying an [`AccountProof`] or a [`StorageProof`].
+#[derive(Debug, thiserror::Error)]
+pub enum ProofError {
+    #[error("malformed trie node: {0}")]
+    Rlp(#[from] rlp::DecoderError),
+    #[error("malformed trie node: {0}")]
+    MalformedNode(&'static str),
+    #[error("hash of a proof node is {actual:?}, expected {expected:?}")]
+    HashMismatch { expected: H256, actual: H256 },
+    #[error("proof ends before reaching the value")]
+    MissingNodes,
+    #[error("proof contains extra nodes after the value")]
+    ExtraNodes,
+    #[error("account {field} differs from the value in the proof")]
+    AccountMismatch { field: &'static str },
+    #[error("value of storage slot {key:?} differs from the value in the proof")]
+    StorageMismatch { key: H256 },
+}
+
+/// Account state and Merkle proofs returned by `eth_getProof`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+    pub address: Address,
+    /// RLP-encoded trie nodes on the path from the state root to the account.
+    pub account_proof: Vec<Bytes>,
+    pub balance: U256,
+    pub code_hash: H256,
+    pub nonce: U256,
+    /// Root of the account storage trie.
+    pub storage_hash: H256,
+    pub storage_proof: Vec<StorageProof>,
+}
+
+impl AccountProof {
+    /// Verifies the account state against the specified state root (e.g., taken from a block header),
+    /// and all storage proofs against the account storage root. Proofs of absence are accepted
+    /// for empty accounts and zero storage values.
+    pub fn verify(&self, state_root: H256) -> Result<(), ProofError> {
+        let key = keccak256(self.address.as_bytes());
+        match verify_trie_proof(state_root, &key, &self.account_proof)? {
+            Some(account) => self.check_account(&account)?,
+            None => self.check_empty_account()?,
+        }
+        for storage_proof in &self.storage_proof {
+            storage_proof.verify(self.storage_hash)?;
+        }
+        Ok(())
+    }
+
+    fn check_account(&self, encoded: &[u8]) -> Result<(), ProofError> {
+        let account = Rlp::new(encoded);
+        if account.item_count()? != 4 {
+            return Err(ProofError::MalformedNode("account must have 4 fields"));
+        }
+        let fields = [
+            ("nonce", self.nonce),
+            ("balance", self.balance),
+            (
+                "storage hash",
+                U256::from_big_endian(self.storage_hash.as_bytes()),
+            ),
+            (
+                "code hash",
+                U256::from_big_endian(self.code_hash.as_bytes()),
+            ),
+        ];
+        for (i, (field, expected)) in fields.into_iter().enumerate() {
+            let actual = account.at(i)?.data()?;
+            if actual.len() > 32 || U256::from_big_endian(actual) != expected {
+                return Err(ProofError::AccountMismatch { field });
+            }
+        }
+        Ok(())
+    }
+
+    fn check_empty_account(&self) -> Result<(), ProofError> {
+        let field = if !self.nonce.is_zero() {
+            "nonce"
+        } else if !self.balance.is_zero() {
+            "balance"
+        } else if self.storage_hash != EMPTY_TRIE_ROOT {
+            "storage hash"
+        } else if self.code_hash != EMPTY_CODE_HASH {
+            "code hash"
+        } else {
+            return Ok(());
+        };
+        Err(ProofError::AccountMismatch { field })
+    }
+}
+
+/// Value of a storage slot and its Merkle proof returned by `eth_getProof`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StorageProof {
+    pub key: H256,
+    pub value: U256,
+    /// RLP-encoded trie nodes on the path from the storage root to the slot.
+    pub proof: Vec<Bytes>,
+}
+
+impl StorageProof {
+    /// Verifies the slot value against the specified storage root.
+    pub fn verify(&self, storage_root: H256) -> Result<(), ProofError> {
+        let key = keccak256(self.key.as_bytes());
+        let value = match verify_trie_proof(storage_root, &key, &self.proof)? {
+            // Storage values are RLP-encoded big-endian integers without leading zeros.
+            Some(encoded) => {
+                let value = Rlp::new(&encoded).data()?.to_vec();
+                if value.len() > 32 {
+                    return Err(ProofError::StorageMismatch { key: self.key });
+                }
+                U256::from_big_endian(&value)
+            }
+            None => U256::zero(),
+        };
+        if value != self.value {
+            return Err(ProofError::StorageMismatch { key: self.key });
+        }
+        Ok(())
+    }
+}
+
+/// Reference to a trie node from its parent.
+#[derive(Debug, Clone, Copy)]
+enum NodeRef<'a> {
+    Hash(H256),
+    /// Nodes shorter than 32 bytes are embedded into the parent node rather than referenced by hash.
+    Inline(&'a [u8]),
+}
+
+impl<'a> NodeRef<'a> {
+    fn new(item: Rlp<'a>) -> Result<Self, ProofError> {
+        if item.is_list() {
+            return Ok(Self::Inline(item.as_raw()));
+        }
+        let hash = item.data()?;
+        if hash.len() != 32 {
+            return Err(ProofError::MalformedNode("child reference is not a hash"));
+        }
+        Ok(Self::Hash(H256::from_slice(hash)))
+    }
+}
+
+/// Verifies a Merkle Patricia trie proof for `key` against `root`. Returns the value stored at the key,
+/// or `None` if the proof shows that the key is absent from the trie.
+fn verify_trie_proof(
+    root: H256,
+    key: &[u8],
+    proof: &[Bytes],
+) -> Result<Option<Vec<u8>>, ProofError> {
+    if proof.is_empty() && root == EMPTY_TRIE_ROOT {
+        return Ok(None);
+    }
+
+    let key: Vec<u8> = key
+        .iter()
+        .flat_map(|&byte| [byte >> 4, byte & 0xf])
+        .collect();
+    let mut key = key.as_slice();
+    let mut proof_nodes = proof.iter();
+    let mut node_ref = NodeRef::Hash(root);
+    let value = loop {
+        let node = match node_ref {
+            NodeRef::Hash(expected) => {
+                let node = proof_nodes.next().ok_or(ProofError::MissingNodes)?;
+                let actual = H256(keccak256(&node.0));
+                if actual != expected {
+                    return Err(ProofError::HashMismatch { expected, actual });
+                }
+                Rlp::new(&node.0)
+            }
+            NodeRef::Inline(node) => Rlp::new(node),
+        };
+
+        match node.item_count()? {
+            BRANCH_NODE_LEN => {
+                let Some((&nibble, rest)) = key.split_first() else {
+                    let value = node.at(BRANCH_NODE_LEN - 1)?.data()?;
+                    break (!value.is_empty()).then(|| value.to_vec());
+                };
+                let child = node.at(nibble.into())?;
+                if child.is_empty() {
+                    break None;
+                }
+                key = rest;
+                node_ref = NodeRef::new(child)?;
+            }
+            2 => {
+                let (path, is_leaf) = decode_path(node.at(0)?.data()?)?;
+                if is_leaf {
+                    if key != path.as_slice() {
+                        break None;
+                    }
+                    break Some(node.at(1)?.data()?.to_vec());
+                }
+                let Some(rest) = key.strip_prefix(path.as_slice()) else {
+                    break None;
+                };
+                key = rest;
+                node_ref = NodeRef::new(node.at(1)?)?;
+            }
+            _ => return Err(ProofError::MalformedNode("unexpected number of items")),
+        }
+    };
+
+    if proof_nodes.next().is_some() {
+        return Err(ProofError::ExtraNodes);
+    }
+    Ok(value)
+}
+
+/// Decodes the hex-prefix encoded path of a leaf or extension node. Returns the path nibbles
+/// and whether the node is a leaf.
+fn decode_path(encoded: &[u8]) -> Result<(Vec<u8>, bool), ProofError> {
+    let (&first, rest) = encoded
+        .split_first()
+        .ok_or(ProofError::MalformedNode("empty node path"))?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(ProofError::MalformedNode("invalid node path prefix"));
+    }
+    let is_leaf = flag & 2 != 0;
+    let is_odd = flag & 1 != 0;
+
+    let mut path = Vec::with_capacity(rest.len() * 2 + 1);
+    if is_odd {
+        path.push(first & 0xf);
+    }
+    path.extend(rest.iter().flat_map(|&byte| [byte >> 4, byte & 0xf]));
+    Ok((path, is_leaf))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ProofFixture {
+        state_root: H256,
+        proof: AccountProof,
+        missing_account_proof: AccountProof,
+    }
+
+    fn load_fixture() -> ProofFixture {
+        serde_json::from_str(include_str!("fixtures/account_proof.json")).unwrap()
+    }
+
+    fn bytes(hex_str: &str) -> Bytes {
+        hex::decode(hex_str).unwrap().into()
+    }
+
+    #[test]
+    fn empty_trie_constants() {
+        assert_eq!(EMPTY_CODE_HASH, H256(keccak256(&[])));
+        assert_eq!(EMPTY_TRIE_ROOT, H256(keccak256(&rlp::NULL_RLP)));
+    }
+
+    #[test]
+    fn verifying_trie_proofs() {
+        // Trie with an extension node, a branch with an inlined leaf, and two hashed leaves.
+        let root: H256 = "0x359ea42eda7f18058bbb84697e3f2e8c214969503f790a781f64b7da1727c185"
+            .parse()
+            .unwrap();
+        let extension =
+            bytes("e4821123a09296ea86e4b0121240bf3b4695b9bf3fa8cafb3277a8f7da8f1438d2b6d94a47");
+        let branch = bytes(
+            "f85180808080a08f936cf4595e02f37e9509d1872f2d8ba744154a854d6ff2fd65088bb6c8967ba0d542b4c9\
+             8e3c28e2aa7261b1b0f22b0f61b8917dbaaaa40712114cd42ca4372e8080808080808080808080",
+        );
+        let inner_branch = bytes(
+            "f38080808080a047663a4ce79756e861a9bb1bd13f86d7cc74d938e0e14feddb20ef75b297f62280c23801\
+             808080808080808080",
+        );
+        let leaf = [&[0xea, 0x36, 0xa8][..], &[0xaa; 40]].concat().into();
+        let other_leaf = [&[0xec, 0x82, 0x20, 0xff, 0xa8][..], &[0xbb; 40]]
+            .concat()
+            .into();
+
+        let proof = [
+            extension.clone(),
+            branch.clone(),
+            inner_branch.clone(),
+            leaf,
+        ];
+        let value = verify_trie_proof(root, &[0x12, 0x34, 0x56], &proof).unwrap();
+        assert_eq!(value, Some(vec![0xaa; 40]));
+
+        let proof = [extension.clone(), branch.clone(), inner_branch.clone()];
+        let value = verify_trie_proof(root, &[0x12, 0x34, 0x78], &proof).unwrap();
+        assert_eq!(value, Some(vec![1]));
+        let value = verify_trie_proof(root, &[0x12, 0x34, 0x00], &proof).unwrap();
+        assert_eq!(value, None);
+
+        let proof = [extension.clone(), branch.clone(), other_leaf];
+        let value = verify_trie_proof(root, &[0x12, 0x35, 0xff], &proof).unwrap();
+        assert_eq!(value, Some(vec![0xbb; 40]));
+        let value = verify_trie_proof(root, &[0x12, 0x35, 0xfe], &proof).unwrap();
+        assert_eq!(value, None);
+
+        // Key diverging from the extension node path
+        let value = verify_trie_proof(root, &[0x13, 0x00, 0x00], &[extension.clone()]).unwrap();
+        assert_eq!(value, None);
+
+        let err = verify_trie_proof(
+            root,
+            &[0x12, 0x34, 0x56],
+            &[extension.clone(), branch.clone()],
+        )
+        .unwrap_err();
+        assert_matches!(err, ProofError::MissingNodes);
+        let proof = [
+            extension.clone(),
+            branch.clone(),
+            inner_branch.clone(),
+            extension.clone(),
+        ];
+        let err = verify_trie_proof(root, &[0x12, 0x34, 0x78], &proof).unwrap_err();
+        assert_matches!(err, ProofError::ExtraNodes);
+        let err = verify_trie_proof(root, &[0x12, 0x34, 0x78], &[branch]).unwrap_err();
+        assert_matches!(err, ProofError::HashMismatch { expected, .. } if expected == root);
+
+        assert_eq!(verify_trie_proof(EMPTY_TRIE_ROOT, &[1], &[]).unwrap(), None);
+        let err = verify_trie_proof(root, &[1], &[]).unwrap_err();
+        assert_matches!(err, ProofError::MissingNodes);
+    }
+
+    #[test]
+    fn verifying_account_proof() {
+        let ProofFixture {
+            state_root,
+            proof,
+            missing_account_proof,
+        } = load_fixture();
+        assert_eq!(proof.nonce, 1.into());
+        assert_eq!(proof.storage_proof.len(), 3);
+        // The last storage slot is unset.
+        assert_eq!(proof.storage_proof[2].value, 0.into());
+        proof.verify(state_root).unwrap();
+
+        assert_eq!(missing_account_proof.storage_hash, EMPTY_TRIE_ROOT);
+        missing_account_proof.verify(state_root).unwrap();
+    }
+
+    #[test]
+    fn tampered_account_proofs_are_rejected() {
+        let ProofFixture {
+            state_root,
+            proof,
+            missing_account_proof,
+        } = load_fixture();
+
+        let err = proof.verify(H256::repeat_byte(1)).unwrap_err();
+        assert_matches!(err, ProofError::HashMismatch { .. });
+
+        let mut tampered = proof.clone();
+        tampered.balance += 1;
+        let err = tampered.verify(state_root).unwrap_err();
+        assert_matches!(err, ProofError::AccountMismatch { field: "balance" });
+
+        let mut tampered = proof.clone();
+        tampered.code_hash = EMPTY_CODE_HASH;
+        let err = tampered.verify(state_root).unwrap_err();
+        assert_matches!(err, ProofError::AccountMismatch { field: "code hash" });
+
+        let mut tampered = proof.clone();
+        tampered.storage_proof[0].value += 1;
+        let err = tampered.verify(state_root).unwrap_err();
+        let expected_key = proof.storage_proof[0].key;
+        assert_matches!(err, ProofError::StorageMismatch { key } if key == expected_key);
+
+        let mut tampered = proof.clone();
+        tampered.storage_proof[2].value = 1.into();
+        let err = tampered.verify(state_root).unwrap_err();
+        assert_matches!(err, ProofError::StorageMismatch { .. });
+
+        let mut tampered = proof.clone();
+        tampered.account_proof[1].0[40] ^= 1;
+        let err = tampered.verify(state_root).unwrap_err();
+        assert_matches!(err, ProofError::HashMismatch { .. });
+
+        // Proof of absence for a non-empty account
+        let mut tampered = missing_account_proof;
+        tampered.balance = 1.into();
+        let err = tampered.verify(state_root).unwrap_err();
+        assert_matches!(err, ProofError::AccountMismatch { field: "balance" });
+    }
+}
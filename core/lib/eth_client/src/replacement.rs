@@ -0,0 +1,243 @@
+//! Replacing and cancelling pending transactions.
+
+use zksync_types::web3::{
+    contract::Options,
+    types::{Address, Transaction, H256, U256},
+};
+
+use crate::{types::Error, BlobTxSidecar, BoundEthInterface};
+
+/// Minimum fee bump (in percent) required by L1 nodes to replace a pending transaction with the same nonce.
+const MIN_FEE_BUMP_PERCENT: u64 = 10;
+/// Minimum fee bump (in percent) required by L1 nodes to replace a pending blob transaction. Geth requires
+/// all fees of blob transactions, including the blob fee, to be doubled.
+const MIN_BLOB_TX_FEE_BUMP_PERCENT: u64 = 100;
+/// Gas limit of a plain ETH transfer used to cancel transactions.
+const TRANSFER_GAS_LIMIT: u64 = 21_000;
+const LEGACY_TX_TYPE: u64 = 0;
+const EIP_4844_TX_TYPE: u64 = 3;
+
+/// Increases `fee` by `percent` percent, rounding up.
+fn bump_fee(fee: U256, percent: u64) -> U256 {
+    let bumped = fee.saturating_mul(U256::from(100 + percent));
+    bumped.saturating_add(U256::from(99)) / 100
+}
+
+/// Fetches the transaction to be replaced and checks that it's still pending.
+async fn get_pending_tx<C: BoundEthInterface + ?Sized>(
+    client: &C,
+    tx_hash: H256,
+    component: &'static str,
+) -> Result<Transaction, Error> {
+    let not_replaceable = |reason| Error::TxNotReplaceable { tx_hash, reason };
+    let Some(tx) = client.get_tx(tx_hash, component).await? else {
+        return Err(not_replaceable("transaction is unknown to the node"));
+    };
+    if tx.block_number.is_some() {
+        return Err(not_replaceable(
+            "transaction is already included into a block",
+        ));
+    }
+    if tx
+        .from
+        .map_or(false, |from| from != client.sender_account())
+    {
+        return Err(not_replaceable("transaction is sent by another account"));
+    }
+    Ok(tx)
+}
+
+/// Returns the recipient of `tx`, which must be retained by its replacement.
+fn replaced_tx_recipient(tx: &Transaction) -> Result<Address, Error> {
+    tx.to.ok_or(Error::TxNotReplaceable {
+        tx_hash: tx.hash,
+        reason: "contract deployments are not supported",
+    })
+}
+
+fn is_blob_tx(tx: &Transaction) -> bool {
+    tx.transaction_type
+        .map_or(false, |tx_type| tx_type.as_u64() == EIP_4844_TX_TYPE)
+}
+
+/// Builds options for the replacement of `tx` with fees bumped by `fee_bump_percent`.
+fn replacement_options(tx: &Transaction, fee_bump_percent: u64) -> Options {
+    let (max_fee_per_gas, max_priority_fee_per_gas) =
+        match (tx.max_fee_per_gas, tx.max_priority_fee_per_gas) {
+            (Some(max_fee), Some(max_priority_fee)) => (max_fee, max_priority_fee),
+            // Legacy transactions pay the gas price both as the base and the priority fee.
+            _ => {
+                let gas_price = tx.gas_price.unwrap_or_default();
+                (gas_price, gas_price)
+            }
+        };
+    let is_legacy = tx
+        .transaction_type
+        .map_or(true, |tx_type| tx_type.as_u64() == LEGACY_TX_TYPE);
+    Options {
+        nonce: Some(tx.nonce),
+        gas: Some(tx.gas),
+        value: Some(tx.value),
+        gas_price: if is_legacy {
+            tx.gas_price.map(|price| bump_fee(price, fee_bump_percent))
+        } else {
+            None
+        },
+        max_fee_per_gas: Some(bump_fee(max_fee_per_gas, fee_bump_percent)),
+        max_priority_fee_per_gas: Some(bump_fee(max_priority_fee_per_gas, fee_bump_percent)),
+        ..Options::default()
+    }
+}
+
+/// Implementation of [`BoundEthInterface::replace_transaction()`].
+pub(crate) async fn replace_transaction<C: BoundEthInterface + ?Sized>(
+    client: &C,
+    original_hash: H256,
+    fee_bump_percent: u64,
+    component: &'static str,
+) -> Result<H256, Error> {
+    let tx = get_pending_tx(client, original_hash, component).await?;
+    if is_blob_tx(&tx) {
+        return Err(Error::TxNotReplaceable {
+            tx_hash: original_hash,
+            reason: "L1 nodes don't return blob sidecars; blob transactions must be replaced \
+                     with `replace_blob_transaction()`",
+        });
+    }
+    let to = replaced_tx_recipient(&tx)?;
+
+    let fee_bump_percent = fee_bump_percent.max(MIN_FEE_BUMP_PERCENT);
+    let options = replacement_options(&tx, fee_bump_percent);
+    let signed_tx = client
+        .sign_prepared_tx_for_addr(tx.input.0, to, options, component)
+        .await?;
+    let new_hash = client.send_raw_tx(signed_tx.raw_tx).await?;
+    tracing::info!(
+        "Replaced transaction {original_hash:?} with nonce {} by {new_hash:?} with fees bumped by {fee_bump_percent}%: \
+         max fee per gas {}, max priority fee per gas {}",
+        signed_tx.nonce,
+        signed_tx.max_fee_per_gas,
+        signed_tx.max_priority_fee_per_gas
+    );
+    Ok(new_hash)
+}
+
+/// Implementation of [`BoundEthInterface::replace_blob_transaction()`].
+pub(crate) async fn replace_blob_transaction<C: BoundEthInterface + ?Sized>(
+    client: &C,
+    original_hash: H256,
+    fee_bump_percent: u64,
+    original_max_fee_per_blob_gas: U256,
+    sidecar: BlobTxSidecar,
+    component: &'static str,
+) -> Result<H256, Error> {
+    let tx = get_pending_tx(client, original_hash, component).await?;
+    let to = replaced_tx_recipient(&tx)?;
+    let fee_bump_percent = fee_bump_percent.max(MIN_BLOB_TX_FEE_BUMP_PERCENT);
+    let options = replacement_options(&tx, fee_bump_percent);
+    let max_fee_per_blob_gas = bump_fee(original_max_fee_per_blob_gas, fee_bump_percent);
+    let signed_tx = client
+        .sign_prepared_blob_tx_for_addr(
+            tx.input.0,
+            to,
+            options,
+            max_fee_per_blob_gas,
+            sidecar,
+            component,
+        )
+        .await?;
+    let new_hash = client.send_raw_tx(signed_tx.raw_tx).await?;
+    tracing::info!(
+        "Replaced blob transaction {original_hash:?} with nonce {} by {new_hash:?} with fees bumped by {fee_bump_percent}%: \
+         max fee per gas {}, max priority fee per gas {}, max fee per blob gas {max_fee_per_blob_gas}",
+        signed_tx.nonce,
+        signed_tx.max_fee_per_gas,
+        signed_tx.max_priority_fee_per_gas
+    );
+    Ok(new_hash)
+}
+
+/// Implementation of [`BoundEthInterface::cancel_transaction()`].
+pub(crate) async fn cancel_transaction<C: BoundEthInterface + ?Sized>(
+    client: &C,
+    nonce: U256,
+    fee_bump_percent: u64,
+    component: &'static str,
+) -> Result<H256, Error> {
+    if nonce < client.current_nonce(component).await? {
+        return Err(Error::NonceAlreadyUsed(nonce));
+    }
+    let sender = client.sender_account();
+    let fee_bump_percent = fee_bump_percent.max(MIN_FEE_BUMP_PERCENT);
+
+    // Network fees may have grown since the original transaction was sent; pay at least the current fees,
+    // so that the cancellation is actually included.
+    let base_fee = client.get_pending_block_base_fee_per_gas(component).await?;
+    let gas_price = client.get_gas_price(component).await?;
+    // `eth_gasPrice` returns the base fee plus the suggested priority fee for EIP-1559 networks.
+    let network_priority_fee_per_gas = gas_price.saturating_sub(base_fee);
+    let network_max_fee_per_gas = base_fee
+        .saturating_mul(2.into())
+        .saturating_add(network_priority_fee_per_gas);
+
+    let original_hash = client.pending_tx_hash(sender, nonce, component).await?;
+    let mut options = if let Some(original_hash) = original_hash {
+        let tx = get_pending_tx(client, original_hash, component).await?;
+        if is_blob_tx(&tx) {
+            return Err(Error::TxNotReplaceable {
+                tx_hash: original_hash,
+                reason: "blob transactions cannot be cancelled by a non-blob transaction",
+            });
+        }
+        replacement_options(&tx, fee_bump_percent)
+    } else {
+        // The node doesn't know the original transaction (or doesn't expose its mempool), so its fees
+        // are unknown. Bump the network fees, which should be enough to replace a transaction sent earlier.
+        tracing::info!(
+            "No pending transaction with nonce {nonce} is found in the mempool; cancelling it based on network fees"
+        );
+        Options {
+            max_fee_per_gas: Some(bump_fee(network_max_fee_per_gas, fee_bump_percent)),
+            max_priority_fee_per_gas: Some(bump_fee(
+                network_priority_fee_per_gas,
+                fee_bump_percent,
+            )),
+            ..Options::default()
+        }
+    };
+    options.gas_price = options.gas_price.map(|price| price.max(gas_price));
+    options.max_fee_per_gas = options
+        .max_fee_per_gas
+        .map(|fee| fee.max(network_max_fee_per_gas));
+    options.max_priority_fee_per_gas = options
+        .max_priority_fee_per_gas
+        .map(|fee| fee.max(network_priority_fee_per_gas));
+    options.nonce = Some(nonce);
+    options.gas = Some(TRANSFER_GAS_LIMIT.into());
+    options.value = Some(U256::zero());
+
+    let signed_tx = client
+        .sign_prepared_tx_for_addr(vec![], sender, options, component)
+        .await?;
+    let new_hash = client.send_raw_tx(signed_tx.raw_tx).await?;
+    tracing::info!(
+        "Sent self-transfer {new_hash:?} cancelling transaction {original_hash:?} with nonce {nonce}: \
+         max fee per gas {}, max priority fee per gas {}",
+        signed_tx.max_fee_per_gas,
+        signed_tx.max_priority_fee_per_gas
+    );
+    Ok(new_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bumping_fees() {
+        assert_eq!(bump_fee(100.into(), 10), 110.into());
+        assert_eq!(bump_fee(10.into(), 15), 12.into());
+        assert_eq!(bump_fee(7.into(), 100), 14.into());
+        assert_eq!(bump_fee(0.into(), 10), 0.into());
+    }
+}
@@ -0,0 +1,318 @@
+//! Decoding revert reasons of failed calls.
+
+use std::fmt;
+
+use serde_json::Value;
+use zksync_types::{
+    web3::ethabi::{self, ParamType, Token},
+    U256,
+};
+
+/// Selector of the `Error(string)` error emitted by `require(condition, "reason")` and `revert("reason")`.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of the `Panic(uint256)` error emitted by failed assertions, arithmetic overflows etc.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decoded reason of a reverted call.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RevertReason {
+    /// `Error(string)` revert, e.g. from `require(condition, "reason")`.
+    Error(String),
+    /// `Panic(uint256)` revert, e.g. caused by a failed assertion or an arithmetic overflow.
+    Panic(U256),
+    /// Custom error resolved using the contract ABI; see [`Self::resolve()`].
+    Custom { name: String, args: Vec<Token> },
+    /// Custom error with an unknown signature.
+    Unknown { selector: [u8; 4], data: Vec<u8> },
+    /// Revert data too short to contain an error selector.
+    Malformed(Vec<u8>),
+    /// Revert without data, e.g. from `require(condition)` or `revert()`.
+    Empty,
+}
+
+impl RevertReason {
+    /// Decodes revert data returned by the node. Custom errors are decoded as [`Self::Unknown`].
+    pub fn decode(data: &[u8]) -> Self {
+        if data.is_empty() {
+            return Self::Empty;
+        }
+        if data.len() < 4 {
+            return Self::Malformed(data.to_vec());
+        }
+        let (selector, args) = data.split_at(4);
+        let selector: [u8; 4] = selector.try_into().unwrap();
+        let decoded = match selector {
+            ERROR_SELECTOR => match ethabi::decode(&[ParamType::String], args).as_deref() {
+                Ok([Token::String(message)]) => Some(Self::Error(message.clone())),
+                _ => None,
+            },
+            PANIC_SELECTOR => match ethabi::decode(&[ParamType::Uint(256)], args).as_deref() {
+                Ok([Token::Uint(code)]) => Some(Self::Panic(*code)),
+                _ => None,
+            },
+            _ => None,
+        };
+        decoded.unwrap_or_else(|| Self::Unknown {
+            selector,
+            data: args.to_vec(),
+        })
+    }
+
+    /// Resolves an unknown custom error using errors defined in the `contract` ABI. If the error is not defined
+    /// in the ABI or its arguments cannot be decoded, returns the reason unchanged.
+    pub fn resolve(self, contract: &ethabi::Contract) -> Self {
+        let Self::Unknown { selector, data } = &self else {
+            return self;
+        };
+        for error in contract.errors.values().flatten() {
+            let param_types: Vec<_> = error
+                .inputs
+                .iter()
+                .map(|param| param.kind.clone())
+                .collect();
+            if ethabi::short_signature(&error.name, &param_types) != *selector {
+                continue;
+            }
+            if let Ok(args) = ethabi::decode(&param_types, data) {
+                return Self::Custom {
+                    name: error.name.clone(),
+                    args,
+                };
+            }
+        }
+        self
+    }
+
+    /// Returns a human-readable description of a `Panic(uint256)` code as documented by Solidity.
+    pub fn panic_description(code: U256) -> Option<&'static str> {
+        if code > U256::from(u8::MAX) {
+            return None;
+        }
+        Some(match code.as_u32() {
+            0x00 => "generic compiler panic",
+            0x01 => "assertion failed",
+            0x11 => "arithmetic overflow or underflow",
+            0x12 => "division or modulo by zero",
+            0x21 => "invalid enum value",
+            0x22 => "incorrectly encoded storage byte array",
+            0x31 => "pop() on an empty array",
+            0x32 => "array index out of bounds",
+            0x41 => "too much memory allocated",
+            0x51 => "call to a zero-initialized internal function",
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error(message) => formatter.write_str(message),
+            Self::Panic(code) => {
+                write!(formatter, "Panic({code:#x})")?;
+                if let Some(description) = Self::panic_description(*code) {
+                    write!(formatter, ": {description}")?;
+                }
+                Ok(())
+            }
+            Self::Custom { name, args } => {
+                write!(formatter, "{name}(")?;
+                fmt_tokens(args, formatter)?;
+                formatter.write_str(")")
+            }
+            Self::Unknown { selector, data } => {
+                write!(formatter, "custom error 0x{}", to_hex(selector))?;
+                if !data.is_empty() {
+                    write!(formatter, " with data 0x{}", to_hex(data))?;
+                }
+                Ok(())
+            }
+            Self::Malformed(data) => write!(formatter, "malformed revert data 0x{}", to_hex(data)),
+            Self::Empty => formatter.write_str("no revert data"),
+        }
+    }
+}
+
+fn fmt_tokens(tokens: &[Token], formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 {
+            formatter.write_str(", ")?;
+        }
+        match token {
+            Token::Address(address) => write!(formatter, "{address:?}")?,
+            Token::Uint(value) => write!(formatter, "{value}")?,
+            // Signed integers are formatted in the two's complement form.
+            Token::Int(value) => write!(formatter, "{value:#x}")?,
+            Token::Bool(value) => write!(formatter, "{value}")?,
+            Token::String(value) => write!(formatter, "{value:?}")?,
+            Token::Bytes(bytes) | Token::FixedBytes(bytes) => {
+                write!(formatter, "0x{}", to_hex(bytes))?;
+            }
+            Token::Array(tokens) | Token::FixedArray(tokens) => {
+                formatter.write_str("[")?;
+                fmt_tokens(tokens, formatter)?;
+                formatter.write_str("]")?;
+            }
+            Token::Tuple(tokens) => {
+                formatter.write_str("(")?;
+                fmt_tokens(tokens, formatter)?;
+                formatter.write_str(")")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Extracts revert data from the `data` field of a JSON-RPC error. Supports plain hex strings (Geth, Erigon),
+/// Nethermind-style `Reverted 0x...` strings and objects with a nested `data` field (e.g., Hardhat).
+/// Returns `None` if the field doesn't contain hex data.
+pub(crate) fn parse_revert_data(data: &Value) -> Option<Vec<u8>> {
+    let data = match data {
+        Value::String(data) => data.as_str(),
+        Value::Object(object) => object.get("data")?.as_str()?,
+        _ => return None,
+    };
+    let data = data.strip_prefix("Reverted ").unwrap_or(data);
+    let hex = data.strip_prefix("0x")?;
+    if hex.len() % 2 != 0 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::json;
+
+    use super::*;
+
+    fn encode_error(selector: [u8; 4], args: &[Token]) -> Vec<u8> {
+        let mut data = selector.to_vec();
+        data.extend_from_slice(&ethabi::encode(args));
+        data
+    }
+
+    #[test]
+    fn decoding_error_string() {
+        let data = encode_error(
+            ERROR_SELECTOR,
+            &[Token::String("insufficient balance".to_owned())],
+        );
+        let reason = RevertReason::decode(&data);
+        assert_eq!(
+            reason,
+            RevertReason::Error("insufficient balance".to_owned())
+        );
+        assert_eq!(reason.to_string(), "insufficient balance");
+
+        // Truncated data is treated as an unknown error.
+        let reason = RevertReason::decode(&data[..40]);
+        assert_matches!(
+            reason,
+            RevertReason::Unknown {
+                selector: ERROR_SELECTOR,
+                ..
+            }
+        );
+    }
+
+    #[test]
+    fn decoding_panic() {
+        let data = encode_error(PANIC_SELECTOR, &[Token::Uint(0x11.into())]);
+        let reason = RevertReason::decode(&data);
+        assert_eq!(reason, RevertReason::Panic(0x11.into()));
+        assert_eq!(
+            reason.to_string(),
+            "Panic(0x11): arithmetic overflow or underflow"
+        );
+
+        let data = encode_error(PANIC_SELECTOR, &[Token::Uint(0x99.into())]);
+        assert_eq!(RevertReason::decode(&data).to_string(), "Panic(0x99)");
+    }
+
+    #[test]
+    fn decoding_custom_errors() {
+        let abi = r#"[
+            { "type": "error", "name": "InsufficientFunds", "inputs": [] },
+            {
+                "type": "error",
+                "name": "Unauthorized",
+                "inputs": [{ "name": "caller", "type": "address" }]
+            }
+        ]"#;
+        let contract = ethabi::Contract::load(abi.as_bytes()).unwrap();
+
+        let selector = ethabi::short_signature("InsufficientFunds", &[]);
+        let reason = RevertReason::decode(&selector);
+        assert_eq!(
+            reason,
+            RevertReason::Unknown {
+                selector,
+                data: vec![]
+            }
+        );
+        assert_eq!(
+            reason.to_string(),
+            format!("custom error 0x{}", to_hex(&selector))
+        );
+        let reason = reason.resolve(&contract);
+        assert_eq!(reason.to_string(), "InsufficientFunds()");
+
+        let caller = zksync_types::Address::repeat_byte(0x11);
+        let selector = ethabi::short_signature("Unauthorized", &[ParamType::Address]);
+        let data = encode_error(selector, &[Token::Address(caller)]);
+        let reason = RevertReason::decode(&data).resolve(&contract);
+        assert_eq!(
+            reason,
+            RevertReason::Custom {
+                name: "Unauthorized".to_owned(),
+                args: vec![Token::Address(caller)],
+            }
+        );
+        assert_eq!(reason.to_string(), format!("Unauthorized({caller:?})"));
+
+        // Errors not defined in the ABI are left as is.
+        let data = [0xde, 0xad, 0xbe, 0xef, 0x01];
+        let reason = RevertReason::decode(&data).resolve(&contract);
+        assert_eq!(reason.to_string(), "custom error 0xdeadbeef with data 0x01");
+    }
+
+    #[test]
+    fn decoding_missing_or_malformed_data() {
+        assert_eq!(RevertReason::decode(&[]), RevertReason::Empty);
+        assert_eq!(RevertReason::decode(&[]).to_string(), "no revert data");
+        assert_eq!(
+            RevertReason::decode(&[1, 2]),
+            RevertReason::Malformed(vec![1, 2])
+        );
+    }
+
+    #[test]
+    fn parsing_revert_data() {
+        assert_eq!(parse_revert_data(&json!("0x")), Some(vec![]));
+        assert_eq!(
+            parse_revert_data(&json!("0xdeadbeef")),
+            Some(vec![0xde, 0xad, 0xbe, 0xef])
+        );
+        assert_eq!(
+            parse_revert_data(&json!("Reverted 0x0102")),
+            Some(vec![1, 2])
+        );
+        assert_eq!(
+            parse_revert_data(&json!({ "message": "reverted", "data": "0x01" })),
+            Some(vec![1])
+        );
+        assert_eq!(parse_revert_data(&json!("Out of gas")), None);
+        assert_eq!(parse_revert_data(&json!("0x123")), None);
+        assert_eq!(parse_revert_data(&json!("0xzz")), None);
+        assert_eq!(parse_revert_data(&json!(42)), None);
+    }
+}
@@ -0,0 +1,97 @@
+//! Waiting for transaction confirmations, taking L1 reorgs into account.
+
+use std::time::Duration;
+
+use futures::StreamExt as _;
+use zksync_types::web3::types::{BlockId, BlockNumber, TransactionReceipt, H256};
+
+use crate::{clients::NewHeadsStream, types::Error, EthInterface};
+
+/// Interval between checks of the transaction status in [`EthInterface::wait_for_tx()`].
+pub(crate) const TX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Outcome of waiting for a transaction using [`EthInterface::wait_for_tx()`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TxWaitOutcome {
+    /// Transaction is included into a canonical block and has the requested number of confirmations.
+    Confirmed(TransactionReceipt),
+    /// Transaction is unknown to the node, e.g. because it was evicted from the mempool or dropped after a reorg.
+    Dropped,
+    /// Transaction hasn't received the requested number of confirmations before the timeout.
+    TimedOut,
+}
+
+/// Event triggering a check of the transaction status.
+#[derive(Debug)]
+pub(crate) enum WaitTrigger {
+    Poll(Duration),
+    NewHeads(NewHeadsStream),
+}
+
+impl WaitTrigger {
+    async fn wait(&mut self) {
+        match self {
+            Self::Poll(interval) => tokio::time::sleep(*interval).await,
+            Self::NewHeads(heads) => {
+                if heads.next().await.is_none() {
+                    tracing::debug!("New L1 heads stream has ended; falling back to polling");
+                    *self = Self::Poll(TX_POLL_INTERVAL);
+                }
+            }
+        }
+    }
+}
+
+/// Implementation of [`EthInterface::wait_for_tx()`] checking the transaction status on each `trigger` event.
+pub(crate) async fn wait_for_tx<C: EthInterface + ?Sized>(
+    client: &C,
+    tx_hash: H256,
+    confirmations: u64,
+    timeout: Duration,
+    mut trigger: WaitTrigger,
+    component: &'static str,
+) -> Result<TxWaitOutcome, Error> {
+    let wait = async {
+        loop {
+            if let Some(outcome) = check_tx(client, tx_hash, confirmations, component).await? {
+                return Ok(outcome);
+            }
+            trigger.wait().await;
+        }
+    };
+    tokio::time::timeout(timeout, wait)
+        .await
+        .unwrap_or(Ok(TxWaitOutcome::TimedOut))
+}
+
+/// Returns `None` if the transaction is pending or doesn't have enough confirmations yet.
+async fn check_tx<C: EthInterface + ?Sized>(
+    client: &C,
+    tx_hash: H256,
+    confirmations: u64,
+    component: &'static str,
+) -> Result<Option<TxWaitOutcome>, Error> {
+    let Some(receipt) = client.tx_receipt(tx_hash, component).await? else {
+        let tx = client.get_tx(tx_hash, component).await?;
+        return Ok(tx.is_none().then_some(TxWaitOutcome::Dropped));
+    };
+    let (Some(block_number), Some(block_hash)) = (receipt.block_number, receipt.block_hash) else {
+        return Ok(None);
+    };
+
+    let latest_block = client.block_number(component).await?;
+    // The receipt may refer to a block that is no longer canonical, e.g. if it's served from a cache
+    // or if the node hasn't finished processing a reorg.
+    let block_id = BlockId::Number(BlockNumber::Number(block_number));
+    let block = client.block(block_id, component).await?;
+    if block.and_then(|block| block.hash) != Some(block_hash) {
+        tracing::info!(
+            "Block #{block_number} with transaction {tx_hash:?} was reorganized; waiting for the transaction \
+             to be included again"
+        );
+        return Ok(None);
+    }
+
+    let is_confirmed = latest_block.as_u64() >= block_number.as_u64() + confirmations;
+    Ok(is_confirmed.then_some(TxWaitOutcome::Confirmed(receipt)))
+}
@@ -1,14 +1,35 @@
-use zksync_types::web3::{
-    contract::{
-        tokens::{Detokenize, Tokenize},
-        Error as ContractError, Options,
+use std::{collections::BTreeMap, path::PathBuf, time::Duration};
+
+use jsonrpc_core::ErrorCode;
+use rlp::RlpStream;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use vise::EncodeLabelValue;
+use zksync_types::{
+    web3::{
+        self,
+        contract::{
+            tokens::{Detokenize, Tokenize},
+            Error as ContractError, Options,
+        },
+        error::TransportError,
+        ethabi,
+        types::{
+            Address, BlockId, BlockNumber, Bytes, FeeHistory, TransactionReceipt, H256, U256, U64,
+        },
     },
-    ethabi,
-    types::{Address, BlockId, TransactionReceipt, H256, U256},
+    L1ChainId,
+};
+
+use crate::{
+    batch_events::L1BatchEventError,
+    clients::LineaEstimateError,
+    hd_wallet::HdWalletError,
+    revert::{parse_revert_data, RevertReason},
 };
 
 /// Wrapper for `Vec<ethabi::Token>` that doesn't wrap them in an additional array in `Tokenize` implementation.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct RawTokens(pub Vec<ethabi::Token>);
 
 impl Tokenize for RawTokens {
@@ -24,7 +45,7 @@ impl Detokenize for RawTokens {
 }
 
 /// Arguments for calling a function in an unspecified Ethereum smart contract.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CallFunctionArgs {
     pub(crate) name: String,
     pub(crate) from: Option<Address>,
@@ -69,13 +90,300 @@ impl CallFunctionArgs {
 
 /// Information sufficient for calling a function in a specific Ethereum smart contract. Instantiated
 /// using [`CallFunctionArgs::for_contract()`].
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ContractCall {
     pub(crate) contract_address: Address,
     pub(crate) contract_abi: ethabi::Contract,
     pub(crate) inner: CallFunctionArgs,
 }
 
+/// State overrides for an `eth_call`, keyed by the account address. Allows simulating calls against a modified
+/// state, e.g. with a funded sender or with the contract code replaced.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct CallOverrides(BTreeMap<Address, AccountOverride>);
+
+impl CallOverrides {
+    /// Adds an override for the specified account, replacing the previous override for it (if any).
+    pub fn with_account(mut self, address: Address, account: AccountOverride) -> Self {
+        self.0.insert(address, account);
+        self
+    }
+
+    /// Returns the override for the specified account.
+    pub fn account(&self, address: Address) -> Option<&AccountOverride> {
+        self.0.get(&address)
+    }
+}
+
+/// Override of a single account state in [`CallOverrides`]. Fields set to `None` are not overridden.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Replaces the entire account storage. Cannot be used together with `state_diff`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<BTreeMap<H256, H256>>,
+    /// Replaces the specified storage slots, leaving other slots intact.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+/// Maximum number of blobs that can be attached to a single EIP-4844 transaction.
+pub const MAX_BLOBS_PER_TX: usize = 6;
+/// Size of a single blob in bytes.
+pub const BYTES_PER_BLOB: usize = 131_072;
+/// Size of a KZG commitment in bytes.
+pub const BYTES_PER_COMMITMENT: usize = 48;
+/// Size of a KZG proof in bytes.
+pub const BYTES_PER_PROOF: usize = 48;
+/// Version byte of blob versioned hashes produced from KZG commitments.
+const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+/// Type of EIP-4844 transactions.
+pub(crate) const EIP_4844_TX_TYPE: u8 = 3;
+
+/// Blob gas consumed by a single blob.
+pub const GAS_PER_BLOB: u64 = 1 << 17;
+/// Target blob gas consumed by a block; blob base fee grows if blocks consume more and decreases otherwise.
+pub const TARGET_BLOB_GAS_PER_BLOCK: u64 = 3 * GAS_PER_BLOB;
+/// Maximum blob gas that can be consumed by a block.
+pub const MAX_BLOB_GAS_PER_BLOCK: u64 = 6 * GAS_PER_BLOB;
+const MIN_BLOB_BASE_FEE: u64 = 1;
+const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3_338_477;
+
+/// Assumed blob gas usage of a future L1 block used to predict blob base fees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobGasUsage {
+    /// Block doesn't contain blobs.
+    Empty,
+    /// Block consumes [`TARGET_BLOB_GAS_PER_BLOCK`], so the blob base fee doesn't change.
+    Target,
+    /// Block consumes [`MAX_BLOB_GAS_PER_BLOCK`], so the blob base fee increases by ~12.5%.
+    Full,
+}
+
+impl BlobGasUsage {
+    fn blob_gas_used(self) -> u64 {
+        match self {
+            Self::Empty => 0,
+            Self::Target => TARGET_BLOB_GAS_PER_BLOCK,
+            Self::Full => MAX_BLOB_GAS_PER_BLOCK,
+        }
+    }
+}
+
+/// Blob gas accounting of an L1 block introduced in EIP-4844.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockBlobGas {
+    /// Total blob gas consumed by transactions in the block.
+    pub blob_gas_used: u64,
+    /// Running total of blob gas consumed in excess of the target prior to the block.
+    pub excess_blob_gas: u64,
+    /// Price of blob gas in the block (in wei).
+    pub blob_base_fee: U256,
+}
+
+impl BlockBlobGas {
+    /// Creates blob gas info for a block, computing the blob base fee from `excess_blob_gas`.
+    pub fn new(blob_gas_used: u64, excess_blob_gas: u64) -> Self {
+        Self {
+            blob_gas_used,
+            excess_blob_gas,
+            blob_base_fee: Self::blob_base_fee_for_excess(excess_blob_gas),
+        }
+    }
+
+    /// Computes excess blob gas of the next block.
+    pub fn next_excess_blob_gas(&self) -> u64 {
+        self.excess_blob_gas
+            .saturating_add(self.blob_gas_used)
+            .saturating_sub(TARGET_BLOB_GAS_PER_BLOCK)
+    }
+
+    /// Computes blob gas accounting of the next block, assuming it has the specified blob gas `usage`.
+    pub fn next_block(&self, usage: BlobGasUsage) -> Self {
+        Self::new(usage.blob_gas_used(), self.next_excess_blob_gas())
+    }
+
+    /// Computes the blob base fee of the next block. Unlike the base fee for execution gas, it is fully
+    /// determined by the current block.
+    pub fn next_blob_base_fee(&self) -> U256 {
+        Self::blob_base_fee_for_excess(self.next_excess_blob_gas())
+    }
+
+    /// Predicts the blob base fee of the block after the next one, assuming that the next block
+    /// has the specified blob gas `usage`. [`BlobGasUsage::Full`] provides an upper bound for the fee.
+    pub fn predicted_blob_base_fee(&self, usage: BlobGasUsage) -> U256 {
+        self.next_block(usage).next_blob_base_fee()
+    }
+
+    /// Computes the blob base fee for the specified excess blob gas as defined in EIP-4844, i.e.
+    /// `MIN_BLOB_BASE_FEE * e ** (excess_blob_gas / BLOB_BASE_FEE_UPDATE_FRACTION)` approximated
+    /// using integer arithmetic.
+    pub fn blob_base_fee_for_excess(excess_blob_gas: u64) -> U256 {
+        fake_exponential(
+            MIN_BLOB_BASE_FEE.into(),
+            excess_blob_gas.into(),
+            BLOB_BASE_FEE_UPDATE_FRACTION.into(),
+        )
+    }
+}
+
+/// Approximates `factor * e ** (numerator / denominator)` using Taylor expansion as defined in EIP-4844.
+/// Saturates at `U256::MAX` if the computation overflows, which only happens for excess blob gas values
+/// far beyond anything reachable on a real chain.
+fn fake_exponential(factor: U256, numerator: U256, denominator: U256) -> U256 {
+    fn compute(factor: U256, numerator: U256, denominator: U256) -> Option<U256> {
+        let mut output = U256::zero();
+        let mut numerator_accum = factor.checked_mul(denominator)?;
+        let mut i = U256::one();
+        while !numerator_accum.is_zero() {
+            output = output.checked_add(numerator_accum)?;
+            numerator_accum =
+                numerator_accum.checked_mul(numerator)? / denominator.checked_mul(i)?;
+            i += U256::one();
+        }
+        Some(output / denominator)
+    }
+
+    compute(factor, numerator, denominator).unwrap_or(U256::MAX)
+}
+
+/// Computes the versioned hash of a blob from its KZG commitment as defined in EIP-4844, i.e.
+/// `VERSIONED_HASH_VERSION_KZG || sha256(commitment)[1..]`.
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> H256 {
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    H256(hash)
+}
+
+/// Errors that can occur when validating a [`BlobTxSidecar`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BlobSidecarError {
+    #[error("blob transaction must carry at least one blob")]
+    NoBlobs,
+    #[error("too many blobs: {0}, at most {MAX_BLOBS_PER_TX} are allowed per transaction")]
+    TooManyBlobs(usize),
+    #[error("sidecar has {blobs} blobs, {commitments} commitments and {proofs} proofs")]
+    LengthMismatch {
+        blobs: usize,
+        commitments: usize,
+        proofs: usize,
+    },
+    #[error("blob #{index} has {len} bytes, expected {BYTES_PER_BLOB}")]
+    InvalidBlobSize { index: usize, len: usize },
+    #[error("commitment #{index} has {len} bytes, expected {BYTES_PER_COMMITMENT}")]
+    InvalidCommitmentSize { index: usize, len: usize },
+    #[error("proof #{index} has {len} bytes, expected {BYTES_PER_PROOF}")]
+    InvalidProofSize { index: usize, len: usize },
+    #[error(
+        "versioned hashes {actual:?} do not match sidecar commitments (expected {expected:?})"
+    )]
+    VersionedHashMismatch {
+        expected: Vec<H256>,
+        actual: Vec<H256>,
+    },
+}
+
+/// Blobs with their KZG commitments and proofs attached to an EIP-4844 transaction.
+/// The `i`-th commitment and proof correspond to the `i`-th blob.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobTxSidecar {
+    pub blobs: Vec<Vec<u8>>,
+    pub commitments: Vec<Vec<u8>>,
+    pub proofs: Vec<Vec<u8>>,
+}
+
+impl BlobTxSidecar {
+    /// Checks that the sidecar is well-formed and can be attached to a transaction.
+    pub fn validate(&self) -> Result<(), BlobSidecarError> {
+        let blob_count = self.blobs.len();
+        if blob_count == 0 {
+            return Err(BlobSidecarError::NoBlobs);
+        }
+        if blob_count > MAX_BLOBS_PER_TX {
+            return Err(BlobSidecarError::TooManyBlobs(blob_count));
+        }
+        if self.commitments.len() != blob_count || self.proofs.len() != blob_count {
+            return Err(BlobSidecarError::LengthMismatch {
+                blobs: blob_count,
+                commitments: self.commitments.len(),
+                proofs: self.proofs.len(),
+            });
+        }
+
+        for (index, blob) in self.blobs.iter().enumerate() {
+            if blob.len() != BYTES_PER_BLOB {
+                return Err(BlobSidecarError::InvalidBlobSize {
+                    index,
+                    len: blob.len(),
+                });
+            }
+        }
+        for (index, commitment) in self.commitments.iter().enumerate() {
+            if commitment.len() != BYTES_PER_COMMITMENT {
+                return Err(BlobSidecarError::InvalidCommitmentSize {
+                    index,
+                    len: commitment.len(),
+                });
+            }
+        }
+        for (index, proof) in self.proofs.iter().enumerate() {
+            if proof.len() != BYTES_PER_PROOF {
+                return Err(BlobSidecarError::InvalidProofSize {
+                    index,
+                    len: proof.len(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Computes versioned hashes of the blobs using [`kzg_to_versioned_hash()`].
+    pub fn versioned_hashes(&self) -> Vec<H256> {
+        self.commitments
+            .iter()
+            .map(|commitment| kzg_to_versioned_hash(commitment))
+            .collect()
+    }
+
+    /// Checks that the provided versioned hashes correspond to the sidecar commitments.
+    pub fn verify_versioned_hashes(
+        &self,
+        versioned_hashes: &[H256],
+    ) -> Result<(), BlobSidecarError> {
+        let expected = self.versioned_hashes();
+        if expected != versioned_hashes {
+            return Err(BlobSidecarError::VersionedHashMismatch {
+                expected,
+                actual: versioned_hashes.to_vec(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Wraps a signed EIP-4844 transaction into its network encoding, i.e.
+    /// `0x03 || rlp([tx_payload_body, blobs, commitments, proofs])`.
+    pub(crate) fn encode_with_signed_tx(&self, signed_tx: &[u8]) -> Vec<u8> {
+        assert_eq!(signed_tx.first(), Some(&EIP_4844_TX_TYPE));
+
+        let mut stream = RlpStream::new_list(4);
+        stream.append_raw(&signed_tx[1..], 1);
+        for items in [&self.blobs, &self.commitments, &self.proofs] {
+            stream.begin_list(items.len());
+            for item in items {
+                stream.append(item);
+            }
+        }
+        [&[EIP_4844_TX_TYPE], stream.as_raw()].concat()
+    }
+}
+
 /// Common error type exposed by the crate,
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -94,6 +402,357 @@ pub enum Error {
     /// Incorrect fee provided for a transaction.
     #[error("Max fee {0} less than priority fee {1}")]
     WrongFeeProvided(U256, U256),
+    /// Invalid blob sidecar provided for an EIP-4844 transaction.
+    #[error("Invalid blob sidecar: {0}")]
+    BlobSidecar(#[from] BlobSidecarError),
+    /// RPC method is not supported by the Ethereum node.
+    #[error("Method `{0}` is not supported by the Ethereum node")]
+    UnsupportedMethod(&'static str),
+    /// Block tag (`safe` or `finalized`) is not supported by the Ethereum node, e.g. because the node
+    /// predates the Merge.
+    #[error("Block tag `{0}` is not supported by the Ethereum node")]
+    UnsupportedBlockTag(&'static str),
+    /// Signer doesn't manage the account the client is configured for.
+    #[error("Signer address mismatch: expected {expected:?}, got {actual:?}")]
+    SignerAddressMismatch { expected: Address, actual: Address },
+    /// Request to the beacon (consensus layer) node has failed.
+    #[error("Request to beacon node failed: {0}")]
+    BeaconApi(#[from] BeaconApiError),
+    /// Ethereum node has rejected state overrides for an `eth_call`, e.g. because it doesn't support them.
+    #[error("State overrides rejected by the Ethereum node: {0}")]
+    CallOverridesRejected(String),
+    /// Request to the Ethereum node has timed out according to the client-side timeout policy.
+    #[error("Request `{method}` timed out after {timeout:?}")]
+    RequestTimeout {
+        method: &'static str,
+        timeout: Duration,
+    },
+    /// Chain ID reported by the Ethereum node differs from the expected one.
+    #[error("Chain ID mismatch: expected {}, got {}", expected.0, actual.0)]
+    ChainIdMismatch {
+        expected: L1ChainId,
+        actual: L1ChainId,
+    },
+    /// Path to the IPC socket of the Ethereum node is invalid, e.g. the socket doesn't exist.
+    #[error("Invalid IPC socket path `{}`: {reason}", path.display())]
+    InvalidIpcPath { path: PathBuf, reason: String },
+    /// Private key cannot be derived from a mnemonic, e.g. because the mnemonic or the derivation path is invalid.
+    #[error("Deriving private key from mnemonic failed: {0}")]
+    HdWallet(#[from] HdWalletError),
+    /// Transaction cannot be replaced, e.g. because it's already included into a block.
+    #[error("Transaction {tx_hash:?} cannot be replaced: {reason}")]
+    TxNotReplaceable { tx_hash: H256, reason: &'static str },
+    /// Transaction with the specified nonce is already included into a block, so it cannot be cancelled.
+    #[error("Nonce {0} is already used by a transaction included into a block")]
+    NonceAlreadyUsed(U256),
+    /// Event log emitted by the zkSync L1 contract for L1 batches cannot be decoded.
+    #[error("Decoding L1 batch event failed: {0}")]
+    L1BatchEvent(#[from] L1BatchEventError),
+    /// Signer with the specified name is not configured for the client.
+    #[error("Signer `{0}` is not configured")]
+    UnknownSigner(String),
+    /// Preflight simulation of a transaction has reverted, so the transaction was not signed.
+    #[error("Transaction would revert: {reason}")]
+    WouldRevert { reason: RevertReason },
+    /// Estimate returned by `linea_estimateGas` failed sanity checks.
+    #[error("Invalid `linea_estimateGas` estimate: {0}")]
+    InvalidLineaEstimate(#[from] LineaEstimateError),
+    /// Percentile in [`PriorityFeeConfig`] is not a number from 0 to 100.
+    #[error("Invalid priority fee percentile {0}; expected a number from 0 to 100")]
+    InvalidPercentile(f64),
+    /// Client wrapping several Ethereum nodes is created without any nodes.
+    #[error("At least one Ethereum node endpoint is required")]
+    NoEndpoints,
+}
+
+/// Prefix of transport error messages for HTTP requests rate-limited by the node with the `Retry-After` header.
+const RETRY_AFTER_MESSAGE_PREFIX: &str = "rate limited (HTTP 429), retry after ";
+
+/// Creates an error for an HTTP request rate-limited by the node, which has asked to retry after the specified delay.
+pub(crate) fn rate_limited_error(retry_after: Duration) -> web3::Error {
+    let message = format!("{RETRY_AFTER_MESSAGE_PREFIX}{}ms", retry_after.as_millis());
+    web3::Error::Transport(TransportError::Message(message))
+}
+
+fn parse_retry_after(message: &str) -> Option<Duration> {
+    let millis = message
+        .strip_prefix(RETRY_AFTER_MESSAGE_PREFIX)?
+        .strip_suffix("ms")?;
+    Some(Duration::from_millis(millis.parse().ok()?))
+}
+
+/// Classification of [`Error`]s returned by [`Error::kind()`]. Used by retry policies and the failover client
+/// to decide whether an error is transient, and as a metric label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// Request has timed out, either according to the client-side timeout policy or in the HTTP client.
+    Timeout,
+    /// Connection-level error, e.g. the node is unreachable or the connection was reset.
+    Connection,
+    /// Node is rate-limiting requests (HTTP status 429 or a JSON-RPC limit error).
+    RateLimited,
+    /// Node is temporarily unavailable (HTTP status 502, 503 or 504).
+    Unavailable,
+    /// Other unsuccessful HTTP status.
+    HttpStatus,
+    /// JSON-RPC request was rejected as invalid: parse error, invalid request or params, or an unsupported method.
+    RpcInvalidRequest,
+    /// Call or transaction execution failed, e.g. reverted or ran out of gas.
+    RpcExecution,
+    /// Other JSON-RPC errors, e.g. internal node errors or transaction pool errors.
+    RpcServer,
+    /// Node response cannot be decoded.
+    Decode,
+    /// Error not related to communication with the node, e.g. a signing error.
+    Other,
+}
+
+impl ErrorKind {
+    /// Checks whether errors of this kind are transient, i.e. the request may succeed if retried.
+    pub fn is_transient(self) -> bool {
+        matches!(
+            self,
+            Self::Timeout | Self::Connection | Self::RateLimited | Self::Unavailable
+        )
+    }
+
+    fn from_http_status(status: u16) -> Self {
+        match status {
+            429 => Self::RateLimited,
+            502..=504 => Self::Unavailable,
+            _ => Self::HttpStatus,
+        }
+    }
+
+    pub(crate) fn from_web3_error(err: &web3::Error) -> Self {
+        match err {
+            web3::Error::Unreachable => Self::Connection,
+            web3::Error::Transport(TransportError::Code(code)) => Self::from_http_status(*code),
+            // Messages correspond to errors on the HTTP client side, such as timeouts or connection resets.
+            web3::Error::Transport(TransportError::Message(message)) => {
+                if parse_retry_after(message).is_some() {
+                    Self::RateLimited
+                } else if message.contains("timed out") || message.contains("timeout") {
+                    Self::Timeout
+                } else {
+                    Self::Connection
+                }
+            }
+            web3::Error::Io(err) if err.kind() == std::io::ErrorKind::TimedOut => Self::Timeout,
+            web3::Error::Io(_) => Self::Connection,
+            web3::Error::Rpc(err) => Self::from_rpc_error(err),
+            web3::Error::Decoder(_) | web3::Error::InvalidResponse(_) => Self::Decode,
+            _ => Self::Other,
+        }
+    }
+
+    fn from_rpc_error(err: &jsonrpc_core::Error) -> Self {
+        /// JSON-RPC error code used by nodes to signal that a request limit is exceeded.
+        const LIMIT_EXCEEDED_CODE: i64 = -32005;
+        /// JSON-RPC error code used by Geth and compatible nodes for reverted calls with data.
+        const EXECUTION_ERROR_CODE: i64 = 3;
+
+        let message = err.message.to_lowercase();
+        // Infura uses the same error code for rate limiting and for `eth_getLogs` queries returning
+        // too many results; the latter should not be retried as is.
+        if Error::is_logs_limit_message(&message) {
+            return Self::RpcServer;
+        }
+        // Some providers (e.g., Alchemy) mirror the HTTP status code in JSON-RPC errors.
+        if err.code.code() == LIMIT_EXCEEDED_CODE
+            || err.code.code() == 429
+            || message.contains("rate limit")
+            || message.contains("too many requests")
+        {
+            return Self::RateLimited;
+        }
+
+        match err.code {
+            ErrorCode::ParseError
+            | ErrorCode::InvalidRequest
+            | ErrorCode::MethodNotFound
+            | ErrorCode::InvalidParams => Self::RpcInvalidRequest,
+            ErrorCode::ServerError(EXECUTION_ERROR_CODE) => Self::RpcExecution,
+            _ if message.contains("revert")
+                || message.contains("execution")
+                || message.contains("out of gas")
+                || message.contains("outofgas") =>
+            {
+                Self::RpcExecution
+            }
+            _ => Self::RpcServer,
+        }
+    }
+}
+
+impl Error {
+    /// Classifies this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::RequestTimeout { .. } => ErrorKind::Timeout,
+            Self::BeaconApi(err) => err.kind(),
+            Self::EthereumGateway(err) | Self::Contract(ContractError::Api(err)) => {
+                ErrorKind::from_web3_error(err)
+            }
+            Self::Contract(_) | Self::Decode(_) | Self::L1BatchEvent(_) => ErrorKind::Decode,
+            Self::UnsupportedMethod(_)
+            | Self::UnsupportedBlockTag(_)
+            | Self::CallOverridesRejected(_) => ErrorKind::RpcInvalidRequest,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    /// Checks whether the error is transient, i.e. the request may succeed if retried.
+    /// Transient errors are network errors, timeouts, and rate limiting / overload responses.
+    pub fn is_transient(&self) -> bool {
+        self.kind().is_transient()
+    }
+
+    /// Returns the delay after which the request may be retried, as specified by the node in the `Retry-After` header
+    /// of an HTTP 429 response.
+    pub fn retry_after(&self) -> Option<Duration> {
+        let (Self::EthereumGateway(web3::Error::Transport(TransportError::Message(message)))
+        | Self::Contract(ContractError::Api(web3::Error::Transport(TransportError::Message(
+            message,
+        ))))) = self
+        else {
+            return None;
+        };
+        parse_retry_after(message)
+    }
+
+    /// Checks whether the error indicates that an `eth_getLogs` query exceeds limits imposed by the node
+    /// on the queried block range or the number of returned logs. Such queries may succeed if the block range
+    /// is split into smaller ranges.
+    pub fn is_logs_limit_error(&self) -> bool {
+        let Self::EthereumGateway(web3::Error::Rpc(err)) = self else {
+            return false;
+        };
+        Self::is_logs_limit_message(&err.message.to_lowercase())
+    }
+
+    fn is_logs_limit_message(message: &str) -> bool {
+        message.contains("query returned more than") // Infura
+            || message.contains("response size exceeded") // Alchemy
+            || message.contains("block range")
+            || message.contains("range is too large")
+    }
+
+    /// Checks whether the error returned when sending a transaction indicates that its nonce is invalid
+    /// (e.g., already used by another transaction).
+    pub fn is_nonce_error(&self) -> bool {
+        let Self::EthereumGateway(web3::Error::Rpc(err)) = self else {
+            return false;
+        };
+        let message = err.message.to_lowercase();
+        message.contains("nonce too low")
+            || message.contains("nonce too high")
+            || message.contains("invalid nonce")
+            || message.contains("replacement transaction underpriced")
+    }
+
+    /// Checks whether the error returned when sending a transaction indicates that the node already knows
+    /// the transaction, e.g. because it was submitted earlier.
+    pub fn is_already_known_error(&self) -> bool {
+        let Self::EthereumGateway(web3::Error::Rpc(err)) = self else {
+            return false;
+        };
+        let message = err.message.to_lowercase();
+        message.contains("already known")
+            || message.contains("known transaction")
+            || message.contains("already imported")
+    }
+
+    /// Checks whether the error returned when simulating or estimating a call indicates that the call
+    /// has run out of gas. Calls reverted with non-empty revert data (e.g., `Error(string)` or a custom error)
+    /// are never classified as out-of-gas, even if the revert reason mentions gas.
+    pub fn is_out_of_gas_error(&self) -> bool {
+        let (Self::EthereumGateway(web3::Error::Rpc(err))
+        | Self::Contract(ContractError::Api(web3::Error::Rpc(err)))) = self
+        else {
+            return false;
+        };
+        let data = err.data.as_ref();
+        let revert_data = data.and_then(parse_revert_data);
+        if revert_data.map_or(false, |data| data.len() >= 4) {
+            return false;
+        }
+        // Some nodes (e.g., Nethermind) return a generic message with the error details in `data`.
+        let data_message = data.and_then(serde_json::Value::as_str).unwrap_or_default();
+        let message = format!("{} {data_message}", err.message).to_lowercase();
+        message.contains("out of gas") // Geth, Erigon, Reth, Besu
+            || message.contains("outofgas") // Nethermind, Anvil
+            || message.contains("gas required exceeds allowance") // Geth, Erigon
+            || message.contains("intrinsic gas too low")
+    }
+
+    /// Converts an error returned for a request with the `safe` or `finalized` block tag into
+    /// [`Self::UnsupportedBlockTag`] if the error indicates that the node doesn't support the tag.
+    /// Other errors are returned unchanged.
+    pub(crate) fn for_block(self, block: impl Into<BlockId>) -> Self {
+        let tag = match block.into() {
+            BlockId::Number(BlockNumber::Safe) => "safe",
+            BlockId::Number(BlockNumber::Finalized) => "finalized",
+            _ => return self,
+        };
+        let (Self::EthereumGateway(web3::Error::Rpc(err))
+        | Self::Contract(ContractError::Api(web3::Error::Rpc(err)))) = &self
+        else {
+            return self;
+        };
+        let message = err.message.to_lowercase();
+        // Nodes not aware of the tag fail to parse the block param; nodes aware of it, but not tracking
+        // finality (e.g., before the Merge) report that the block is not found.
+        let is_unsupported = err.code == ErrorCode::InvalidParams
+            || message.contains(tag) // e.g., "finalized block not found" (Geth)
+            || message.contains("unknown block");
+        if is_unsupported {
+            Self::UnsupportedBlockTag(tag)
+        } else {
+            self
+        }
+    }
+
+    /// Decodes the revert reason if the error was returned for a reverted call (e.g., by `eth_call`
+    /// or `eth_estimateGas`). Returns [`RevertReason::Empty`] if the call was reverted without data.
+    /// Custom errors are returned as [`RevertReason::Unknown`]; use [`RevertReason::resolve()`] to decode them
+    /// using the contract ABI.
+    pub fn revert_reason(&self) -> Option<RevertReason> {
+        let (Self::EthereumGateway(web3::Error::Rpc(err))
+        | Self::Contract(ContractError::Api(web3::Error::Rpc(err)))) = self
+        else {
+            return None;
+        };
+        if let Some(data) = err.data.as_ref().and_then(parse_revert_data) {
+            return Some(RevertReason::decode(&data));
+        }
+        let is_revert = err.message.to_lowercase().contains("revert");
+        is_revert.then_some(RevertReason::Empty)
+    }
+}
+
+/// Errors returned by the beacon node REST API.
+#[derive(Debug, thiserror::Error)]
+pub enum BeaconApiError {
+    /// Sending the request or receiving the response has failed, e.g. due to a network error.
+    #[error("HTTP request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    /// Beacon node has responded with an error status.
+    #[error("beacon node responded with status {status}: {message}")]
+    Status { status: u16, message: String },
+}
+
+impl BeaconApiError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Request(err) if err.is_timeout() => ErrorKind::Timeout,
+            Self::Request(err) if err.is_connect() => ErrorKind::Connection,
+            Self::Request(err) if err.is_decode() => ErrorKind::Decode,
+            Self::Request(_) => ErrorKind::Other,
+            Self::Status { status, .. } => ErrorKind::from_http_status(*status),
+        }
+    }
 }
 
 /// Raw transaction bytes.
@@ -160,8 +819,56 @@ pub struct FailureInfo {
     pub gas_limit: U256,
 }
 
+/// Parameters for suggesting a priority fee based on the fee history of the latest blocks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriorityFeeConfig {
+    /// Number of the latest blocks to take into account.
+    pub block_count: usize,
+    /// Percentile of priority fees paid in each block, from 0 to 100.
+    pub percentile: f64,
+}
+
+impl Default for PriorityFeeConfig {
+    fn default() -> Self {
+        Self {
+            block_count: 20,
+            percentile: 50.0,
+        }
+    }
+}
+
+impl PriorityFeeConfig {
+    /// Checks that the percentile is a number from 0 to 100.
+    pub fn validate(&self) -> Result<(), Error> {
+        if (0.0..=100.0).contains(&self.percentile) {
+            Ok(())
+        } else {
+            Err(Error::InvalidPercentile(self.percentile))
+        }
+    }
+
+    /// Computes the suggested priority fee from the fee history requested with a single reward percentile.
+    /// The suggestion is the median of per-block rewards; blocks without transactions are ignored.
+    pub(crate) fn suggest(history: &FeeHistory) -> Option<U256> {
+        let rewards = history.reward.as_deref().unwrap_or_default();
+        let mut block_fees: Vec<_> = rewards
+            .iter()
+            .zip(&history.gas_used_ratio)
+            .filter(|(_, gas_used_ratio)| **gas_used_ratio > 0.0)
+            .filter_map(|(block_rewards, _)| block_rewards.first().copied())
+            .collect();
+        if block_fees.is_empty() {
+            return None;
+        }
+        block_fees.sort_unstable();
+        Some(block_fees[block_fees.len() / 2])
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
+
     use super::*;
 
     #[test]
@@ -176,4 +883,431 @@ mod tests {
         let hash = H256::from_tokens(output_tokens).unwrap();
         assert_eq!(hash, H256::repeat_byte(1));
     }
+
+    #[test]
+    fn computing_blob_base_fee() {
+        let fee = |excess| BlockBlobGas::blob_base_fee_for_excess(excess).as_u64();
+        assert_eq!(fee(0), MIN_BLOB_BASE_FEE);
+        assert_eq!(fee(TARGET_BLOB_GAS_PER_BLOCK), 1);
+        assert_eq!(fee(BLOB_BASE_FEE_UPDATE_FRACTION), 2);
+        assert_eq!(fee(10 * BLOB_BASE_FEE_UPDATE_FRACTION), 22_026);
+        assert_eq!(fee(20 * BLOB_BASE_FEE_UPDATE_FRACTION), 485_165_195);
+    }
+
+    #[test]
+    fn validating_priority_fee_config() {
+        PriorityFeeConfig::default().validate().unwrap();
+        for percentile in [-1.0, 100.5, f64::NAN] {
+            let config = PriorityFeeConfig {
+                block_count: 20,
+                percentile,
+            };
+            let err = config.validate().unwrap_err();
+            assert_matches!(err, Error::InvalidPercentile(_));
+        }
+    }
+
+    /// Test vectors for the `fake_exponential()` function from the EIP-4844 reference implementation
+    /// in `go-ethereum`.
+    #[test]
+    fn computing_fake_exponential() {
+        let test_vectors: [(u64, u64, u64, u64); 15] = [
+            (1, 0, 1, 1),
+            (38_493, 0, 1_000, 38_493),
+            (0, 1_234, 2_345, 0),
+            (1, 2, 1, 6), // approximates 7.389
+            (1, 4, 2, 6),
+            (1, 3, 1, 16), // approximates 20.09
+            (1, 6, 2, 18),
+            (1, 4, 1, 49), // approximates 54.60
+            (1, 8, 2, 50),
+            (10, 8, 2, 542), // approximates 540.598
+            (11, 8, 2, 596), // approximates 600.58
+            (1, 5, 1, 136),  // approximates 148.4
+            (1, 5, 2, 11),   // approximates 12.18
+            (2, 5, 2, 23),   // approximates 24.36
+            (1, 50_000_000, 2_225_652, 5_709_098_764),
+        ];
+        for (factor, numerator, denominator, expected) in test_vectors {
+            let output = fake_exponential(factor.into(), numerator.into(), denominator.into());
+            assert_eq!(
+                output,
+                expected.into(),
+                "factor={factor}, numerator={numerator}, denominator={denominator}"
+            );
+        }
+
+        let fee = |excess| BlockBlobGas::blob_base_fee_for_excess(excess).as_u64();
+        assert_eq!(fee(2_314_057), 1);
+        assert_eq!(fee(2_314_058), 2);
+        assert_eq!(fee(10 * 1_024 * 1_024), 23);
+    }
+
+    #[test]
+    fn blob_base_fee_saturates_for_huge_excess_blob_gas() {
+        assert_eq!(BlockBlobGas::blob_base_fee_for_excess(u64::MAX), U256::MAX);
+        assert_eq!(
+            BlockBlobGas::blob_base_fee_for_excess(1_000 * BLOB_BASE_FEE_UPDATE_FRACTION),
+            U256::MAX
+        );
+
+        let block = BlockBlobGas::new(MAX_BLOB_GAS_PER_BLOCK, u64::MAX);
+        assert_eq!(block.blob_base_fee, U256::MAX);
+        assert_eq!(
+            block.next_excess_blob_gas(),
+            u64::MAX - TARGET_BLOB_GAS_PER_BLOCK
+        );
+        assert_eq!(block.predicted_blob_base_fee(BlobGasUsage::Full), U256::MAX);
+    }
+
+    #[test]
+    fn predicting_blob_base_fee() {
+        let excess_blob_gas = 10 * BLOB_BASE_FEE_UPDATE_FRACTION;
+        let block = BlockBlobGas::new(TARGET_BLOB_GAS_PER_BLOCK, excess_blob_gas);
+        assert_eq!(block.blob_base_fee, 22_026.into());
+        assert_eq!(block.next_blob_base_fee(), block.blob_base_fee);
+        assert_eq!(
+            block.predicted_blob_base_fee(BlobGasUsage::Target),
+            block.blob_base_fee
+        );
+
+        let full_fee = block.predicted_blob_base_fee(BlobGasUsage::Full);
+        let next_block = block.next_block(BlobGasUsage::Full);
+        assert_eq!(next_block.blob_gas_used, MAX_BLOB_GAS_PER_BLOCK);
+        assert_eq!(next_block.excess_blob_gas, excess_blob_gas);
+        assert_eq!(
+            full_fee,
+            BlockBlobGas::blob_base_fee_for_excess(excess_blob_gas + TARGET_BLOB_GAS_PER_BLOCK)
+        );
+        // The fee should grow by ~12.5% after a full block.
+        assert!(full_fee > block.blob_base_fee * 112 / 100, "{full_fee}");
+        assert!(full_fee < block.blob_base_fee * 113 / 100, "{full_fee}");
+
+        let empty_fee = block.predicted_blob_base_fee(BlobGasUsage::Empty);
+        assert!(empty_fee < block.blob_base_fee, "{empty_fee}");
+    }
+
+    #[test]
+    fn computing_next_excess_blob_gas() {
+        let block = BlockBlobGas::new(MAX_BLOB_GAS_PER_BLOCK, 0);
+        assert_eq!(block.next_excess_blob_gas(), TARGET_BLOB_GAS_PER_BLOCK);
+        let block = BlockBlobGas::new(GAS_PER_BLOB, TARGET_BLOB_GAS_PER_BLOCK);
+        assert_eq!(block.next_excess_blob_gas(), GAS_PER_BLOB);
+        let block = BlockBlobGas::new(0, GAS_PER_BLOB);
+        assert_eq!(block.next_excess_blob_gas(), 0);
+    }
+
+    fn test_sidecar(blob_count: usize) -> BlobTxSidecar {
+        BlobTxSidecar {
+            blobs: vec![vec![1; BYTES_PER_BLOB]; blob_count],
+            commitments: (0..blob_count)
+                .map(|i| vec![i as u8; BYTES_PER_COMMITMENT])
+                .collect(),
+            proofs: vec![vec![3; BYTES_PER_PROOF]; blob_count],
+        }
+    }
+
+    #[test]
+    fn validating_blob_sidecar() {
+        test_sidecar(1).validate().unwrap();
+        test_sidecar(MAX_BLOBS_PER_TX).validate().unwrap();
+
+        assert_eq!(test_sidecar(0).validate(), Err(BlobSidecarError::NoBlobs));
+        assert_eq!(
+            test_sidecar(MAX_BLOBS_PER_TX + 1).validate(),
+            Err(BlobSidecarError::TooManyBlobs(MAX_BLOBS_PER_TX + 1))
+        );
+
+        let mut sidecar = test_sidecar(2);
+        sidecar.proofs.pop();
+        assert_eq!(
+            sidecar.validate(),
+            Err(BlobSidecarError::LengthMismatch {
+                blobs: 2,
+                commitments: 2,
+                proofs: 1
+            })
+        );
+
+        let mut sidecar = test_sidecar(2);
+        sidecar.blobs[1].pop();
+        assert_eq!(
+            sidecar.validate(),
+            Err(BlobSidecarError::InvalidBlobSize {
+                index: 1,
+                len: BYTES_PER_BLOB - 1
+            })
+        );
+    }
+
+    #[test]
+    fn computing_versioned_hashes() {
+        let sidecar = test_sidecar(2);
+        let hashes = sidecar.versioned_hashes();
+        assert_eq!(hashes.len(), 2);
+        assert_ne!(hashes[0], hashes[1]);
+        for (hash, commitment) in hashes.iter().zip(&sidecar.commitments) {
+            let digest = Sha256::digest(commitment);
+            assert_eq!(hash[0], VERSIONED_HASH_VERSION_KZG);
+            assert_eq!(hash[1..], digest[1..]);
+        }
+
+        sidecar.verify_versioned_hashes(&hashes).unwrap();
+        let mut swapped_hashes = hashes;
+        swapped_hashes.swap(0, 1);
+        assert_matches!(
+            sidecar.verify_versioned_hashes(&swapped_hashes),
+            Err(BlobSidecarError::VersionedHashMismatch { .. })
+        );
+    }
+
+    fn rpc_error(message: &str, data: Option<serde_json::Value>) -> Error {
+        let err = jsonrpc_core::Error {
+            code: jsonrpc_core::ErrorCode::ServerError(-32000),
+            message: message.to_owned(),
+            data,
+        };
+        Error::EthereumGateway(web3::Error::Rpc(err))
+    }
+
+    #[test]
+    fn classifying_out_of_gas_errors() {
+        let out_of_gas_errors = [
+            // Geth / Erigon
+            rpc_error("out of gas", None),
+            rpc_error("gas required exceeds allowance (21000)", None),
+            rpc_error("intrinsic gas too low: have 20000, want 21000", None),
+            // Nethermind
+            rpc_error("OutOfGas", Some("OutOfGas".into())),
+            rpc_error("VM execution error.", Some("Out of gas".into())),
+            // Besu
+            rpc_error("Transaction ran out of gas", None),
+            // Reverts without data may be caused by an out-of-gas error in a nested call
+            rpc_error("execution reverted: out of gas", Some("0x".into())),
+        ];
+        for err in &out_of_gas_errors {
+            assert!(err.is_out_of_gas_error(), "{err}");
+        }
+
+        let revert_reason = "0x08c379a0\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            000000000000000000000000000000000000000000000000000000000000000a\
+            6f7574206f662067617300000000000000000000000000000000000000000000";
+        let other_errors = [
+            rpc_error("execution reverted", Some("0x4e487b71".into())),
+            rpc_error("execution reverted: out of gas", Some(revert_reason.into())),
+            rpc_error(
+                "VM execution error.",
+                Some(format!("Reverted {revert_reason}").into()),
+            ),
+            rpc_error("insufficient funds for gas * price + value", None),
+            rpc_error("nonce too low", None),
+            Error::EthereumGateway(web3::Error::Transport(TransportError::Message(
+                "out of gas".to_owned(),
+            ))),
+            Error::WrongFeeProvided(1.into(), 2.into()),
+        ];
+        for err in &other_errors {
+            assert!(!err.is_out_of_gas_error(), "{err}");
+        }
+    }
+
+    #[test]
+    fn classifying_errors_by_kind() {
+        let rpc_error_with_code = |code: i64, message: &str| {
+            let err = jsonrpc_core::Error {
+                code: code.into(),
+                message: message.to_owned(),
+                data: None,
+            };
+            Error::EthereumGateway(web3::Error::Rpc(err))
+        };
+        let transport_error =
+            |err: TransportError| Error::EthereumGateway(web3::Error::Transport(err));
+
+        let errors = [
+            (
+                Error::RequestTimeout {
+                    method: "eth_call",
+                    timeout: Duration::from_secs(1),
+                },
+                ErrorKind::Timeout,
+            ),
+            (
+                transport_error(TransportError::Message(
+                    "error sending request: operation timed out".to_owned(),
+                )),
+                ErrorKind::Timeout,
+            ),
+            (
+                Error::EthereumGateway(web3::Error::Io(std::io::ErrorKind::TimedOut.into())),
+                ErrorKind::Timeout,
+            ),
+            (
+                transport_error(TransportError::Message(
+                    "error trying to connect: tcp connect error: Connection refused".to_owned(),
+                )),
+                ErrorKind::Connection,
+            ),
+            (
+                Error::EthereumGateway(web3::Error::Unreachable),
+                ErrorKind::Connection,
+            ),
+            (
+                transport_error(TransportError::Code(429)),
+                ErrorKind::RateLimited,
+            ),
+            (
+                rpc_error_with_code(-32005, "limit exceeded"),
+                ErrorKind::RateLimited,
+            ),
+            (
+                rpc_error_with_code(-32000, "Too Many Requests"),
+                ErrorKind::RateLimited,
+            ),
+            (
+                transport_error(TransportError::Code(503)),
+                ErrorKind::Unavailable,
+            ),
+            (
+                transport_error(TransportError::Code(401)),
+                ErrorKind::HttpStatus,
+            ),
+            (
+                rpc_error_with_code(-32601, "the method eth_foo does not exist"),
+                ErrorKind::RpcInvalidRequest,
+            ),
+            (
+                rpc_error_with_code(-32602, "invalid argument 0: hex string without 0x prefix"),
+                ErrorKind::RpcInvalidRequest,
+            ),
+            (
+                Error::UnsupportedMethod("linea_estimateGas"),
+                ErrorKind::RpcInvalidRequest,
+            ),
+            (
+                rpc_error_with_code(3, "execution reverted"),
+                ErrorKind::RpcExecution,
+            ),
+            (
+                rpc_error("execution reverted", None),
+                ErrorKind::RpcExecution,
+            ),
+            (
+                rpc_error("VM execution error.", Some("Out of gas".into())),
+                ErrorKind::RpcExecution,
+            ),
+            (rpc_error("nonce too low", None), ErrorKind::RpcServer),
+            (
+                rpc_error_with_code(-32603, "internal error"),
+                ErrorKind::RpcServer,
+            ),
+            // Infura error for `eth_getLogs` shares the code with rate limiting errors.
+            (
+                rpc_error_with_code(-32005, "query returned more than 10000 results"),
+                ErrorKind::RpcServer,
+            ),
+            (
+                Error::EthereumGateway(web3::Error::Decoder("invalid type".to_owned())),
+                ErrorKind::Decode,
+            ),
+            (
+                Error::EthereumGateway(web3::Error::InvalidResponse(
+                    "missing field `hash`".to_owned(),
+                )),
+                ErrorKind::Decode,
+            ),
+            (
+                Error::Contract(ContractError::InvalidOutputType("bool".to_owned())),
+                ErrorKind::Decode,
+            ),
+            (
+                Error::from(BeaconApiError::Status {
+                    status: 502,
+                    message: "Bad gateway".to_owned(),
+                }),
+                ErrorKind::Unavailable,
+            ),
+            (
+                Error::WrongFeeProvided(1.into(), 2.into()),
+                ErrorKind::Other,
+            ),
+        ];
+        for (err, expected_kind) in &errors {
+            assert_eq!(err.kind(), *expected_kind, "{err}");
+            assert_eq!(err.is_transient(), expected_kind.is_transient(), "{err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn classifying_transport_decode_errors() {
+        use web3::Transport as _;
+
+        use crate::clients::{mock_server::MockRpcServer, HttpTransport};
+
+        let server = MockRpcServer::spawn(|_, _| Ok(serde_json::Value::Null)).await;
+        // Successful HTTP response with an empty body, which is not valid JSON-RPC.
+        server.respond_with_status("200 OK", &[]);
+        let transport = HttpTransport::new(&server.url()).unwrap();
+        let err = transport.execute("eth_chainId", vec![]).await.unwrap_err();
+        assert_matches!(&err, web3::Error::Decoder(message) if message.contains("failed to deserialize"));
+
+        let err = Error::EthereumGateway(err);
+        assert_eq!(err.kind(), ErrorKind::Decode);
+        assert!(!err.is_transient(), "{err}");
+    }
+
+    #[test]
+    fn getting_revert_reason() {
+        let revert_data = "0x08c379a0\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            0000000000000000000000000000000000000000000000000000000000000014\
+            696e73756666696369656e742062616c616e6365000000000000000000000000";
+        let err = rpc_error(
+            "execution reverted: insufficient balance",
+            Some(revert_data.into()),
+        );
+        assert_eq!(
+            err.revert_reason(),
+            Some(RevertReason::Error("insufficient balance".to_owned()))
+        );
+
+        let err = rpc_error("execution reverted", Some("0xdeadbeef".into()));
+        let reason = err.revert_reason().unwrap();
+        assert_eq!(reason.to_string(), "custom error 0xdeadbeef");
+
+        // Reverts without revert data
+        let err = rpc_error("execution reverted", None);
+        assert_eq!(err.revert_reason(), Some(RevertReason::Empty));
+        let err = rpc_error("execution reverted", Some("0x".into()));
+        assert_eq!(err.revert_reason(), Some(RevertReason::Empty));
+
+        // Errors unrelated to reverts
+        assert_eq!(rpc_error("out of gas", None).revert_reason(), None);
+        assert_eq!(rpc_error("nonce too low", None).revert_reason(), None);
+        let err = Error::EthereumGateway(web3::Error::Transport(TransportError::Message(
+            "connection reset".to_owned(),
+        )));
+        assert_eq!(err.revert_reason(), None);
+    }
+
+    #[test]
+    fn encoding_blob_tx_with_sidecar() {
+        let sidecar = test_sidecar(2);
+        let mut body = RlpStream::new_list(2);
+        body.append(&1_u64).append(&"test");
+        let signed_tx = [&[EIP_4844_TX_TYPE], body.as_raw()].concat();
+
+        let encoded = sidecar.encode_with_signed_tx(&signed_tx);
+        assert_eq!(encoded[0], EIP_4844_TX_TYPE);
+        let rlp = rlp::Rlp::new(&encoded[1..]);
+        assert_eq!(rlp.item_count().unwrap(), 4);
+        assert_eq!(rlp.at(0).unwrap().as_raw(), &signed_tx[1..]);
+        let blobs: Vec<Vec<u8>> = rlp.list_at(1).unwrap();
+        assert_eq!(blobs, sidecar.blobs);
+        let commitments: Vec<Vec<u8>> = rlp.list_at(2).unwrap();
+        assert_eq!(commitments, sidecar.commitments);
+        let proofs: Vec<Vec<u8>> = rlp.list_at(3).unwrap();
+        assert_eq!(proofs, sidecar.proofs);
+    }
 }
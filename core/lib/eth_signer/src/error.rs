@@ -29,6 +29,8 @@ pub enum SignerError {
     DefineAddress,
     #[error("Recover address from signature failed: {0}")]
     RecoverAddress(String),
+    #[error("Invalid EIP-712 typed data: {0}")]
+    InvalidTypedData(String),
     #[error("{0}")]
     CustomError(String),
 }
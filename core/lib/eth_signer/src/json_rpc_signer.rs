@@ -8,7 +8,7 @@ use crate::{
     error::{RpcSignerError, SignerError},
     json_rpc_signer::messages::JsonRpcRequest,
     raw_ethereum_tx::TransactionParameters,
-    EthereumSigner,
+    EthereumSigner, TypedData,
 };
 
 pub fn is_signature_from_address(
@@ -117,11 +117,41 @@ impl EthereumSigner for JsonRpcSigner {
         }
     }
 
+    /// Signs arbitrary EIP-712 typed data via `eth_signTypedData_v4`.
+    async fn sign_typed_data_v4(
+        &self,
+        typed_data: &TypedData,
+    ) -> Result<PackedEthSignature, SignerError> {
+        let signed_bytes = typed_data.signing_hash()?;
+        let signature: PackedEthSignature = {
+            let message = JsonRpcRequest::sign_typed_data_v4(self.address()?, typed_data);
+            let ret = self
+                .post(&message)
+                .await
+                .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+            serde_json::from_value(ret)
+                .map_err(|err| SignerError::SigningFailed(err.to_string()))?
+        };
+
+        if is_signature_from_address(&signature, &signed_bytes, self.address()?)? {
+            Ok(signature)
+        } else {
+            Err(SignerError::SigningFailed(
+                "Invalid signature from JsonRpcSigner".to_string(),
+            ))
+        }
+    }
+
     /// Signs and returns the RLP-encoded transaction.
     async fn sign_transaction(
         &self,
         raw_tx: TransactionParameters,
     ) -> Result<Vec<u8>, SignerError> {
+        if raw_tx.blob_versioned_hashes.is_some() {
+            return Err(SignerError::SigningFailed(
+                "EIP-4844 transactions are not supported by JsonRpcSigner".to_string(),
+            ));
+        }
         let msg = JsonRpcRequest::sign_transaction(self.address()?, raw_tx);
 
         let ret = self
@@ -333,7 +363,7 @@ mod messages {
         eip712_signature::utils::get_eip712_json, Address, EIP712TypedStructure, Eip712Domain,
     };
 
-    use crate::raw_ethereum_tx::TransactionParameters;
+    use crate::{raw_ethereum_tx::TransactionParameters, TypedData};
 
     #[derive(Debug, Serialize, Deserialize)]
     pub struct JsonRpcRequest {
@@ -394,6 +424,15 @@ mod messages {
             Self::create("eth_signTypedData_v3", params)
         }
 
+        /// Signs arbitrary EIP-712 typed data. The address to sign with must be unlocked.
+        pub fn sign_typed_data_v4(address: Address, typed_data: &TypedData) -> Self {
+            let params = vec![
+                serde_json::to_value(address).expect("serialization fail"),
+                serde_json::to_value(typed_data).expect("serialization fail"),
+            ];
+            Self::create("eth_signTypedData_v4", params)
+        }
+
         /// Signs a transaction that can be submitted to the network.
         /// The address to sign with must be unlocked.
         pub fn sign_transaction(from: Address, tx_data: TransactionParameters) -> Self {
@@ -444,7 +483,10 @@ mod tests {
     use zksync_types::{tx::primitives::PackedEthSignature, Address};
 
     use super::{is_signature_from_address, messages::JsonRpcRequest};
-    use crate::{raw_ethereum_tx::TransactionParameters, EthereumSigner, JsonRpcSigner};
+    use crate::{
+        raw_ethereum_tx::TransactionParameters, EthereumSigner, JsonRpcSigner, TypedData,
+        TypedDataDomain, TypedDataField, TypedDataTypes,
+    };
 
     #[post("/")]
     async fn index(req: web::Json<JsonRpcRequest>, state: web::Data<State>) -> impl Responder {
@@ -467,6 +509,15 @@ mod tests {
                         .unwrap();
                 create_success(json!(signature))
             }
+            "eth_signTypedData_v4" => {
+                let typed_data: TypedData = serde_json::from_value(req.params[1].clone()).unwrap();
+                let signature = PackedEthSignature::sign_raw(
+                    &state.key_pairs[0].secret().0.into(),
+                    &typed_data.signing_hash().unwrap(),
+                )
+                .unwrap();
+                create_success(json!(signature))
+            }
             "eth_signTransaction" => {
                 let tx_value = json!(req.params[0].clone()).to_string();
                 let tx = tx_value.as_bytes();
@@ -546,6 +597,22 @@ mod tests {
                 .unwrap()
         );
 
+        let domain = TypedDataDomain {
+            name: Some("Test".to_owned()),
+            chain_id: Some(1.into()),
+            ..TypedDataDomain::default()
+        };
+        let types = TypedDataTypes::from([(
+            "Attestation".to_owned(),
+            vec![TypedDataField::new("batch", "uint256")],
+        )]);
+        let typed_data = TypedData::new(domain, types, json!({ "batch": 42 })).unwrap();
+        let signature = client.sign_typed_data_v4(&typed_data).await.unwrap();
+        assert_eq!(
+            typed_data.recover_signer(&signature).unwrap(),
+            client.address().unwrap()
+        );
+
         let transaction_signature = client
             .sign_transaction(TransactionParameters::default())
             .await
@@ -6,12 +6,16 @@ use zksync_types::{
     tx::primitives::PackedEthSignature, Address, EIP712TypedStructure, Eip712Domain,
 };
 
-pub use crate::raw_ethereum_tx::TransactionParameters;
+pub use crate::{
+    raw_ethereum_tx::TransactionParameters,
+    typed_data::{TypedData, TypedDataDomain, TypedDataField, TypedDataTypes},
+};
 
 pub mod error;
 pub mod json_rpc_signer;
 pub mod pk_signer;
 pub mod raw_ethereum_tx;
+pub mod typed_data;
 
 #[async_trait]
 pub trait EthereumSigner: 'static + Send + Sync + Clone {
@@ -21,6 +25,11 @@ pub trait EthereumSigner: 'static + Send + Sync + Clone {
         domain: &Eip712Domain,
         typed_struct: &S,
     ) -> Result<PackedEthSignature, SignerError>;
+    /// Signs arbitrary EIP-712 typed data. The result is equivalent to calling `eth_signTypedData_v4`.
+    async fn sign_typed_data_v4(
+        &self,
+        typed_data: &TypedData,
+    ) -> Result<PackedEthSignature, SignerError>;
     async fn sign_transaction(&self, raw_tx: TransactionParameters)
         -> Result<Vec<u8>, SignerError>;
     async fn get_address(&self) -> Result<Address, SignerError>;
@@ -5,7 +5,7 @@ use zksync_types::{
 
 use crate::{
     raw_ethereum_tx::{Transaction, TransactionParameters},
-    EthereumSigner, SignerError,
+    EthereumSigner, SignerError, TypedData,
 };
 
 #[derive(Clone)]
@@ -54,12 +54,27 @@ impl EthereumSigner for PrivateKeySigner {
         Ok(signature)
     }
 
+    /// Signs arbitrary EIP-712 typed data using Ethereum private key.
+    async fn sign_typed_data_v4(
+        &self,
+        typed_data: &TypedData,
+    ) -> Result<PackedEthSignature, SignerError> {
+        let signing_hash = typed_data.signing_hash()?;
+        PackedEthSignature::sign_raw(&self.private_key, &signing_hash)
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))
+    }
+
     /// Signs and returns the RLP-encoded transaction.
     async fn sign_transaction(
         &self,
         raw_tx: TransactionParameters,
     ) -> Result<Vec<u8>, SignerError> {
         let key = SecretKey::from_slice(self.private_key.as_bytes()).unwrap();
+        if raw_tx.blob_versioned_hashes.is_some() && raw_tx.to.is_none() {
+            return Err(SignerError::SigningFailed(
+                "EIP-4844 transaction must have a recipient".to_owned(),
+            ));
+        }
 
         // According to the code in web3 <https://docs.rs/web3/latest/src/web3/api/accounts.rs.html#86>
         // We should use `max_fee_per_gas` as `gas_price` if we use EIP1559
@@ -77,6 +92,8 @@ impl EthereumSigner for PrivateKeySigner {
             transaction_type: raw_tx.transaction_type,
             access_list: raw_tx.access_list.unwrap_or_default(),
             max_priority_fee_per_gas,
+            max_fee_per_blob_gas: raw_tx.max_fee_per_blob_gas.unwrap_or_default(),
+            blob_versioned_hashes: raw_tx.blob_versioned_hashes.unwrap_or_default(),
         };
 
         let signed = tx.sign(&key, raw_tx.chain_id);
@@ -107,6 +124,8 @@ mod test {
             chain_id: 270,
             transaction_type: Some(U64::from(1u32)),
             access_list: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
         };
         let raw_tx = signer
             .sign_transaction(raw_transaction.clone())
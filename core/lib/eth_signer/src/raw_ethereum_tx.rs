@@ -16,12 +16,13 @@ use zksync_types::{
         signing::{self, Signature},
         types::{AccessList, SignedTransaction},
     },
-    U256, U64,
+    H256, U256, U64,
 };
 
 const LEGACY_TX_ID: u64 = 0;
 const ACCESSLISTS_TX_ID: u64 = 1;
 const EIP1559_TX_ID: u64 = 2;
+const EIP4844_TX_ID: u64 = 3;
 
 #[derive(Clone, Debug, PartialEq, Default)]
 pub struct TransactionParameters {
@@ -47,6 +48,10 @@ pub struct TransactionParameters {
     pub max_fee_per_gas: U256,
     /// miner bribe
     pub max_priority_fee_per_gas: U256,
+    /// Max fee per blob gas (EIP-4844 transactions only)
+    pub max_fee_per_blob_gas: Option<U256>,
+    /// Versioned hashes of the blobs carried by the transaction (EIP-4844 transactions only)
+    pub blob_versioned_hashes: Option<Vec<H256>>,
 }
 
 /// A transaction used for RLP encoding, hashing and signing.
@@ -61,6 +66,8 @@ pub struct Transaction {
     pub transaction_type: Option<U64>,
     pub access_list: AccessList,
     pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_blob_gas: U256,
+    pub blob_versioned_hashes: Vec<H256>,
 }
 
 impl Transaction {
@@ -147,6 +154,37 @@ impl Transaction {
         stream
     }
 
+    fn encode_eip4844_payload(&self, chain_id: u64, signature: Option<&Signature>) -> RlpStream {
+        let mut stream = RlpStream::new();
+
+        let list_size = if signature.is_some() { 14 } else { 11 };
+        stream.begin_list(list_size);
+
+        stream.append(&chain_id);
+        stream.append(&self.nonce);
+        stream.append(&self.max_priority_fee_per_gas);
+        stream.append(&self.gas_price);
+        stream.append(&self.gas);
+        // EIP-4844 transactions cannot be used to create contracts; this is checked by the signer.
+        stream.append(&self.to.expect("EIP-4844 transaction must have a recipient"));
+        stream.append(&self.value);
+        stream.append(&self.data);
+
+        self.rlp_append_access_list(&mut stream);
+
+        stream.append(&self.max_fee_per_blob_gas);
+        stream.begin_list(self.blob_versioned_hashes.len());
+        for versioned_hash in &self.blob_versioned_hashes {
+            stream.append(versioned_hash);
+        }
+
+        if let Some(signature) = signature {
+            self.rlp_append_signature(&mut stream, signature);
+        }
+
+        stream
+    }
+
     fn rlp_append_signature(&self, stream: &mut RlpStream, signature: &Signature) {
         stream.append(&signature.v);
         stream.append(&U256::from_big_endian(signature.r.as_bytes()));
@@ -184,6 +222,12 @@ impl Transaction {
                 [&[tx_id], stream.as_raw()].concat()
             }
 
+            Some(EIP4844_TX_ID) => {
+                let tx_id: u8 = EIP4844_TX_ID as u8;
+                let stream = self.encode_eip4844_payload(chain_id, signature);
+                [&[tx_id], stream.as_raw()].concat()
+            }
+
             _ => {
                 panic!("Unsupported transaction type");
             }
@@ -0,0 +1,520 @@
+//! Arbitrary EIP-712 typed data, as accepted by the `eth_signTypedData_v4` RPC method.
+//!
+//! Unlike [`EIP712TypedStructure`](zksync_types::EIP712TypedStructure), which requires a Rust type
+//! for each signed structure, types here are described at runtime.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zksync_types::{
+    tx::primitives::PackedEthSignature, web3::signing::keccak256, Address, H256, U256,
+};
+
+use crate::SignerError;
+
+const DOMAIN_TYPE_NAME: &str = "EIP712Domain";
+
+/// Member of a struct type in [`TypedData`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypedDataField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+}
+
+impl TypedDataField {
+    pub fn new(name: impl Into<String>, field_type: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            field_type: field_type.into(),
+        }
+    }
+}
+
+/// Struct types used in [`TypedData`] keyed by the type name.
+pub type TypedDataTypes = BTreeMap<String, Vec<TypedDataField>>;
+
+/// EIP-712 domain. Only the specified fields are included into the domain type.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedDataDomain {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verifying_contract: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<H256>,
+}
+
+impl TypedDataDomain {
+    /// Returns the `EIP712Domain` type for this domain; fields are ordered as per the EIP-712 spec.
+    fn fields(&self) -> Vec<TypedDataField> {
+        let fields = [
+            ("name", "string", self.name.is_some()),
+            ("version", "string", self.version.is_some()),
+            ("chainId", "uint256", self.chain_id.is_some()),
+            (
+                "verifyingContract",
+                "address",
+                self.verifying_contract.is_some(),
+            ),
+            ("salt", "bytes32", self.salt.is_some()),
+        ];
+        fields
+            .iter()
+            .filter(|(_, _, is_present)| *is_present)
+            .map(|(name, field_type, _)| TypedDataField::new(*name, *field_type))
+            .collect()
+    }
+}
+
+/// Typed data in the format of `eth_signTypedData_v4`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    pub types: TypedDataTypes,
+    pub primary_type: String,
+    pub domain: TypedDataDomain,
+    pub message: Value,
+}
+
+impl TypedData {
+    /// Creates typed data. The `EIP712Domain` type is derived from `domain` unless it's present in `types`.
+    /// The primary type is the only type in `types` not referenced by other types.
+    pub fn new(
+        domain: TypedDataDomain,
+        mut types: TypedDataTypes,
+        message: Value,
+    ) -> Result<Self, SignerError> {
+        types
+            .entry(DOMAIN_TYPE_NAME.to_owned())
+            .or_insert_with(|| domain.fields());
+        let primary_type = Self::infer_primary_type(&types)?;
+        Ok(Self {
+            types,
+            primary_type,
+            domain,
+            message,
+        })
+    }
+
+    fn infer_primary_type(types: &TypedDataTypes) -> Result<String, SignerError> {
+        let referenced_types: BTreeSet<_> = types
+            .values()
+            .flatten()
+            .map(|field| base_type(&field.field_type))
+            .collect();
+        let candidates: Vec<_> = types
+            .keys()
+            .filter(|&name| name != DOMAIN_TYPE_NAME && !referenced_types.contains(name.as_str()))
+            .collect();
+        match candidates.as_slice() {
+            [name] => Ok((*name).clone()),
+            _ => Err(invalid_data(format!(
+                "cannot determine primary type; candidates: {candidates:?}"
+            ))),
+        }
+    }
+
+    /// Computes the digest to be signed, i.e. `keccak256("\x19\x01" || domainSeparator || hashStruct(message))`.
+    pub fn signing_hash(&self) -> Result<H256, SignerError> {
+        let mut bytes = b"\x19\x01".to_vec();
+        bytes.extend_from_slice(self.domain_separator()?.as_bytes());
+        // As per EIP-712, the message hash is omitted if the primary type is the domain type.
+        if self.primary_type != DOMAIN_TYPE_NAME {
+            let message_hash = self.hash_struct(&self.primary_type, &self.message)?;
+            bytes.extend_from_slice(message_hash.as_bytes());
+        }
+        Ok(H256(keccak256(&bytes)))
+    }
+
+    /// Computes the domain separator, i.e. the hash of the domain struct.
+    pub fn domain_separator(&self) -> Result<H256, SignerError> {
+        let domain = serde_json::to_value(&self.domain).expect("failed serializing domain");
+        if self.types.contains_key(DOMAIN_TYPE_NAME) {
+            self.hash_struct(DOMAIN_TYPE_NAME, &domain)
+        } else {
+            let mut types = self.types.clone();
+            types.insert(DOMAIN_TYPE_NAME.to_owned(), self.domain.fields());
+            Self {
+                types,
+                ..self.clone()
+            }
+            .hash_struct(DOMAIN_TYPE_NAME, &domain)
+        }
+    }
+
+    /// Recovers the address that has produced the provided signature for this data.
+    pub fn recover_signer(&self, signature: &PackedEthSignature) -> Result<Address, SignerError> {
+        let signing_hash = self.signing_hash()?;
+        signature
+            .signature_recover_signer(&signing_hash)
+            .map_err(|err| SignerError::RecoverAddress(err.to_string()))
+    }
+
+    /// Encodes the type together with all its (transitive) dependencies, e.g.
+    /// `Mail(Person from,Person to,string contents)Person(string name,address wallet)`.
+    pub fn encode_type(&self, type_name: &str) -> Result<String, SignerError> {
+        let mut dependencies = BTreeSet::new();
+        self.collect_dependencies(type_name, &mut dependencies);
+        if !dependencies.remove(type_name) {
+            return Err(invalid_data(format!("unknown type `{type_name}`")));
+        }
+
+        let mut encoded = String::new();
+        for name in std::iter::once(type_name).chain(dependencies) {
+            let fields: Vec<_> = self.types[name]
+                .iter()
+                .map(|field| format!("{} {}", field.field_type, field.name))
+                .collect();
+            encoded += &format!("{name}({})", fields.join(","));
+        }
+        Ok(encoded)
+    }
+
+    fn collect_dependencies<'a>(
+        &'a self,
+        type_name: &'a str,
+        dependencies: &mut BTreeSet<&'a str>,
+    ) {
+        let Some(fields) = self.types.get(type_name) else {
+            return;
+        };
+        if !dependencies.insert(type_name) {
+            return;
+        }
+        for field in fields {
+            self.collect_dependencies(base_type(&field.field_type), dependencies);
+        }
+    }
+
+    fn hash_struct(&self, type_name: &str, value: &Value) -> Result<H256, SignerError> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| invalid_data(format!("expected object for `{type_name}`")))?;
+        let type_hash = keccak256(self.encode_type(type_name)?.as_bytes());
+        let mut bytes = type_hash.to_vec();
+        for field in &self.types[type_name] {
+            let value = object.get(&field.name).ok_or_else(|| {
+                invalid_data(format!("missing field `{}` in `{type_name}`", field.name))
+            })?;
+            let encoded = self.encode_value(&field.field_type, value).map_err(|err| {
+                invalid_data(format!("invalid `{type_name}.{}`: {err}", field.name))
+            })?;
+            bytes.extend_from_slice(&encoded);
+        }
+        Ok(H256(keccak256(&bytes)))
+    }
+
+    fn encode_value(&self, field_type: &str, value: &Value) -> Result<[u8; 32], String> {
+        if let Some(item_type_end) = field_type.strip_suffix(']').and_then(|ty| ty.rfind('[')) {
+            let item_type = &field_type[..item_type_end];
+            let len = &field_type[item_type_end + 1..field_type.len() - 1];
+            let items = value.as_array().ok_or("expected array")?;
+            if !len.is_empty() && len.parse::<usize>().ok() != Some(items.len()) {
+                return Err(format!("expected {len} items, got {}", items.len()));
+            }
+            let mut bytes = Vec::with_capacity(items.len() * 32);
+            for item in items {
+                bytes.extend_from_slice(&self.encode_value(item_type, item)?);
+            }
+            return Ok(keccak256(&bytes));
+        }
+        if self.types.contains_key(field_type) {
+            return self
+                .hash_struct(field_type, value)
+                .map(|hash| hash.0)
+                .map_err(|err| err.to_string());
+        }
+
+        match field_type {
+            "string" => Ok(keccak256(
+                value.as_str().ok_or("expected string")?.as_bytes(),
+            )),
+            "bytes" => Ok(keccak256(&parse_hex(value)?)),
+            "bool" => {
+                let value = value.as_bool().ok_or("expected bool")?;
+                Ok(u256_to_bytes(U256::from(u8::from(value))))
+            }
+            "address" => {
+                let address: Address = serde_json::from_value(value.clone())
+                    .map_err(|err| format!("expected address: {err}"))?;
+                Ok(H256::from(address).0)
+            }
+            _ => {
+                if let Some(len) = field_type.strip_prefix("bytes") {
+                    let len = parse_size(len, 1..=32)?;
+                    let bytes = parse_hex(value)?;
+                    if bytes.len() != len {
+                        return Err(format!("expected {len} bytes, got {}", bytes.len()));
+                    }
+                    let mut encoded = [0_u8; 32];
+                    encoded[..len].copy_from_slice(&bytes);
+                    Ok(encoded)
+                } else if let Some(bits) = field_type.strip_prefix("uint") {
+                    parse_size(bits, 8..=256)?;
+                    parse_uint(value).map(u256_to_bytes)
+                } else if let Some(bits) = field_type.strip_prefix("int") {
+                    parse_size(bits, 8..=256)?;
+                    parse_int(value).map(u256_to_bytes)
+                } else {
+                    Err(format!("unsupported type `{field_type}`"))
+                }
+            }
+        }
+    }
+}
+
+fn invalid_data(message: String) -> SignerError {
+    SignerError::InvalidTypedData(message)
+}
+
+/// Strips array suffixes from the type, e.g. `Person[][2]` -> `Person`.
+fn base_type(field_type: &str) -> &str {
+    field_type.split('[').next().unwrap_or(field_type)
+}
+
+fn parse_size(size: &str, range: std::ops::RangeInclusive<usize>) -> Result<usize, String> {
+    if size.is_empty() && *range.end() == 256 {
+        // `uint` / `int` are aliases for `uint256` / `int256`
+        return Ok(256);
+    }
+    size.parse()
+        .ok()
+        .filter(|size| range.contains(size))
+        .ok_or_else(|| format!("invalid type size `{size}`"))
+}
+
+fn parse_hex(value: &Value) -> Result<Vec<u8>, String> {
+    let value = value.as_str().ok_or("expected hex string")?;
+    let value = value.strip_prefix("0x").ok_or("expected 0x-prefixed hex")?;
+    hex::decode(value).map_err(|err| format!("invalid hex: {err}"))
+}
+
+fn parse_uint(value: &Value) -> Result<U256, String> {
+    match value {
+        Value::Number(number) => number
+            .as_u64()
+            .map(U256::from)
+            .ok_or_else(|| format!("expected unsigned integer, got {number}")),
+        Value::String(s) => {
+            if let Some(hex) = s.strip_prefix("0x") {
+                U256::from_str_radix(hex, 16).map_err(|err| err.to_string())
+            } else {
+                U256::from_dec_str(s).map_err(|err| err.to_string())
+            }
+        }
+        _ => Err("expected integer".to_owned()),
+    }
+}
+
+/// Parses a signed integer into its two's complement representation.
+fn parse_int(value: &Value) -> Result<U256, String> {
+    let (is_negative, abs_value) = match value {
+        Value::Number(number) => {
+            let number = number
+                .as_i64()
+                .ok_or_else(|| format!("expected integer, got {number}"))?;
+            (number < 0, U256::from(number.unsigned_abs()))
+        }
+        Value::String(s) => match s.strip_prefix('-') {
+            Some(abs_value) => (true, parse_uint(&Value::String(abs_value.to_owned()))?),
+            None => (false, parse_uint(value)?),
+        },
+        _ => return Err("expected integer".to_owned()),
+    };
+    Ok(if is_negative {
+        (!abs_value).overflowing_add(U256::one()).0
+    } else {
+        abs_value
+    })
+}
+
+fn u256_to_bytes(value: U256) -> [u8; 32] {
+    let mut bytes = [0_u8; 32];
+    value.to_big_endian(&mut bytes);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// Example from the EIP-712 spec.
+    fn mail_typed_data() -> TypedData {
+        let domain = TypedDataDomain {
+            name: Some("Ether Mail".to_owned()),
+            version: Some("1".to_owned()),
+            chain_id: Some(1.into()),
+            verifying_contract: Some(
+                "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+                    .parse()
+                    .unwrap(),
+            ),
+            salt: None,
+        };
+        let types = TypedDataTypes::from([
+            (
+                "Person".to_owned(),
+                vec![
+                    TypedDataField::new("name", "string"),
+                    TypedDataField::new("wallet", "address"),
+                ],
+            ),
+            (
+                "Mail".to_owned(),
+                vec![
+                    TypedDataField::new("from", "Person"),
+                    TypedDataField::new("to", "Person"),
+                    TypedDataField::new("contents", "string"),
+                ],
+            ),
+        ]);
+        let message = json!({
+            "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+            "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+            "contents": "Hello, Bob!",
+        });
+        TypedData::new(domain, types, message).unwrap()
+    }
+
+    #[test]
+    fn eip712_test_vectors() {
+        let typed_data = mail_typed_data();
+        assert_eq!(typed_data.primary_type, "Mail");
+        assert_eq!(
+            typed_data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            typed_data.domain_separator().unwrap(),
+            "0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            typed_data.hash_struct("Mail", &typed_data.message).unwrap(),
+            "0xc52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            typed_data.signing_hash().unwrap(),
+            "0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+                .parse()
+                .unwrap()
+        );
+
+        let private_key = H256(keccak256(b"cow"));
+        let signature =
+            PackedEthSignature::sign_raw(&private_key, &typed_data.signing_hash().unwrap())
+                .unwrap();
+        let signature = signature.serialize_packed();
+        assert_eq!(
+            hex::encode(&signature[..32]),
+            "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d"
+        );
+        assert_eq!(
+            hex::encode(&signature[32..64]),
+            "07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562"
+        );
+        assert_eq!(signature[64], 28);
+    }
+
+    #[test]
+    fn typed_data_serialization_roundtrip() {
+        let typed_data = mail_typed_data();
+        let json = serde_json::to_value(&typed_data).unwrap();
+        assert_eq!(json["primaryType"], "Mail");
+        assert_eq!(json["domain"]["chainId"], "0x1");
+        assert_eq!(
+            json["types"]["EIP712Domain"],
+            json!([
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" },
+            ])
+        );
+
+        let restored: TypedData = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, typed_data);
+    }
+
+    #[test]
+    fn encoding_atomic_and_array_values() {
+        let types = TypedDataTypes::from([(
+            "Values".to_owned(),
+            vec![
+                TypedDataField::new("flag", "bool"),
+                TypedDataField::new("amount", "uint256"),
+                TypedDataField::new("delta", "int8"),
+                TypedDataField::new("tag", "bytes4"),
+                TypedDataField::new("data", "bytes"),
+                TypedDataField::new("ids", "uint64[2]"),
+            ],
+        )]);
+        let message = json!({
+            "flag": true,
+            "amount": "0x10",
+            "delta": -1,
+            "tag": "0xdeadbeef",
+            "data": "0x",
+            "ids": [1, "2"],
+        });
+        let typed_data =
+            TypedData::new(TypedDataDomain::default(), types, message.clone()).unwrap();
+        assert_eq!(typed_data.primary_type, "Values");
+
+        assert_eq!(
+            typed_data.encode_value("int8", &json!(-1)).unwrap(),
+            [0xff; 32]
+        );
+        assert_eq!(
+            typed_data.encode_value("int256", &json!("-1")).unwrap(),
+            [0xff; 32]
+        );
+        let mut expected_tag = [0; 32];
+        expected_tag[..4].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(
+            typed_data
+                .encode_value("bytes4", &json!("0xdeadbeef"))
+                .unwrap(),
+            expected_tag
+        );
+        assert_eq!(
+            typed_data
+                .encode_value("uint64[2]", &json!([1, "2"]))
+                .unwrap(),
+            keccak256(&[u256_to_bytes(1.into()), u256_to_bytes(2.into())].concat())
+        );
+        typed_data.signing_hash().unwrap();
+
+        let mut invalid_message = message;
+        invalid_message["ids"] = json!([1, 2, 3]);
+        let err = TypedData {
+            message: invalid_message,
+            ..typed_data
+        }
+        .signing_hash()
+        .unwrap_err();
+        assert!(err.to_string().contains("Values.ids"), "{err}");
+    }
+
+    #[test]
+    fn ambiguous_primary_type() {
+        let types = TypedDataTypes::from([
+            ("A".to_owned(), vec![TypedDataField::new("x", "uint256")]),
+            ("B".to_owned(), vec![TypedDataField::new("y", "uint256")]),
+        ]);
+        let err = TypedData::new(TypedDataDomain::default(), types, json!({})).unwrap_err();
+        assert!(matches!(err, SignerError::InvalidTypedData(_)), "{err}");
+    }
+}
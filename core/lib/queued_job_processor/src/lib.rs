@@ -1,5 +1,5 @@
 use std::{
-    fmt::Debug,
+    fmt::{self, Debug},
     time::{Duration, Instant},
 };
 
@@ -23,6 +23,22 @@ struct JobProcessorMetrics {
 #[vise::register]
 static METRICS: vise::Global<JobProcessorMetrics> = vise::Global::new();
 
+/// Error returned from [`JobProcessor::save_result()`] if the job has not succeeded even though
+/// [`JobProcessor::process_job()`] has completed, e.g. because its result could not be delivered.
+///
+/// Unlike other errors, it doesn't stop [`JobProcessor::run()`]; the job is counted as failed instead.
+/// `save_result()` is responsible for persisting the failure if necessary.
+#[derive(Debug)]
+pub struct JobNotSucceeded(pub String);
+
+impl fmt::Display for JobNotSucceeded {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for JobNotSucceeded {}
+
 #[async_trait]
 pub trait JobProcessor: Sync + Send {
     type Job: Send + 'static;
@@ -54,6 +70,9 @@ pub trait JobProcessor: Sync + Send {
     /// To run indefinitely, pass `None`,
     /// To process one job, pass `Some(1)`,
     /// To process a batch, pass `Some(batch_size)`.
+    ///
+    /// By default, each picked job counts as an iteration regardless of its outcome;
+    /// see [`Self::count_only_successes()`] to change this.
     async fn run(
         self,
         stop_receiver: watch::Receiver<bool>,
@@ -71,12 +90,16 @@ pub trait JobProcessor: Sync + Send {
                 );
                 return Ok(());
             }
+            if self.is_paused() {
+                tracing::trace!("{} is paused, not picking new jobs", Self::SERVICE_NAME);
+                sleep(Duration::from_millis(Self::POLLING_INTERVAL_MS)).await;
+                continue;
+            }
             if let Some((job_id, job)) =
                 Self::get_next_job(&self).await.context("get_next_job()")?
             {
                 let started_at = Instant::now();
                 backoff = Self::POLLING_INTERVAL_MS;
-                iterations_left = iterations_left.map(|i| i - 1);
 
                 tracing::debug!(
                     "Spawning thread processing {:?} job with id {:?}",
@@ -85,9 +108,13 @@ pub trait JobProcessor: Sync + Send {
                 );
                 let task = self.process_job(job, started_at).await;
 
-                self.wait_for_task(job_id, started_at, task)
+                let succeeded = self
+                    .wait_for_task(job_id, started_at, task)
                     .await
                     .context("wait_for_task")?;
+                if succeeded || !self.count_only_successes() {
+                    iterations_left = iterations_left.map(|i| i - 1);
+                }
             } else if iterations_left.is_some() {
                 tracing::info!("No more jobs to process. Server can stop now.");
                 return Ok(());
@@ -102,12 +129,13 @@ pub trait JobProcessor: Sync + Send {
     }
 
     /// Polls task handle, saving its outcome.
+    /// Returns `true` if the job was processed successfully, and `false` if it has failed.
     async fn wait_for_task(
         &self,
         job_id: Self::JobId,
         started_at: Instant,
         task: JoinHandle<anyhow::Result<Self::JobArtifacts>>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<bool> {
         let attempts = self.get_job_attempts(&job_id).await?;
         let max_attempts = self.max_attempts();
         if attempts == max_attempts {
@@ -139,10 +167,18 @@ pub trait JobProcessor: Sync + Send {
                     job_id
                 );
                 METRICS.attempts[&Self::SERVICE_NAME].observe(attempts as usize);
-                return self
-                    .save_result(job_id, started_at, data)
-                    .await
-                    .context("save_result()");
+                return match self.save_result(job_id, started_at, data).await {
+                    Ok(()) => Ok(true),
+                    Err(err) if err.downcast_ref::<JobNotSucceeded>().is_some() => {
+                        tracing::warn!(
+                            "{} job {:?} has not succeeded: {err:#}",
+                            Self::SERVICE_NAME,
+                            job_id
+                        );
+                        Ok(false)
+                    }
+                    Err(err) => Err(err.context("save_result()")),
+                };
             }
             Ok(Err(error)) => error.to_string(),
             Err(error) => try_extract_panic_message(error),
@@ -155,10 +191,11 @@ pub trait JobProcessor: Sync + Send {
         );
 
         self.save_failure(job_id, started_at, error_message).await;
-        Ok(())
+        Ok(false)
     }
 
-    /// Invoked when `process_job` doesn't panic
+    /// Invoked when `process_job` doesn't panic.
+    /// Should return [`JobNotSucceeded`] if the job must not be counted as successful.
     async fn save_result(
         &self,
         job_id: Self::JobId,
@@ -168,6 +205,171 @@ pub trait JobProcessor: Sync + Send {
 
     fn max_attempts(&self) -> u32;
 
+    /// If `true`, only successfully processed jobs are counted towards `iterations_left` in [`Self::run()`];
+    /// failed jobs are still retried according to [`Self::max_attempts()`], but don't consume iterations.
+    fn count_only_successes(&self) -> bool {
+        false
+    }
+
+    /// If `true`, [`Self::run()`] doesn't pick new jobs until the processor is resumed.
+    /// Jobs already being processed are not affected.
+    fn is_paused(&self) -> bool {
+        false
+    }
+
     /// Invoked in `wait_for_task` for in-progress job.
     async fn get_job_attempts(&self, job_id: &Self::JobId) -> anyhow::Result<u32>;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    };
+
+    use super::*;
+
+    /// Processor that fails every odd job and succeeds on every even one.
+    #[derive(Debug, Default)]
+    struct AlternatingProcessor {
+        count_only_successes: bool,
+        /// If set, odd jobs fail when saving their result rather than when being processed.
+        fail_on_save: bool,
+        paused: Arc<AtomicBool>,
+        next_job_id: AtomicU32,
+        succeeded: Arc<AtomicU32>,
+        failed: Arc<AtomicU32>,
+    }
+
+    #[async_trait]
+    impl JobProcessor for AlternatingProcessor {
+        type Job = u32;
+        type JobId = u32;
+        type JobArtifacts = ();
+
+        const POLLING_INTERVAL_MS: u64 = 1;
+        const SERVICE_NAME: &'static str = "AlternatingProcessor";
+
+        async fn get_next_job(&self) -> anyhow::Result<Option<(u32, u32)>> {
+            let job_id = self.next_job_id.fetch_add(1, Ordering::SeqCst);
+            Ok(Some((job_id, job_id)))
+        }
+
+        async fn save_failure(&self, _job_id: u32, _started_at: Instant, _error: String) {
+            self.failed.fetch_add(1, Ordering::SeqCst);
+        }
+
+        async fn process_job(
+            &self,
+            job_id: u32,
+            _started_at: Instant,
+        ) -> JoinHandle<anyhow::Result<()>> {
+            let fail_on_save = self.fail_on_save;
+            tokio::spawn(async move {
+                if job_id % 2 == 1 && !fail_on_save {
+                    anyhow::bail!("job {job_id} failed");
+                }
+                Ok(())
+            })
+        }
+
+        async fn save_result(
+            &self,
+            job_id: u32,
+            _started_at: Instant,
+            _artifacts: (),
+        ) -> anyhow::Result<()> {
+            if job_id % 2 == 1 {
+                self.failed.fetch_add(1, Ordering::SeqCst);
+                return Err(JobNotSucceeded(format!("job {job_id} was not delivered")).into());
+            }
+            self.succeeded.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn max_attempts(&self) -> u32 {
+            1
+        }
+
+        async fn get_job_attempts(&self, _job_id: &u32) -> anyhow::Result<u32> {
+            Ok(0)
+        }
+
+        fn count_only_successes(&self) -> bool {
+            self.count_only_successes
+        }
+
+        fn is_paused(&self) -> bool {
+            self.paused.load(Ordering::SeqCst)
+        }
+    }
+
+    async fn run_processor(
+        count_only_successes: bool,
+        fail_on_save: bool,
+        iterations: usize,
+    ) -> (u32, u32) {
+        let processor = AlternatingProcessor {
+            count_only_successes,
+            fail_on_save,
+            ..AlternatingProcessor::default()
+        };
+        let succeeded = processor.succeeded.clone();
+        let failed = processor.failed.clone();
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        processor
+            .run(stop_receiver, Some(iterations))
+            .await
+            .unwrap();
+        (
+            succeeded.load(Ordering::SeqCst),
+            failed.load(Ordering::SeqCst),
+        )
+    }
+
+    #[tokio::test]
+    async fn all_jobs_are_counted_by_default() {
+        let (succeeded, failed) = run_processor(false, false, 4).await;
+        assert_eq!((succeeded, failed), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn counting_only_successful_jobs() {
+        let (succeeded, failed) = run_processor(true, false, 4).await;
+        assert_eq!((succeeded, failed), (4, 3));
+    }
+
+    #[tokio::test]
+    async fn jobs_failing_on_save_are_counted_by_default() {
+        let (succeeded, failed) = run_processor(false, true, 4).await;
+        assert_eq!((succeeded, failed), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn jobs_failing_on_save_are_not_counted_as_successes() {
+        let (succeeded, failed) = run_processor(true, true, 4).await;
+        assert_eq!((succeeded, failed), (4, 3));
+    }
+
+    #[tokio::test]
+    async fn no_jobs_are_picked_while_paused() {
+        let processor = AlternatingProcessor::default();
+        processor.paused.store(true, Ordering::SeqCst);
+        let paused = processor.paused.clone();
+        let succeeded = processor.succeeded.clone();
+        let failed = processor.failed.clone();
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let run_handle = tokio::spawn(processor.run(stop_receiver, Some(2)));
+
+        sleep(Duration::from_millis(50)).await;
+        assert!(!run_handle.is_finished());
+        assert_eq!(succeeded.load(Ordering::SeqCst), 0);
+        assert_eq!(failed.load(Ordering::SeqCst), 0);
+
+        paused.store(false, Ordering::SeqCst);
+        run_handle.await.unwrap().unwrap();
+        assert_eq!(succeeded.load(Ordering::SeqCst), 1);
+        assert_eq!(failed.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,4 +1,4 @@
-use zksync_types::web3::contract;
+use zksync_types::{web3::contract, U256};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ETHSenderError {
@@ -6,4 +6,20 @@ pub enum ETHSenderError {
     EthereumGateWayError(#[from] zksync_eth_client::Error),
     #[error("Token parsing Error: {0}")]
     ParseError(#[from] contract::Error),
+    #[error("Fee conversion Error: {0}")]
+    FeeConversionError(#[from] FeeConversionError),
+}
+
+/// Errors converting fees estimated by the L1 node (e.g., via `linea_estimateGas`) into fees used by the sender.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum FeeConversionError {
+    #[error("estimated `{field}` {value} doesn't fit into u64")]
+    Overflow { field: &'static str, value: U256 },
+    #[error(
+        "total fee per gas (base fee {base_fee_per_gas} + priority fee {priority_fee_per_gas}) doesn't fit into u64"
+    )]
+    TotalFeeOverflow {
+        base_fee_per_gas: u64,
+        priority_fee_per_gas: u64,
+    },
 }
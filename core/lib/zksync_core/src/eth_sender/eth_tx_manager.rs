@@ -2,10 +2,11 @@ use std::{sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use tokio::sync::watch;
-use zksync_config::configs::eth_sender::SenderConfig;
+use zksync_config::{configs::eth_sender::SenderConfig, ETHSenderConfig};
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::{
-    BoundEthInterface, Error, EthInterface, ExecutedTxStatus, RawTransactionBytes, SignedCallResult,
+    fee_oracle_from_config, BoundEthInterface, DynEthInterface, Error, EthInterface,
+    ExecutedTxStatus, FeeEstimate, FeeOracle, RawTransactionBytes, SignedCallResult,
 };
 use zksync_types::{
     eth_sender::EthTx,
@@ -18,13 +19,42 @@ use zksync_types::{
 };
 use zksync_utils::time::seconds_since_epoch;
 
-use super::{metrics::METRICS, ETHSenderError};
+use super::{metrics::METRICS, ETHSenderError, FeeConversionError};
 use crate::{l1_gas_price::L1TxParamsProvider, metrics::BlockL1Stage};
 
-#[derive(Debug)]
-struct EthFee {
-    base_fee_per_gas: u64,
-    priority_fee_per_gas: u64,
+/// Fees of a transaction sent by [`EthTxManager`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct EthFee {
+    pub(super) base_fee_per_gas: u64,
+    pub(super) priority_fee_per_gas: u64,
+}
+
+impl TryFrom<FeeEstimate> for EthFee {
+    type Error = FeeConversionError;
+
+    /// Narrows fees to `u64`. Also checks that the total fee per gas fits into `u64`, since it's used
+    /// as the max fee per gas of the transaction. The gas limit of the estimate is ignored.
+    fn try_from(estimate: FeeEstimate) -> Result<Self, Self::Error> {
+        let narrow = |field, value: U256| {
+            if value > U256::from(u64::MAX) {
+                Err(FeeConversionError::Overflow { field, value })
+            } else {
+                Ok(value.as_u64())
+            }
+        };
+        let base_fee_per_gas = narrow("base_fee_per_gas", estimate.base_fee_per_gas)?;
+        let priority_fee_per_gas = narrow("priority_fee_per_gas", estimate.priority_fee_per_gas)?;
+        if base_fee_per_gas.checked_add(priority_fee_per_gas).is_none() {
+            return Err(FeeConversionError::TotalFeeOverflow {
+                base_fee_per_gas,
+                priority_fee_per_gas,
+            });
+        }
+        Ok(Self {
+            base_fee_per_gas,
+            priority_fee_per_gas,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -51,18 +81,23 @@ pub struct EthTxManager {
     ethereum_gateway: Arc<dyn BoundEthInterface>,
     config: SenderConfig,
     gas_adjuster: Arc<dyn L1TxParamsProvider>,
+    /// Oracle used to estimate fees on Linea if `linea_estimateGas` is enabled in the config.
+    fee_oracle: Arc<dyn FeeOracle>,
 }
 
 impl EthTxManager {
     pub fn new(
-        config: SenderConfig,
+        config: ETHSenderConfig,
         gas_adjuster: Arc<dyn L1TxParamsProvider>,
         ethereum_gateway: Arc<dyn BoundEthInterface>,
     ) -> Self {
+        let client: DynEthInterface = Arc::new(ethereum_gateway.clone());
+        let fee_oracle = fee_oracle_from_config(&config, client);
         Self {
             ethereum_gateway,
-            config,
+            config: config.sender,
             gas_adjuster,
+            fee_oracle,
         }
     }
 
@@ -189,8 +224,7 @@ impl EthTxManager {
         const LINEA_TEST_CHAIN_ID: L1ChainId = L1ChainId(59141);
         const LINEA_MAINNET_CHAIN_ID: L1ChainId = L1ChainId(59144);
         let current_gate_way_chain_id = self.ethereum_gateway.chain_id();
-        let (base_fee_per_gas, priority_fee_per_gas) = if (current_gate_way_chain_id
-            == LINEA_TEST_CHAIN_ID
+        let fee = if (current_gate_way_chain_id == LINEA_TEST_CHAIN_ID
             || current_gate_way_chain_id == LINEA_MAINNET_CHAIN_ID)
             && self.config.enable_linea_estimate_gas
         {
@@ -199,18 +233,18 @@ impl EthTxManager {
                 .to(tx.contract_address)
                 .data(tx.raw_tx.clone().into())
                 .build();
-            let fee = self
-                .ethereum_gateway
-                .linea_estimate_gas(call_request)
+            let estimate = self
+                .fee_oracle
+                .estimate_fees(&call_request, "eth_tx_manager")
                 .await?;
-            (
-                fee.base_fee_per_gas.as_u64(),
-                fee.priority_fee_per_gas.as_u64(),
-            )
+            EthFee::try_from(estimate)?
         } else {
-            let fee = self.calculate_fee(storage, tx, time_in_mempool).await?;
-            (fee.base_fee_per_gas, fee.priority_fee_per_gas)
+            self.calculate_fee(storage, tx, time_in_mempool).await?
         };
+        let EthFee {
+            base_fee_per_gas,
+            priority_fee_per_gas,
+        } = fee;
 
         METRICS.used_base_fee_per_gas.observe(base_fee_per_gas);
         METRICS
@@ -10,6 +10,8 @@ mod zksync_functions;
 mod tests;
 
 pub use self::{
-    aggregator::Aggregator, error::ETHSenderError, eth_tx_aggregator::EthTxAggregator,
+    aggregator::Aggregator,
+    error::{ETHSenderError, FeeConversionError},
+    eth_tx_aggregator::EthTxAggregator,
     eth_tx_manager::EthTxManager,
 };
@@ -2,12 +2,16 @@ use std::sync::Arc;
 
 use assert_matches::assert_matches;
 use once_cell::sync::Lazy;
+use test_casing::{test_casing, Product};
 use zksync_config::{
     configs::eth_sender::{ProofSendingMode, SenderConfig},
     ContractsConfig, ETHSenderConfig, GasAdjusterConfig,
 };
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_eth_client::{clients::MockEthereum, EthInterface};
+use zksync_eth_client::{
+    clients::{LineaEstimateGas, MockEthereum},
+    EthInterface,
+};
 use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{
     aggregated_operations::{
@@ -18,12 +22,13 @@ use zksync_types::{
     ethabi::Token,
     helpers::unix_timestamp_ms,
     web3::contract::Error,
-    Address, L1BatchNumber, L1BlockNumber, ProtocolVersionId, H256,
+    Address, L1BatchNumber, L1BlockNumber, ProtocolVersionId, H256, U256,
 };
 
 use crate::{
     eth_sender::{
-        eth_tx_manager::L1BlockNumbers, Aggregator, ETHSenderError, EthTxAggregator, EthTxManager,
+        eth_tx_manager::{EthFee, L1BlockNumbers},
+        Aggregator, ETHSenderError, EthTxAggregator, EthTxManager, FeeConversionError,
     },
     l1_gas_price::GasAdjuster,
     utils::testonly::create_l1_batch,
@@ -113,11 +118,7 @@ impl EthSenderTester {
             0,
         );
 
-        let manager = EthTxManager::new(
-            eth_sender_config.sender,
-            gas_adjuster.clone(),
-            gateway.clone(),
-        );
+        let manager = EthTxManager::new(eth_sender_config, gas_adjuster.clone(), gateway.clone());
         Self {
             gateway,
             manager,
@@ -870,6 +871,69 @@ async fn get_multicall_data() {
     assert!(multicall_data.is_ok());
 }
 
+const FEE_BOUNDARY_VALUES: [u64; 5] = [0, 1, u32::MAX as u64, u64::MAX - 1, u64::MAX];
+/// Fees not fitting into `u64`: `2^64`, `2^128 - 1` and `2^256 - 1`.
+const OVERFLOWING_FEES: [U256; 3] = [
+    U256([0, 1, 0, 0]),
+    U256([u64::MAX, u64::MAX, 0, 0]),
+    U256::MAX,
+];
+
+#[test_casing(25, Product((FEE_BOUNDARY_VALUES, FEE_BOUNDARY_VALUES)))]
+#[test]
+fn converting_linea_estimate_to_fee(base_fee_per_gas: u64, priority_fee_per_gas: u64) {
+    let estimate = LineaEstimateGas {
+        base_fee_per_gas: base_fee_per_gas.into(),
+        gas_limit: u64::MAX.into(),
+        priority_fee_per_gas: priority_fee_per_gas.into(),
+    };
+    let result = EthFee::try_from(estimate.into_fee_params());
+
+    if base_fee_per_gas.checked_add(priority_fee_per_gas).is_some() {
+        assert_eq!(
+            result.unwrap(),
+            EthFee {
+                base_fee_per_gas,
+                priority_fee_per_gas,
+            }
+        );
+    } else {
+        assert_eq!(
+            result.unwrap_err(),
+            FeeConversionError::TotalFeeOverflow {
+                base_fee_per_gas,
+                priority_fee_per_gas,
+            }
+        );
+    }
+}
+
+#[test_casing(6, Product((OVERFLOWING_FEES, [false, true])))]
+#[test]
+fn converting_linea_estimate_with_overflowing_fee(fee: U256, is_base_fee: bool) {
+    let mut estimate = LineaEstimateGas {
+        base_fee_per_gas: 1.into(),
+        gas_limit: 21_000.into(),
+        priority_fee_per_gas: 1.into(),
+    };
+    let expected_field = if is_base_fee {
+        estimate.base_fee_per_gas = fee;
+        "base_fee_per_gas"
+    } else {
+        estimate.priority_fee_per_gas = fee;
+        "priority_fee_per_gas"
+    };
+
+    let err = EthFee::try_from(estimate.into_fee_params()).unwrap_err();
+    assert_eq!(
+        err,
+        FeeConversionError::Overflow {
+            field: expected_field,
+            value: fee,
+        }
+    );
+}
+
 async fn insert_genesis_protocol_version(tester: &EthSenderTester) {
     tester
         .storage()
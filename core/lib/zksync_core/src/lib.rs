@@ -584,7 +584,7 @@ pub async fn initialize_components(
         let eth_client =
             PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
         let eth_tx_manager_actor = EthTxManager::new(
-            eth_sender.sender,
+            eth_sender,
             gas_adjuster
                 .get_or_init()
                 .await
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use zksync::signer::Signer;
 use zksync_eth_signer::{
-    error::SignerError, raw_ethereum_tx::TransactionParameters, EthereumSigner,
+    error::SignerError, raw_ethereum_tx::TransactionParameters, EthereumSigner, TypedData,
 };
 use zksync_types::{
     fee::Fee, l2::L2Tx, Address, EIP712TypedStructure, Eip712Domain, PackedEthSignature, H256,
@@ -80,6 +80,13 @@ impl EthereumSigner for CorruptedSigner {
         Ok(Self::bad_signature())
     }
 
+    async fn sign_typed_data_v4(
+        &self,
+        _typed_data: &TypedData,
+    ) -> Result<PackedEthSignature, SignerError> {
+        Ok(Self::bad_signature())
+    }
+
     async fn sign_transaction(
         &self,
         _raw_tx: TransactionParameters,
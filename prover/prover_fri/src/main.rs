@@ -52,6 +52,7 @@ async fn graceful_shutdown(port: u16) -> anyhow::Result<impl Future<Output = ()>
             .fri_gpu_prover_queue_dal()
             .update_prover_instance_status(address, GpuProverInstanceStatus::Dead, zone)
             .await
+            .unwrap();
     })
 }
 
@@ -1,6 +1,8 @@
 use std::time::Duration;
 
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Histogram, LabeledFamily, Metrics};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, LabeledFamily, Metrics,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
 pub(crate) struct CircuitLabels {
@@ -38,6 +40,8 @@ pub(crate) struct ProverFriMetrics {
     pub gpu_assembly_generation_time: LabeledFamily<String, Histogram<Duration>>,
     #[metrics(buckets = Buckets::LATENCIES, labels = ["circuit_type"])]
     pub blob_save_time: LabeledFamily<String, Histogram<Duration>>,
+    /// Number of received witness vectors that failed the integrity check.
+    pub corrupted_witness_vectors: Counter,
 }
 
 #[vise::register]
@@ -12,8 +12,10 @@ pub mod gpu_socket_listener {
         sync::watch,
     };
     use zksync_dal::ConnectionPool;
-    use zksync_object_store::bincode;
-    use zksync_prover_fri_types::{CircuitWrapper, ProverServiceDataKey, WitnessVectorArtifacts};
+    use zksync_prover_fri_types::{
+        checksum::{deserialize_with_checksum, ChecksumError, CORRUPTED_ARTIFACT_ERROR_CLASS},
+        CircuitWrapper, ProverServiceDataKey, WitnessVectorArtifacts,
+    };
     use zksync_types::proofs::{AggregationRound, GpuProverInstanceStatus, SocketAddress};
     use zksync_vk_setup_data_server_fri::{
         get_finalization_hints, get_round_for_recursive_circuit_type,
@@ -119,8 +121,38 @@ pub mod gpu_socket_listener {
             METRICS.witness_vector_blob_time[&(file_size_in_gb as u64)]
                 .observe(started_at.elapsed());
 
-            let witness_vector = bincode::deserialize::<WitnessVectorArtifacts>(&assembly)
-                .context("Failed deserializing witness vector")?;
+            let witness_vector =
+                match deserialize_with_checksum::<WitnessVectorArtifacts>(&assembly) {
+                    Ok((_, witness_vector)) => witness_vector,
+                    Err(err @ ChecksumError::Mismatch { job_id }) => {
+                        tracing::error!("Received corrupted witness vector: {err}");
+                        METRICS.corrupted_witness_vectors.inc();
+                        self.pool
+                            .access_storage()
+                            .await
+                            .unwrap()
+                            .fri_prover_jobs_dal()
+                            .save_proof_error_with_class(
+                                job_id,
+                                CORRUPTED_ARTIFACT_ERROR_CLASS,
+                                &err.to_string(),
+                            )
+                            .await
+                            .unwrap();
+                        self.mark_prover_instance_as_available().await;
+                        return Ok(());
+                    }
+                    Err(err @ ChecksumError::UnsupportedVersion { .. }) => {
+                        // The job cannot be attributed, so it's left to be re-queued
+                        // after its processing timeout.
+                        tracing::error!("Received witness vector in unsupported format: {err}");
+                        self.mark_prover_instance_as_available().await;
+                        return Ok(());
+                    }
+                    Err(err) => {
+                        return Err(err).context("Failed deserializing witness vector");
+                    }
+                };
             tracing::info!(
                 "Deserialized witness vector after {:?}",
                 started_at.elapsed()
@@ -159,7 +191,8 @@ pub mod gpu_socket_listener {
                 .unwrap()
                 .fri_gpu_prover_queue_dal()
                 .update_prover_instance_status(self.address.clone(), status, self.zone.clone())
-                .await;
+                .await
+                .unwrap();
             tracing::info!(
                 "Marked prover as {:?} after {:?}",
                 status,
@@ -167,6 +200,25 @@ pub mod gpu_socket_listener {
             );
             Ok(())
         }
+
+        /// Releases the reservation made by the witness vector generator if the received
+        /// witness vector was discarded.
+        async fn mark_prover_instance_as_available(&self) {
+            let queue = self.queue.lock().await;
+            let status = if queue.capacity() == queue.size() {
+                GpuProverInstanceStatus::Full
+            } else {
+                GpuProverInstanceStatus::Available
+            };
+            self.pool
+                .access_storage()
+                .await
+                .unwrap()
+                .fri_gpu_prover_queue_dal()
+                .update_prover_instance_status(self.address.clone(), status, self.zone.clone())
+                .await
+                .unwrap();
+        }
     }
 
     pub fn generate_assembly_for_repeated_proving(
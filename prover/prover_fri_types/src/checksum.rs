@@ -0,0 +1,182 @@
+//! Integrity checks for witness vectors sent from witness vector generators to GPU provers.
+//!
+//! A witness vector is sent over the wire as
+//! `version (1 byte) || job_id (4 bytes, big-endian) || sha256(payload) || payload`,
+//! where `payload` is the bincode-serialized witness vector. The job ID is placed in the header,
+//! so that the receiver can attribute a truncated or corrupted payload to a job.
+//!
+//! There is no negotiation of the format: the receiver rejects data with a version other than
+//! [`FORMAT_VERSION`], so witness vector generators and GPU provers must be upgraded in lockstep
+//! whenever the version is bumped.
+
+use std::fmt;
+
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use zksync_object_store::bincode;
+
+/// Class of the error persisted for jobs whose witness vector failed the integrity check.
+pub const CORRUPTED_ARTIFACT_ERROR_CLASS: &str = "corrupted_artifact";
+
+/// Version of the wire format produced by [`serialize_with_checksum()`]. Must be bumped whenever
+/// the header layout or the payload encoding changes.
+pub const FORMAT_VERSION: u8 = 1;
+
+const VERSION_LEN: usize = 1;
+const JOB_ID_LEN: usize = 4;
+const CHECKSUM_LEN: usize = 32;
+const CHECKSUM_OFFSET: usize = VERSION_LEN + JOB_ID_LEN;
+/// Length of the header prepended to the serialized witness vector.
+pub const HEADER_LEN: usize = CHECKSUM_OFFSET + CHECKSUM_LEN;
+
+/// Errors that can occur when reading a checksummed witness vector.
+#[derive(Debug)]
+pub enum ChecksumError {
+    /// Data is too short to even contain a header.
+    MissingHeader { len: usize },
+    /// Data was produced by a sender using a different version of the format.
+    UnsupportedVersion { version: u8 },
+    /// Checksum of the payload doesn't match the one in the header.
+    Mismatch { job_id: u32 },
+    /// Payload has a valid checksum, but cannot be deserialized.
+    Deserialization { job_id: u32, err: bincode::Error },
+}
+
+impl ChecksumError {
+    /// Returns the ID of the job the data belongs to, if it could be recovered.
+    pub fn job_id(&self) -> Option<u32> {
+        match self {
+            Self::MissingHeader { .. } | Self::UnsupportedVersion { .. } => None,
+            Self::Mismatch { job_id } | Self::Deserialization { job_id, .. } => Some(*job_id),
+        }
+    }
+}
+
+impl fmt::Display for ChecksumError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingHeader { len } => write!(
+                formatter,
+                "witness vector data is too short ({len} bytes) to contain a header"
+            ),
+            Self::UnsupportedVersion { version } => write!(
+                formatter,
+                "unsupported witness vector format version {version} (expected {FORMAT_VERSION}); \
+                 witness vector generators and provers must run compatible versions"
+            ),
+            Self::Mismatch { job_id } => write!(
+                formatter,
+                "checksum mismatch for witness vector of job {job_id}; data is corrupted"
+            ),
+            Self::Deserialization { job_id, err } => write!(
+                formatter,
+                "failed deserializing witness vector of job {job_id}: {err}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChecksumError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Deserialization { err, .. } => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Serializes `value` using bincode and prepends a header with the format version, `job_id`
+/// and the checksum of the payload.
+pub fn serialize_with_checksum<T: Serialize>(job_id: u32, value: &T) -> bincode::Result<Vec<u8>> {
+    let mut buffer = vec![0_u8; HEADER_LEN];
+    bincode::serialize_into(&mut buffer, value)?;
+    let checksum = Sha256::digest(&buffer[HEADER_LEN..]);
+    buffer[0] = FORMAT_VERSION;
+    buffer[VERSION_LEN..CHECKSUM_OFFSET].copy_from_slice(&job_id.to_be_bytes());
+    buffer[CHECKSUM_OFFSET..HEADER_LEN].copy_from_slice(&checksum);
+    Ok(buffer)
+}
+
+/// Verifies the checksum of data produced by [`serialize_with_checksum()`] and deserializes it.
+/// Returns the job ID together with the deserialized value.
+pub fn deserialize_with_checksum<T: DeserializeOwned>(
+    data: &[u8],
+) -> Result<(u32, T), ChecksumError> {
+    if data.len() < HEADER_LEN {
+        return Err(ChecksumError::MissingHeader { len: data.len() });
+    }
+    let (header, payload) = data.split_at(HEADER_LEN);
+    if header[0] != FORMAT_VERSION {
+        return Err(ChecksumError::UnsupportedVersion { version: header[0] });
+    }
+    let job_id = u32::from_be_bytes(header[VERSION_LEN..CHECKSUM_OFFSET].try_into().unwrap());
+    if Sha256::digest(payload).as_slice() != &header[CHECKSUM_OFFSET..] {
+        return Err(ChecksumError::Mismatch { job_id });
+    }
+    let value = bincode::deserialize(payload)
+        .map_err(|err| ChecksumError::Deserialization { job_id, err })?;
+    Ok((job_id, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_value() -> Vec<(u64, String)> {
+        (0..100).map(|i| (i, format!("value #{i}"))).collect()
+    }
+
+    #[test]
+    fn checksum_roundtrip() {
+        let value = test_value();
+        let data = serialize_with_checksum(42, &value).unwrap();
+        let (job_id, restored): (_, Vec<(u64, String)>) = deserialize_with_checksum(&data).unwrap();
+        assert_eq!(job_id, 42);
+        assert_eq!(restored, value);
+    }
+
+    #[test]
+    fn truncated_data_is_detected() {
+        let data = serialize_with_checksum(42, &test_value()).unwrap();
+        let err =
+            deserialize_with_checksum::<Vec<(u64, String)>>(&data[..data.len() - 1]).unwrap_err();
+        assert!(
+            matches!(err, ChecksumError::Mismatch { job_id: 42 }),
+            "{err}"
+        );
+
+        let err = deserialize_with_checksum::<Vec<(u64, String)>>(&data[..10]).unwrap_err();
+        assert!(
+            matches!(err, ChecksumError::MissingHeader { len: 10 }),
+            "{err}"
+        );
+        assert_eq!(err.job_id(), None);
+    }
+
+    #[test]
+    fn unsupported_version_is_detected() {
+        let mut data = serialize_with_checksum(42, &test_value()).unwrap();
+        assert_eq!(data[0], FORMAT_VERSION);
+        // Data produced before the version byte was introduced starts with the job ID.
+        data[0] = 0;
+        let err = deserialize_with_checksum::<Vec<(u64, String)>>(&data).unwrap_err();
+        assert!(
+            matches!(err, ChecksumError::UnsupportedVersion { version: 0 }),
+            "{err}"
+        );
+        assert_eq!(err.job_id(), None);
+    }
+
+    #[test]
+    fn corrupted_data_is_detected() {
+        let mut data = serialize_with_checksum(42, &test_value()).unwrap();
+        let last_byte = data.last_mut().unwrap();
+        *last_byte ^= 1;
+        let err = deserialize_with_checksum::<Vec<(u64, String)>>(&data).unwrap_err();
+        assert!(
+            matches!(err, ChecksumError::Mismatch { job_id: 42 }),
+            "{err}"
+        );
+        assert_eq!(err.job_id(), Some(42));
+    }
+}
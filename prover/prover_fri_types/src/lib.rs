@@ -14,6 +14,7 @@ use circuit_definitions::{
 use zksync_object_store::{serialize_using_bincode, Bucket, FriCircuitKey, StoredObject};
 use zksync_types::{proofs::AggregationRound, L1BatchNumber};
 
+pub mod checksum;
 pub mod queue;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
@@ -1,7 +1,7 @@
 use std::time::Instant;
 
-use zksync_dal::StorageProcessor;
-use zksync_object_store::{FriCircuitKey, ObjectStore};
+use zksync_dal::{SqlxError, StorageProcessor};
+use zksync_object_store::{FriCircuitKey, ObjectStore, ObjectStoreError};
 use zksync_prover_fri_types::{
     circuit_definitions::{
         circuit_definitions::recursion_layer::{
@@ -12,7 +12,8 @@ use zksync_prover_fri_types::{
     get_current_pod_name, CircuitWrapper, ProverJob, ProverServiceDataKey,
 };
 use zksync_types::{
-    basic_fri_types::CircuitIdRoundTuple, proofs::AggregationRound,
+    basic_fri_types::CircuitIdRoundTuple,
+    proofs::{AggregationRound, FriProverJobMetadata},
     protocol_version::L1VerifierConfig,
 };
 
@@ -28,10 +29,35 @@ pub async fn fetch_next_circuit(
     circuit_ids_for_round_to_be_proven: &Vec<CircuitIdRoundTuple>,
     vk_commitments: &L1VerifierConfig,
 ) -> Option<ProverJob> {
+    let prover_job = pick_next_prover_job(
+        storage,
+        circuit_ids_for_round_to_be_proven,
+        vk_commitments,
+        false,
+    )
+    .await
+    .unwrap()?;
+    let job = fetch_circuit_input(blob_store, &prover_job)
+        .await
+        .unwrap_or_else(|err| panic!("{err:?}"));
+    Some(job)
+}
+
+/// Marks the next queued prover job as `in_progress` and returns its metadata
+/// without downloading the circuit input.
+///
+/// If `critical_path_first` is set, jobs of the oldest batch that doesn't have a complete proof yet
+/// are picked first, both by generalized and specialized provers.
+pub async fn pick_next_prover_job(
+    storage: &mut StorageProcessor<'_>,
+    circuit_ids_for_round_to_be_proven: &[CircuitIdRoundTuple],
+    vk_commitments: &L1VerifierConfig,
+    critical_path_first: bool,
+) -> Result<Option<FriProverJobMetadata>, SqlxError> {
     let protocol_versions = storage
         .fri_protocol_versions_dal()
         .protocol_version_for(vk_commitments)
-        .await;
+        .await?;
     let pod_name = get_current_pod_name();
     let prover_job = match &circuit_ids_for_round_to_be_proven.is_empty() {
         false => {
@@ -42,6 +68,7 @@ pub async fn fetch_next_circuit(
                     circuit_ids_for_round_to_be_proven,
                     &protocol_versions,
                     &pod_name,
+                    critical_path_first,
                 )
                 .await
         }
@@ -49,12 +76,21 @@ pub async fn fetch_next_circuit(
             // Generalized prover: proving all circuits.
             storage
                 .fri_prover_jobs_dal()
-                .get_next_job(&protocol_versions, &pod_name)
+                .get_next_job(&protocol_versions, &pod_name, critical_path_first)
                 .await
         }
     }?;
-    tracing::info!("Started processing prover job: {:?}", prover_job);
+    if let Some(prover_job) = &prover_job {
+        tracing::info!("Started processing prover job: {:?}", prover_job);
+    }
+    Ok(prover_job)
+}
 
+/// Downloads the circuit input for a previously picked prover job.
+pub async fn fetch_circuit_input(
+    blob_store: &dyn ObjectStore,
+    prover_job: &FriProverJobMetadata,
+) -> Result<ProverJob, ObjectStoreError> {
     let circuit_key = FriCircuitKey {
         block_number: prover_job.block_number,
         sequence_number: prover_job.sequence_number,
@@ -63,10 +99,7 @@ pub async fn fetch_next_circuit(
         depth: prover_job.depth,
     };
     let started_at = Instant::now();
-    let input = blob_store
-        .get(circuit_key)
-        .await
-        .unwrap_or_else(|err| panic!("{err:?}"));
+    let input = blob_store.get(circuit_key).await?;
 
     let label = CircuitLabels {
         circuit_type: prover_job.circuit_id,
@@ -78,7 +111,7 @@ pub async fn fetch_next_circuit(
         circuit_id: prover_job.circuit_id,
         round: prover_job.aggregation_round,
     };
-    Some(ProverJob::new(
+    Ok(ProverJob::new(
         prover_job.block_number,
         prover_job.id,
         input,
@@ -108,7 +108,8 @@ async fn main() -> anyhow::Result<()> {
         .unwrap()
         .fri_protocol_versions_dal()
         .protocol_version_for(&vk_commitments)
-        .await;
+        .await
+        .unwrap();
 
     // If `batch_size` is none, it means that the job is 'looping forever' (this is the usual setup in local network).
     // At the same time, we're reading the `protocol_version` only once at startup - so if there is no protocol version
@@ -0,0 +1,123 @@
+use std::fmt;
+
+use vise::{EncodeLabelSet, EncodeLabelValue};
+
+/// Maximum length (in bytes) of an error message persisted to the database.
+pub const MAX_ERROR_MESSAGE_LEN: usize = 1024;
+
+/// Class of a witness vector job failure. Persisted alongside the error message
+/// so that failures can be charted without parsing free-form error strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "error_class", rename_all = "snake_case")]
+pub enum ErrorClass {
+    /// Circuit input could not be fetched from the object store.
+    Download,
+    /// Finalization hints could not be loaded, or circuit synthesis failed.
+    Synthesis,
+    /// Witness vector artifacts could not be serialized.
+    Serialization,
+    /// Witness vector could not be delivered to a GPU prover.
+    Delivery,
+    /// Any other failure, e.g. a panic outside of synthesis.
+    Other,
+}
+
+impl ErrorClass {
+    const ALL: [Self; 5] = [
+        Self::Download,
+        Self::Synthesis,
+        Self::Serialization,
+        Self::Delivery,
+        Self::Other,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Download => "download",
+            Self::Synthesis => "synthesis",
+            Self::Serialization => "serialization",
+            Self::Delivery => "delivery",
+            Self::Other => "other",
+        }
+    }
+
+    /// Wraps an error into an `anyhow::Error` tagged with this class. The tag survives
+    /// the conversion to a string in `JobProcessor::save_failure()`, where it is recovered
+    /// using [`Self::classify()`].
+    pub fn error(self, err: impl fmt::Display) -> anyhow::Error {
+        anyhow::anyhow!("[{}] {err}", self.as_str())
+    }
+
+    /// Splits an error message produced by [`Self::error()`] into its class and the original message.
+    /// Messages without a recognized tag are classified as [`Self::Other`].
+    pub fn classify(message: &str) -> (Self, &str) {
+        for class in Self::ALL {
+            let tag = class.as_str();
+            let stripped = message
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_prefix(tag))
+                .and_then(|rest| rest.strip_prefix("] "));
+            if let Some(stripped) = stripped {
+                return (class, stripped);
+            }
+        }
+        (Self::Other, message)
+    }
+}
+
+impl fmt::Display for ErrorClass {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+/// Truncates the error message to [`MAX_ERROR_MESSAGE_LEN`] bytes, respecting char boundaries.
+pub fn truncate_error_message(message: &str) -> &str {
+    if message.len() <= MAX_ERROR_MESSAGE_LEN {
+        return message;
+    }
+    let mut end = MAX_ERROR_MESSAGE_LEN;
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    &message[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classification_roundtrip() {
+        for class in ErrorClass::ALL {
+            let message = class.error("something went wrong").to_string();
+            assert_eq!(
+                ErrorClass::classify(&message),
+                (class, "something went wrong")
+            );
+        }
+    }
+
+    #[test]
+    fn untagged_messages_are_classified_as_other() {
+        assert_eq!(
+            ErrorClass::classify("Unknown panic"),
+            (ErrorClass::Other, "Unknown panic")
+        );
+        assert_eq!(
+            ErrorClass::classify("[downloads] test"),
+            (ErrorClass::Other, "[downloads] test")
+        );
+    }
+
+    #[test]
+    fn truncating_error_message() {
+        let short = "short message";
+        assert_eq!(truncate_error_message(short), short);
+
+        let long = "ü".repeat(MAX_ERROR_MESSAGE_LEN);
+        let truncated = truncate_error_message(&long);
+        assert!(truncated.len() <= MAX_ERROR_MESSAGE_LEN);
+        assert!(long.starts_with(truncated));
+    }
+}
@@ -1,30 +1,91 @@
 use std::{
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 
 use anyhow::Context as _;
 use async_trait::async_trait;
 use tokio::{task::JoinHandle, time::sleep};
-use zksync_config::configs::FriWitnessVectorGeneratorConfig;
+use zksync_config::configs::{
+    fri_witness_vector_generator::JobOrdering, FriWitnessVectorGeneratorConfig,
+};
 use zksync_dal::ConnectionPool;
 use zksync_object_store::ObjectStore;
 use zksync_prover_fri_types::{
+    checksum::serialize_with_checksum,
     circuit_definitions::boojum::field::goldilocks::GoldilocksField, CircuitWrapper, ProverJob,
     WitnessVectorArtifacts,
 };
 use zksync_prover_fri_utils::{
-    fetch_next_circuit, get_numeric_circuit_id, socket_utils::send_assembly,
+    fetch_circuit_input, get_numeric_circuit_id, pick_next_prover_job, socket_utils::send_assembly,
 };
-use zksync_queued_job_processor::JobProcessor;
+use zksync_queued_job_processor::{JobNotSucceeded, JobProcessor};
 use zksync_types::{
     basic_fri_types::CircuitIdRoundTuple,
-    proofs::{GpuProverInstanceStatus, SocketAddress},
+    proofs::{FriProverJobMetadata, GpuProverInstanceStatus, SocketAddress},
     protocol_version::L1VerifierConfig,
 };
+use zksync_utils::panic_extractor::try_extract_panic_message;
 use zksync_vk_setup_data_server_fri::get_finalization_hints;
 
-use crate::metrics::METRICS;
+use crate::{
+    error::{truncate_error_message, ErrorClass},
+    metrics::METRICS,
+    rate_limiter::JobRateLimiter,
+    reconnect::ReconnectPolicy,
+};
+
+/// Witness vector serialized for sending to a prover instance.
+#[derive(Debug)]
+pub struct SerializedWitnessVector {
+    /// Numeric ID of the circuit the witness vector was generated for.
+    pub circuit_id: u8,
+    /// Witness vector artifacts serialized with [`serialize_with_checksum()`].
+    pub bytes: Vec<u8>,
+}
+
+impl SerializedWitnessVector {
+    /// Serializes `artifacts` for the job they were generated for.
+    /// Returned errors are tagged with [`ErrorClass::Serialization`].
+    pub fn new(artifacts: &WitnessVectorArtifacts) -> anyhow::Result<Self> {
+        let bytes =
+            serialize_with_checksum(artifacts.prover_job.job_id, artifacts).map_err(|err| {
+                ErrorClass::Serialization.error(format_args!(
+                    "Failed to serialize witness vector artifacts: {err}"
+                ))
+            })?;
+        Ok(Self {
+            circuit_id: get_numeric_circuit_id(&artifacts.prover_job.circuit_wrapper),
+            bytes,
+        })
+    }
+}
+
+/// Shared flag allowing to pause and resume job picking by a [`WitnessVectorGenerator`]
+/// without restarting it.
+#[derive(Debug, Clone, Default)]
+pub struct PauseFlag(Arc<AtomicBool>);
+
+impl PauseFlag {
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.0.store(paused, Ordering::SeqCst);
+        METRICS.paused.set(paused.into());
+    }
+
+    /// Toggles the flag, returning the new state.
+    pub fn toggle(&self) -> bool {
+        let paused = !self.0.fetch_xor(true, Ordering::SeqCst);
+        METRICS.paused.set(paused.into());
+        paused
+    }
+}
 
 pub struct WitnessVectorGenerator {
     blob_store: Arc<dyn ObjectStore>,
@@ -34,6 +95,10 @@ pub struct WitnessVectorGenerator {
     config: FriWitnessVectorGeneratorConfig,
     vk_commitments: L1VerifierConfig,
     max_attempts: u32,
+    count_only_successes: bool,
+    pause_flag: PauseFlag,
+    rate_limiter: Option<JobRateLimiter>,
+    reconnect_policy: ReconnectPolicy,
 }
 
 impl WitnessVectorGenerator {
@@ -46,6 +111,8 @@ impl WitnessVectorGenerator {
         vk_commitments: L1VerifierConfig,
         max_attempts: u32,
     ) -> Self {
+        let rate_limiter = config.max_jobs_per_minute.map(JobRateLimiter::per_minute);
+        let reconnect_policy = ReconnectPolicy::from_config(&config);
         Self {
             blob_store,
             pool: prover_connection_pool,
@@ -54,9 +121,24 @@ impl WitnessVectorGenerator {
             config,
             vk_commitments,
             max_attempts,
+            count_only_successes: false,
+            pause_flag: PauseFlag::default(),
+            rate_limiter,
+            reconnect_policy,
         }
     }
 
+    /// Returns a handle that can be used to pause and resume job picking after the generator is started.
+    pub fn pause_flag(&self) -> PauseFlag {
+        self.pause_flag.clone()
+    }
+
+    /// Makes the generator count only successfully processed jobs as iterations.
+    pub fn with_count_only_successes(mut self, count_only_successes: bool) -> Self {
+        self.count_only_successes = count_only_successes;
+        self
+    }
+
     pub fn generate_witness_vector(job: ProverJob) -> anyhow::Result<WitnessVectorArtifacts> {
         let finalization_hints = get_finalization_hints(job.setup_data_key.clone())
             .context("get_finalization_hints()")?;
@@ -73,58 +155,114 @@ impl WitnessVectorGenerator {
             job,
         ))
     }
+
+    /// Downloads the circuit input for the picked job, generates a witness vector for it
+    /// and serializes the vector. Returned errors are tagged with an [`ErrorClass`].
+    pub async fn fetch_and_generate_witness_vector(
+        blob_store: Arc<dyn ObjectStore>,
+        job: FriProverJobMetadata,
+    ) -> anyhow::Result<SerializedWitnessVector> {
+        let job = fetch_circuit_input(&*blob_store, &job)
+            .await
+            .map_err(|err| ErrorClass::Download.error(err))?;
+        let artifacts = tokio::task::spawn_blocking(move || Self::generate_witness_vector(job))
+            .await
+            .map_err(|err| ErrorClass::Synthesis.error(try_extract_panic_message(err)))?
+            .map_err(|err| ErrorClass::Synthesis.error(format_args!("{err:#}")))?;
+        SerializedWitnessVector::new(&artifacts)
+    }
 }
 
 #[async_trait]
 impl JobProcessor for WitnessVectorGenerator {
-    type Job = ProverJob;
+    type Job = FriProverJobMetadata;
     type JobId = u32;
-    type JobArtifacts = WitnessVectorArtifacts;
+    type JobArtifacts = SerializedWitnessVector;
 
     const POLLING_INTERVAL_MS: u64 = 15000;
     const SERVICE_NAME: &'static str = "WitnessVectorGenerator";
 
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
-        let mut storage = self.pool.access_storage().await.unwrap();
-        let Some(job) = fetch_next_circuit(
-            &mut storage,
-            &*self.blob_store,
-            &self.circuit_ids_for_round_to_be_proven,
-            &self.vk_commitments,
-        )
-        .await
-        else {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.wait_for_token().await;
+        }
+        let critical_path_first = self.config.job_ordering == JobOrdering::CriticalPathFirst;
+        let job = self
+            .reconnect_policy
+            .run(|| async move {
+                let mut storage = self.pool.access_storage().await?;
+                pick_next_prover_job(
+                    &mut storage,
+                    &self.circuit_ids_for_round_to_be_proven,
+                    &self.vk_commitments,
+                    critical_path_first,
+                )
+                .await
+                .context("pick_next_prover_job()")
+            })
+            .await?;
+        let Some(job) = job else {
             return Ok(None);
         };
-        Ok(Some((job.job_id, job)))
+        if critical_path_first {
+            // The job is already picked, so failing to report the lag must not fail the job.
+            let oldest_unproven_batch = async {
+                self.pool
+                    .access_storage()
+                    .await?
+                    .fri_prover_jobs_dal()
+                    .get_oldest_unproven_l1_batch_number()
+                    .await
+                    .context("get_oldest_unproven_l1_batch_number()")
+            };
+            match oldest_unproven_batch.await {
+                Ok(Some(oldest_unproven_batch)) => {
+                    let lag = job.block_number.0.saturating_sub(oldest_unproven_batch.0);
+                    METRICS.picked_job_batch_lag.observe(lag as usize);
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    tracing::warn!("Failed reporting the batch lag of a picked job: {err:#}")
+                }
+            }
+        }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.consume();
+        }
+        Ok(Some((job.id, job)))
     }
 
     async fn save_failure(&self, job_id: Self::JobId, _started_at: Instant, error: String) {
-        self.pool
-            .access_storage()
-            .await
-            .unwrap()
-            .fri_prover_jobs_dal()
-            .save_proof_error(job_id, error)
-            .await;
+        let (error_class, message) = ErrorClass::classify(&error);
+        let result = save_classified_failure(
+            &self.pool,
+            &self.reconnect_policy,
+            job_id,
+            error_class,
+            message,
+        )
+        .await;
+        if let Err(err) = result {
+            tracing::error!("Failed saving failure of witness vector job {job_id}: {err:#}");
+        }
     }
 
     async fn process_job(
         &self,
-        job: ProverJob,
+        job: FriProverJobMetadata,
         _started_at: Instant,
     ) -> JoinHandle<anyhow::Result<Self::JobArtifacts>> {
-        tokio::task::spawn_blocking(move || Self::generate_witness_vector(job))
+        let blob_store = self.blob_store.clone();
+        tokio::spawn(Self::fetch_and_generate_witness_vector(blob_store, job))
     }
 
     async fn save_result(
         &self,
         job_id: Self::JobId,
         started_at: Instant,
-        artifacts: WitnessVectorArtifacts,
+        artifacts: SerializedWitnessVector,
     ) -> anyhow::Result<()> {
-        let circuit_type =
-            get_numeric_circuit_id(&artifacts.prover_job.circuit_wrapper).to_string();
+        let circuit_type = artifacts.circuit_id.to_string();
 
         METRICS.gpu_witness_vector_generation_time[&circuit_type].observe(started_at.elapsed());
 
@@ -134,25 +272,28 @@ impl JobProcessor for WitnessVectorGenerator {
             started_at.elapsed()
         );
 
-        let serialized: Vec<u8> =
-            bincode::serialize(&artifacts).expect("Failed to serialize witness vector artifacts");
+        let serialized = artifacts.bytes;
 
         let now = Instant::now();
         let mut attempts = 0;
 
         while now.elapsed() < self.config.prover_instance_wait_timeout() {
             let prover = self
-                .pool
-                .access_storage()
-                .await
-                .unwrap()
-                .fri_gpu_prover_queue_dal()
-                .lock_available_prover(
-                    self.config.max_prover_reservation_duration(),
-                    self.config.specialized_group_id,
-                    self.zone.clone(),
-                )
-                .await;
+                .reconnect_policy
+                .run(|| async move {
+                    self.pool
+                        .access_storage()
+                        .await?
+                        .fri_gpu_prover_queue_dal()
+                        .lock_available_prover(
+                            self.config.max_prover_reservation_duration(),
+                            self.config.specialized_group_id,
+                            self.zone.clone(),
+                        )
+                        .await
+                        .context("lock_available_prover()")
+                })
+                .await?;
 
             if let Some(address) = prover {
                 tracing::info!(
@@ -160,7 +301,15 @@ impl JobProcessor for WitnessVectorGenerator {
                     now.elapsed()
                 );
                 let result = send_assembly(job_id, &serialized, &address);
-                handle_send_result(&result, job_id, &address, &self.pool, self.zone.clone()).await;
+                handle_send_result(
+                    &result,
+                    job_id,
+                    &address,
+                    &self.pool,
+                    &self.reconnect_policy,
+                    self.zone.clone(),
+                )
+                .await?;
 
                 if result.is_ok() {
                     METRICS.prover_waiting_time[&circuit_type].observe(now.elapsed());
@@ -191,25 +340,39 @@ impl JobProcessor for WitnessVectorGenerator {
         tracing::warn!(
             "Not able to get any free prover instance for sending witness vector for job: {job_id} after {:?}", now.elapsed()
         );
-        Ok(())
+        Err(JobNotSucceeded(format!(
+            "witness vector for job {job_id} was not delivered to a prover after {attempts} send attempts"
+        ))
+        .into())
     }
 
     fn max_attempts(&self) -> u32 {
         self.max_attempts
     }
 
+    fn count_only_successes(&self) -> bool {
+        self.count_only_successes
+    }
+
+    fn is_paused(&self) -> bool {
+        self.pause_flag.is_paused()
+    }
+
     async fn get_job_attempts(&self, job_id: &u32) -> anyhow::Result<u32> {
-        let mut prover_storage = self
-            .pool
-            .access_storage()
+        let job_id = *job_id;
+        self.reconnect_policy
+            .run(|| async move {
+                self.pool
+                    .access_storage()
+                    .await
+                    .context("failed to acquire DB connection for WitnessVectorGenerator")?
+                    .fri_prover_jobs_dal()
+                    .get_prover_job_attempts(job_id)
+                    .await
+                    .map(|attempts| attempts.unwrap_or(0))
+                    .context("failed to get job attempts for WitnessVectorGenerator")
+            })
             .await
-            .context("failed to acquire DB connection for WitnessVectorGenerator")?;
-        prover_storage
-            .fri_prover_jobs_dal()
-            .get_prover_job_attempts(*job_id)
-            .await
-            .map(|attempts| attempts.unwrap_or(0))
-            .context("failed to get job attempts for WitnessVectorGenerator")
     }
 }
 
@@ -218,8 +381,9 @@ async fn handle_send_result(
     job_id: u32,
     address: &SocketAddress,
     pool: &ConnectionPool,
+    reconnect_policy: &ReconnectPolicy,
     zone: String,
-) {
+) -> anyhow::Result<()> {
     match result {
         Ok((elapsed, len)) => {
             let blob_size_in_mb = len / (1024 * 1024);
@@ -231,12 +395,16 @@ async fn handle_send_result(
 
             METRICS.blob_sending_time[&blob_size_in_mb.to_string()].observe(*elapsed);
 
-            pool.access_storage()
-                .await
-                .unwrap()
-                .fri_prover_jobs_dal()
-                .update_status(job_id, "in_gpu_proof")
-                .await;
+            reconnect_policy
+                .run(|| async move {
+                    pool.access_storage()
+                        .await?
+                        .fri_prover_jobs_dal()
+                        .update_status(job_id, "in_gpu_proof")
+                        .await
+                        .context("update_status()")
+                })
+                .await?;
         }
 
         Err(err) => {
@@ -246,20 +414,58 @@ async fn handle_send_result(
             );
 
             // mark prover instance in `gpu_prover_queue` dead
-            pool.access_storage()
-                .await
-                .unwrap()
-                .fri_gpu_prover_queue_dal()
-                .update_prover_instance_status(address.clone(), GpuProverInstanceStatus::Dead, zone)
-                .await;
+            reconnect_policy
+                .run(|| {
+                    let zone = zone.clone();
+                    async move {
+                        pool.access_storage()
+                            .await?
+                            .fri_gpu_prover_queue_dal()
+                            .update_prover_instance_status(
+                                address.clone(),
+                                GpuProverInstanceStatus::Dead,
+                                zone,
+                            )
+                            .await
+                            .context("update_prover_instance_status()")
+                    }
+                })
+                .await?;
 
             // mark the job as failed
-            pool.access_storage()
-                .await
-                .unwrap()
-                .fri_prover_jobs_dal()
-                .save_proof_error(job_id, "prover instance unreachable".to_string())
-                .await;
+            save_classified_failure(
+                pool,
+                reconnect_policy,
+                job_id,
+                ErrorClass::Delivery,
+                "prover instance unreachable",
+            )
+            .await?;
         }
     }
+    Ok(())
+}
+
+async fn save_classified_failure(
+    pool: &ConnectionPool,
+    reconnect_policy: &ReconnectPolicy,
+    job_id: u32,
+    error_class: ErrorClass,
+    message: &str,
+) -> anyhow::Result<()> {
+    METRICS.job_failures[&error_class].inc();
+    reconnect_policy
+        .run(|| async move {
+            pool.access_storage()
+                .await?
+                .fri_prover_jobs_dal()
+                .save_proof_error_with_class(
+                    job_id,
+                    error_class.as_str(),
+                    truncate_error_message(message),
+                )
+                .await
+                .context("save_proof_error_with_class()")
+        })
+        .await
 }
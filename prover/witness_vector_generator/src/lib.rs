@@ -1,5 +1,10 @@
 #![feature(generic_const_exprs)]
 
+pub mod error;
 pub mod generator;
 
 pub mod metrics;
+pub mod queue_monitor;
+pub mod rate_limiter;
+pub mod reconnect;
+pub mod self_test;
@@ -1,12 +1,18 @@
 #![feature(generic_const_exprs)]
 
+use std::{path::Path, time::Duration};
+
 use anyhow::Context as _;
 use prometheus_exporter::PrometheusExporterConfig;
 use structopt::StructOpt;
-use tokio::sync::{oneshot, watch};
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::{oneshot, watch},
+};
 use zksync_config::configs::{
-    fri_prover_group::FriProverGroupConfig, object_store::ObjectStoreMode, FriProverConfig,
-    FriWitnessVectorGeneratorConfig, PostgresConfig,
+    fri_prover_group::FriProverGroupConfig, house_keeper::HouseKeeperConfig,
+    object_store::ObjectStoreMode, FriProverConfig, FriWitnessVectorGeneratorConfig,
+    PostgresConfig,
 };
 use zksync_dal::ConnectionPool;
 use zksync_env_config::{object_store::ProverObjectStoreConfig, FromEnv};
@@ -16,10 +22,19 @@ use zksync_queued_job_processor::JobProcessor;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 use zksync_vk_setup_data_server_fri::commitment_utils::get_cached_commitments;
 
-use crate::generator::WitnessVectorGenerator;
+use crate::{
+    generator::{PauseFlag, WitnessVectorGenerator},
+    metrics::METRICS,
+    queue_monitor::OldestQueuedJobReporter,
+};
 
+mod error;
 mod generator;
 mod metrics;
+mod queue_monitor;
+mod rate_limiter;
+mod reconnect;
+mod self_test;
 
 #[derive(Debug, StructOpt)]
 #[structopt(
@@ -30,6 +45,9 @@ struct Opt {
     /// Number of times `witness_vector_generator` should be run.
     #[structopt(short = "n", long = "n_iterations")]
     number_of_iterations: Option<usize>,
+    /// If set, only successfully processed jobs are counted towards `n_iterations`.
+    #[structopt(long = "count-only-successes")]
+    count_only_successes: bool,
 }
 
 #[tokio::main]
@@ -74,6 +92,34 @@ async fn main() -> anyhow::Result<()> {
         get_all_circuit_id_round_tuples_for(circuit_ids_for_round_to_be_proven);
     let fri_prover_config = FriProverConfig::from_env().context("FriProverConfig::from_env()")?;
     let zone = fri_prover_config.zone_read_url.clone();
+    if let Some((circuit_path, expected_checksum)) = config.self_test()? {
+        let circuit_path = circuit_path.to_owned();
+        let expected_checksum = expected_checksum.to_owned();
+        let duration = tokio::task::spawn_blocking(move || {
+            self_test::run_self_test(Path::new(&circuit_path), &expected_checksum)
+        })
+        .await
+        .context("self-test panicked")?
+        .context("startup self-test failed; refusing to serve jobs")?;
+        tracing::info!("Startup self-test passed in {duration:?}");
+        METRICS.self_test_duration.set(duration);
+    }
+    // The reporter uses a separate connection, so that it doesn't compete with job picking.
+    let reporter_pool = ConnectionPool::singleton(postgres_config.prover_url()?)
+        .build()
+        .await
+        .context("failed to build a connection pool for the queue reporter")?;
+    // Reported at the same interval as the prover queue depth.
+    let reporting_interval = Duration::from_millis(
+        HouseKeeperConfig::from_env()
+            .context("HouseKeeperConfig::from_env()")?
+            .fri_prover_stats_reporting_interval_ms,
+    );
+    let oldest_queued_job_reporter = OldestQueuedJobReporter::new(
+        reporter_pool,
+        circuit_ids_for_round_to_be_proven.clone(),
+        reporting_interval,
+    );
     let vk_commitments = get_cached_commitments();
     let witness_vector_generator = WitnessVectorGenerator::new(
         blob_store,
@@ -83,7 +129,9 @@ async fn main() -> anyhow::Result<()> {
         config,
         vk_commitments,
         fri_prover_config.max_attempts,
-    );
+    )
+    .with_count_only_successes(opt.count_only_successes);
+    let pause_flag = witness_vector_generator.pause_flag();
 
     let (stop_sender, stop_receiver) = watch::channel(false);
 
@@ -98,8 +146,12 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("Starting witness vector generation for group: {} with circuits: {:?} in zone: {} with vk_commitments: {:?}", specialized_group_id, circuit_ids_for_round_to_be_proven, zone, vk_commitments);
 
+    METRICS.observe_generator_info(specialized_group_id, &zone);
+
     let tasks = vec![
         tokio::spawn(exporter_config.run(stop_receiver.clone())),
+        tokio::spawn(toggle_pause_on_signal(pause_flag, stop_receiver.clone())),
+        tokio::spawn(oldest_queued_job_reporter.run(stop_receiver.clone())),
         tokio::spawn(witness_vector_generator.run(stop_receiver, opt.number_of_iterations)),
     ];
 
@@ -114,3 +166,24 @@ async fn main() -> anyhow::Result<()> {
     stop_sender.send(true).ok();
     Ok(())
 }
+
+/// Toggles job picking each time the process receives `SIGUSR1`.
+async fn toggle_pause_on_signal(
+    pause_flag: PauseFlag,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let mut signals =
+        signal(SignalKind::user_defined1()).context("failed installing SIGUSR1 handler")?;
+    loop {
+        tokio::select! {
+            _ = signals.recv() => {
+                if pause_flag.toggle() {
+                    tracing::info!("Received SIGUSR1, pausing job picking");
+                } else {
+                    tracing::info!("Received SIGUSR1, resuming job picking");
+                }
+            }
+            _ = stop_receiver.changed() => return Ok(()),
+        }
+    }
+}
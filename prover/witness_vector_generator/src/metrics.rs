@@ -1,6 +1,22 @@
 use std::time::Duration;
 
-use vise::{Buckets, Histogram, LabeledFamily, Metrics};
+use vise::{Buckets, Counter, EncodeLabelSet, Family, Gauge, Histogram, LabeledFamily, Metrics};
+
+use crate::error::ErrorClass;
+
+/// Labels identifying a witness vector generator deployment.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(crate) struct GeneratorInfoLabels {
+    group_id: String,
+    zone: String,
+}
+
+/// Labels identifying a circuit in the prover queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(crate) struct QueuedJobLabels {
+    pub circuit_id: u8,
+    pub aggregation_round: u8,
+}
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "prover_fri_witness_vector_generator")]
@@ -13,6 +29,34 @@ pub(crate) struct WitnessVectorGeneratorMetrics {
     pub prover_waiting_time: LabeledFamily<String, Histogram<Duration>>,
     #[metrics(buckets = Buckets::exponential(1.0..=64.0, 2.0), labels = ["circuit_type"])]
     pub prover_attempts_count: LabeledFamily<String, Histogram<usize>>,
+    /// Number of failed witness vector jobs, grouped by the failure class.
+    pub job_failures: Family<ErrorClass, Counter>,
+    /// Difference between the batch number of a picked job and the oldest batch without a complete proof.
+    /// Only reported if jobs are picked in the critical-path-first order.
+    #[metrics(buckets = Buckets::exponential(1.0..=1024.0, 2.0))]
+    pub picked_job_batch_lag: Histogram<usize>,
+    /// Info-style metric that is always set to 1. Its labels can be used to join other
+    /// generator metrics against the `group_id` and `zone` of the emitting deployment.
+    pub info: Family<GeneratorInfoLabels, Gauge<u64>>,
+    /// Set to 1 if job picking is paused, and to 0 otherwise.
+    pub paused: Gauge<u64>,
+    /// Set to 0 while the generator is unable to connect to the database, and to 1 otherwise.
+    pub db_available: Gauge<u64>,
+    /// Age of the oldest queued job for each circuit processed by the generator.
+    pub oldest_queued_job_age: Family<QueuedJobLabels, Gauge<Duration>>,
+    /// Time taken by the startup self-test.
+    pub self_test_duration: Gauge<Duration>,
+}
+
+impl WitnessVectorGeneratorMetrics {
+    /// Reports the effective specialized group ID and zone of this generator.
+    pub fn observe_generator_info(&self, specialized_group_id: u8, zone: &str) {
+        let labels = GeneratorInfoLabels {
+            group_id: specialized_group_id.to_string(),
+            zone: zone.to_owned(),
+        };
+        self.info[&labels].set(1);
+    }
 }
 
 #[vise::register]
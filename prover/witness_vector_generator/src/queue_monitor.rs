@@ -0,0 +1,140 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_types::basic_fri_types::CircuitIdRoundTuple;
+
+use crate::metrics::{QueuedJobLabels, METRICS};
+
+/// Periodically reports the age of the oldest queued job for each circuit processed by the generator.
+#[derive(Debug)]
+pub struct OldestQueuedJobReporter {
+    pool: ConnectionPool,
+    /// Circuits to report; if empty, all circuits with queued jobs are reported.
+    circuit_ids_for_round_to_be_proven: Vec<CircuitIdRoundTuple>,
+    reporting_interval: Duration,
+    reported_circuits: HashSet<(u8, u8)>,
+}
+
+impl OldestQueuedJobReporter {
+    pub fn new(
+        pool: ConnectionPool,
+        circuit_ids_for_round_to_be_proven: Vec<CircuitIdRoundTuple>,
+        reporting_interval: Duration,
+    ) -> Self {
+        Self {
+            pool,
+            reporting_interval,
+            reported_circuits: circuit_ids_for_round_to_be_proven
+                .iter()
+                .map(|tuple| (tuple.circuit_id, tuple.aggregation_round))
+                .collect(),
+            circuit_ids_for_round_to_be_proven,
+        }
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if let Err(err) = self.report().await {
+                // The reporter must not bring down the generator; the gauge is simply not updated.
+                tracing::warn!("Failed reporting the age of the oldest queued jobs: {err:#}");
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(self.reporting_interval) => {}
+                _ = stop_receiver.changed() => return Ok(()),
+            }
+        }
+    }
+
+    async fn report(&mut self) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage().await?;
+        let ages = storage
+            .fri_prover_jobs_dal()
+            .get_oldest_queued_job_ages()
+            .await?;
+        drop(storage);
+
+        for ((circuit_id, aggregation_round), age) in self.ages_to_report(ages) {
+            let labels = QueuedJobLabels {
+                circuit_id,
+                aggregation_round,
+            };
+            METRICS.oldest_queued_job_age[&labels].set(age);
+        }
+        Ok(())
+    }
+
+    /// Filters `ages` by the circuits processed by the generator. Circuits that were reported before
+    /// but no longer have queued jobs are reported with zero age, so that stale values don't linger.
+    fn ages_to_report(
+        &mut self,
+        mut ages: HashMap<(u8, u8), Duration>,
+    ) -> HashMap<(u8, u8), Duration> {
+        if !self.circuit_ids_for_round_to_be_proven.is_empty() {
+            ages.retain(|circuit, _| self.reported_circuits.contains(circuit));
+        }
+        self.reported_circuits.extend(ages.keys().copied());
+        for &circuit in &self.reported_circuits {
+            ages.entry(circuit).or_insert(Duration::ZERO);
+        }
+        ages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn reporter(circuits: &[(u8, u8)]) -> OldestQueuedJobReporter {
+        let circuits = circuits
+            .iter()
+            .map(|&(circuit_id, aggregation_round)| CircuitIdRoundTuple {
+                circuit_id,
+                aggregation_round,
+            })
+            .collect();
+        let pool = ConnectionPool::test_pool().await;
+        OldestQueuedJobReporter::new(pool, circuits, Duration::from_secs(1))
+    }
+
+    #[tokio::test]
+    async fn specialized_reporter_filters_circuits() {
+        let mut reporter = reporter(&[(1, 0), (2, 0)]).await;
+        let ages = HashMap::from([
+            ((1, 0), Duration::from_secs(5)),
+            ((3, 0), Duration::from_secs(10)),
+        ]);
+        let reported = reporter.ages_to_report(ages);
+        assert_eq!(
+            reported,
+            HashMap::from([((1, 0), Duration::from_secs(5)), ((2, 0), Duration::ZERO)])
+        );
+    }
+
+    #[tokio::test]
+    async fn generalized_reporter_resets_drained_circuits() {
+        let mut reporter = reporter(&[]).await;
+        let ages = HashMap::from([
+            ((1, 0), Duration::from_secs(5)),
+            ((3, 1), Duration::from_secs(10)),
+        ]);
+        let reported = reporter.ages_to_report(ages.clone());
+        assert_eq!(reported, ages);
+
+        let ages = HashMap::from([((3, 1), Duration::from_secs(12))]);
+        let reported = reporter.ages_to_report(ages);
+        assert_eq!(
+            reported,
+            HashMap::from([((1, 0), Duration::ZERO), ((3, 1), Duration::from_secs(12))])
+        );
+
+        let reported = reporter.ages_to_report(HashMap::new());
+        assert_eq!(
+            reported,
+            HashMap::from([((1, 0), Duration::ZERO), ((3, 1), Duration::ZERO)])
+        );
+    }
+}
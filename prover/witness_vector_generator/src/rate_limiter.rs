@@ -0,0 +1,111 @@
+use std::{num::NonZeroU32, sync::Mutex, time::Duration};
+
+use tokio::time::{sleep, Instant};
+
+/// Token bucket limiting how many jobs can be picked per minute.
+///
+/// The bucket holds at most a single token, so picks are spread evenly over time
+/// rather than happening in bursts. Tokens are consumed only by successful picks;
+/// polling for jobs while a token is available doesn't consume it.
+#[derive(Debug)]
+pub struct JobRateLimiter {
+    refill_interval: Duration,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    /// Point in time at which the next token becomes available.
+    next_token_at: Instant,
+}
+
+impl JobRateLimiter {
+    /// Creates a limiter allowing to pick up to `max_jobs_per_minute` jobs per minute.
+    pub fn per_minute(max_jobs_per_minute: NonZeroU32) -> Self {
+        Self {
+            refill_interval: Duration::from_secs(60) / max_jobs_per_minute.get(),
+            state: Mutex::new(BucketState {
+                next_token_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Waits until a token is available. Doesn't consume the token.
+    pub async fn wait_for_token(&self) {
+        let next_token_at = self.state.lock().unwrap().next_token_at;
+        let now = Instant::now();
+        if next_token_at > now {
+            tracing::debug!(
+                "Job picking is throttled, sleeping for {:?}",
+                next_token_at - now
+            );
+            sleep(next_token_at - now).await;
+        }
+    }
+
+    /// Consumes a token after a job was picked.
+    pub fn consume(&self) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        // The bucket holds at most one token, so tokens don't accumulate while the generator is idle.
+        state.next_token_at = state.next_token_at.max(now) + self.refill_interval;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn picks_respect_configured_rate() {
+        let limiter = JobRateLimiter::per_minute(NonZeroU32::new(30).unwrap());
+        let mut pick_timestamps = vec![];
+        for _ in 0..5 {
+            limiter.wait_for_token().await;
+            limiter.consume();
+            pick_timestamps.push(Instant::now());
+        }
+
+        let expected_interval = Duration::from_secs(2);
+        let tolerance = Duration::from_millis(10);
+        for window in pick_timestamps.windows(2) {
+            let interval = window[1] - window[0];
+            assert!(
+                interval + tolerance >= expected_interval,
+                "picks are too frequent: {interval:?}"
+            );
+            assert!(
+                interval <= expected_interval + tolerance,
+                "picks are throttled too much: {interval:?}"
+            );
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn polling_does_not_consume_tokens() {
+        let limiter = JobRateLimiter::per_minute(NonZeroU32::new(30).unwrap());
+        let started_at = Instant::now();
+        for _ in 0..3 {
+            // Poll without picking a job.
+            limiter.wait_for_token().await;
+        }
+        assert_eq!(Instant::now(), started_at);
+
+        limiter.consume();
+        limiter.wait_for_token().await;
+        assert_eq!(Instant::now() - started_at, Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn tokens_do_not_accumulate_while_idle() {
+        let limiter = JobRateLimiter::per_minute(NonZeroU32::new(30).unwrap());
+        limiter.consume();
+        sleep(Duration::from_secs(60)).await;
+
+        let started_at = Instant::now();
+        limiter.wait_for_token().await;
+        limiter.consume();
+        limiter.wait_for_token().await;
+        assert_eq!(Instant::now() - started_at, Duration::from_secs(2));
+    }
+}
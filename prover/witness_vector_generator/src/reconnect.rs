@@ -0,0 +1,249 @@
+//! Reconnection logic allowing the generator to survive brief database outages (e.g., a primary failover).
+
+use std::{future::Future, time::Duration};
+
+use anyhow::Context as _;
+use zksync_config::configs::FriWitnessVectorGeneratorConfig;
+use zksync_dal::SqlxError;
+
+use crate::metrics::METRICS;
+
+/// Policy for retrying database operations that fail because the database is unavailable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReconnectPolicy {
+    /// Delay after the first failed attempt. Doubled after each subsequent failure.
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between attempts.
+    pub max_backoff: Duration,
+    /// Number of consecutive failed attempts after which the error is returned to the caller.
+    pub max_consecutive_failures: u32,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            max_consecutive_failures: 10,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Creates a policy based on the generator `config`. Params not set in the config take default values.
+    pub fn from_config(config: &FriWitnessVectorGeneratorConfig) -> Self {
+        let default = Self::default();
+        Self {
+            initial_backoff: config
+                .reconnect_initial_backoff_ms
+                .map_or(default.initial_backoff, Duration::from_millis),
+            max_backoff: config
+                .reconnect_max_backoff_ms
+                .map_or(default.max_backoff, Duration::from_millis),
+            max_consecutive_failures: config
+                .reconnect_max_consecutive_failures
+                .unwrap_or(default.max_consecutive_failures),
+        }
+    }
+
+    /// Runs a database operation, retrying it with exponential backoff if it fails because the database
+    /// is unavailable. Other errors (e.g., constraint violations) are returned immediately.
+    ///
+    /// The operation should acquire a connection on each call, so that a broken connection isn't reused.
+    pub async fn run<T, F, Fut>(&self, mut action: F) -> anyhow::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let mut backoff = self.initial_backoff;
+        let mut failures = 0;
+        loop {
+            match action().await {
+                Ok(value) => {
+                    METRICS.db_available.set(1);
+                    return Ok(value);
+                }
+                Err(err) if !is_connection_error(&err) => return Err(err),
+                Err(err) => {
+                    METRICS.db_available.set(0);
+                    failures += 1;
+                    if failures >= self.max_consecutive_failures {
+                        return Err(err).with_context(|| {
+                            format!("database is unavailable after {failures} consecutive attempts")
+                        });
+                    }
+                    tracing::warn!(
+                        "Database is unavailable (attempt {failures}/{}), reconnecting in {backoff:?}: {err:#}",
+                        self.max_consecutive_failures
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(self.max_backoff);
+                }
+            }
+        }
+    }
+}
+
+/// Checks whether `err` is caused by an unavailable database rather than by the operation itself.
+/// Errors not originating from `sqlx` are never considered to be caused by the database.
+fn is_connection_error(err: &anyhow::Error) -> bool {
+    let Some(err) = err.chain().find_map(|err| err.downcast_ref::<SqlxError>()) else {
+        return false;
+    };
+    match err {
+        SqlxError::Io(_)
+        | SqlxError::Tls(_)
+        | SqlxError::Protocol(_)
+        | SqlxError::PoolTimedOut
+        | SqlxError::WorkerCrashed => true,
+        // Class 08 is "connection exception", class 57P is raised when the server is shut down
+        // (e.g., during a failover).
+        SqlxError::Database(err) => err.code().map_or(false, |code| {
+            code.starts_with("08") || code.starts_with("57P")
+        }),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use tokio::time::Instant;
+    use zksync_dal::ConnectionPool;
+
+    use super::*;
+
+    const POLICY: ReconnectPolicy = ReconnectPolicy {
+        initial_backoff: Duration::from_secs(1),
+        max_backoff: Duration::from_secs(4),
+        max_consecutive_failures: 5,
+    };
+
+    fn connection_reset() -> anyhow::Error {
+        SqlxError::Io(io::ErrorKind::ConnectionReset.into()).into()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reconnecting_with_exponential_backoff() {
+        let started_at = Instant::now();
+        let mut attempts = 0;
+        let value = POLICY
+            .run(|| {
+                attempts += 1;
+                let result = if attempts < 5 {
+                    Err(connection_reset())
+                } else {
+                    Ok(attempts)
+                };
+                async move { result }
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, 5);
+        // 1s + 2s + 4s + 4s (capped)
+        assert_eq!(started_at.elapsed(), Duration::from_secs(11));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn giving_up_after_consecutive_failures() {
+        let mut attempts = 0;
+        let err = POLICY
+            .run(|| {
+                attempts += 1;
+                async { anyhow::Result::<()>::Err(connection_reset()) }
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(attempts, POLICY.max_consecutive_failures);
+        assert!(is_connection_error(&err), "{err:#}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retrying_queries_failed_because_of_broken_connection() {
+        let mut attempts = 0;
+        let value = POLICY
+            .run(|| {
+                attempts += 1;
+                let result = if attempts < 3 {
+                    Err(connection_reset().context("update_status()"))
+                } else {
+                    Ok(attempts)
+                };
+                async move { result }
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn not_retrying_other_query_errors() {
+        let mut attempts = 0;
+        let err = POLICY
+            .run(|| {
+                attempts += 1;
+                async { anyhow::Result::<()>::Err(SqlxError::RowNotFound.into()) }
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(attempts, 1);
+        assert!(!is_connection_error(&err));
+        assert!(is_connection_error(&connection_reset()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn not_retrying_errors_without_sqlx_source() {
+        let mut attempts = 0;
+        let err = POLICY
+            .run(|| {
+                attempts += 1;
+                async {
+                    anyhow::Result::<()>::Err(
+                        anyhow::anyhow!("unexpected job status").context("update_status()"),
+                    )
+                }
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(attempts, 1);
+        assert_eq!(format!("{err:#}"), "update_status(): unexpected job status");
+    }
+
+    #[test]
+    fn creating_policy_from_config() {
+        let config = FriWitnessVectorGeneratorConfig {
+            max_prover_reservation_duration_in_secs: 1000,
+            prover_instance_wait_timeout_in_secs: 200,
+            prover_instance_poll_time_in_milli_secs: 250,
+            prometheus_listener_port: 3316,
+            prometheus_pushgateway_url: "http://127.0.0.1:9091".to_string(),
+            prometheus_push_interval_ms: None,
+            specialized_group_id: 1,
+            max_jobs_per_minute: None,
+            self_test_circuit_path: None,
+            self_test_expected_checksum: None,
+            job_ordering: Default::default(),
+            reconnect_initial_backoff_ms: Some(100),
+            reconnect_max_backoff_ms: None,
+            reconnect_max_consecutive_failures: Some(3),
+        };
+        let policy = ReconnectPolicy::from_config(&config);
+        assert_eq!(
+            policy,
+            ReconnectPolicy {
+                initial_backoff: Duration::from_millis(100),
+                max_backoff: ReconnectPolicy::default().max_backoff,
+                max_consecutive_failures: 3,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn connection_errors_keep_sqlx_source() {
+        let pool = ConnectionPool::test_pool().await;
+        pool.close().await;
+        let err = pool.access_storage().await.map(drop).unwrap_err();
+        assert!(err.chain().any(|err| err.is::<SqlxError>()), "{err:#}");
+    }
+}
@@ -0,0 +1,98 @@
+//! Startup self-test checking that the generator is able to synthesize circuits before it starts serving jobs.
+
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use sha2::{Digest, Sha256};
+use zksync_prover_fri_types::{CircuitWrapper, ProverJob, ProverServiceDataKey};
+use zksync_types::{proofs::AggregationRound, L1BatchNumber};
+
+use crate::generator::WitnessVectorGenerator;
+
+/// Upper bound on the size of a serialized witness vector.
+const MAX_WITNESS_VECTOR_SIZE: usize = 1_000_000_000;
+
+/// Synthesizes the circuit at `circuit_path` and checks that the produced witness vector is well-formed
+/// and matches `expected_checksum` (a hex-encoded SHA-256 digest of the serialized witness vector).
+/// Returns the time taken by the test.
+pub fn run_self_test(circuit_path: &Path, expected_checksum: &str) -> anyhow::Result<Duration> {
+    let started_at = Instant::now();
+    let circuit = fs::read(circuit_path)
+        .with_context(|| format!("failed reading self-test circuit from {circuit_path:?}"))?;
+    let checksum = witness_vector_checksum(&circuit)?;
+    anyhow::ensure!(
+        checksum.eq_ignore_ascii_case(expected_checksum.trim_start_matches("0x")),
+        "self-test witness vector checksum mismatch: expected {expected_checksum}, got {checksum}"
+    );
+    Ok(started_at.elapsed())
+}
+
+/// Synthesizes a bincode-serialized base layer `circuit` and returns the hex-encoded checksum
+/// of the serialized witness vector.
+fn witness_vector_checksum(circuit: &[u8]) -> anyhow::Result<String> {
+    let circuit_wrapper = bincode::deserialize::<CircuitWrapper>(circuit)
+        .context("failed deserializing self-test circuit")?;
+    let job = ProverJob {
+        block_number: L1BatchNumber(0),
+        job_id: 0,
+        circuit_wrapper,
+        setup_data_key: ProverServiceDataKey {
+            circuit_id: 1,
+            round: AggregationRound::BasicCircuits,
+        },
+    };
+    let artifacts = WitnessVectorGenerator::generate_witness_vector(job).context(
+        "failed synthesizing self-test circuit; check that setup data is available and up to date",
+    )?;
+
+    let witness_vector = &artifacts.witness_vector;
+    anyhow::ensure!(
+        !witness_vector.all_values.is_empty()
+            && !witness_vector.multiplicities.is_empty()
+            && !witness_vector.public_inputs_locations.is_empty(),
+        "self-test produced an incomplete witness vector"
+    );
+    let serialized =
+        bincode::serialize(&artifacts).context("failed serializing self-test witness vector")?;
+    anyhow::ensure!(
+        !serialized.is_empty() && serialized.len() < MAX_WITNESS_VECTOR_SIZE,
+        "self-test produced a serialized witness vector of unexpected size: {} bytes",
+        serialized.len()
+    );
+
+    let checksum = hex::encode(Sha256::digest(&serialized));
+    tracing::info!(
+        "Self-test produced a witness vector of {} bytes with checksum {checksum}",
+        serialized.len()
+    );
+    Ok(checksum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CIRCUIT_PATH: &str = "./tests/data/base_layer_main_vm.bin";
+
+    #[test]
+    fn self_test_checks_witness_vector_checksum() {
+        let circuit = fs::read(CIRCUIT_PATH).unwrap();
+        let checksum = witness_vector_checksum(&circuit).unwrap();
+        assert_eq!(checksum.len(), 64);
+
+        // Synthesis must be deterministic for the checksum to be meaningful.
+        run_self_test(Path::new(CIRCUIT_PATH), &format!("0x{checksum}")).unwrap();
+        let err = run_self_test(Path::new(CIRCUIT_PATH), &"0".repeat(64)).unwrap_err();
+        assert!(format!("{err:#}").contains("checksum mismatch"), "{err:#}");
+    }
+
+    #[test]
+    fn self_test_fails_for_missing_circuit() {
+        let err = run_self_test(Path::new("./tests/data/missing.bin"), "00").unwrap_err();
+        assert!(format!("{err:#}").contains("failed reading"), "{err:#}");
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    fs,
+    net::{IpAddr, Ipv4Addr, TcpListener},
+    time::Instant,
+};
+
+use zksync_config::configs::FriWitnessVectorGeneratorConfig;
+use zksync_dal::ConnectionPool;
+use zksync_object_store::{FriCircuitKey, ObjectStoreFactory};
+use zksync_prover_fri_types::{
+    checksum::deserialize_with_checksum, CircuitWrapper, WitnessVectorArtifacts,
+};
+use zksync_queued_job_processor::{JobNotSucceeded, JobProcessor};
+use zksync_types::{
+    proofs::{AggregationRound, FriProverJobMetadata, SocketAddress},
+    protocol_version::{FriProtocolVersionId, L1VerifierConfig},
+    L1BatchNumber,
+};
+use zksync_witness_vector_generator::{
+    error::ErrorClass,
+    generator::{SerializedWitnessVector, WitnessVectorGenerator},
+};
+
+fn test_config() -> FriWitnessVectorGeneratorConfig {
+    FriWitnessVectorGeneratorConfig {
+        max_prover_reservation_duration_in_secs: 1000,
+        prover_instance_wait_timeout_in_secs: 1,
+        prover_instance_poll_time_in_milli_secs: 250,
+        prometheus_listener_port: 3316,
+        prometheus_pushgateway_url: "http://127.0.0.1:9091".to_string(),
+        prometheus_push_interval_ms: Some(100),
+        specialized_group_id: 1,
+        max_jobs_per_minute: None,
+        self_test_circuit_path: None,
+        self_test_expected_checksum: None,
+        job_ordering: Default::default(),
+        reconnect_initial_backoff_ms: None,
+        reconnect_max_backoff_ms: None,
+        reconnect_max_consecutive_failures: None,
+    }
+}
+
+fn job_metadata(id: u32, circuit_id: u8) -> FriProverJobMetadata {
+    FriProverJobMetadata {
+        id,
+        block_number: L1BatchNumber(1),
+        circuit_id,
+        aggregation_round: AggregationRound::BasicCircuits,
+        sequence_number: 0,
+        depth: 0,
+        is_node_final_proof: false,
+    }
+}
+
+fn circuit_key(job: &FriProverJobMetadata) -> FriCircuitKey {
+    FriCircuitKey {
+        block_number: job.block_number,
+        sequence_number: job.sequence_number,
+        circuit_id: job.circuit_id,
+        aggregation_round: job.aggregation_round,
+        depth: job.depth,
+    }
+}
+
+async fn insert_prover_job(pool: &ConnectionPool, circuit_id: u8) -> u32 {
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .fri_protocol_versions_dal()
+        .save_prover_protocol_version(FriProtocolVersionId::default(), L1VerifierConfig::default())
+        .await;
+    storage
+        .fri_prover_jobs_dal()
+        .insert_prover_job(
+            L1BatchNumber(1),
+            circuit_id,
+            0,
+            0,
+            AggregationRound::BasicCircuits,
+            "circuit.bin",
+            false,
+            FriProtocolVersionId::default(),
+        )
+        .await;
+    let job = storage
+        .fri_prover_jobs_dal()
+        .get_next_job(&[FriProtocolVersionId::default()], "test", false)
+        .await
+        .unwrap()
+        .expect("no queued job");
+    job.id
+}
+
+async fn create_generator(pool: &ConnectionPool) -> WitnessVectorGenerator {
+    WitnessVectorGenerator::new(
+        ObjectStoreFactory::mock().create_store().await,
+        pool.clone(),
+        vec![],
+        "zone".to_string(),
+        test_config(),
+        L1VerifierConfig::default(),
+        3,
+    )
+}
+
+fn read_circuit() -> CircuitWrapper {
+    let file = fs::read("./tests/data/base_layer_main_vm.bin").expect("failed reading circuit");
+    bincode::deserialize::<CircuitWrapper>(&file).expect("circuit wrapper deserialization failed")
+}
+
+async fn assert_stored_class(error: anyhow::Error, expected_class: ErrorClass) {
+    let pool = ConnectionPool::test_pool().await;
+    let job_id = insert_prover_job(&pool, 1).await;
+    let generator = create_generator(&pool).await;
+
+    generator
+        .save_failure(job_id, Instant::now(), error.to_string())
+        .await;
+    assert_job_error_class(&pool, job_id, expected_class).await;
+}
+
+async fn assert_job_error_class(pool: &ConnectionPool, job_id: u32, expected_class: ErrorClass) {
+    let (error_class, message) = pool
+        .access_storage()
+        .await
+        .unwrap()
+        .fri_prover_jobs_dal()
+        .get_prover_job_error(job_id)
+        .await
+        .unwrap()
+        .expect("job not found");
+    assert_eq!(error_class.as_deref(), Some(expected_class.as_str()));
+    let message = message.unwrap();
+    assert!(!message.starts_with('['), "{message}");
+}
+
+#[tokio::test]
+async fn download_failure_is_classified() {
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    let error =
+        WitnessVectorGenerator::fetch_and_generate_witness_vector(blob_store, job_metadata(1, 1))
+            .await
+            .unwrap_err();
+    assert_eq!(
+        ErrorClass::classify(&error.to_string()).0,
+        ErrorClass::Download
+    );
+    assert_stored_class(error, ErrorClass::Download).await;
+}
+
+#[tokio::test]
+async fn synthesis_failure_is_classified() {
+    // There are no finalization hints for this circuit ID, so the synthesis stage will fail.
+    let job = job_metadata(1, u8::MAX);
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    blob_store
+        .put(circuit_key(&job), &read_circuit())
+        .await
+        .unwrap();
+
+    let error = WitnessVectorGenerator::fetch_and_generate_witness_vector(blob_store, job)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        ErrorClass::classify(&error.to_string()).0,
+        ErrorClass::Synthesis
+    );
+    assert_stored_class(error, ErrorClass::Synthesis).await;
+}
+
+// There's no test for `ErrorClass::Serialization`: it can't be forced, since bincode serialization
+// of `WitnessVectorArtifacts` only fails for malformed `Serialize` implementations, which synthesis
+// never produces.
+
+#[tokio::test]
+async fn delivery_failure_is_classified() {
+    let pool = ConnectionPool::test_pool().await;
+    let job_id = insert_prover_job(&pool, 1).await;
+    // Register a prover instance at an address nothing listens on.
+    let unreachable_port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let prover_address = SocketAddress {
+        host: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        port: unreachable_port,
+    };
+    pool.access_storage()
+        .await
+        .unwrap()
+        .fri_gpu_prover_queue_dal()
+        .insert_prover_instance(prover_address, 1, "zone".to_string())
+        .await;
+
+    let job = job_metadata(job_id, 1);
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    blob_store
+        .put(circuit_key(&job), &read_circuit())
+        .await
+        .unwrap();
+    let vector = WitnessVectorGenerator::fetch_and_generate_witness_vector(blob_store, job)
+        .await
+        .unwrap();
+    assert_eq!(vector.circuit_id, 1);
+    let (serialized_job_id, _) =
+        deserialize_with_checksum::<WitnessVectorArtifacts>(&vector.bytes).unwrap();
+    assert_eq!(serialized_job_id, job_id);
+
+    let generator = create_generator(&pool).await;
+    let err = generator
+        .save_result(job_id, Instant::now(), vector)
+        .await
+        .unwrap_err();
+    assert!(err.downcast_ref::<JobNotSucceeded>().is_some(), "{err:#}");
+    assert_job_error_class(&pool, job_id, ErrorClass::Delivery).await;
+}
+
+#[tokio::test]
+async fn job_without_available_prover_is_not_succeeded() {
+    let pool = ConnectionPool::test_pool().await;
+    let job_id = insert_prover_job(&pool, 1).await;
+    let vector = SerializedWitnessVector {
+        circuit_id: 1,
+        bytes: vec![0; 64],
+    };
+
+    let generator = create_generator(&pool).await;
+    let err = generator
+        .save_result(job_id, Instant::now(), vector)
+        .await
+        .unwrap_err();
+    assert!(err.downcast_ref::<JobNotSucceeded>().is_some(), "{err:#}");
+}
+
+#[tokio::test]
+async fn untagged_failure_is_classified_as_other() {
+    assert_stored_class(anyhow::anyhow!("Unknown panic"), ErrorClass::Other).await;
+}
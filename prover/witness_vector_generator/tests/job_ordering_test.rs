@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    basic_fri_types::CircuitIdRoundTuple,
+    proofs::AggregationRound,
+    protocol_version::{FriProtocolVersionId, L1VerifierConfig},
+    L1BatchNumber,
+};
+
+/// Inserts an unproven batch #1 with a basic circuit job, and a leaf aggregation job for batch #2.
+async fn prepare_jobs(pool: &ConnectionPool) {
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .fri_protocol_versions_dal()
+        .save_prover_protocol_version(FriProtocolVersionId::default(), L1VerifierConfig::default())
+        .await;
+    for number in [1, 2] {
+        storage
+            .fri_witness_generator_dal()
+            .create_aggregation_jobs(
+                L1BatchNumber(number),
+                &vec![],
+                "scheduler.bin",
+                |circuit_id| circuit_id,
+                FriProtocolVersionId::default(),
+            )
+            .await;
+    }
+    let jobs = [
+        (L1BatchNumber(1), AggregationRound::BasicCircuits),
+        (L1BatchNumber(2), AggregationRound::LeafAggregation),
+    ];
+    for (l1_batch_number, aggregation_round) in jobs {
+        storage
+            .fri_prover_jobs_dal()
+            .insert_prover_job(
+                l1_batch_number,
+                1,
+                0,
+                0,
+                aggregation_round,
+                "circuit.bin",
+                false,
+                FriProtocolVersionId::default(),
+            )
+            .await;
+    }
+}
+
+async fn pick_job_batch(pool: &ConnectionPool, critical_path_first: bool) -> L1BatchNumber {
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .fri_prover_jobs_dal()
+        .get_next_job(
+            &[FriProtocolVersionId::default()],
+            "test",
+            critical_path_first,
+        )
+        .await
+        .unwrap()
+        .expect("no queued job")
+        .block_number
+}
+
+#[tokio::test]
+async fn standard_ordering_prefers_later_rounds() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_jobs(&pool).await;
+    assert_eq!(pick_job_batch(&pool, false).await, L1BatchNumber(2));
+}
+
+#[tokio::test]
+async fn critical_path_first_prefers_oldest_unproven_batch() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_jobs(&pool).await;
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let oldest_unproven_batch = storage
+        .fri_prover_jobs_dal()
+        .get_oldest_unproven_l1_batch_number()
+        .await
+        .unwrap();
+    assert_eq!(oldest_unproven_batch, Some(L1BatchNumber(1)));
+    drop(storage);
+
+    assert_eq!(pick_job_batch(&pool, true).await, L1BatchNumber(1));
+    assert_eq!(pick_job_batch(&pool, true).await, L1BatchNumber(2));
+}
+
+#[tokio::test]
+async fn critical_path_first_applies_to_specialized_provers() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_jobs(&pool).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    // Batch #1 is proven, but still has a (e.g., requeued) basic circuit job.
+    storage
+        .fri_witness_generator_dal()
+        .mark_scheduler_job_as_successful(L1BatchNumber(1), Duration::ZERO)
+        .await;
+    storage
+        .fri_prover_jobs_dal()
+        .insert_prover_job(
+            L1BatchNumber(2),
+            1,
+            0,
+            1,
+            AggregationRound::BasicCircuits,
+            "circuit.bin",
+            false,
+            FriProtocolVersionId::default(),
+        )
+        .await;
+
+    let circuits = [CircuitIdRoundTuple {
+        circuit_id: 1,
+        aggregation_round: AggregationRound::BasicCircuits as u8,
+    }];
+    let job = storage
+        .fri_prover_jobs_dal()
+        .get_next_job_for_circuit_id_round(
+            &circuits,
+            &[FriProtocolVersionId::default()],
+            "test",
+            true,
+        )
+        .await
+        .unwrap()
+        .expect("no queued job");
+    assert_eq!(job.block_number, L1BatchNumber(2));
+}
@@ -0,0 +1,47 @@
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    proofs::AggregationRound,
+    protocol_version::{FriProtocolVersionId, L1VerifierConfig},
+    L1BatchNumber,
+};
+
+#[tokio::test]
+async fn oldest_queued_job_ages_are_reported_per_circuit() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .fri_protocol_versions_dal()
+        .save_prover_protocol_version(FriProtocolVersionId::default(), L1VerifierConfig::default())
+        .await;
+    for circuit_id in [1, 2] {
+        storage
+            .fri_prover_jobs_dal()
+            .insert_prover_job(
+                L1BatchNumber(1),
+                circuit_id,
+                0,
+                0,
+                AggregationRound::BasicCircuits,
+                "circuit.bin",
+                false,
+                FriProtocolVersionId::default(),
+            )
+            .await;
+    }
+    // Picked jobs are no longer queued.
+    storage
+        .fri_prover_jobs_dal()
+        .get_next_job(&[FriProtocolVersionId::default()], "test", false)
+        .await
+        .unwrap()
+        .expect("no queued job");
+
+    let ages = storage
+        .fri_prover_jobs_dal()
+        .get_oldest_queued_job_ages()
+        .await
+        .unwrap();
+    assert_eq!(ages.len(), 1, "{ages:?}");
+    let (&(_, aggregation_round), _) = ages.iter().next().unwrap();
+    assert_eq!(aggregation_round, AggregationRound::BasicCircuits as u8);
+}
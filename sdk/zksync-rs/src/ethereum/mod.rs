@@ -7,7 +7,8 @@ use std::{
 
 use serde_json::{Map, Value};
 use zksync_eth_client::{
-    clients::SigningClient, BoundEthInterface, CallFunctionArgs, Error, EthInterface,
+    clients::{HttpTransport, SigningClient},
+    BoundEthInterface, CallFunctionArgs, Error, EthInterface,
 };
 use zksync_eth_signer::EthereumSigner;
 use zksync_types::{
@@ -20,7 +21,6 @@ use zksync_types::{
             Options,
         },
         ethabi,
-        transports::Http,
         types::{TransactionReceipt, H160, H256, U256},
     },
     Address, L1ChainId, L1TxCommonData, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE,
@@ -87,7 +87,7 @@ impl<S: EthereumSigner> EthereumProvider<S> {
     where
         P: ZksNamespaceClient + Sync,
     {
-        let transport = Http::new(eth_web3_url.as_ref())
+        let transport = HttpTransport::new(eth_web3_url.as_ref())
             .map_err(|err| ClientError::NetworkError(err.to_string()))?;
 
         let l1_chain_id = provider.l1_chain_id().await?;